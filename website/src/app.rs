@@ -0,0 +1,461 @@
+/// Application state and router construction
+///
+/// `app` builds the complete Axum router for a given `AppState`. The binary
+/// (`main.rs`) serves it on a TCP port; the integration tests in `tests/`
+/// drive it directly with `tower::ServiceExt::oneshot` against a temporary
+/// data directory.
+///
+/// # Architecture
+///
+/// Following axum-web-framework patterns for router composition:
+/// - **Explicit state**: Every path the app reads from or writes to comes
+///   from `AppState`, not the working directory
+/// - **Data directory scope**: A middleware runs each request inside
+///   `persistence::with_data_dir`, so handlers and templates that call
+///   `data_dir()` see the state's directory without threading it through
+///   every signature
+/// - **Static assets**: `/assets`, `/media` and `/features` are served with
+///   `ServeDir` from the state's directories
+/// - **Injected time**: Handlers that depend on the current time read it
+///   from `AppState::clock`, so tests can control it
+/// - **Request ids**: A middleware gives every request an `X-Request-Id`
+///   (the client's, if it sent a usable one), stores it as a `RequestId`
+///   extension for the audit log and echoes it on the response
+/// - **Security headers**: A middleware adds the `AppState::security_headers`
+///   set for the path (public or `/admin`) and runs the request with a fresh
+///   CSP nonce for templates (see `core::security_headers`)
+/// - **Request limits**: `AppState::limits` sets the default body limit and
+///   the request timeout, and middlewares rate limit mutating requests per
+///   client address and per authenticated API token (see `core::limits`).
+///   The binary serves the router with `ConnectInfo` so clients are told
+///   apart by address
+/// - **Sign-in**: A middleware resolves the signed-in user of `/admin`
+///   requests from `AppState::sessions` or an API token, or sends them to
+///   the sign-in form (see `pages::admin::auth`)
+///
+/// # Background Tasks
+///
+/// `spawn_scheduler` starts the task that publishes scheduled drafts (see
+/// `core::schedule`). The binary spawns it next to the server; tests that
+/// need it drive `core::schedule` directly.
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{ConnectInfo, DefaultBodyLimit, Request, State};
+use axum::http::{HeaderName, HeaderValue, Method, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::{Router, routing::delete, routing::get, routing::post};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tower_http::services::ServeDir;
+use tower_http::timeout::TimeoutLayer;
+use uuid::Uuid;
+
+use crate::core;
+use crate::core::limits::{RateLimiter, RequestLimits, address_key, retry_after_secs, token_key};
+use crate::core::persistence::{default_data_dir, with_data_dir};
+use crate::core::presence::Presence;
+use crate::core::schedule::{Clock, SystemClock, run_scheduler};
+use crate::core::security_headers::{SecurityHeaders, generate_nonce, with_csp_nonce};
+use crate::core::sessions::Sessions;
+use crate::pages;
+use crate::pages::admin::CurrentUser;
+use crate::pages::admin::auth::MAX_CONCURRENT_SIGN_INS;
+
+/// Directories the application reads from and writes to
+#[derive(Debug, Clone)]
+pub struct AppState {
+    /// Routes, content, settings and media (see `core::persistence`)
+    pub data_dir: PathBuf,
+    /// Global stylesheets and scripts served at `/assets`
+    pub assets_dir: PathBuf,
+    /// Feature directories served at `/features` (component CSS and JS)
+    pub features_dir: PathBuf,
+    /// Source of the current time for scheduled publishing
+    pub clock: Arc<dyn Clock>,
+    /// Who is editing which route, and live publish notifications
+    pub presence: Presence,
+    /// Response headers for public and admin pages
+    pub security_headers: SecurityHeaders,
+    /// Body, content, time and rate limits
+    pub limits: RequestLimits,
+    /// Token buckets for `limits.rate_limit`, shared by clones of the state
+    pub rate_limiter: RateLimiter,
+    /// Signed-in admin sessions
+    pub sessions: Sessions,
+    /// Permits for password checks, which are slow on purpose: at most
+    /// `MAX_CONCURRENT_SIGN_INS` run at once
+    pub sign_ins: Arc<Semaphore>,
+}
+
+impl AppState {
+    /// State using `data_dir` and the asset directories of this package
+    pub fn new(data_dir: impl Into<PathBuf>) -> Self {
+        let package_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        Self {
+            data_dir: data_dir.into(),
+            assets_dir: package_dir.join("assets"),
+            features_dir: package_dir.join("src").join("features"),
+            clock: Arc::new(SystemClock),
+            presence: Presence::new(),
+            security_headers: SecurityHeaders::default(),
+            limits: RequestLimits::default(),
+            rate_limiter: RateLimiter::new(RequestLimits::default().rate_limit),
+            sessions: Sessions::new(),
+            sign_ins: Arc::new(Semaphore::new(MAX_CONCURRENT_SIGN_INS)),
+        }
+    }
+
+    /// Use `clock` instead of the system clock
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Send `headers` instead of the default security headers
+    pub fn with_security_headers(mut self, headers: SecurityHeaders) -> Self {
+        self.security_headers = headers;
+        self
+    }
+
+    /// Apply `limits` instead of the defaults, with empty rate limit buckets
+    pub fn with_limits(mut self, limits: RequestLimits) -> Self {
+        self.rate_limiter = RateLimiter::new(limits.rate_limit);
+        self.limits = limits;
+        self
+    }
+}
+
+impl Default for AppState {
+    /// State using the workspace data directory (`{workspace_root}/data`)
+    fn default() -> Self {
+        Self::new(default_data_dir())
+    }
+}
+
+/// How often the scheduler checks for due drafts
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Spawn the task that publishes scheduled drafts into the state's data directory
+pub fn spawn_scheduler(state: &AppState) -> JoinHandle<()> {
+    tokio::spawn(with_data_dir(
+        state.data_dir.clone(),
+        run_scheduler(
+            state.clock.clone(),
+            SCHEDULER_INTERVAL,
+            state.presence.clone(),
+        ),
+    ))
+}
+
+/// Build the application router
+///
+/// Following axum-web-framework patterns for router composition
+pub fn app(state: AppState) -> Router {
+    Router::new()
+        // Public pages
+        .route("/", get(pages::homepage))
+        .route("/{locale}/", get(pages::localized_homepage))
+        .route("/health", get(|| async { "OK" }))
+        .route("/sitemap.xml", get(pages::sitemap_xml))
+        .route("/robots.txt", get(pages::robots_txt))
+        // Admin pages (route handlers in pages::admin)
+        .route("/admin", get(pages::admin::admin_index))
+        .route(
+            "/admin/login",
+            get(pages::admin::login_page).post(pages::admin::sign_in),
+        )
+        .route(
+            "/admin/login/two-factor",
+            post(pages::admin::sign_in_two_factor),
+        )
+        .route("/admin/logout", post(pages::admin::sign_out))
+        .route(
+            "/admin/account/two-factor",
+            get(pages::admin::two_factor_page).post(pages::admin::enable_two_factor_form),
+        )
+        .route(
+            "/admin/account/two-factor/disable",
+            post(pages::admin::disable_two_factor_form),
+        )
+        .route(
+            "/admin/account/two-factor/recovery-codes",
+            post(pages::admin::reset_recovery_codes_form),
+        )
+        .route("/admin/route/", get(pages::admin::admin_route_index))
+        .route("/admin/route/{name}/", get(pages::admin::admin_route_page))
+        .route(
+            "/admin/route/{name}/preview",
+            get(pages::admin::route_preview_page),
+        )
+        .route("/admin/ws/route/{name}", get(pages::admin::route_socket))
+        // Admin features (component story system)
+        .route("/admin/features/", get(pages::admin::features_index))
+        .route("/admin/features/{name}/", get(pages::admin::feature_story))
+        .route(
+            "/admin/features/{name}/{variant}",
+            get(pages::admin::feature_story_variant),
+        )
+        .route("/admin/audit/", get(pages::admin::audit_page))
+        .route("/admin/media/", get(pages::admin::media_index))
+        .route("/admin/schedule/", get(pages::admin::schedule_page))
+        .route(
+            "/admin/users/",
+            get(pages::admin::users_page).post(pages::admin::save_user_form),
+        )
+        .route(
+            "/admin/users/{name}/delete",
+            post(pages::admin::delete_user_form),
+        )
+        .route(
+            "/admin/users/{name}/two-factor/reset",
+            post(pages::admin::reset_two_factor_form),
+        )
+        .route(
+            "/admin/tokens/",
+            get(pages::admin::tokens_page).post(pages::admin::create_token_form),
+        )
+        .route(
+            "/admin/tokens/{id}/revoke",
+            post(pages::admin::revoke_token_form),
+        )
+        .route("/admin/settings/", get(pages::admin::settings_page))
+        .route(
+            "/admin/settings/preview",
+            get(pages::admin::settings_preview_saved),
+        )
+        // Admin API endpoints
+        .route(
+            "/admin/api/media",
+            get(pages::admin::list_media_api)
+                .post(pages::admin::upload_media)
+                .layer(DefaultBodyLimit::max(core::media::MAX_UPLOAD_BYTES)),
+        )
+        .route(
+            "/admin/api/media/{id}",
+            post(pages::admin::update_media_item).delete(pages::admin::delete_media_item),
+        )
+        .route("/admin/api/settings", post(pages::admin::update_settings))
+        .route(
+            "/admin/api/settings/preview",
+            post(pages::admin::settings_preview),
+        )
+        .route(
+            "/admin/api/schema/block-options",
+            get(pages::admin::block_options_schema_json),
+        )
+        .route(
+            "/admin/api/features/{name}/render",
+            post(pages::admin::render_story_props),
+        )
+        .route("/admin/api/a11y", post(pages::admin::check_accessibility))
+        .route(
+            "/admin/api/scheduled/{id}",
+            delete(pages::admin::cancel_scheduled_draft),
+        )
+        .route("/admin/api/audit/export", get(pages::admin::export_audit))
+        .route("/admin/api/export", get(pages::admin::export_archive))
+        .route(
+            "/admin/api/import",
+            post(pages::admin::import_archive)
+                .layer(DefaultBodyLimit::max(core::archive::MAX_ARCHIVE_BYTES)),
+        )
+        .route(
+            "/admin/api/routes",
+            get(pages::admin::list_routes_api).post(pages::admin::replace_routes),
+        )
+        .route(
+            "/admin/api/homepage",
+            get(pages::admin::read_homepage).post(pages::admin::update_homepage),
+        )
+        .route(
+            "/admin/api/{route_name}",
+            get(pages::admin::read_route).post(pages::admin::update_route),
+        )
+        .nest_service("/assets", ServeDir::new(&state.assets_dir))
+        .nest_service("/media", ServeDir::new(state.data_dir.join("media")))
+        .nest_service("/features", ServeDir::new(&state.features_dir))
+        .layer(DefaultBodyLimit::max(state.limits.max_body_bytes))
+        .layer(TimeoutLayer::with_status_code(
+            StatusCode::REQUEST_TIMEOUT,
+            state.limits.request_timeout,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit_token,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            pages::admin::require_sign_in,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            scope_data_dir,
+        ))
+        .layer(middleware::from_fn_with_state(state.clone(), rate_limit))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            add_security_headers,
+        ))
+        .layer(middleware::from_fn(assign_request_id))
+        .with_state(state)
+}
+
+/// Header carrying the request id in both directions
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied request id that is kept
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Id of the current request, available to handlers as an extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+/// Middleware: assign a request id and echo it in the response headers
+///
+/// A client-supplied `X-Request-Id` is kept if it is short and made of
+/// ASCII letters, digits, `-` and `_`; anything else is replaced with a
+/// fresh UUID so it can't inject text into the audit log.
+async fn assign_request_id(mut request: Request, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    request.extensions_mut().insert(RequestId(id.clone()));
+    let mut response = next.run(request).await;
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Middleware: run the request with `data_dir()` set to the state's directory
+async fn scope_data_dir(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    with_data_dir(state.data_dir, next.run(request)).await
+}
+
+/// Middleware: add the path's security headers and scope a fresh CSP nonce
+///
+/// Headers the handler already set are kept. Header names or values that
+/// aren't valid HTTP are skipped.
+async fn add_security_headers(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let nonce = generate_nonce();
+    let headers = state
+        .security_headers
+        .for_path(request.uri().path())
+        .render(&nonce);
+
+    let mut response = with_csp_nonce(nonce, next.run(request)).await;
+    for (name, value) in headers {
+        let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(&value),
+        ) else {
+            continue;
+        };
+        if !response.headers().contains_key(&name) {
+            response.headers_mut().insert(name, value);
+        }
+    }
+    response
+}
+
+/// Middleware: rate limit mutating requests per client address
+///
+/// GET, HEAD and OPTIONS pass through. Other requests take a token from the
+/// bucket of the client's address (see `client_key`) or get 429 with
+/// `Retry-After`.
+async fn rate_limit(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if !is_rate_limited(request.method()) {
+        return next.run(request).await;
+    }
+
+    let key = client_key(&request, state.limits.trust_forwarded_for);
+    match state.rate_limiter.check(&key, state.clock.now()) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => too_many_requests(wait),
+    }
+}
+
+/// Middleware: rate limit mutating requests per API token too
+///
+/// Runs inside `require_sign_in`, so only a token that authenticated gets a
+/// bucket (see `token_key`), on top of its address's bucket. A made-up
+/// bearer value gets none and is limited by its address alone.
+async fn rate_limit_token(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let token_id = request
+        .extensions()
+        .get::<CurrentUser>()
+        .and_then(|user| user.token.as_ref())
+        .map(|token| token.id.clone());
+    let Some(token_id) = token_id.filter(|_| is_rate_limited(request.method())) else {
+        return next.run(request).await;
+    };
+
+    match state
+        .rate_limiter
+        .check(&token_key(&token_id), state.clock.now())
+    {
+        Ok(()) => next.run(request).await,
+        Err(wait) => too_many_requests(wait),
+    }
+}
+
+/// Whether requests with `method` are rate limited (everything but reads)
+fn is_rate_limited(method: &Method) -> bool {
+    !matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+/// 429 with `Retry-After` for a client that must wait `wait`
+fn too_many_requests(wait: Duration) -> Response {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, retry_after_secs(wait).to_string())],
+        "Too many requests, try again later",
+    )
+        .into_response()
+}
+
+/// Rate limit key: the client address
+///
+/// The address is the first `X-Forwarded-For` entry if `trust_forwarded_for`
+/// is set, else the peer address from `ConnectInfo`. Requests with neither
+/// (e.g. a router driven without `ConnectInfo`) share one bucket. Bearer
+/// tokens don't pick the bucket, so a client can't get a fresh one by
+/// sending a random `Authorization` header.
+fn client_key(request: &Request, trust_forwarded_for: bool) -> String {
+    let headers = request.headers();
+    let forwarded = headers
+        .get("x-forwarded-for")
+        .filter(|_| trust_forwarded_for)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .map(str::trim)
+        .filter(|address| !address.is_empty());
+    match forwarded {
+        Some(address) => address_key(address),
+        None => match request.extensions().get::<ConnectInfo<SocketAddr>>() {
+            Some(ConnectInfo(address)) => address_key(&address.ip().to_string()),
+            None => address_key("unknown"),
+        },
+    }
+}
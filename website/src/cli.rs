@@ -0,0 +1,222 @@
+/// Command-line interface for the website binary
+///
+/// Without arguments the binary starts the web server. Maintenance commands
+/// operate directly on the default data directory (`{workspace_root}/data`)
+/// and exit.
+///
+/// # Commands
+///
+/// ```text
+/// website                              Start the web server
+/// website export <archive.tar.gz>      Export the site (see core::archive)
+/// website import <archive.tar.gz> [--dry-run]
+///                                      Import an archive, replacing the site
+/// website migrate [--dry-run]          Rewrite routes.json content paths as
+///                                      ids, then upgrade content files to the
+///                                      current schema version (see core::migrations)
+/// ```
+///
+/// Arguments are parsed by hand: the command set is small and adding a
+/// parser dependency isn't worth it yet.
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+
+use crate::core::archive::{export_site, import_site};
+use crate::core::migrations::{
+    CONTENT_VERSION, MIGRATIONS, migrate_content_files, migrate_content_references,
+};
+
+/// Usage text printed for `--help` and invalid arguments
+pub const USAGE: &str = "\
+Usage: website [COMMAND]
+
+Commands:
+  (none)                                Start the web server
+  export <archive.tar.gz>               Export routes, content, settings, drafts and media
+  import <archive.tar.gz> [--dry-run]   Import an archive, replacing the site
+  migrate [--dry-run]                   Convert content paths to ids and upgrade content files";
+
+/// A parsed command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Serve,
+    Help,
+    Export { output: PathBuf },
+    Import { input: PathBuf, dry_run: bool },
+    Migrate { dry_run: bool },
+}
+
+impl Command {
+    /// Parse arguments (without the program name)
+    ///
+    /// # Errors
+    ///
+    /// Returns a message for unknown commands, options or missing arguments.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        match args.as_slice() {
+            [] => Ok(Command::Serve),
+            ["help" | "--help" | "-h"] => Ok(Command::Help),
+            ["export", output] => Ok(Command::Export {
+                output: PathBuf::from(output),
+            }),
+            ["import", rest @ ..] => {
+                let dry_run = rest.contains(&"--dry-run");
+                match rest
+                    .iter()
+                    .filter(|arg| **arg != "--dry-run")
+                    .collect::<Vec<_>>()
+                    .as_slice()
+                {
+                    [input] if !input.starts_with("--") => Ok(Command::Import {
+                        input: PathBuf::from(input),
+                        dry_run,
+                    }),
+                    _ => Err("import expects <archive.tar.gz> [--dry-run]".to_string()),
+                }
+            }
+            ["migrate"] => Ok(Command::Migrate { dry_run: false }),
+            ["migrate", "--dry-run"] => Ok(Command::Migrate { dry_run: true }),
+            ["migrate", ..] => Err("migrate expects [--dry-run]".to_string()),
+            ["export", ..] => Err("export expects <archive.tar.gz>".to_string()),
+            [command, ..] => Err(format!("unknown command '{}'", command)),
+        }
+    }
+}
+
+/// Run a maintenance command, printing its result to stdout
+///
+/// `Serve` and `Help` are handled by `main`.
+///
+/// # Errors
+///
+/// Returns a message describing the failure; `main` prints it and exits
+/// with a non-zero status.
+pub fn run(command: Command) -> Result<(), String> {
+    match command {
+        Command::Serve | Command::Help => Ok(()),
+        Command::Export { output } => {
+            let file = File::create(&output)
+                .map_err(|e| format!("Failed to create {}: {}", output.display(), e))?;
+            let manifest = export_site(BufWriter::new(file)).map_err(|e| e.to_string())?;
+            println!(
+                "Exported {} route(s) and {} file(s) to {}",
+                manifest.routes.len(),
+                manifest.files.len(),
+                output.display()
+            );
+            Ok(())
+        }
+        Command::Import { input, dry_run } => {
+            let file = File::open(&input)
+                .map_err(|e| format!("Failed to open {}: {}", input.display(), e))?;
+            let report = import_site(BufReader::new(file), dry_run).map_err(|e| e.to_string())?;
+            println!("{}", report);
+            Ok(())
+        }
+        Command::Migrate { dry_run } => migrate(dry_run),
+    }
+}
+
+/// Migrate routes.json and all route content files, printing one line per
+/// rewritten reference and per file
+fn migrate(dry_run: bool) -> Result<(), String> {
+    let references = migrate_content_references(dry_run)
+        .map_err(|e| format!("Failed to migrate routes.json: {}", e))?;
+    let mut failures = 0;
+    for reference in &references {
+        match &reference.to {
+            Some(id) => {
+                let verb = if dry_run { "would rewrite" } else { "rewrote" };
+                println!(
+                    "routes.json: {} {} '{}' -> '{}'",
+                    reference.route, verb, reference.from, id
+                );
+            }
+            None => {
+                failures += 1;
+                eprintln!(
+                    "routes.json: {}: '{}' is outside the content directory; move the file \
+                     into data/content/ and list its id",
+                    reference.route, reference.from
+                );
+            }
+        }
+    }
+
+    let results = migrate_content_files(dry_run);
+    for file in &results {
+        match &file.result {
+            Ok(version) if *version == CONTENT_VERSION => {
+                println!("{}: up to date (v{})", file.path.display(), version)
+            }
+            Ok(version) => {
+                let verb = if dry_run { "would migrate" } else { "migrated" };
+                println!(
+                    "{}: {} v{} -> v{}",
+                    file.path.display(),
+                    verb,
+                    version,
+                    CONTENT_VERSION
+                );
+                for migration in &MIGRATIONS[*version as usize..] {
+                    println!("    v{}: {}", migration.from + 1, migration.description);
+                }
+            }
+            Err(e) => {
+                failures += 1;
+                eprintln!("{}: {}", file.path.display(), e);
+            }
+        }
+    }
+
+    match failures {
+        0 => Ok(()),
+        n => Err(format!(
+            "{} content reference(s) or file(s) could not be migrated",
+            n
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        Command::parse(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse(&[]), Ok(Command::Serve));
+        assert_eq!(parse(&["--help"]), Ok(Command::Help));
+        assert_eq!(
+            parse(&["export", "site.tar.gz"]),
+            Ok(Command::Export {
+                output: PathBuf::from("site.tar.gz")
+            })
+        );
+        assert_eq!(
+            parse(&["import", "--dry-run", "site.tar.gz"]),
+            Ok(Command::Import {
+                input: PathBuf::from("site.tar.gz"),
+                dry_run: true
+            })
+        );
+        assert_eq!(
+            parse(&["migrate", "--dry-run"]),
+            Ok(Command::Migrate { dry_run: true })
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_arguments() {
+        assert!(parse(&["export"]).is_err());
+        assert!(parse(&["import"]).is_err());
+        assert!(parse(&["import", "a.tar.gz", "--force"]).is_err());
+        assert!(parse(&["migrate", "--force"]).is_err());
+        assert!(parse(&["publish"]).is_err());
+    }
+}
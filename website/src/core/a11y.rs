@@ -0,0 +1,551 @@
+/// Accessibility linter for rendered markup
+///
+/// Parses the HTML produced by Maud templates and reports common
+/// accessibility mistakes. It is deliberately small: Maud always emits
+/// well-formed, quoted, escaped markup, so a forgiving tokenizer is enough and
+/// no browser or DOM crate is needed.
+///
+/// # Rules
+///
+/// | Rule | Reports |
+/// |------|---------|
+/// | `missing_label` | `input`/`select`/`textarea` with no `<label>`, `aria-label` or `aria-labelledby` |
+/// | `duplicate_id` | An `id` used by more than one element |
+/// | `empty_link` | `<a href>` with no text, alt text or accessible name |
+/// | `empty_button` | `<button>` with no text or accessible name |
+/// | `empty_aria_label` | `aria-label=""` (hides the element's text from screen readers) |
+/// | `heading_order` | A heading that skips a level (`h1` followed by `h3`) |
+/// | `missing_alt` | `<img>` without an `alt` attribute (`alt=""` is fine for decoration) |
+///
+/// # Usage
+///
+/// - Tests: `assert_accessible(&markup.into_string())` fails with every issue
+/// - Admin: `lint_blocks` runs before publish and powers the editor's
+///   warnings panel (`POST /admin/api/a11y`)
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use strum::{Display, EnumIter};
+
+use crate::core::block::{BlockWithId, render_block};
+
+/// Which check produced an issue
+///
+/// Displays as its serialized (snake_case) identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Display, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Rule {
+    MissingLabel,
+    DuplicateId,
+    EmptyLink,
+    EmptyButton,
+    EmptyAriaLabel,
+    HeadingOrder,
+    MissingAlt,
+}
+
+impl Rule {
+    /// Whether the rule needs the whole page rather than a single block
+    pub fn is_page_level(self) -> bool {
+        matches!(self, Rule::DuplicateId | Rule::HeadingOrder)
+    }
+}
+
+/// One accessibility problem found in the markup
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Issue {
+    pub rule: Rule,
+    pub message: String,
+}
+
+/// An issue attributed to the block that caused it
+///
+/// `block_id` is `None` for page-level rules (duplicate ids, heading order)
+/// that only make sense across blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlockIssue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    #[serde(flatten)]
+    pub issue: Issue,
+}
+
+/// Form controls that never need a label
+const UNLABELLED_INPUT_TYPES: &[&str] = &["hidden", "submit", "button", "reset", "image"];
+
+/// Elements that never have a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    attrs: Vec<(String, String)>,
+    parent: Option<usize>,
+    /// Text content of all descendants, including `img` alt text
+    text: String,
+}
+
+impl Element {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn has_accessible_name(&self) -> bool {
+        !self.text.trim().is_empty()
+            || ["aria-label", "aria-labelledby", "title"]
+                .iter()
+                .any(|attr| self.attr(attr).is_some_and(|v| !v.trim().is_empty()))
+    }
+
+    fn describe(&self) -> String {
+        let mut out = format!("<{}", self.name);
+        for key in ["id", "name", "href", "src"] {
+            if let Some(value) = self.attr(key) {
+                out.push_str(&format!(" {}=\"{}\"", key, value));
+            }
+        }
+        out.push('>');
+        out
+    }
+}
+
+/// Lint an HTML document or fragment
+pub fn lint(html: &str) -> Vec<Issue> {
+    let elements = parse(html);
+    let mut issues = Vec::new();
+
+    let label_targets: HashSet<&str> = elements
+        .iter()
+        .filter(|el| el.name == "label")
+        .filter_map(|el| el.attr("for"))
+        .collect();
+    let mut id_counts: HashMap<&str, usize> = HashMap::new();
+    let mut previous_heading: Option<u8> = None;
+
+    for (index, el) in elements.iter().enumerate() {
+        if let Some(id) = el.attr("id") {
+            *id_counts.entry(id).or_default() += 1;
+        }
+
+        if el.attr("aria-label").is_some_and(|v| v.trim().is_empty()) {
+            issues.push(Issue {
+                rule: Rule::EmptyAriaLabel,
+                message: format!(
+                    "{} has an empty aria-label; remove it or describe the element",
+                    el.describe()
+                ),
+            });
+        }
+
+        match el.name.as_str() {
+            "img" if el.attr("alt").is_none() => issues.push(Issue {
+                rule: Rule::MissingAlt,
+                message: format!("{} has no alt attribute", el.describe()),
+            }),
+            "a" if el.attr("href").is_some() && !el.has_accessible_name() => issues.push(Issue {
+                rule: Rule::EmptyLink,
+                message: format!("{} has no text or accessible name", el.describe()),
+            }),
+            "button" if !el.has_accessible_name() => issues.push(Issue {
+                rule: Rule::EmptyButton,
+                message: format!("{} has no text or accessible name", el.describe()),
+            }),
+            "input" | "select" | "textarea" if needs_label(el) => {
+                let labelled = el.attr("id").is_some_and(|id| label_targets.contains(id))
+                    || has_ancestor(&elements, index, "label")
+                    || ["aria-label", "aria-labelledby"]
+                        .iter()
+                        .any(|attr| el.attr(attr).is_some_and(|v| !v.trim().is_empty()));
+                if !labelled {
+                    issues.push(Issue {
+                        rule: Rule::MissingLabel,
+                        message: format!("{} has no associated label", el.describe()),
+                    });
+                }
+            }
+            _ => {}
+        }
+
+        if let Some(level) = heading_level(&el.name) {
+            if let Some(previous) = previous_heading
+                && level > previous + 1
+            {
+                issues.push(Issue {
+                    rule: Rule::HeadingOrder,
+                    message: format!("<h{}> follows <h{}> and skips a level", level, previous),
+                });
+            }
+            previous_heading = Some(level);
+        }
+    }
+
+    let mut duplicates: Vec<(&str, usize)> = id_counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .collect();
+    duplicates.sort();
+    for (id, count) in duplicates {
+        issues.push(Issue {
+            rule: Rule::DuplicateId,
+            message: format!("id \"{}\" is used by {} elements", id, count),
+        });
+    }
+
+    issues
+}
+
+/// Lint a page's blocks as they would be rendered publicly
+///
+/// Element-level rules are reported per block so the editor can point at the
+/// offending block; duplicate ids and heading order are checked across the
+/// whole page.
+pub fn lint_blocks(blocks: &[BlockWithId]) -> Vec<BlockIssue> {
+    let mut issues = Vec::new();
+    let mut page = String::new();
+
+    for block in blocks {
+        let html = render_block(block).into_string();
+        issues.extend(
+            lint(&html)
+                .into_iter()
+                .filter(|issue| !issue.rule.is_page_level())
+                .map(|issue| BlockIssue {
+                    block_id: Some(block.id.clone()),
+                    issue,
+                }),
+        );
+        page.push_str(&html);
+    }
+
+    issues.extend(
+        lint(&page)
+            .into_iter()
+            .filter(|issue| issue.rule.is_page_level())
+            .map(|issue| BlockIssue {
+                block_id: None,
+                issue,
+            }),
+    );
+    issues
+}
+
+/// Panic with every accessibility issue found in `html`
+///
+/// Test helper for template and story tests.
+#[cfg(test)]
+pub fn assert_accessible(html: &str) {
+    let issues = lint(html);
+    assert!(
+        issues.is_empty(),
+        "accessibility issues:\n{}\n\nin:\n{}",
+        issues
+            .iter()
+            .map(|issue| format!("- {:?}: {}", issue.rule, issue.message))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        html
+    );
+}
+
+fn needs_label(el: &Element) -> bool {
+    el.name != "input" || !UNLABELLED_INPUT_TYPES.contains(&el.attr("type").unwrap_or("text"))
+}
+
+fn has_ancestor(elements: &[Element], index: usize, name: &str) -> bool {
+    let mut current = elements[index].parent;
+    while let Some(parent) = current {
+        if elements[parent].name == name {
+            return true;
+        }
+        current = elements[parent].parent;
+    }
+    false
+}
+
+fn heading_level(name: &str) -> Option<u8> {
+    match name {
+        "h1" => Some(1),
+        "h2" => Some(2),
+        "h3" => Some(3),
+        "h4" => Some(4),
+        "h5" => Some(5),
+        "h6" => Some(6),
+        _ => None,
+    }
+}
+
+/// Parse HTML into a flat list of elements in document order
+///
+/// Comments, doctypes and the contents of `script`/`style` are skipped.
+/// Unclosed elements are closed by their ancestors' end tags.
+fn parse(html: &str) -> Vec<Element> {
+    let mut elements: Vec<Element> = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("<!--") {
+            rest = after.find("-->").map_or("", |i| &after[i + 3..]);
+        } else if let Some(after) = rest.strip_prefix("</") {
+            let end = after.find('>').unwrap_or(after.len());
+            let name = after[..end].trim().to_ascii_lowercase();
+            if let Some(pos) = open.iter().rposition(|&i| elements[i].name == name) {
+                open.truncate(pos);
+            }
+            rest = after.get(end + 1..).unwrap_or("");
+        } else if let Some(after) = rest.strip_prefix("<!") {
+            rest = after.find('>').map_or("", |i| &after[i + 1..]);
+        } else if rest.starts_with('<') {
+            let (element, self_closing, remainder) = parse_tag(rest);
+            rest = remainder;
+
+            let index = elements.len();
+            let name = element.name.clone();
+            let alt = (name == "img")
+                .then(|| element.attr("alt").map(str::to_string))
+                .flatten();
+            elements.push(Element {
+                parent: open.last().copied(),
+                ..element
+            });
+
+            // Image alt text names its enclosing link or button
+            if let Some(alt) = alt {
+                append_text(&mut elements, &open, &alt);
+            }
+
+            if name == "script" || name == "style" {
+                let close = format!("</{}", name);
+                rest = rest.find(&close).map_or("", |i| &rest[i..]);
+            }
+            if !self_closing && !VOID_ELEMENTS.contains(&name.as_str()) {
+                open.push(index);
+            }
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            append_text(&mut elements, &open, &decode_entities(&rest[..end]));
+            rest = &rest[end..];
+        }
+    }
+
+    elements
+}
+
+fn append_text(elements: &mut [Element], open: &[usize], text: &str) {
+    for &index in open {
+        elements[index].text.push_str(text);
+    }
+}
+
+/// Parse one start tag at the beginning of `input`
+///
+/// Returns the element (without parent), whether it was self-closing, and
+/// the remaining input.
+fn parse_tag(input: &str) -> (Element, bool, &str) {
+    let body = &input[1..];
+    let name_end = body
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(body.len());
+    let name = body[..name_end].to_ascii_lowercase();
+    let mut rest = &body[name_end..];
+    let mut attrs = Vec::new();
+    let mut self_closing = false;
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        if let Some(after) = rest.strip_prefix("/>") {
+            self_closing = true;
+            rest = after;
+            break;
+        }
+        if let Some(after) = rest.strip_prefix('>') {
+            rest = after;
+            break;
+        }
+        if let Some(after) = rest.strip_prefix('/') {
+            rest = after;
+            continue;
+        }
+
+        let key_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+            .unwrap_or(rest.len());
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+
+        let value = if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (value, remainder) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after[1..];
+                    let end = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after
+                        .find(|c: char| c.is_whitespace() || c == '>')
+                        .unwrap_or(after.len());
+                    after.split_at(end)
+                }
+            };
+            rest = remainder;
+            decode_entities(value)
+        } else {
+            String::new()
+        };
+        attrs.push((key, value));
+    }
+
+    (
+        Element {
+            name,
+            attrs,
+            parent: None,
+            text: String::new(),
+        },
+        self_closing,
+        rest,
+    )
+}
+
+/// Decode the entities Maud's escaping produces
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::features::story::all_stories;
+    use strum::IntoEnumIterator;
+
+    fn rules(html: &str) -> Vec<Rule> {
+        lint(html).into_iter().map(|issue| issue.rule).collect()
+    }
+
+    #[test]
+    fn test_every_story_variant_is_accessible() {
+        for story in all_stories() {
+            for variant in story.variants() {
+                assert_accessible(&variant.markup.into_string());
+            }
+        }
+    }
+
+    #[test]
+    fn test_rule_ids_match_serde() {
+        for rule in Rule::iter() {
+            assert_eq!(serde_json::to_value(rule).unwrap(), rule.to_string());
+        }
+    }
+
+    #[test]
+    fn test_labels() {
+        assert_eq!(
+            rules(r#"<input id="a" type="text">"#),
+            vec![Rule::MissingLabel]
+        );
+        assert!(rules(r#"<label for="a">A</label><input id="a">"#).is_empty());
+        assert!(rules(r#"<label>A <input type="checkbox"></label>"#).is_empty());
+        assert!(rules(r#"<input aria-label="Search">"#).is_empty());
+        assert!(rules(r#"<input type="hidden" name="x">"#).is_empty());
+        assert_eq!(rules("<textarea></textarea>"), vec![Rule::MissingLabel]);
+    }
+
+    #[test]
+    fn test_duplicate_ids() {
+        let issues = lint(r#"<div id="x"></div><span id="x"></span><p id="y"></p>"#);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule, Rule::DuplicateId);
+        assert!(issues[0].message.contains("\"x\""));
+    }
+
+    #[test]
+    fn test_links_and_buttons() {
+        assert_eq!(rules(r#"<a href="/x"></a>"#), vec![Rule::EmptyLink]);
+        assert_eq!(rules(r#"<a href="/x">  </a>"#), vec![Rule::EmptyLink]);
+        assert!(rules(r#"<a href="/x"><img src="i" alt="Home"></a>"#).is_empty());
+        assert!(rules(r#"<a href="/x" aria-label="Home"></a>"#).is_empty());
+        assert!(rules(r#"<a id="anchor"></a>"#).is_empty());
+        assert_eq!(rules("<button></button>"), vec![Rule::EmptyButton]);
+        assert!(rules("<button>Save</button>").is_empty());
+    }
+
+    #[test]
+    fn test_empty_aria_label() {
+        assert_eq!(
+            rules(r#"<a href="/x" aria-label="">Go</a>"#),
+            vec![Rule::EmptyAriaLabel]
+        );
+    }
+
+    #[test]
+    fn test_heading_order() {
+        assert!(rules("<h1>A</h1><h2>B</h2><h3>C</h3><h2>D</h2>").is_empty());
+        assert_eq!(rules("<h1>A</h1><h3>B</h3>"), vec![Rule::HeadingOrder]);
+        // Fragments may start at any level
+        assert!(rules("<h2>A</h2>").is_empty());
+    }
+
+    #[test]
+    fn test_missing_alt() {
+        assert_eq!(rules(r#"<img src="a.png">"#), vec![Rule::MissingAlt]);
+        assert!(rules(r#"<img src="a.png" alt="">"#).is_empty());
+    }
+
+    #[test]
+    fn test_parser_handles_escapes_comments_and_scripts() {
+        let html = r#"<!DOCTYPE html><!-- <img> --><script>if (a < b) { x = "<img>"; }</script>
+            <a href="/q?a=1&amp;b=2" title="&quot;quoted&quot;"></a>"#;
+        let elements = parse(html);
+        assert_eq!(elements.len(), 2);
+        assert_eq!(elements[1].attr("href"), Some("/q?a=1&b=2"));
+        assert_eq!(elements[1].attr("title"), Some("\"quoted\""));
+        assert!(rules(html).is_empty());
+    }
+
+    #[test]
+    fn test_lint_blocks_attributes_issues() {
+        use crate::core::block::{Block, HeaderProps};
+        use crate::core::options::BlockOptions;
+        use crate::features::button::ButtonProps;
+
+        let header = |id: &str, anchor: &str, label: &str| BlockWithId {
+            id: id.to_string(),
+            block: Block::Header(HeaderProps {
+                headline: "Hi".to_string(),
+                button: ButtonProps {
+                    href: "/x".to_string(),
+                    text: String::new(),
+                    aria_label: label.to_string(),
+                },
+            }),
+            options: BlockOptions {
+                anchor: Some(anchor.to_string()),
+                ..Default::default()
+            },
+            schedule: Default::default(),
+        };
+
+        let issues = lint_blocks(&[header("a", "top", "Go"), header("b", "top", "")]);
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert_eq!(issues[0].block_id.as_deref(), Some("b"));
+        assert_eq!(issues[0].issue.rule, Rule::EmptyLink);
+        assert_eq!(issues[1].block_id, None);
+        assert_eq!(issues[1].issue.rule, Rule::DuplicateId);
+    }
+}
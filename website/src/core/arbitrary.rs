@@ -0,0 +1,287 @@
+/// Proptest strategies for content types
+///
+/// Implements `proptest::arbitrary::Arbitrary` for every props type, the
+/// options and schedule envelopes, `Block` and `BlockWithId`, so property
+/// tests can ask for `any::<BlockWithId>()` and get a fully random block.
+///
+/// # Architecture
+///
+/// Following rust-core-patterns for testing:
+/// - **Test-only**: Compiled under `#[cfg(test)]`, so proptest stays a
+///   dev-dependency
+/// - **One place**: Strategies live next to each other instead of in every
+///   feature, since the property tests exercise them together
+/// - **Hostile text**: Every user-editable string comes from `user_text`,
+///   which mixes arbitrary unicode with markup injection attempts
+///
+/// # Adding New Block Types
+///
+/// Add an `Arbitrary` impl for the new props type and a `prop_oneof!` arm in
+/// the `Block` impl below; the property tests in `core::block` pick it up.
+use proptest::prelude::*;
+use strum::IntoEnumIterator;
+
+use crate::core::block::{Block, BlockWithId};
+use crate::core::options::{
+    Alignment, BlockOptions, BlockVariant, ColorToken, Spacing, Visibility,
+};
+use crate::core::schedule::Schedule;
+use crate::features::button::ButtonProps;
+use crate::features::checkbox::CheckboxProps;
+use crate::features::header::HeaderProps;
+use crate::features::hero::HeroProps;
+use crate::features::image::ImageProps;
+use crate::features::input::InputProps;
+
+/// Markup that must never appear unescaped in rendered output
+pub const INJECTION: &str = "\"'><x-injected onclick=alert(1)>&amp;</x-injected><!--";
+
+/// Strategy for user-editable text
+///
+/// Arbitrary unicode, printable ASCII (more likely to hit `<`, `&` and
+/// quotes), or either with `INJECTION` embedded.
+pub fn user_text() -> impl Strategy<Value = String> {
+    let plain = prop_oneof![any::<String>(), "[ -~]{0,40}"];
+    prop_oneof![
+        3 => plain.clone(),
+        1 => (plain.clone(), plain).prop_map(|(a, b)| format!("{a}{INJECTION}{b}")),
+    ]
+}
+
+/// Strategy for a media id: a UUID (usually not in the library) or any text
+fn media_id() -> impl Strategy<Value = String> {
+    prop_oneof![
+        "[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}",
+        user_text(),
+    ]
+}
+
+/// Arbitrary impl for a fieldless options enum via `IntoEnumIterator`
+macro_rules! arbitrary_option_value {
+    ($($ty:ty),+ $(,)?) => {
+        $(
+            impl Arbitrary for $ty {
+                type Parameters = ();
+                type Strategy = BoxedStrategy<Self>;
+
+                fn arbitrary_with(_: ()) -> Self::Strategy {
+                    prop::sample::select(<$ty>::iter().collect::<Vec<_>>()).boxed()
+                }
+            }
+        )+
+    };
+}
+
+arbitrary_option_value!(BlockVariant, Alignment, ColorToken, Spacing, Visibility);
+
+impl Arbitrary for BlockOptions {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (
+            any::<BlockVariant>(),
+            any::<Alignment>(),
+            any::<Option<ColorToken>>(),
+            any::<Spacing>(),
+            prop::option::of(prop_oneof!["[a-z][a-z0-9-]{0,20}", user_text()]),
+            any::<Visibility>(),
+        )
+            .prop_map(
+                |(variant, alignment, background, spacing, anchor, visibility)| BlockOptions {
+                    variant,
+                    alignment,
+                    background,
+                    spacing,
+                    anchor,
+                    visibility,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for ButtonProps {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (user_text(), user_text(), user_text())
+            .prop_map(|(href, text, aria_label)| ButtonProps {
+                href,
+                text,
+                aria_label,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for CheckboxProps {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (
+            user_text(),
+            user_text(),
+            prop::option::of(user_text()),
+            prop::option::of(user_text()),
+            any::<bool>(),
+            any::<bool>(),
+            prop::option::of(user_text()),
+        )
+            .prop_map(
+                |(label, name, id, value, checked, required, aria_describedby)| CheckboxProps {
+                    label,
+                    name,
+                    id,
+                    value,
+                    checked,
+                    required,
+                    aria_describedby,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for HeaderProps {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (user_text(), any::<ButtonProps>())
+            .prop_map(|(headline, button)| HeaderProps { headline, button })
+            .boxed()
+    }
+}
+
+impl Arbitrary for HeroProps {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (user_text(), user_text())
+            .prop_map(|(headline, subheadline)| HeroProps {
+                headline,
+                subheadline,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for ImageProps {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (
+            media_id(),
+            prop::option::of(user_text()),
+            prop::option::of(user_text()),
+            prop::option::of(user_text()),
+        )
+            .prop_map(|(media_id, alt, sizes, caption)| ImageProps {
+                media_id,
+                alt,
+                sizes,
+                caption,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for InputProps {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (
+            (user_text(), user_text(), prop::option::of(user_text())),
+            prop_oneof![
+                Just("text".to_string()),
+                Just("email".to_string()),
+                user_text()
+            ],
+            prop::option::of(user_text()),
+            prop::option::of(user_text()),
+            any::<bool>(),
+            prop::option::of(user_text()),
+            prop::option::of(user_text()),
+        )
+            .prop_map(
+                |(
+                    (label, name, id),
+                    input_type,
+                    placeholder,
+                    value,
+                    required,
+                    aria_describedby,
+                    error,
+                )| InputProps {
+                    label,
+                    name,
+                    id,
+                    input_type,
+                    placeholder,
+                    value,
+                    required,
+                    aria_describedby,
+                    error,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for Block {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        prop_oneof![
+            any::<HeaderProps>().prop_map(Block::Header),
+            any::<HeroProps>().prop_map(Block::Hero),
+            any::<ImageProps>().prop_map(Block::Image),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for Schedule {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        // Whole seconds between 2000 and 2100, which round-trip through RFC 3339
+        let instant = (946_684_800i64..4_102_444_800)
+            .prop_map(|secs| chrono::DateTime::from_timestamp(secs, 0).unwrap());
+        (prop::option::of(instant.clone()), prop::option::of(instant))
+            .prop_map(|(publish_at, unpublish_at)| Schedule {
+                publish_at,
+                unpublish_at,
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for BlockWithId {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        (
+            prop_oneof![Just(String::new()), "[0-9a-f-]{1,36}", user_text()],
+            any::<Block>(),
+            any::<BlockOptions>(),
+            any::<Schedule>(),
+        )
+            .prop_map(|(id, block, options, schedule)| BlockWithId {
+                id,
+                block,
+                options,
+                schedule,
+            })
+            .boxed()
+    }
+}
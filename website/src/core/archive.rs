@@ -0,0 +1,927 @@
+/// Site export/import as a single portable archive
+///
+/// Moves a whole site (routes, content files, settings, scheduled drafts
+/// and the media library) between environments, e.g. from local dev to the Render.com
+/// instance, without copying `data/` by hand.
+///
+/// # Archive Format
+///
+/// A gzip-compressed tar file whose layout mirrors the data directory:
+///
+/// ```text
+/// manifest.json          # Manifest: format version, export time, file list
+/// routes.json            # Routes, exactly as loaded
+/// settings.json          # Site settings (only if the site has any)
+/// content/homepage.json  # One file per content id listed in routes.json
+/// scheduled/{uuid}.json  # Scheduled drafts, exactly as queued
+/// media/{uuid}/...       # Media items, exactly as stored
+/// ```
+///
+/// Routes list their content by id (see `core::persistence`), so content
+/// files are stored as `content/{id}.json` and `routes.json` needs no
+/// rewriting. A file listed by several routes is stored once and stays
+/// shared after an import. Locale variants of a content file (see
+/// `core::i18n`) are stored next to it, e.g. `content/homepage.de.json`.
+///
+/// Version 1 archives listed `blockIds` as archive paths
+/// (`content/homepage.json`); they are converted to ids on import. Archives
+/// before version 3 have no scheduled drafts, so importing one cancels any
+/// queued here.
+///
+/// # Importing
+///
+/// An import replaces the site: every file in the archive is written and
+/// every managed file in the data directory that isn't in the archive
+/// (`routes.json`, `settings.json`, `content/*.json`, `scheduled/*.json`,
+/// `media/**`) is deleted, so no draft is left targeting a deleted file.
+/// The archive is fully read and validated before anything is written, and
+/// a dry run returns the same `ImportReport` without touching disk.
+///
+/// # Versioning
+///
+/// `ARCHIVE_VERSION` is bumped whenever the layout changes incompatibly.
+/// Archives from newer versions are rejected rather than half-imported.
+///
+/// # Error Handling
+///
+/// Following rust-error-handling patterns, operations return `ArchiveError`
+/// so API handlers and the CLI can report what was wrong with the archive.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path};
+
+use chrono::{DateTime, Utc};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::migrations::parse_content;
+use crate::core::persistence::{
+    ContentError, Route, content_file_name, content_file_path, data_dir, legacy_content_id,
+    load_routes,
+};
+use crate::core::schedule::ScheduledDraft;
+use crate::core::settings::{SiteSettings, load_settings};
+
+/// Current archive format version
+pub const ARCHIVE_VERSION: u32 = 3;
+
+/// Maximum accepted archive upload size in bytes (512 MiB)
+pub const MAX_ARCHIVE_BYTES: usize = 512 * 1024 * 1024;
+
+/// Maximum total size of the unpacked archive (guards against gzip bombs)
+const MAX_UNPACKED_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const ROUTES_FILE: &str = "routes.json";
+const SETTINGS_FILE: &str = "settings.json";
+const CONTENT_DIR: &str = "content";
+const SCHEDULED_DIR: &str = "scheduled";
+const MEDIA_DIR: &str = "media";
+
+/// Errors that can occur while exporting or importing an archive
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveError {
+    #[error("invalid archive: {0}")]
+    Invalid(String),
+
+    #[error(
+        "archive format version {0} is not supported (this build supports up to {ARCHIVE_VERSION})"
+    )]
+    UnsupportedVersion(u32),
+
+    #[error("unexpected file in archive: {0}")]
+    UnexpectedEntry(String),
+
+    #[error("invalid {path}: {source}")]
+    InvalidJson {
+        path: String,
+        source: serde_json::Error,
+    },
+
+    #[error("route content: {0}")]
+    Content(#[from] ContentError),
+
+    #[error("filesystem error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Archive manifest, stored as `manifest.json`
+///
+/// # Example JSON
+///
+/// ```json
+/// {
+///   "version": 3,
+///   "exported_at": "2025-11-25T05:35:55Z",
+///   "routes": ["homepage", "foo"],
+///   "files": ["content/foo.json", "content/homepage.json", "routes.json",
+///             "scheduled/0b6c4f0e-….json"]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Route names, in routes.json order
+    pub routes: Vec<String>,
+    /// Every other file in the archive, sorted
+    pub files: Vec<String>,
+}
+
+/// What an import did (or would do, for a dry run)
+///
+/// Paths are relative to the data directory, sorted.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub created: Vec<String>,
+    pub overwritten: Vec<String>,
+    pub deleted: Vec<String>,
+    /// Files in the archive identical to the existing file
+    pub unchanged: Vec<String>,
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let groups = [
+            ("create", &self.created),
+            ("overwrite", &self.overwritten),
+            ("delete", &self.deleted),
+        ];
+        let verb = if self.dry_run { "Would" } else { "Did" };
+        for (action, paths) in groups {
+            writeln!(f, "{} {} {} file(s)", verb, action, paths.len())?;
+            for path in paths {
+                writeln!(f, "  {}", path)?;
+            }
+        }
+        write!(f, "{} file(s) unchanged", self.unchanged.len())
+    }
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+/// Export the site in the current data directory as a `.tar.gz` archive
+///
+/// Route content files that don't exist yet are left out of the archive.
+/// Fails with `ArchiveError::Content` if a route lists a content id that
+/// doesn't resolve inside the content directory.
+pub fn export_site(writer: impl Write) -> Result<Manifest, ArchiveError> {
+    export_site_at(writer, Utc::now())
+}
+
+fn export_site_at(writer: impl Write, now: DateTime<Utc>) -> Result<Manifest, ArchiveError> {
+    let root = data_dir();
+    let mut files: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+
+    let locales = load_settings().i18n.locales;
+    let routes = load_routes();
+    for id in routes.iter().flat_map(|route| &route.block_ids) {
+        for locale in std::iter::once(None).chain(locales.iter().map(|l| Some(l.as_str()))) {
+            let archive_path = format!("{}/{}", CONTENT_DIR, content_file_name(id, locale));
+            read_into(&mut files, &archive_path, &content_file_path(id, locale)?)?;
+        }
+    }
+    files.insert(ROUTES_FILE.to_string(), to_json_bytes(&routes)?);
+
+    match fs::read(root.join(SETTINGS_FILE)) {
+        Ok(bytes) => {
+            files.insert(SETTINGS_FILE.to_string(), bytes);
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+
+    // Only draft files the scheduler would read; anything else there is stray
+    let drafts = list_files(&root, SCHEDULED_DIR)?
+        .into_iter()
+        .filter(|path| classify(path) == Some(Entry::Scheduled));
+    for path in list_files(&root, MEDIA_DIR)?.into_iter().chain(drafts) {
+        let bytes = fs::read(root.join(&path))?;
+        files.insert(path, bytes);
+    }
+
+    let manifest = Manifest {
+        version: ARCHIVE_VERSION,
+        exported_at: now,
+        routes: routes.iter().map(|r| r.name.clone()).collect(),
+        files: files.keys().cloned().collect(),
+    };
+
+    let mut tar = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+    append_file(&mut tar, MANIFEST_FILE, &to_json_bytes(&manifest)?, now)?;
+    for (path, bytes) in &files {
+        append_file(&mut tar, path, bytes, now)?;
+    }
+    tar.into_inner()?.finish()?;
+
+    Ok(manifest)
+}
+
+/// Add the file at `path` to `files` as `archive_path`, if it exists
+fn read_into(
+    files: &mut BTreeMap<String, Vec<u8>>,
+    archive_path: &str,
+    path: &Path,
+) -> Result<(), ArchiveError> {
+    match fs::read(path) {
+        Ok(bytes) => {
+            files.insert(archive_path.to_string(), bytes);
+            Ok(())
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn append_file<W: Write>(
+    tar: &mut tar::Builder<W>,
+    path: &str,
+    bytes: &[u8],
+    mtime: DateTime<Utc>,
+) -> io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(bytes.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(mtime.timestamp().max(0) as u64);
+    header.set_cksum();
+    tar.append_data(&mut header, path, bytes)
+}
+
+// ============================================================================
+// Import
+// ============================================================================
+
+/// Import a `.tar.gz` archive into the current data directory
+///
+/// With `dry_run`, validates the archive and reports what would change
+/// without writing anything.
+///
+/// # Errors
+///
+/// - `Invalid` / `UnexpectedEntry` / `InvalidJson` if the archive is
+///   malformed; nothing is written
+/// - `UnsupportedVersion` if the archive is from a newer format version
+/// - `Io` if reading the data directory or writing files fails
+pub fn import_site(archive: impl Read, dry_run: bool) -> Result<ImportReport, ArchiveError> {
+    let root = data_dir();
+    let files = plan_import(read_archive(archive)?, &root)?;
+
+    let mut existing: BTreeSet<String> = BTreeSet::new();
+    for file in [ROUTES_FILE, SETTINGS_FILE] {
+        if root.join(file).is_file() {
+            existing.insert(file.to_string());
+        }
+    }
+    existing.extend(list_files(&root, CONTENT_DIR)?);
+    existing.extend(list_files(&root, SCHEDULED_DIR)?);
+    existing.extend(list_files(&root, MEDIA_DIR)?);
+
+    let mut report = ImportReport {
+        dry_run,
+        ..Default::default()
+    };
+    for (path, bytes) in &files {
+        if !existing.contains(path) {
+            report.created.push(path.clone());
+        } else if fs::read(root.join(path))? == *bytes {
+            report.unchanged.push(path.clone());
+        } else {
+            report.overwritten.push(path.clone());
+        }
+    }
+    report.deleted = existing
+        .into_iter()
+        .filter(|path| !files.contains_key(path))
+        .collect();
+
+    if dry_run {
+        return Ok(report);
+    }
+
+    for path in report.created.iter().chain(&report.overwritten) {
+        let target = root.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(target, &files[path])?;
+    }
+    for path in &report.deleted {
+        fs::remove_file(root.join(path))?;
+        remove_empty_parents(&root, path);
+    }
+
+    Ok(report)
+}
+
+/// Read every entry of a `.tar.gz` archive into memory
+fn read_archive(archive: impl Read) -> Result<BTreeMap<String, Vec<u8>>, ArchiveError> {
+    let decoder = GzDecoder::new(archive).take(MAX_UNPACKED_BYTES);
+    let mut tar = tar::Archive::new(decoder);
+    let mut entries = BTreeMap::new();
+
+    let invalid = |e: io::Error| ArchiveError::Invalid(e.to_string());
+    for entry in tar.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+        let path = entry.path().map_err(invalid)?.into_owned();
+        let path = normalize_entry_path(&path)
+            .ok_or_else(|| ArchiveError::UnexpectedEntry(path.display().to_string()))?;
+        if !entry.header().entry_type().is_file() {
+            return Err(ArchiveError::UnexpectedEntry(path));
+        }
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes).map_err(invalid)?;
+        entries.insert(path, bytes);
+    }
+
+    Ok(entries)
+}
+
+/// Validate archive entries and map them to data directory files
+///
+/// Checks the manifest, the allowed layout, that every JSON file parses, that
+/// each draft is stored under its own id and that route blockIds are content
+/// ids (converting version 1 archive paths).
+fn plan_import(
+    mut entries: BTreeMap<String, Vec<u8>>,
+    root: &Path,
+) -> Result<BTreeMap<String, Vec<u8>>, ArchiveError> {
+    let manifest_bytes = entries
+        .remove(MANIFEST_FILE)
+        .ok_or_else(|| ArchiveError::Invalid(format!("missing {}", MANIFEST_FILE)))?;
+    let manifest: Manifest = parse_json(MANIFEST_FILE, &manifest_bytes)?;
+    if manifest.version > ARCHIVE_VERSION {
+        return Err(ArchiveError::UnsupportedVersion(manifest.version));
+    }
+
+    let listed: BTreeSet<&String> = manifest.files.iter().collect();
+    let present: BTreeSet<&String> = entries.keys().collect();
+    if let Some(missing) = listed.difference(&present).next() {
+        return Err(ArchiveError::Invalid(format!(
+            "{} is listed in the manifest but missing",
+            missing
+        )));
+    }
+    if let Some(extra) = present.difference(&listed).next() {
+        return Err(ArchiveError::UnexpectedEntry(extra.to_string()));
+    }
+
+    for (path, bytes) in &entries {
+        match classify(path) {
+            Some(Entry::Routes | Entry::Media) => {}
+            Some(Entry::Settings) => {
+                let settings: SiteSettings = parse_json(path, bytes)?;
+                settings
+                    .validate()
+                    .map_err(|e| ArchiveError::Invalid(format!("{}: {}", path, e)))?;
+            }
+            Some(Entry::Content) => {
+                // Older content versions are fine; they migrate on load
+                let json = String::from_utf8_lossy(bytes);
+                parse_content(&json)
+                    .map_err(|e| ArchiveError::Invalid(format!("{}: {}", path, e)))?;
+            }
+            Some(Entry::Scheduled) => {
+                let draft: ScheduledDraft = parse_json(path, bytes)?;
+                if Some(draft.id.as_str()) != draft_id(path) {
+                    return Err(ArchiveError::Invalid(format!(
+                        "{}: draft id '{}' doesn't match the file name",
+                        path, draft.id
+                    )));
+                }
+            }
+            None => return Err(ArchiveError::UnexpectedEntry(path.clone())),
+        }
+    }
+
+    let routes_bytes = entries
+        .get(ROUTES_FILE)
+        .ok_or_else(|| ArchiveError::Invalid(format!("missing {}", ROUTES_FILE)))?;
+    let mut routes: Vec<Route> = parse_json(ROUTES_FILE, routes_bytes)?;
+    let content_root = root.join(CONTENT_DIR);
+    for block_id in routes.iter_mut().flat_map(|r| r.block_ids.iter_mut()) {
+        match legacy_content_id(block_id, &content_root) {
+            Some(id) => *block_id = id,
+            None => {
+                return Err(ArchiveError::Invalid(format!(
+                    "route content '{}' is not a content id",
+                    block_id
+                )));
+            }
+        }
+    }
+    entries.insert(ROUTES_FILE.to_string(), to_json_bytes(&routes)?);
+
+    Ok(entries)
+}
+
+/// Kinds of file an archive may contain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Entry {
+    Routes,
+    Settings,
+    Content,
+    Scheduled,
+    Media,
+}
+
+/// Classify an archive path, or `None` if it isn't allowed
+fn classify(path: &str) -> Option<Entry> {
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.as_slice() {
+        [ROUTES_FILE] => Some(Entry::Routes),
+        [SETTINGS_FILE] => Some(Entry::Settings),
+        [CONTENT_DIR, file] if file.ends_with(".json") && is_safe_file_name(file) => {
+            Some(Entry::Content)
+        }
+        [SCHEDULED_DIR, _] if draft_id(path).is_some() => Some(Entry::Scheduled),
+        [MEDIA_DIR, id, file] if Uuid::parse_str(id).is_ok() && is_safe_file_name(file) => {
+            Some(Entry::Media)
+        }
+        _ => None,
+    }
+}
+
+/// Id of a `scheduled/{uuid}.json` path, as `schedule::draft_path` names it
+fn draft_id(path: &str) -> Option<&str> {
+    let id = path
+        .strip_prefix(SCHEDULED_DIR)?
+        .strip_prefix('/')?
+        .strip_suffix(".json")?;
+    Uuid::parse_str(id)
+        .is_ok_and(|uuid| uuid.to_string() == id)
+        .then_some(id)
+}
+
+/// Relative path with only normal components, joined with `/`
+fn normalize_entry_path(path: &Path) -> Option<String> {
+    let mut parts = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => parts.push(part.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// File name made of ASCII letters, digits, `-`, `_` and `.`, not hidden
+fn is_safe_file_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+fn parse_json<T: serde::de::DeserializeOwned>(path: &str, bytes: &[u8]) -> Result<T, ArchiveError> {
+    serde_json::from_slice(bytes).map_err(|source| ArchiveError::InvalidJson {
+        path: path.to_string(),
+        source,
+    })
+}
+
+fn to_json_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, ArchiveError> {
+    serde_json::to_vec_pretty(value).map_err(|source| ArchiveError::InvalidJson {
+        path: "archive".to_string(),
+        source,
+    })
+}
+
+/// Files under `root/dir`, recursively, as `/`-joined paths relative to `root`
+fn list_files(root: &Path, dir: &str) -> io::Result<Vec<String>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_string()];
+    while let Some(relative) = pending.pop() {
+        let entries = match fs::read_dir(root.join(&relative)) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let path = format!("{}/{}", relative, entry.file_name().to_string_lossy());
+            if entry.file_type()?.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Remove now-empty directories between `root/path` and `root`
+fn remove_empty_parents(root: &Path, path: &str) {
+    let mut dir = Path::new(path).parent();
+    while let Some(relative) = dir.filter(|d| !d.as_os_str().is_empty()) {
+        if fs::remove_dir(root.join(relative)).is_err() {
+            break;
+        }
+        dir = relative.parent();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::persistence::{ContentFile, with_data_dir_sync};
+    use crate::core::schedule::{schedule_draft, scheduled_drafts};
+    use tempfile::TempDir;
+
+    const MEDIA_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+
+    /// A data directory with two routes, settings and one media item
+    fn site() -> TempDir {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("content")).unwrap();
+        fs::create_dir_all(root.join("media").join(MEDIA_ID)).unwrap();
+
+        let routes = serde_json::json!([
+            { "path": "/", "name": "homepage", "blockIds": ["homepage"] },
+            { "path": "/foo", "name": "foo", "blockIds": ["foo"] },
+        ]);
+        fs::write(root.join("routes.json"), routes.to_string()).unwrap();
+        for name in ["homepage", "foo"] {
+            let content = serde_json::json!({ "blocks": [{
+                "id": name, "type": "Hero",
+                "props": { "headline": name, "subheadline": "" }
+            }]});
+            fs::write(
+                root.join(format!("content/{name}.json")),
+                content.to_string(),
+            )
+            .unwrap();
+        }
+        fs::write(
+            root.join("settings.json"),
+            serde_json::to_string(&SiteSettings::default()).unwrap(),
+        )
+        .unwrap();
+        fs::write(root.join("media").join(MEDIA_ID).join("320.webp"), b"webp").unwrap();
+        dir
+    }
+
+    fn export(dir: &Path) -> (Manifest, Vec<u8>) {
+        let mut bytes = Vec::new();
+        let manifest = with_data_dir_sync(dir.to_path_buf(), || {
+            export_site_at(&mut bytes, DateTime::UNIX_EPOCH).unwrap()
+        });
+        (manifest, bytes)
+    }
+
+    fn import(dir: &Path, bytes: &[u8], dry_run: bool) -> Result<ImportReport, ArchiveError> {
+        with_data_dir_sync(dir.to_path_buf(), || import_site(bytes, dry_run))
+    }
+
+    /// Build a raw archive from (path, contents) pairs
+    ///
+    /// Writes names straight into the header so tests can include paths
+    /// that `tar::Builder` itself refuses (e.g. `../`).
+    fn raw_archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (path, bytes) in files {
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+            header.set_size(bytes.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append(&header, *bytes).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_export_manifest_lists_every_file() {
+        let site = site();
+        let (manifest, _) = export(site.path());
+
+        assert_eq!(manifest.version, ARCHIVE_VERSION);
+        assert_eq!(manifest.routes, vec!["homepage", "foo"]);
+        assert_eq!(
+            manifest.files,
+            vec![
+                "content/foo.json".to_string(),
+                "content/homepage.json".to_string(),
+                format!("media/{MEDIA_ID}/320.webp"),
+                "routes.json".to_string(),
+                "settings.json".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_into_empty_dir_recreates_site() {
+        let site = site();
+        let (_, archive) = export(site.path());
+        let target = TempDir::new().unwrap();
+
+        let dry = import(target.path(), &archive, true).unwrap();
+        assert_eq!(dry.created.len(), 5);
+        assert!(
+            !target.path().join("routes.json").exists(),
+            "dry run writes nothing"
+        );
+
+        let report = import(target.path(), &archive, false).unwrap();
+        assert_eq!(report.created, dry.created);
+
+        let routes: Vec<Route> =
+            serde_json::from_slice(&fs::read(target.path().join("routes.json")).unwrap()).unwrap();
+        let homepage = target.path().join("content/homepage.json");
+        assert_eq!(routes[0].block_ids, vec!["homepage"]);
+        assert_eq!(
+            fs::read(&homepage).unwrap(),
+            fs::read(site.path().join("content/homepage.json")).unwrap()
+        );
+        assert_eq!(
+            fs::read(target.path().join("media").join(MEDIA_ID).join("320.webp")).unwrap(),
+            b"webp"
+        );
+    }
+
+    #[test]
+    fn test_shared_content_file_is_exported_once() {
+        let site = site();
+        let root = site.path();
+        fs::write(root.join("content/header.json"), r#"{"blocks":[]}"#).unwrap();
+        let routes = serde_json::json!([
+            { "path": "/", "name": "homepage", "blockIds": ["header", "homepage"] },
+            { "path": "/foo", "name": "foo", "blockIds": ["header", "foo"] },
+        ]);
+        fs::write(root.join("routes.json"), routes.to_string()).unwrap();
+
+        let (manifest, archive) = export(root);
+        let content: Vec<_> = manifest
+            .files
+            .iter()
+            .filter(|path| path.starts_with("content/"))
+            .collect();
+        assert_eq!(
+            content,
+            vec![
+                "content/foo.json",
+                "content/header.json",
+                "content/homepage.json"
+            ]
+        );
+
+        let target = TempDir::new().unwrap();
+        import(target.path(), &archive, false).unwrap();
+        let routes: Vec<Route> =
+            serde_json::from_slice(&fs::read(target.path().join("routes.json")).unwrap()).unwrap();
+        assert_eq!(routes[0].block_ids, vec!["header", "homepage"]);
+        assert_eq!(routes[1].block_ids, vec!["header", "foo"]);
+    }
+
+    #[test]
+    fn test_locale_variants_travel_with_their_file() {
+        let site = site();
+        let root = site.path();
+        let mut settings = SiteSettings::default();
+        settings.i18n.locales = vec!["de".to_string()];
+        fs::write(
+            root.join("settings.json"),
+            serde_json::to_string(&settings).unwrap(),
+        )
+        .unwrap();
+        fs::write(root.join("content/homepage.de.json"), r#"{"blocks":[]}"#).unwrap();
+
+        let (manifest, archive) = export(root);
+        assert!(
+            manifest
+                .files
+                .contains(&"content/homepage.de.json".to_string())
+        );
+        assert!(!manifest.files.contains(&"content/foo.de.json".to_string()));
+
+        let target = TempDir::new().unwrap();
+        import(target.path(), &archive, false).unwrap();
+        assert_eq!(
+            fs::read(target.path().join("content/homepage.de.json")).unwrap(),
+            br#"{"blocks":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_scheduled_drafts_round_trip() {
+        let site = site();
+        let root = site.path();
+        let draft = with_data_dir_sync(root.to_path_buf(), || {
+            let files = vec![ContentFile {
+                source: "foo".to_string(),
+                blocks: vec![],
+            }];
+            schedule_draft("foo", None, DateTime::UNIX_EPOCH, files).unwrap()
+        });
+        fs::write(root.join("scheduled/notes.txt"), b"stray").unwrap();
+
+        let (manifest, archive) = export(root);
+        let draft_file = format!("scheduled/{}.json", draft.id);
+        assert!(manifest.files.contains(&draft_file));
+        assert!(!manifest.files.contains(&"scheduled/notes.txt".to_string()));
+
+        // Drafts queued in the target but not in the archive are cancelled
+        let target = TempDir::new().unwrap();
+        let stale = "scheduled/650e8400-e29b-41d4-a716-446655440000.json";
+        fs::create_dir_all(target.path().join("scheduled")).unwrap();
+        fs::write(target.path().join(stale), b"{}").unwrap();
+
+        let report = import(target.path(), &archive, false).unwrap();
+        assert!(report.created.contains(&draft_file));
+        assert_eq!(report.deleted, vec![stale]);
+        let drafts = with_data_dir_sync(target.path().to_path_buf(), scheduled_drafts);
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].id, draft.id);
+        assert_eq!(drafts[0].route, "foo");
+    }
+
+    #[test]
+    fn test_import_reports_overwrites_and_deletions() {
+        let source = site();
+        let (_, archive) = export(source.path());
+
+        let target = site();
+        let root = target.path();
+        // Re-import so routes.json blockIds match what an import writes
+        import(root, &archive, false).unwrap();
+        fs::write(root.join("content/foo.json"), r#"{"blocks":[]}"#).unwrap();
+        fs::write(root.join("content/old.json"), r#"{"blocks":[]}"#).unwrap();
+        let old_media = "650e8400-e29b-41d4-a716-446655440000";
+        fs::create_dir_all(root.join("media").join(old_media)).unwrap();
+        fs::write(root.join("media").join(old_media).join("320.webp"), b"old").unwrap();
+
+        let report = import(root, &archive, false).unwrap();
+        assert!(report.created.is_empty());
+        assert_eq!(report.overwritten, vec!["content/foo.json"]);
+        assert_eq!(
+            report.deleted,
+            vec![
+                "content/old.json".to_string(),
+                format!("media/{old_media}/320.webp")
+            ]
+        );
+        assert_eq!(report.unchanged.len(), 4);
+        assert!(!root.join("media").join(old_media).exists());
+    }
+
+    #[test]
+    fn test_import_converts_version_1_paths() {
+        let manifest = serde_json::json!({
+            "version": 1,
+            "exported_at": "2025-01-01T00:00:00Z",
+            "routes": ["homepage"],
+            "files": ["content/shared.json", "content/homepage.json", "routes.json"]
+        });
+        let routes = serde_json::json!([{ "path": "/", "name": "homepage",
+            "blockIds": ["content/homepage.json", "content/shared.json"] }]);
+        let archive = raw_archive(&[
+            ("manifest.json", manifest.to_string().as_bytes()),
+            ("content/homepage.json", br#"{"blocks":[]}"#),
+            ("content/shared.json", br#"{"blocks":[]}"#),
+            ("routes.json", routes.to_string().as_bytes()),
+        ]);
+        let target = TempDir::new().unwrap();
+
+        import(target.path(), &archive, false).unwrap();
+        let routes: Vec<Route> =
+            serde_json::from_slice(&fs::read(target.path().join("routes.json")).unwrap()).unwrap();
+        assert_eq!(routes[0].block_ids, vec!["homepage", "shared"]);
+        assert!(target.path().join("content/shared.json").exists());
+    }
+
+    #[test]
+    fn test_import_rejects_newer_version() {
+        let manifest = serde_json::json!({
+            "version": ARCHIVE_VERSION + 1,
+            "exported_at": "2025-01-01T00:00:00Z",
+            "routes": [],
+            "files": []
+        });
+        let archive = raw_archive(&[("manifest.json", manifest.to_string().as_bytes())]);
+        let target = TempDir::new().unwrap();
+
+        assert!(matches!(
+            import(target.path(), &archive, true),
+            Err(ArchiveError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_rejects_unexpected_and_invalid_files() {
+        let manifest = |files: &[&str]| {
+            serde_json::json!({
+                "version": ARCHIVE_VERSION,
+                "exported_at": "2025-01-01T00:00:00Z",
+                "routes": [],
+                "files": files,
+            })
+            .to_string()
+        };
+        let target = TempDir::new().unwrap();
+
+        let escaping = raw_archive(&[
+            ("manifest.json", manifest(&["routes.json"]).as_bytes()),
+            ("routes.json", b"[]"),
+            ("../evil.json", b"{}"),
+        ]);
+        assert!(matches!(
+            import(target.path(), &escaping, true),
+            Err(ArchiveError::UnexpectedEntry(_))
+        ));
+
+        let bad_content = raw_archive(&[
+            (
+                "manifest.json",
+                manifest(&["content/a.json", "routes.json"]).as_bytes(),
+            ),
+            ("content/a.json", b"not json"),
+            ("routes.json", b"[]"),
+        ]);
+        assert!(matches!(
+            import(target.path(), &bad_content, true),
+            Err(ArchiveError::Invalid(_))
+        ));
+
+        let draft = |id: &str| {
+            serde_json::json!({ "id": id, "route": "x",
+                "publish_at": "2025-01-01T00:00:00Z", "files": [] })
+            .to_string()
+        };
+        let file = format!("scheduled/{MEDIA_ID}.json");
+        let misnamed_draft = raw_archive(&[
+            (
+                "manifest.json",
+                manifest(&[&file, "routes.json"]).as_bytes(),
+            ),
+            (
+                &file,
+                draft("650e8400-e29b-41d4-a716-446655440000").as_bytes(),
+            ),
+            ("routes.json", b"[]"),
+        ]);
+        assert!(matches!(
+            import(target.path(), &misnamed_draft, true),
+            Err(ArchiveError::Invalid(_))
+        ));
+        let bad_draft = raw_archive(&[
+            (
+                "manifest.json",
+                manifest(&[&file, "routes.json"]).as_bytes(),
+            ),
+            (&file, b"{}"),
+            ("routes.json", b"[]"),
+        ]);
+        assert!(matches!(
+            import(target.path(), &bad_draft, true),
+            Err(ArchiveError::InvalidJson { .. })
+        ));
+        let not_a_draft = raw_archive(&[
+            (
+                "manifest.json",
+                manifest(&["scheduled/a.json", "routes.json"]).as_bytes(),
+            ),
+            ("scheduled/a.json", draft("a").as_bytes()),
+            ("routes.json", b"[]"),
+        ]);
+        assert!(matches!(
+            import(target.path(), &not_a_draft, true),
+            Err(ArchiveError::UnexpectedEntry(_))
+        ));
+
+        let outside_content = raw_archive(&[
+            ("manifest.json", manifest(&["routes.json"]).as_bytes()),
+            (
+                "routes.json",
+                br#"[{"path":"/","name":"x","blockIds":["/etc/passwd"]}]"#,
+            ),
+        ]);
+        assert!(matches!(
+            import(target.path(), &outside_content, true),
+            Err(ArchiveError::Invalid(_))
+        ));
+
+        assert!(matches!(
+            import(target.path(), b"not an archive", true),
+            Err(ArchiveError::Invalid(_))
+        ));
+        assert!(fs::read_dir(target.path()).unwrap().next().is_none());
+    }
+}
@@ -0,0 +1,397 @@
+/// Append-only audit log of admin actions
+///
+/// Every handler that changes the site records who did what, to which route
+/// and file, and what the file looked like before and after, so a broken
+/// page can be traced back to the save that broke it.
+///
+/// # Storage
+///
+/// Entries are appended as JSON Lines to `data/audit.jsonl`, one object per
+/// line, oldest first. The file is only ever appended to; nothing in the
+/// app rewrites or truncates it.
+///
+/// ```json
+/// {"timestamp":"2026-03-01T09:00:00Z","actor":"anonymous","action":"content_save","route":"homepage","target":"content/homepage.json","before":"sha256:9f86…","after":"sha256:60303…","request_id":"1b4e…"}
+/// ```
+///
+/// # Hashes
+///
+/// `before` and `after` are SHA-256 digests of the affected file, `null`
+/// when the file didn't exist (before a create, after a delete). Comparing
+/// an entry's `before` with the previous entry's `after` for the same target
+/// shows whether the file was changed outside the admin in between.
+///
+/// # Architecture
+///
+/// Following rust-core-patterns:
+/// - **No framework types**: Actor and request id are plain strings here;
+///   `pages::admin::audit::AuditContext` extracts them from the request
+/// - **Best effort**: A failed append is logged but doesn't fail the action
+///   it records, which has already happened
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use strum::{EnumIter, EnumString, IntoStaticStr};
+
+use crate::core::persistence::data_dir;
+
+/// Audit log file in the data directory
+const AUDIT_FILE: &str = "audit.jsonl";
+
+/// Actor recorded when the request isn't attributed to anyone
+pub const ANONYMOUS: &str = "anonymous";
+
+/// Actor recorded for changes made by the scheduled publishing task
+pub const SCHEDULER: &str = "scheduler";
+
+/// Serializes appends from concurrent requests
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
+/// What an admin action did
+///
+/// Variants are listed in the order shown in the filter; the serialized
+/// name (`content_save`) is also the filter value.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter, EnumString, IntoStaticStr,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AuditAction {
+    /// Route content file written from the editor or API
+    ContentSave,
+    /// Save queued as a scheduled draft
+    ContentSchedule,
+    /// Scheduled draft written by the scheduler
+    DraftPublish,
+    /// Scheduled draft cancelled
+    DraftCancel,
+    MediaUpload,
+    MediaUpdate,
+    MediaDelete,
+    SettingsUpdate,
+    /// Whole-site archive imported
+    SiteImport,
+    /// Admin user created, or its role, routes or password changed
+    UserSave,
+    UserDelete,
+    /// routes.json replaced through the routes API
+    RoutesUpdate,
+    /// API token created; the target is the token's id
+    TokenCreate,
+    TokenRevoke,
+    /// Two-factor sign-in turned on; the target is the user
+    TwoFactorEnable,
+    /// Two-factor sign-in turned off by the user, or reset by an admin
+    TwoFactorDisable,
+    RecoveryCodesReset,
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub actor: String,
+    pub action: AuditAction,
+    /// Route whose content changed, if any
+    #[serde(default)]
+    pub route: Option<String>,
+    /// File, media id or draft the action applied to
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Hash of the target before the action (`None` if it didn't exist)
+    #[serde(default)]
+    pub before: Option<String>,
+    /// Hash of the target after the action (`None` if it no longer exists)
+    #[serde(default)]
+    pub after: Option<String>,
+    /// `X-Request-Id` of the request that made the change
+    #[serde(default)]
+    pub request_id: Option<String>,
+}
+
+impl AuditEntry {
+    /// An entry with no route, target or hashes yet
+    pub fn new(
+        timestamp: DateTime<Utc>,
+        actor: impl Into<String>,
+        action: AuditAction,
+        request_id: Option<String>,
+    ) -> Self {
+        Self {
+            timestamp,
+            actor: actor.into(),
+            action,
+            route: None,
+            target: None,
+            before: None,
+            after: None,
+            request_id,
+        }
+    }
+
+    pub fn route(mut self, route: impl Into<String>) -> Self {
+        self.route = Some(route.into());
+        self
+    }
+
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    pub fn hashes(mut self, before: Option<String>, after: Option<String>) -> Self {
+        self.before = before;
+        self.after = after;
+        self
+    }
+}
+
+/// Which entries to show or export; unset fields match everything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    pub action: Option<AuditAction>,
+    pub route: Option<String>,
+    /// First day included (UTC)
+    pub since: Option<NaiveDate>,
+    /// Last day included (UTC)
+    pub until: Option<NaiveDate>,
+}
+
+impl AuditFilter {
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        let day = entry.timestamp.date_naive();
+        self.actor
+            .as_ref()
+            .is_none_or(|actor| *actor == entry.actor)
+            && self.action.is_none_or(|action| action == entry.action)
+            && self
+                .route
+                .as_ref()
+                .is_none_or(|route| entry.route.as_ref() == Some(route))
+            && self.since.is_none_or(|since| since <= day)
+            && self.until.is_none_or(|until| day <= until)
+    }
+}
+
+fn audit_path() -> PathBuf {
+    data_dir().join(AUDIT_FILE)
+}
+
+/// SHA-256 of a file's contents as `sha256:{hex}`, or `None` if it's missing
+pub fn hash_file(path: &Path) -> Option<String> {
+    fs::read(path).ok().map(|bytes| hash_bytes(&bytes))
+}
+
+/// SHA-256 of `bytes` as `sha256:{hex}`
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256:{}", hex)
+}
+
+/// Append an entry to the audit log
+///
+/// Errors are logged to stderr rather than returned; see the module docs.
+pub fn record(entry: &AuditEntry) {
+    if let Err(e) = append(&audit_path(), entry) {
+        eprintln!("Failed to write audit entry {:?}: {}", entry, e);
+    }
+}
+
+fn append(path: &Path, entry: &AuditEntry) -> io::Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    let _guard = APPEND_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())
+}
+
+/// Run `change`, then record `entry` once per path with its before/after hash
+///
+/// Each path is recorded as the entry's `target`, relative to the data
+/// directory (see `data_target`). Nothing is recorded if `change` fails.
+pub fn record_file_changes<T, E>(
+    entry: &AuditEntry,
+    paths: &[PathBuf],
+    change: impl FnOnce() -> Result<T, E>,
+) -> Result<T, E> {
+    let before: Vec<Option<String>> = paths.iter().map(|path| hash_file(path)).collect();
+    let result = change()?;
+    for (path, before) in paths.iter().zip(before) {
+        record(
+            &entry
+                .clone()
+                .target(data_target(path))
+                .hashes(before, hash_file(path)),
+        );
+    }
+    Ok(result)
+}
+
+/// `path` relative to the data directory, e.g. `content/homepage.json`
+///
+/// The log never shows where the data directory is on the server: a path
+/// outside it is recorded by its file name only.
+fn data_target(path: &Path) -> String {
+    let relative = path
+        .strip_prefix(data_dir())
+        .unwrap_or_else(|_| Path::new(path.file_name().unwrap_or_default()));
+    relative.to_string_lossy().into_owned()
+}
+
+/// Entries matching `filter`, oldest first
+///
+/// Lines that can't be parsed are logged and skipped.
+pub fn read_entries(filter: &AuditFilter) -> Vec<AuditEntry> {
+    let path = audit_path();
+    let file = match fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return vec![],
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", path.display(), e);
+            return vec![];
+        }
+    };
+
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.ok()?;
+            if line.trim().is_empty() {
+                return None;
+            }
+            serde_json::from_str::<AuditEntry>(&line)
+                .inspect_err(|e| eprintln!("Skipping {} line {}: {}", AUDIT_FILE, index + 1, e))
+                .ok()
+        })
+        .filter(|entry| filter.matches(entry))
+        .collect()
+}
+
+/// Render entries as JSON Lines
+pub fn to_jsonl(entries: &[AuditEntry]) -> String {
+    entries
+        .iter()
+        .filter_map(|entry| serde_json::to_string(entry).ok())
+        .map(|line| line + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::persistence::with_data_dir_sync;
+    use chrono::TimeZone;
+    use strum::IntoEnumIterator;
+    use tempfile::TempDir;
+
+    fn entry(day: u32, actor: &str, action: AuditAction) -> AuditEntry {
+        let at = Utc.with_ymd_and_hms(2026, 3, day, 12, 0, 0).unwrap();
+        AuditEntry::new(at, actor, action, None).route("homepage")
+    }
+
+    #[test]
+    fn test_action_ids_match_serde() {
+        for action in AuditAction::iter() {
+            let id: &str = action.into();
+            let json = serde_json::to_string(&action).unwrap();
+            assert_eq!(json, format!("\"{id}\""));
+            assert_eq!(id.parse::<AuditAction>(), Ok(action));
+        }
+    }
+
+    #[test]
+    fn test_entries_are_appended_and_filtered() {
+        let dir = TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            record(&entry(1, "ana", AuditAction::ContentSave));
+            record(&entry(2, "ben", AuditAction::MediaUpload));
+            record(&entry(3, "ana", AuditAction::SettingsUpdate));
+
+            assert_eq!(read_entries(&AuditFilter::default()).len(), 3);
+
+            let by_actor = AuditFilter {
+                actor: Some("ana".to_string()),
+                ..Default::default()
+            };
+            let actions: Vec<_> = read_entries(&by_actor).iter().map(|e| e.action).collect();
+            assert_eq!(
+                actions,
+                vec![AuditAction::ContentSave, AuditAction::SettingsUpdate]
+            );
+
+            let by_days = AuditFilter {
+                since: NaiveDate::from_ymd_opt(2026, 3, 2),
+                until: NaiveDate::from_ymd_opt(2026, 3, 2),
+                ..Default::default()
+            };
+            assert_eq!(read_entries(&by_days)[0].actor, "ben");
+
+            let jsonl = to_jsonl(&read_entries(&AuditFilter::default()));
+            assert_eq!(jsonl.lines().count(), 3);
+            let raw = fs::read_to_string(dir.path().join(AUDIT_FILE)).unwrap();
+            assert_eq!(jsonl, raw);
+        });
+    }
+
+    #[test]
+    fn test_record_file_changes_hashes_before_and_after() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("content.json");
+        fs::write(&file, "old").unwrap();
+
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let template = entry(1, "ana", AuditAction::ContentSave);
+            let paths = [file.clone(), dir.path().join("new.json")];
+            record_file_changes(&template, &paths, || {
+                fs::write(&paths[0], "new")?;
+                fs::write(&paths[1], "created")
+            })
+            .unwrap();
+            let failed: io::Result<()> =
+                record_file_changes(&template, &paths, || Err(io::Error::other("disk full")));
+            assert!(failed.is_err());
+
+            let entries = read_entries(&AuditFilter::default());
+            assert_eq!(entries.len(), 2, "failed changes aren't recorded");
+            assert_eq!(entries[0].before, Some(hash_bytes(b"old")));
+            assert_eq!(entries[0].after, Some(hash_bytes(b"new")));
+            assert_eq!(entries[1].before, None);
+            assert_eq!(entries[1].after, Some(hash_bytes(b"created")));
+            assert_eq!(entries[0].target.as_deref(), Some("content.json"));
+            assert_eq!(entries[1].target.as_deref(), Some("new.json"));
+        });
+    }
+
+    #[test]
+    fn test_targets_are_relative_to_the_data_dir() {
+        let dir = TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let nested = dir.path().join("content").join("homepage.json");
+            assert_eq!(data_target(&nested), "content/homepage.json");
+            assert_eq!(
+                data_target(Path::new("/srv/other/secret.json")),
+                "secret.json"
+            );
+        });
+    }
+
+    #[test]
+    fn test_hash_format() {
+        assert_eq!(
+            hash_bytes(b"abc"),
+            "sha256:ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -28,7 +28,15 @@
 /// ```
 ///
 /// The `BlockWithId` wrapper preserves the original Block enum's serde structure
-/// while adding a unique identifier for each block instance.
+/// while adding a unique identifier for each block instance and an optional
+/// `options` envelope (see `core::options`) for presentation settings.
+///
+/// # Lenient Loading
+///
+/// Stored block lists deserialize entry by entry into `ContentBlock`: a
+/// malformed block or one of an unknown type becomes `ContentBlock::Invalid`
+/// holding its raw JSON, so the rest of the page still renders and saving
+/// the list again writes the broken block back unchanged.
 ///
 /// # Schema Imports
 ///
@@ -36,15 +44,24 @@
 /// - `ButtonProps`: features/button/schema.rs
 /// - `HeaderProps`: features/header/schema.rs
 /// - `HeroProps`: features/hero/schema.rs
+/// - `ImageProps`: features/image/schema.rs
 ///
 /// This enables each feature to own its data shape while allowing core
 /// to orchestrate them into the Block enum.
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use strum::{IntoStaticStr, VariantNames};
+use uuid::Uuid;
+
+use crate::core::options::BlockOptions;
+use crate::core::schedule::Schedule;
 
 // Import schemas from feature modules
 // These are pub use to allow re-exporting from core/mod.rs
 pub use crate::features::header::HeaderProps;
 pub use crate::features::hero::HeroProps;
+pub use crate::features::image::ImageProps;
 
 // ============================================================================
 // Block Enum (Type-Safe Component Variants)
@@ -52,11 +69,22 @@ pub use crate::features::hero::HeroProps;
 
 /// Each variant represents a component with its unique data shape
 /// Uses serde's "type" tagging for JSON serialization
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// `Block::VARIANTS` lists every type name (the serialized `"type"` tag);
+/// the story registry test uses it to require a story for each block.
+#[derive(Debug, Clone, Serialize, Deserialize, VariantNames, IntoStaticStr)]
 #[serde(tag = "type", content = "props")]
 pub enum Block {
     Header(HeaderProps),
     Hero(HeroProps),
+    Image(ImageProps),
+}
+
+impl Block {
+    /// The serialized `"type"` tag of this block (e.g. `"Hero"`)
+    pub fn type_name(&self) -> &'static str {
+        self.into()
+    }
 }
 
 // ============================================================================
@@ -84,6 +112,9 @@ pub enum Block {
 /// The `#[serde(flatten)]` attribute on `block` merges the Block's fields
 /// (type, props) into the same level as the id field, creating the desired
 /// JSON structure.
+///
+/// `options` and `schedule` are omitted from the JSON when every field has its
+/// default value.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockWithId {
     /// Unique identifier for this block instance
@@ -93,6 +124,146 @@ pub struct BlockWithId {
     /// The block content and type
     #[serde(flatten)]
     pub block: Block,
+
+    /// Presentation options shared by every block type
+    #[serde(default, skip_serializing_if = "BlockOptions::is_default")]
+    pub options: BlockOptions,
+
+    /// Publish window; outside it the block isn't rendered publicly
+    #[serde(default, skip_serializing_if = "Schedule::is_default")]
+    pub schedule: Schedule,
+}
+
+// ============================================================================
+// Content Blocks (Lenient Loading)
+// ============================================================================
+
+/// A stored block that failed to deserialize
+///
+/// Kept as the raw JSON so that content written by a newer build (an unknown
+/// block type) or edited by hand survives a load/save round trip instead of
+/// blanking the page or being dropped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidBlock {
+    /// The block exactly as stored
+    pub raw: Value,
+    /// Why it couldn't be deserialized, including the offending field path
+    pub error: String,
+}
+
+impl InvalidBlock {
+    /// The stored `"id"`, if there is a string one
+    pub fn id(&self) -> Option<&str> {
+        self.raw.get("id").and_then(Value::as_str)
+    }
+
+    /// The stored `"type"` tag, if there is a string one
+    pub fn type_name(&self) -> Option<&str> {
+        self.raw.get("type").and_then(Value::as_str)
+    }
+}
+
+/// One entry of a stored block list: a valid block or one kept raw
+///
+/// Deserialization never fails for an individual entry; an entry that isn't
+/// a valid `BlockWithId` becomes `Invalid`. Serialization writes `Valid`
+/// blocks normally and `Invalid` blocks back out unchanged.
+#[derive(Debug, Clone)]
+pub enum ContentBlock {
+    Valid(BlockWithId),
+    Invalid(InvalidBlock),
+}
+
+impl ContentBlock {
+    /// The valid block, if this entry parsed
+    pub fn as_valid(&self) -> Option<&BlockWithId> {
+        match self {
+            ContentBlock::Valid(block) => Some(block),
+            ContentBlock::Invalid(_) => None,
+        }
+    }
+
+    /// The block ID (`None` for an invalid block without a string id)
+    pub fn id(&self) -> Option<&str> {
+        match self {
+            ContentBlock::Valid(block) => Some(&block.id),
+            ContentBlock::Invalid(invalid) => invalid.id(),
+        }
+    }
+}
+
+impl From<BlockWithId> for ContentBlock {
+    fn from(block: BlockWithId) -> Self {
+        ContentBlock::Valid(block)
+    }
+}
+
+impl Serialize for ContentBlock {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ContentBlock::Valid(block) => block.serialize(serializer),
+            ContentBlock::Invalid(invalid) => invalid.raw.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ContentBlock {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Value::deserialize(deserializer)?;
+        Ok(match serde_path_to_error::deserialize(&raw) {
+            Ok(block) => ContentBlock::Valid(block),
+            Err(e) => {
+                // Errors inside flattened props lose their path, so name the
+                // block type the props were checked against
+                let error = match raw.get("type").and_then(Value::as_str) {
+                    Some(name) if Block::VARIANTS.contains(&name) => {
+                        format!("{} block: {}", name, e)
+                    }
+                    _ => e.to_string(),
+                };
+                ContentBlock::Invalid(InvalidBlock { raw, error })
+            }
+        })
+    }
+}
+
+/// The valid blocks of a block list, in order
+pub fn valid_blocks(blocks: &[ContentBlock]) -> Vec<BlockWithId> {
+    blocks
+        .iter()
+        .filter_map(ContentBlock::as_valid)
+        .cloned()
+        .collect()
+}
+
+/// Give every block a unique, non-empty ID
+///
+/// Blank IDs (new blocks from the editor) and repeats of an ID already used
+/// earlier in the list (blocks duplicated by copy-paste, or the same ID in
+/// two of a route's files) get a fresh UUID v4. The first occurrence of each
+/// ID and the block order are preserved.
+///
+/// Invalid blocks are never modified, but their IDs count as used.
+pub fn assign_block_ids(blocks: Vec<ContentBlock>) -> Vec<ContentBlock> {
+    let mut seen = HashSet::new();
+    blocks
+        .into_iter()
+        .map(|block| match block {
+            ContentBlock::Valid(mut block) => {
+                if block.id.trim().is_empty() || !seen.insert(block.id.clone()) {
+                    block.id = Uuid::new_v4().to_string();
+                    seen.insert(block.id.clone());
+                }
+                ContentBlock::Valid(block)
+            }
+            ContentBlock::Invalid(invalid) => {
+                if let Some(id) = invalid.id() {
+                    seen.insert(id.to_string());
+                }
+                ContentBlock::Invalid(invalid)
+            }
+        })
+        .collect()
 }
 
 // ============================================================================
@@ -111,6 +282,8 @@ pub struct BlockWithId {
 /// - **Type safety**: Exhaustive match ensures all Block variants are handled
 /// - **Feature dispatch**: Calls feature-specific render functions
 /// - **Centralized**: Single source of truth for block rendering
+/// - **Options**: Passes the block's `BlockOptions` to the template and skips
+///   blocks whose visibility is `Hidden`
 ///
 /// # Adding New Block Types
 ///
@@ -137,13 +310,256 @@ pub struct BlockWithId {
 ///             aria_label: "Navigate to start".to_string(),
 ///         },
 ///     }),
+///     options: BlockOptions::default(),
 /// };
 ///
 /// let markup = render_block(&block_with_id);
 /// ```
 pub fn render_block(block_with_id: &BlockWithId) -> maud::Markup {
+    let options = &block_with_id.options;
+    if !options.is_rendered() {
+        return maud::html! {};
+    }
+
     match &block_with_id.block {
-        Block::Header(props) => crate::features::header::render_header(props),
-        Block::Hero(props) => crate::features::hero::render_hero(props),
+        Block::Header(props) => crate::features::header::render_header(props, options),
+        Block::Hero(props) => crate::features::hero::render_hero(props, options),
+        Block::Image(props) => crate::features::image::render_image(props, options),
+    }
+}
+
+/// Render a stored block, with a hidden placeholder for an invalid one
+///
+/// The placeholder keeps the block's position visible in the page source
+/// for debugging without showing visitors anything; the parse error itself
+/// is only shown in the admin editor.
+pub fn render_content_block(block: &ContentBlock) -> maud::Markup {
+    match block {
+        ContentBlock::Valid(block) => render_block(block),
+        ContentBlock::Invalid(invalid) => maud::html! {
+            div hidden data-invalid-block=(invalid.id().unwrap_or_default()) {}
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use serde_json::Value;
+
+    use super::*;
+    use crate::core::arbitrary::INJECTION;
+    use crate::core::render::Render;
+    use crate::features::checkbox::CheckboxProps;
+    use crate::features::image::ImageProps;
+    use crate::features::image::schema::STORY_MEDIA_ID;
+    use crate::features::input::InputProps;
+    use crate::features::story::ComponentStory;
+
+    /// Remove `<!-- ... -->` comments, which may quote user text verbatim
+    ///
+    /// A comment that user text manages to close early leaves the rest of
+    /// the text outside the comment, where the escaping check catches it.
+    fn strip_comments(html: &str) -> String {
+        let mut out = String::new();
+        let mut rest = html;
+        while let Some(start) = rest.find("<!--") {
+            out.push_str(&rest[..start]);
+            rest = rest[start..]
+                .find("-->")
+                .map_or("", |end| &rest[start + end + 3..]);
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn assert_escaped(html: &str) {
+        let html = strip_comments(html);
+        assert!(
+            !html.contains("<x-injected") && !html.contains("\"'>"),
+            "unescaped user text in {html}"
+        );
+    }
+
+    #[test]
+    fn test_assign_block_ids_replaces_blank_ids() {
+        let block = |id: &str| BlockWithId {
+            id: id.to_string(),
+            block: Block::Hero(crate::features::hero::HeroProps {
+                headline: "Hi".to_string(),
+                subheadline: String::new(),
+            }),
+            options: BlockOptions::default(),
+            schedule: Default::default(),
+        };
+
+        let invalid = ContentBlock::Invalid(InvalidBlock {
+            raw: serde_json::json!({ "id": "b", "type": "Gallery" }),
+            error: "unknown variant `Gallery`".to_string(),
+        });
+
+        let assigned = assign_block_ids(vec![
+            block("a").into(),
+            block(" ").into(),
+            block("").into(),
+            invalid.clone(),
+        ]);
+        let ids: Vec<&str> = assigned.iter().filter_map(ContentBlock::id).collect();
+        assert_eq!(ids[0], "a");
+        assert!(Uuid::parse_str(ids[1]).is_ok());
+        assert!(Uuid::parse_str(ids[2]).is_ok());
+        assert_ne!(ids[1], ids[2]);
+        assert!(
+            matches!(&assigned[3], ContentBlock::Invalid(kept) if kept.raw == serde_json::json!({ "id": "b", "type": "Gallery" }))
+        );
+    }
+
+    #[test]
+    fn test_assign_block_ids_replaces_duplicate_ids() {
+        let block = |id: &str| BlockWithId {
+            id: id.to_string(),
+            block: Block::Hero(crate::features::hero::HeroProps {
+                headline: "Hi".to_string(),
+                subheadline: String::new(),
+            }),
+            options: BlockOptions::default(),
+            schedule: Default::default(),
+        };
+
+        let invalid = ContentBlock::Invalid(InvalidBlock {
+            raw: serde_json::json!({ "id": "b", "type": "Gallery" }),
+            error: "unknown variant `Gallery`".to_string(),
+        });
+
+        let assigned = assign_block_ids(vec![
+            block("a").into(),
+            block("a").into(),
+            invalid,
+            block("b").into(),
+        ]);
+        let ids: Vec<&str> = assigned.iter().filter_map(ContentBlock::id).collect();
+        assert_eq!(ids[0], "a", "first use keeps its id");
+        assert!(Uuid::parse_str(ids[1]).is_ok());
+        assert_eq!(ids[2], "b", "invalid blocks are never modified");
+        assert!(
+            Uuid::parse_str(ids[3]).is_ok(),
+            "id used by an invalid block"
+        );
+    }
+
+    #[test]
+    fn test_content_blocks_keep_invalid_entries_raw() {
+        let json = serde_json::json!([
+            { "id": "a", "type": "Hero", "props": { "headline": "H", "subheadline": "S" } },
+            { "id": "b", "type": "Gallery", "props": { "images": [] }, "extra": true },
+            { "id": "c", "type": "Hero", "props": { "headline": 1 } },
+            "not a block",
+        ]);
+        let blocks: Vec<ContentBlock> = serde_json::from_value(json.clone()).unwrap();
+
+        assert!(blocks[0].as_valid().is_some());
+        let errors: Vec<&str> = blocks[1..]
+            .iter()
+            .map(|block| match block {
+                ContentBlock::Invalid(invalid) => invalid.error.as_str(),
+                ContentBlock::Valid(_) => panic!("expected an invalid block"),
+            })
+            .collect();
+        assert!(errors[0].contains("Gallery"), "{}", errors[0]);
+        assert!(errors[1].starts_with("Hero block: "), "{}", errors[1]);
+        assert_eq!(blocks[2].id(), Some("c"));
+        assert_eq!(blocks[3].id(), None);
+
+        assert_eq!(serde_json::to_value(&blocks).unwrap(), json);
+    }
+
+    #[test]
+    fn test_invalid_block_renders_hidden_placeholder() {
+        let block = ContentBlock::Invalid(InvalidBlock {
+            raw: serde_json::json!({ "id": "b", "type": "Gallery", "props": { "caption": "secret" } }),
+            error: "unknown variant `Gallery`".to_string(),
+        });
+        let html = render_content_block(&block).into_string();
+        assert_eq!(html, r#"<div hidden data-invalid-block="b"></div>"#);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_block_json_round_trip(block in any::<BlockWithId>()) {
+            let json = serde_json::to_string(&block).unwrap();
+            let value: Value = serde_json::from_str(&json).unwrap();
+
+            // `type` and `props` are flattened next to `id`
+            prop_assert_eq!(&value["id"], &Value::from(block.id.as_str()));
+            prop_assert_eq!(&value["type"], &Value::from(block.block.type_name()));
+            prop_assert!(value["props"].is_object());
+            prop_assert_eq!(value.get("options").is_some(), !block.options.is_default());
+
+            let parsed: BlockWithId = serde_json::from_str(&json).unwrap();
+            prop_assert_eq!(&parsed.id, &block.id);
+            prop_assert_eq!(&parsed.options, &block.options);
+            prop_assert_eq!(serde_json::to_value(&parsed).unwrap(), value);
+        }
+
+        #[test]
+        fn prop_render_block_escapes_user_text(block in any::<BlockWithId>()) {
+            assert_escaped(&render_block(&block).into_string());
+        }
+
+        #[test]
+        fn prop_image_with_media_escapes_user_text(
+            props in any::<ImageProps>(),
+            options in any::<BlockOptions>(),
+        ) {
+            // The story media id resolves without a media library on disk
+            let props = ImageProps { media_id: STORY_MEDIA_ID.to_string(), ..props };
+            assert_escaped(&props.render_story_with_options(&options).into_string());
+        }
+
+        #[test]
+        fn prop_form_primitives_escape_user_text(
+            checkbox in any::<CheckboxProps>(),
+            input in any::<InputProps>(),
+        ) {
+            assert_escaped(&checkbox.render().into_string());
+            assert_escaped(&input.render().into_string());
+        }
+
+        #[test]
+        fn prop_injection_is_rendered_as_text(headline in "[a-z ]{0,10}") {
+            let block = BlockWithId {
+                id: "id".to_string(),
+                block: Block::Hero(crate::features::hero::HeroProps {
+                    headline: format!("{headline}{INJECTION}"),
+                    subheadline: String::new(),
+                }),
+                options: BlockOptions::default(),
+                schedule: Default::default(),
+            };
+            let html = render_block(&block).into_string();
+            prop_assert!(html.contains("&lt;x-injected"));
+            assert_escaped(&html);
+        }
+
+        #[test]
+        fn prop_assign_block_ids_unique_and_non_empty(
+            blocks in prop::collection::vec(any::<BlockWithId>(), 0..12),
+        ) {
+            let original: Vec<String> = blocks.iter().map(|b| b.id.clone()).collect();
+            let assigned = valid_blocks(&assign_block_ids(
+                blocks.into_iter().map(ContentBlock::from).collect(),
+            ));
+
+            prop_assert_eq!(assigned.len(), original.len());
+            let ids: HashSet<&str> = assigned.iter().map(|b| b.id.as_str()).collect();
+            prop_assert_eq!(ids.len(), assigned.len());
+            for (i, block) in assigned.iter().enumerate() {
+                prop_assert!(!block.id.trim().is_empty());
+                let first_use = !original[i].trim().is_empty() && !original[..i].contains(&original[i]);
+                if first_use {
+                    prop_assert_eq!(&block.id, &original[i]);
+                }
+            }
+        }
     }
 }
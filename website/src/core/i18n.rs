@@ -0,0 +1,465 @@
+/// Localized content: locale settings, negotiation and content variants
+///
+/// A site has one default locale and any number of further locales. Route
+/// content is translated per content file: next to the file of each content
+/// id in a route's `blockIds` sits one variant per further locale, named by
+/// inserting the locale before the extension:
+///
+/// ```text
+/// data/content/homepage.json      # Default locale (e.g. "en")
+/// data/content/homepage.de.json   # German variant
+/// data/content/homepage.fr.json   # French variant
+/// ```
+///
+/// # Public URLs
+///
+/// Every locale is served under a prefix (`/en/`, `/de/`). The unprefixed
+/// `/` negotiates a locale from `Accept-Language`: visitors preferring the
+/// default locale get the page directly, everyone else is redirected to
+/// their locale's prefix. Pages link their translations with
+/// `<link rel="alternate" hreflang>`, with `/` as `x-default`.
+///
+/// # Fallback Policy
+///
+/// `FallbackPolicy` decides what a localized page shows for content files
+/// that have no variant yet: the default locale's blocks, or a 404 until
+/// every file of the route is translated.
+///
+/// # Untranslated Blocks
+///
+/// Blocks are matched across locales by ID. A block counts as untranslated
+/// when the variant lacks it or still holds the default locale's version
+/// unchanged (as it does right after the editor copies the default content
+/// into a new variant).
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumMessage};
+
+use crate::core::block::ContentBlock;
+use crate::core::persistence::{ContentFile, content_file_path, load_content, load_content_files};
+
+/// Locale configuration, stored as `i18n` in data/settings.json
+///
+/// # Example JSON
+///
+/// ```json
+/// { "default_locale": "en", "locales": ["de", "fr"], "fallback": "default_locale" }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocaleSettings {
+    /// Locale of the plain content files (`homepage.json`)
+    pub default_locale: String,
+    /// Further locales with content variants (`homepage.de.json`)
+    pub locales: Vec<String>,
+    /// What localized pages show for content files without a variant
+    pub fallback: FallbackPolicy,
+}
+
+impl Default for LocaleSettings {
+    fn default() -> Self {
+        Self {
+            default_locale: "en".to_string(),
+            locales: vec![],
+            fallback: FallbackPolicy::default(),
+        }
+    }
+}
+
+/// What a localized page shows for content that isn't translated yet
+///
+/// Variants are in the order shown in the settings form; each displays as
+/// its serialized name (the `<option>` value) and its label is the variant's
+/// strum message.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumIter,
+    EnumMessage,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FallbackPolicy {
+    /// Show the default locale's blocks for files without a variant
+    #[default]
+    #[strum(message = "Show default locale content")]
+    DefaultLocale,
+    /// Respond 404 unless every content file of the route has a variant
+    #[strum(message = "Not found until fully translated")]
+    NotFound,
+}
+
+impl FallbackPolicy {
+    /// Human-readable name for the settings form
+    pub fn label(self) -> &'static str {
+        self.get_message().unwrap_or_default()
+    }
+}
+
+impl LocaleSettings {
+    /// Whether any locale besides the default is configured
+    pub fn is_multilingual(&self) -> bool {
+        !self.locales.is_empty()
+    }
+
+    /// Every locale, default first
+    pub fn all(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.default_locale.as_str()).chain(self.locales.iter().map(String::as_str))
+    }
+
+    /// The configured spelling of `tag`, matched case-insensitively
+    pub fn find(&self, tag: &str) -> Option<&str> {
+        self.all().find(|locale| locale.eq_ignore_ascii_case(tag))
+    }
+
+    /// The locale whose content variant to use: `None` for the default
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if `tag` isn't a configured locale.
+    pub fn variant<'a>(&'a self, tag: &str) -> Result<Option<&'a str>, String> {
+        match self.find(tag) {
+            Some(locale) if locale == self.default_locale => Ok(None),
+            Some(locale) => Ok(Some(locale)),
+            None => Err(format!("'{}' is not a configured locale", tag)),
+        }
+    }
+
+    /// Pick the best configured locale for an `Accept-Language` header
+    ///
+    /// Ranges are tried by descending quality (ties keep header order). A
+    /// range matches a locale exactly or by primary language (`de-AT`
+    /// matches `de`, `de` matches `de-CH`); `*` and no match give the default.
+    pub fn negotiate(&self, accept_language: Option<&str>) -> &str {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .filter_map(|param| param.trim().strip_prefix("q="))
+                    .find_map(|q| q.trim().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        for (tag, _) in ranges {
+            if tag == "*" {
+                break;
+            }
+            if let Some(locale) = self.find(tag) {
+                return locale;
+            }
+            let language = primary_language(tag);
+            if let Some(locale) = self
+                .all()
+                .find(|locale| primary_language(locale).eq_ignore_ascii_case(language))
+            {
+                return locale;
+            }
+        }
+        &self.default_locale
+    }
+
+    /// Check that every locale is a usable tag and listed once
+    ///
+    /// Locales appear in URLs and file names, so only simple language tags
+    /// (`de`, `pt-BR`, `zh-Hant`) are accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the first invalid locale.
+    pub fn validate(&self) -> Result<(), String> {
+        let locales: Vec<&str> = self.all().collect();
+        for (index, locale) in locales.iter().enumerate() {
+            if !is_locale_tag(locale) {
+                return Err(format!(
+                    "i18n locale '{}' must be a language tag like 'de' or 'pt-BR'",
+                    locale
+                ));
+            }
+            if locales[..index]
+                .iter()
+                .any(|other| other.eq_ignore_ascii_case(locale))
+            {
+                return Err(format!("i18n locale '{}' is listed twice", locale));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The language subtag of a locale (`pt` for `pt-BR`)
+fn primary_language(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+/// A 2-3 letter lowercase language, then alphanumeric subtags of 2-8
+fn is_locale_tag(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let language = subtags.next().unwrap_or_default();
+    (2..=3).contains(&language.len())
+        && language.bytes().all(|b| b.is_ascii_lowercase())
+        && subtags.all(|subtag| {
+            (2..=8).contains(&subtag.len()) && subtag.bytes().all(|b| b.is_ascii_alphanumeric())
+        })
+}
+
+/// Path of a content file's variant for `locale`
+///
+/// `content/homepage.json` becomes `content/homepage.de.json`.
+pub fn localized_path(source: &str, locale: &str) -> String {
+    match source.strip_suffix(".json") {
+        Some(stem) => format!("{}.{}.json", stem, locale),
+        None => format!("{}.{}", source, locale),
+    }
+}
+
+/// Whether a content file has a variant for `locale`
+///
+/// `source` is a content id; ids that don't resolve have no variants.
+pub fn has_variant(source: &str, locale: &str) -> bool {
+    content_file_path(source, Some(locale)).is_ok_and(|path| path.exists())
+}
+
+// ============================================================================
+// Loading Localized Content
+// ============================================================================
+
+/// Load a route's blocks in `locale`, applying the fallback policy
+///
+/// Returns `None` when the policy is `NotFound` and some content file of
+/// the route has no variant for `locale`.
+pub fn load_localized_content(
+    route_name: &str,
+    locale: &str,
+    settings: &LocaleSettings,
+) -> Option<Vec<ContentBlock>> {
+    let Ok(Some(locale)) = settings.variant(locale) else {
+        return Some(load_content(route_name));
+    };
+
+    let files = localized_files(route_name, locale);
+    let mut blocks = Vec::new();
+    for (file, translated) in files {
+        if !translated && settings.fallback == FallbackPolicy::NotFound {
+            return None;
+        }
+        blocks.extend(file.blocks);
+    }
+    Some(blocks)
+}
+
+/// A route's content files in `locale`, each with whether it has a variant
+///
+/// Files without a variant hold the default locale's blocks, which is also
+/// what the editor starts a new translation from.
+pub fn localized_files(route_name: &str, locale: &str) -> Vec<(ContentFile, bool)> {
+    let variants = crate::core::persistence::load_content_files_in(route_name, Some(locale));
+    load_content_files(route_name)
+        .into_iter()
+        .zip(variants)
+        .map(|(default, variant)| {
+            if has_variant(&default.source, locale) {
+                (variant, true)
+            } else {
+                (default, false)
+            }
+        })
+        .collect()
+}
+
+/// A block of the default locale that has no translation yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UntranslatedBlock {
+    /// Content file (as listed in `blockIds`) the block belongs to
+    pub source: String,
+    pub id: String,
+    pub type_name: Option<String>,
+    /// The variant doesn't contain the block at all
+    pub missing: bool,
+}
+
+/// Blocks of `default` that `localized` lacks or holds unchanged
+///
+/// Both lists are matched file by file (by `source`) and block by block
+/// (by ID); blocks without an ID can't be matched and are skipped.
+pub fn untranslated_blocks(
+    default: &[ContentFile],
+    localized: &[ContentFile],
+) -> Vec<UntranslatedBlock> {
+    let mut untranslated = Vec::new();
+    for file in default {
+        let variant = localized.iter().find(|f| f.source == file.source);
+        for block in &file.blocks {
+            let Some(id) = block.id() else { continue };
+            let translation =
+                variant.and_then(|variant| variant.blocks.iter().find(|b| b.id() == Some(id)));
+            let unchanged = translation.is_some_and(|translation| {
+                serde_json::to_value(translation).ok() == serde_json::to_value(block).ok()
+            });
+            if translation.is_none() || unchanged {
+                untranslated.push(UntranslatedBlock {
+                    source: file.source.clone(),
+                    id: id.to_string(),
+                    type_name: block_type_name(block),
+                    missing: translation.is_none(),
+                });
+            }
+        }
+    }
+    untranslated
+}
+
+fn block_type_name(block: &ContentBlock) -> Option<String> {
+    match block {
+        ContentBlock::Valid(block) => Some(block.block.type_name().to_string()),
+        ContentBlock::Invalid(invalid) => invalid.type_name().map(str::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::{Block, BlockWithId};
+    use crate::core::options::BlockOptions;
+    use crate::core::persistence::with_data_dir_sync;
+    use crate::features::hero::HeroProps;
+    use std::fs;
+    use strum::IntoEnumIterator;
+    use tempfile::TempDir;
+
+    fn settings(locales: &[&str]) -> LocaleSettings {
+        LocaleSettings {
+            locales: locales.iter().map(|l| l.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn hero(id: &str, headline: &str) -> ContentBlock {
+        ContentBlock::Valid(BlockWithId {
+            id: id.to_string(),
+            block: Block::Hero(HeroProps {
+                headline: headline.to_string(),
+                subheadline: String::new(),
+            }),
+            options: BlockOptions::default(),
+            schedule: Default::default(),
+        })
+    }
+
+    #[test]
+    fn test_negotiate_accept_language() {
+        let i18n = settings(&["de", "pt-BR"]);
+        assert_eq!(i18n.negotiate(None), "en");
+        assert_eq!(i18n.negotiate(Some("de-AT,de;q=0.9,en;q=0.8")), "de");
+        assert_eq!(i18n.negotiate(Some("fr, en;q=0.5, de;q=0.7")), "de");
+        assert_eq!(i18n.negotiate(Some("PT-br")), "pt-BR");
+        assert_eq!(i18n.negotiate(Some("pt-PT")), "pt-BR");
+        assert_eq!(i18n.negotiate(Some("fr, *;q=0.5, de;q=0.1")), "en");
+        assert_eq!(i18n.negotiate(Some("de;q=0, fr")), "en");
+        assert_eq!(i18n.negotiate(Some(";;,q=")), "en");
+    }
+
+    #[test]
+    fn test_variant_and_validation() {
+        let i18n = settings(&["de"]);
+        assert_eq!(i18n.variant("en"), Ok(None));
+        assert_eq!(i18n.variant("DE"), Ok(Some("de")));
+        assert!(i18n.variant("fr").is_err());
+
+        assert!(settings(&["de", "pt-BR", "zh-Hant"]).validate().is_ok());
+        for bad in ["DE", "de/..", "d", "de-", "en"] {
+            assert!(settings(&[bad]).validate().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_localized_path() {
+        assert_eq!(
+            localized_path("../data/content/homepage.json", "de"),
+            "../data/content/homepage.de.json"
+        );
+        assert_eq!(localized_path("content/x", "de"), "content/x.de");
+    }
+
+    #[test]
+    fn test_untranslated_blocks() {
+        let source = "homepage".to_string();
+        let default = vec![ContentFile {
+            source: source.clone(),
+            blocks: vec![hero("a", "Hello"), hero("b", "World"), hero("c", "Again")],
+        }];
+        let localized = vec![ContentFile {
+            source,
+            blocks: vec![hero("a", "Hallo"), hero("b", "World")],
+        }];
+
+        let untranslated = untranslated_blocks(&default, &localized);
+        let ids: Vec<(&str, bool)> = untranslated
+            .iter()
+            .map(|b| (b.id.as_str(), b.missing))
+            .collect();
+        assert_eq!(ids, vec![("b", false), ("c", true)]);
+        assert_eq!(untranslated[0].type_name.as_deref(), Some("Hero"));
+    }
+
+    #[test]
+    fn test_fallback_policy_names_match_serde() {
+        for policy in FallbackPolicy::iter() {
+            let json = serde_json::to_string(&policy).unwrap();
+            assert_eq!(json, format!("\"{policy}\""));
+            assert!(!policy.label().is_empty(), "{policy} has a label");
+        }
+    }
+
+    #[test]
+    fn test_fallback_policy() {
+        let dir = TempDir::new().unwrap();
+        let content = dir.path().join("content");
+        fs::create_dir(&content).unwrap();
+        let header = content.join("header.json");
+        let body = content.join("body.json");
+        let write = |path: &std::path::Path, blocks: Vec<ContentBlock>| {
+            let data = crate::pages::homepage::HomepageData::new(blocks);
+            fs::write(path, serde_json::to_string(&data).unwrap()).unwrap();
+        };
+        write(&header, vec![hero("h", "Header")]);
+        write(&body, vec![hero("b", "Body")]);
+        write(&content.join("body.de.json"), vec![hero("b", "Inhalt")]);
+        let routes = serde_json::json!([{ "path": "/", "name": "homepage", "blockIds": ["header", "body"] }]);
+        fs::write(dir.path().join("routes.json"), routes.to_string()).unwrap();
+
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let headline = |block: &ContentBlock| match &block.as_valid().unwrap().block {
+                Block::Hero(props) => props.headline.clone(),
+                _ => unreachable!(),
+            };
+
+            let mut i18n = settings(&["de", "fr"]);
+            let de = load_localized_content("homepage", "de", &i18n).unwrap();
+            assert_eq!(
+                de.iter().map(headline).collect::<Vec<_>>(),
+                vec!["Header", "Inhalt"]
+            );
+            let fr = load_localized_content("homepage", "fr", &i18n).unwrap();
+            assert_eq!(
+                fr.iter().map(headline).collect::<Vec<_>>(),
+                vec!["Header", "Body"]
+            );
+
+            i18n.fallback = FallbackPolicy::NotFound;
+            assert!(load_localized_content("homepage", "de", &i18n).is_none());
+            assert!(load_localized_content("homepage", "en", &i18n).is_some());
+        });
+    }
+}
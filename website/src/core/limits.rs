@@ -0,0 +1,392 @@
+/// Request limits and per-client rate limiting
+///
+/// `RequestLimits` bounds what a single request may ask of the server:
+///
+/// - **Body size**: `max_body_bytes` for every body read by an extractor.
+///   Media uploads and archive imports keep their own, larger limits
+///   (`media::MAX_UPLOAD_BYTES`, `archive::MAX_ARCHIVE_BYTES`)
+/// - **Content size**: a route save may hold at most `max_blocks_per_route`
+///   blocks across its files, and no string in it may be longer than
+///   `max_string_chars` characters (see `check_content`)
+/// - **Time**: a request that takes longer than `request_timeout` is
+///   answered with 408 Request Timeout
+/// - **Rate**: mutating requests (anything but GET, HEAD and OPTIONS) are
+///   limited per client by a token bucket (see `RateLimiter`)
+///
+/// Requests over a limit get 413 Payload Too Large, or 429 Too Many Requests
+/// with a `Retry-After` header in seconds.
+///
+/// # Clients
+///
+/// Every request is limited by the peer address, or by the first
+/// `X-Forwarded-For` address when `trust_forwarded_for` is set. A request made with an API token that authenticated is also limited by the
+/// token, keyed by its id, so one token can't spread its requests over many
+/// addresses. Unauthenticated bearer values never pick a bucket.
+///
+/// **Behind a reverse proxy `trust_forwarded_for` must be on.** On Render
+/// every request arrives from the proxy, so with it off all clients share a
+/// single bucket and one busy client rate limits everyone. It is off by
+/// default because without a proxy clients could send any `X-Forwarded-For`
+/// and pick their own bucket; `main` turns it on when `PORT` is set, as
+/// Render does.
+///
+/// Buckets that have refilled completely are dropped once per
+/// `SWEEP_INTERVAL`; a full bucket behaves the same as a new one.
+///
+/// # Configuration
+///
+/// ```ignore
+/// let limits = RequestLimits {
+///     max_blocks_per_route: 200,
+///     rate_limit: RateLimit { burst: 10, per_minute: 20 },
+///     ..RequestLimits::default()
+/// };
+/// let state = AppState::default().with_limits(limits);
+/// ```
+///
+/// # Architecture
+///
+/// Following rust-core-patterns:
+/// - **No framework types**: Limits and buckets are plain values; the
+///   middleware in `app` maps them to layers and responses
+/// - **Injected time**: `RateLimiter::check` takes `now` from the app's
+///   clock, so tests refill buckets by moving a `ManualClock`
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use thiserror::Error;
+
+/// How often buckets that have refilled completely are dropped
+const SWEEP_INTERVAL: chrono::TimeDelta = chrono::TimeDelta::minutes(1);
+
+/// Limits applied to every request
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestLimits {
+    /// Largest request body read by an extractor, in bytes
+    pub max_body_bytes: usize,
+    /// Most blocks a route save may contain, across all its files
+    pub max_blocks_per_route: usize,
+    /// Longest string in a route save, in characters
+    pub max_string_chars: usize,
+    /// Longest a request may take before it is answered with 408
+    pub request_timeout: Duration,
+    /// Token bucket for mutating requests
+    pub rate_limit: RateLimit,
+    /// Key clients by `X-Forwarded-For` instead of the peer address
+    ///
+    /// Turn on behind a proxy that sets the header (Render's does), or every
+    /// client shares the proxy's bucket; leave off when clients connect
+    /// directly, or they can choose their own bucket (see the module docs).
+    pub trust_forwarded_for: bool,
+}
+
+impl Default for RequestLimits {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 1024 * 1024,
+            max_blocks_per_route: 500,
+            max_string_chars: 10_000,
+            request_timeout: Duration::from_secs(30),
+            rate_limit: RateLimit::default(),
+            trust_forwarded_for: false,
+        }
+    }
+}
+
+/// Size of a token bucket and how fast it refills
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Requests a client may make at once, with a full bucket
+    pub burst: u32,
+    /// Requests a client may make per minute, sustained
+    pub per_minute: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            burst: 60,
+            per_minute: 60,
+        }
+    }
+}
+
+impl RateLimit {
+    /// Tokens added per second
+    fn refill_rate(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+/// Why a route save is over the content limits
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LimitError {
+    #[error("too many blocks: {count} (at most {max})")]
+    TooManyBlocks { count: usize, max: usize },
+
+    #[error("string at {path} is too long: {chars} characters (at most {max})")]
+    StringTooLong {
+        path: String,
+        chars: usize,
+        max: usize,
+    },
+}
+
+impl RequestLimits {
+    /// Check a save's block count and the length of every string in it
+    ///
+    /// `content` is the save as JSON; keys count as strings too. The error
+    /// names the first string over the limit by its JSON path.
+    pub fn check_content(&self, block_count: usize, content: &Value) -> Result<(), LimitError> {
+        if block_count > self.max_blocks_per_route {
+            return Err(LimitError::TooManyBlocks {
+                count: block_count,
+                max: self.max_blocks_per_route,
+            });
+        }
+        check_strings(content, "$", self.max_string_chars)
+    }
+}
+
+fn check_strings(value: &Value, path: &str, max: usize) -> Result<(), LimitError> {
+    let check = |text: &str, path: &str| {
+        let chars = text.chars().count();
+        if chars > max {
+            Err(LimitError::StringTooLong {
+                path: path.to_string(),
+                chars,
+                max,
+            })
+        } else {
+            Ok(())
+        }
+    };
+
+    match value {
+        Value::String(text) => check(text, path),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, item)| check_strings(item, &format!("{path}[{i}]"), max)),
+        Value::Object(fields) => fields.iter().try_for_each(|(key, item)| {
+            let path = format!("{path}.{key}");
+            check(key, &path)?;
+            check_strings(item, &path, max)
+        }),
+        Value::Null | Value::Bool(_) | Value::Number(_) => Ok(()),
+    }
+}
+
+// ============================================================================
+// Rate Limiting
+// ============================================================================
+
+/// Key of the bucket for an authenticated API token, by its id
+pub fn token_key(token_id: &str) -> String {
+    format!("token:{token_id}")
+}
+
+/// Key of the bucket for a client address
+pub fn address_key(address: &str) -> String {
+    format!("ip:{address}")
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct Buckets {
+    by_key: HashMap<String, Bucket>,
+    /// When full buckets were last dropped
+    swept: Option<DateTime<Utc>>,
+}
+
+/// In-process token buckets, one per client key
+///
+/// Each bucket starts full with `burst` tokens and refills continuously at
+/// `per_minute` tokens a minute. Clones share the same buckets.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Arc::new(Mutex::new(Buckets::default())),
+        }
+    }
+
+    /// Take a token from `key`'s bucket at `now`
+    ///
+    /// Returns how long to wait before the next token if the bucket is empty.
+    pub fn check(&self, key: &str, now: DateTime<Utc>) -> Result<(), Duration> {
+        let burst = f64::from(self.limit.burst);
+        let rate = self.limit.refill_rate();
+        let mut buckets = self.buckets.lock().unwrap();
+
+        if buckets
+            .swept
+            .is_none_or(|swept| now - swept >= SWEEP_INTERVAL)
+        {
+            buckets
+                .by_key
+                .retain(|_, bucket| refilled(bucket, now, rate, burst) < burst);
+            buckets.swept = Some(now);
+        }
+
+        let bucket = buckets.by_key.entry(key.to_string()).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+        bucket.tokens = refilled(bucket, now, rate, burst);
+        bucket.updated = now.max(bucket.updated);
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        if rate > 0.0 {
+            Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+        } else {
+            Err(Duration::MAX)
+        }
+    }
+}
+
+/// Tokens in `bucket` at `now`; a clock that moved backwards adds none
+fn refilled(bucket: &Bucket, now: DateTime<Utc>, rate: f64, burst: f64) -> f64 {
+    let elapsed = (now - bucket.updated).to_std().unwrap_or_default();
+    (bucket.tokens + elapsed.as_secs_f64() * rate).min(burst)
+}
+
+/// `Retry-After` value for a wait: whole seconds, rounded up, at least 1
+pub fn retry_after_secs(wait: Duration) -> u64 {
+    let secs = wait
+        .as_secs()
+        .saturating_add(u64::from(wait.subsec_nanos() > 0));
+    secs.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    fn noon() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_bucket_allows_burst_then_refills() {
+        let limiter = RateLimiter::new(RateLimit {
+            burst: 2,
+            per_minute: 30,
+        });
+        assert_eq!(limiter.check("a", noon()), Ok(()));
+        assert_eq!(limiter.check("a", noon()), Ok(()));
+        assert_eq!(limiter.check("a", noon()), Err(Duration::from_secs(2)));
+
+        // Other clients have their own bucket
+        assert_eq!(limiter.check("b", noon()), Ok(()));
+
+        let later = noon() + chrono::Duration::seconds(1);
+        assert_eq!(limiter.check("a", later), Err(Duration::from_secs(1)));
+        let later = noon() + chrono::Duration::seconds(2);
+        assert_eq!(limiter.check("a", later), Ok(()));
+        assert!(limiter.check("a", later).is_err());
+    }
+
+    #[test]
+    fn test_clock_moving_backwards_adds_no_tokens() {
+        let limiter = RateLimiter::new(RateLimit {
+            burst: 1,
+            per_minute: 60,
+        });
+        assert_eq!(limiter.check("a", noon()), Ok(()));
+        let earlier = noon() - chrono::Duration::hours(1);
+        assert!(limiter.check("a", earlier).is_err());
+    }
+
+    #[test]
+    fn test_retry_after_rounds_up() {
+        assert_eq!(retry_after_secs(Duration::from_millis(1)), 1);
+        assert_eq!(retry_after_secs(Duration::from_secs(2)), 2);
+        assert_eq!(retry_after_secs(Duration::from_millis(2001)), 3);
+        assert_eq!(retry_after_secs(Duration::ZERO), 1);
+    }
+
+    #[test]
+    fn test_full_buckets_are_swept() {
+        let limiter = RateLimiter::new(RateLimit {
+            burst: 2,
+            per_minute: 1,
+        });
+        let keys = || {
+            let mut keys: Vec<String> = limiter
+                .buckets
+                .lock()
+                .unwrap()
+                .by_key
+                .keys()
+                .cloned()
+                .collect();
+            keys.sort();
+            keys
+        };
+        assert_eq!(limiter.check("a", noon()), Ok(()));
+        let later = noon() + chrono::Duration::seconds(30);
+        assert_eq!(limiter.check("b", later), Ok(()));
+        assert_eq!(keys(), ["a", "b"]);
+
+        // A minute on, "a" has refilled and is dropped; "b" hasn't yet
+        let later = noon() + chrono::Duration::seconds(61);
+        assert_eq!(limiter.check("c", later), Ok(()));
+        assert_eq!(keys(), ["b", "c"]);
+    }
+
+    #[test]
+    fn test_bucket_keys_are_namespaced() {
+        assert_eq!(token_key("tok_1"), "token:tok_1");
+        assert_eq!(address_key("10.0.0.1"), "ip:10.0.0.1");
+        assert_ne!(token_key("x"), address_key("x"));
+    }
+
+    #[test]
+    fn test_check_content_limits_blocks_and_strings() {
+        let limits = RequestLimits {
+            max_blocks_per_route: 2,
+            max_string_chars: 6,
+            ..RequestLimits::default()
+        };
+        let content = json!({ "blocks": [{ "props": { "title": "Hello" } }] });
+        assert_eq!(limits.check_content(2, &content), Ok(()));
+        assert_eq!(
+            limits.check_content(3, &content),
+            Err(LimitError::TooManyBlocks { count: 3, max: 2 })
+        );
+
+        // Characters, not bytes
+        let content = json!({ "blocks": [{ "props": { "title": "Grüße!" } }] });
+        assert_eq!(limits.check_content(1, &content), Ok(()));
+
+        let content = json!({ "blocks": [{ "props": { "title": "Hello,!" } }] });
+        assert_eq!(
+            limits.check_content(1, &content),
+            Err(LimitError::StringTooLong {
+                path: "$.blocks[0].props.title".to_string(),
+                chars: 7,
+                max: 6,
+            })
+        );
+    }
+}
@@ -0,0 +1,647 @@
+/// Media library: uploaded images, resized variants and metadata
+///
+/// Uploads are stored on the local filesystem under the data directory so the
+/// whole library works offline and travels with the rest of the content.
+///
+/// # Storage Layout
+///
+/// Each media item gets its own directory named after its UUID:
+///
+/// ```text
+/// data/media/
+/// └── 550e8400-e29b-41d4-a716-446655440000/
+///     ├── media.json      # MediaItem metadata
+///     ├── original.jpg    # Untouched upload
+///     ├── 320.webp        # Resized variants (one per width)
+///     ├── 640.webp
+///     └── ...
+/// ```
+///
+/// The directory is served at `/media`, so a variant's public URL is
+/// `/media/{id}/{width}.webp`.
+///
+/// # Image Processing
+///
+/// On upload the original is decoded, its dimensions recorded, and a WebP
+/// variant is generated for every width in `VARIANT_WIDTHS` that is smaller
+/// than the original, plus one at the original width. Decoding and encoding
+/// are CPU-bound, so async callers should run `store_upload` inside
+/// `tokio::task::spawn_blocking`.
+///
+/// # Error Handling
+///
+/// Following rust-error-handling patterns, operations return `MediaError` so
+/// API handlers can map each failure to an appropriate HTTP status code.
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::core::persistence::{content_dir, data_dir};
+use crate::core::schedule::scheduled_dir;
+use crate::core::settings::load_settings;
+
+/// Widths (in pixels) of the generated WebP variants
+pub const VARIANT_WIDTHS: [u32; 5] = [320, 640, 960, 1280, 1920];
+
+/// Maximum accepted upload size in bytes (20 MiB)
+pub const MAX_UPLOAD_BYTES: usize = 20 * 1024 * 1024;
+
+/// Name of the per-item metadata file
+const METADATA_FILE: &str = "media.json";
+
+/// Errors that can occur while managing the media library
+#[derive(Debug, thiserror::Error)]
+pub enum MediaError {
+    #[error("media item '{0}' not found")]
+    NotFound(String),
+
+    #[error("unsupported or corrupt image: {0}")]
+    InvalidImage(#[from] image::ImageError),
+
+    #[error("media item is used by: {}", .0.join(", "))]
+    InUse(Vec<String>),
+
+    #[error("invalid media id '{0}'")]
+    InvalidId(String),
+
+    #[error("filesystem error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("metadata error: {0}")]
+    Metadata(#[from] serde_json::Error),
+}
+
+/// Point of interest within an image, as fractions of width and height
+///
+/// `(0.5, 0.5)` is the centre. Used as `object-position` so that cropped
+/// renditions keep the subject in frame.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FocalPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Default for FocalPoint {
+    fn default() -> Self {
+        Self { x: 0.5, y: 0.5 }
+    }
+}
+
+impl FocalPoint {
+    /// Clamp both coordinates into the `0.0..=1.0` range
+    pub fn clamped(self) -> Self {
+        Self {
+            x: self.x.clamp(0.0, 1.0),
+            y: self.y.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// A generated, resized rendition of a media item
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MediaVariant {
+    pub width: u32,
+    pub height: u32,
+    /// File name relative to the item directory, e.g. "640.webp"
+    pub file: String,
+}
+
+/// Metadata for one uploaded image
+///
+/// # Example JSON
+///
+/// ```json
+/// {
+///   "id": "550e8400-e29b-41d4-a716-446655440000",
+///   "filename": "team-offsite.jpg",
+///   "original": "original.jpg",
+///   "width": 2400,
+///   "height": 1600,
+///   "alt": "The team at the 2025 offsite",
+///   "focal_point": { "x": 0.5, "y": 0.3 },
+///   "variants": [{ "width": 320, "height": 213, "file": "320.webp" }],
+///   "uploaded_at": "2025-11-25T05:35:55Z"
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MediaItem {
+    pub id: String,
+    /// Original file name as uploaded (for display only)
+    pub filename: String,
+    /// Stored original file name relative to the item directory
+    pub original: String,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub alt: String,
+    #[serde(default)]
+    pub focal_point: FocalPoint,
+    /// Variants ordered by ascending width
+    pub variants: Vec<MediaVariant>,
+    pub uploaded_at: DateTime<Utc>,
+}
+
+impl MediaItem {
+    /// Public URL of the untouched original
+    pub fn original_url(&self) -> String {
+        format!("/media/{}/{}", self.id, self.original)
+    }
+
+    /// Public URL of a variant
+    pub fn variant_url(&self, variant: &MediaVariant) -> String {
+        format!("/media/{}/{}", self.id, variant.file)
+    }
+
+    /// `srcset` attribute value listing every variant with its width descriptor
+    pub fn srcset(&self) -> String {
+        self.variants
+            .iter()
+            .map(|v| format!("{} {}w", self.variant_url(v), v.width))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Best default `src`: the largest variant no wider than 1280px
+    pub fn default_src(&self) -> String {
+        self.variants
+            .iter()
+            .rev()
+            .find(|v| v.width <= 1280)
+            .or_else(|| self.variants.first())
+            .map(|v| self.variant_url(v))
+            .unwrap_or_else(|| self.original_url())
+    }
+}
+
+/// Metadata fields that editors can change after upload
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MediaUpdate {
+    pub alt: Option<String>,
+    pub focal_point: Option<FocalPoint>,
+}
+
+/// Get the media library directory (`data/media`)
+pub fn media_dir() -> PathBuf {
+    data_dir().join("media")
+}
+
+/// Metadata file of a media item (`data/media/{id}/media.json`)
+///
+/// Used by the audit log to hash an item before and after a change.
+pub fn metadata_path(id: &str) -> Result<PathBuf, MediaError> {
+    Ok(item_dir(&media_dir(), id)?.join(METADATA_FILE))
+}
+
+/// List all media items, newest first
+///
+/// Directories with missing or unreadable metadata are skipped and logged.
+pub fn list_media() -> Vec<MediaItem> {
+    list_media_in(&media_dir())
+}
+
+/// Find a single media item by id
+pub fn find_media(id: &str) -> Option<MediaItem> {
+    read_item(&media_dir(), id).ok()
+}
+
+/// Store an uploaded image and generate its variants
+///
+/// # Errors
+///
+/// - `InvalidImage` if the bytes can't be decoded as a supported format
+/// - `Io` / `Metadata` if writing to disk fails
+pub fn store_upload(filename: &str, bytes: &[u8], alt: &str) -> Result<MediaItem, MediaError> {
+    store_upload_in(&media_dir(), filename, bytes, alt, Utc::now())
+}
+
+/// Update alt text and/or focal point of an existing item
+pub fn update_media(id: &str, update: MediaUpdate) -> Result<MediaItem, MediaError> {
+    update_media_in(&media_dir(), id, update)
+}
+
+/// Delete a media item if nothing references it
+///
+/// # Errors
+///
+/// Returns `InUse` with the places still using the item (see
+/// `media_usage`), so editors can fix those first.
+pub fn delete_media(id: &str) -> Result<(), MediaError> {
+    let usage = media_usage(id);
+    if !usage.is_empty() {
+        return Err(MediaError::InUse(usage));
+    }
+    delete_media_in(&media_dir(), id)
+}
+
+/// Everything that references the given media item
+///
+/// Returns the content files (`homepage.json`, `homepage.de.json`, …) and
+/// scheduled drafts (`scheduled draft for homepage`) that use it, and
+/// `site logo` if it is the logo. Files are scanned as raw JSON rather than
+/// loaded as blocks, so locale variants and invalid blocks kept for repair
+/// count too: any `media_id` field naming the item is a use. A file that
+/// isn't valid JSON counts if it mentions the id at all.
+pub fn media_usage(id: &str) -> Vec<String> {
+    let mut usage: Vec<String> = json_files(&content_dir())
+        .into_iter()
+        .filter(|(_, json)| json_references_media(json, id))
+        .map(|(name, _)| name)
+        .collect();
+    usage.extend(
+        json_files(&scheduled_dir())
+            .into_iter()
+            .filter(|(_, json)| json_references_media(json, id))
+            .map(|(name, json)| match json["route"].as_str() {
+                Some(route) => format!("scheduled draft for {}", route),
+                None => format!("scheduled draft {}", name),
+            }),
+    );
+    if load_settings().logo.as_deref() == Some(id) {
+        usage.push("site logo".to_string());
+    }
+    usage
+}
+
+/// The `.json` files directly in `dir`, by name, sorted
+///
+/// A file that isn't valid JSON is returned as a JSON string of its text.
+fn json_files(dir: &Path) -> Vec<(String, Value)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut files: Vec<(String, Value)> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| {
+            let text = fs::read_to_string(entry.path()).ok()?;
+            let json = serde_json::from_str(&text).unwrap_or(Value::String(text));
+            Some((entry.file_name().to_string_lossy().to_string(), json))
+        })
+        .collect();
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    files
+}
+
+/// Whether `json` has a `media_id` field naming `id` at any depth
+fn json_references_media(json: &Value, id: &str) -> bool {
+    match json {
+        Value::Object(fields) => fields.iter().any(|(key, value)| {
+            (key == "media_id" && value.as_str() == Some(id)) || json_references_media(value, id)
+        }),
+        Value::Array(items) => items.iter().any(|item| json_references_media(item, id)),
+        Value::String(text) => text.contains(id),
+        _ => false,
+    }
+}
+
+// ============================================================================
+// Directory-parameterised implementations (used directly by tests)
+// ============================================================================
+
+fn list_media_in(root: &Path) -> Vec<MediaItem> {
+    let entries = match fs::read_dir(root) {
+        Ok(entries) => entries,
+        Err(e) => {
+            if e.kind() != io::ErrorKind::NotFound {
+                eprintln!("Failed to read {}: {}", root.display(), e);
+            }
+            return vec![];
+        }
+    };
+
+    let mut items: Vec<MediaItem> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            read_item(root, &id)
+                .inspect_err(|e| eprintln!("Skipping media '{}': {}", id, e))
+                .ok()
+        })
+        .collect();
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.uploaded_at));
+    items
+}
+
+fn store_upload_in(
+    root: &Path,
+    filename: &str,
+    bytes: &[u8],
+    alt: &str,
+    now: DateTime<Utc>,
+) -> Result<MediaItem, MediaError> {
+    let format = image::guess_format(bytes)?;
+    let decoded = image::load_from_memory_with_format(bytes, format)?;
+
+    let id = Uuid::new_v4().to_string();
+    let item_dir = root.join(&id);
+    fs::create_dir_all(&item_dir)?;
+
+    let extension = format.extensions_str().first().copied().unwrap_or("bin");
+    let original = format!("original.{}", extension);
+    fs::write(item_dir.join(&original), bytes)?;
+
+    let variants = match write_variants(&item_dir, &decoded) {
+        Ok(variants) => variants,
+        Err(e) => {
+            // Don't leave half-processed uploads behind
+            let _ = fs::remove_dir_all(&item_dir);
+            return Err(e);
+        }
+    };
+
+    let item = MediaItem {
+        id,
+        filename: filename.to_string(),
+        original,
+        width: decoded.width(),
+        height: decoded.height(),
+        alt: alt.trim().to_string(),
+        focal_point: FocalPoint::default(),
+        variants,
+        uploaded_at: now,
+    };
+    write_item(root, &item)?;
+    Ok(item)
+}
+
+fn update_media_in(root: &Path, id: &str, update: MediaUpdate) -> Result<MediaItem, MediaError> {
+    let mut item = read_item(root, id)?;
+    if let Some(alt) = update.alt {
+        item.alt = alt.trim().to_string();
+    }
+    if let Some(focal_point) = update.focal_point {
+        item.focal_point = focal_point.clamped();
+    }
+    write_item(root, &item)?;
+    Ok(item)
+}
+
+fn delete_media_in(root: &Path, id: &str) -> Result<(), MediaError> {
+    let dir = item_dir(root, id)?;
+    if !dir.is_dir() {
+        return Err(MediaError::NotFound(id.to_string()));
+    }
+    fs::remove_dir_all(dir)?;
+    Ok(())
+}
+
+/// Widths to generate for an image of the given original width
+///
+/// Every configured width below the original, plus the original width itself,
+/// so small images are never upscaled.
+fn variant_widths(original_width: u32) -> Vec<u32> {
+    let mut widths: Vec<u32> = VARIANT_WIDTHS
+        .iter()
+        .copied()
+        .filter(|w| *w < original_width)
+        .collect();
+    widths.push(original_width);
+    widths
+}
+
+fn write_variants(item_dir: &Path, image: &DynamicImage) -> Result<Vec<MediaVariant>, MediaError> {
+    variant_widths(image.width())
+        .into_iter()
+        .map(|width| {
+            let resized = if width == image.width() {
+                image.clone()
+            } else {
+                let height = scaled_height(image.width(), image.height(), width);
+                image.resize_exact(width, height, FilterType::Lanczos3)
+            };
+            let file = format!("{}.webp", width);
+            // The WebP encoder only accepts 8-bit RGB(A)
+            DynamicImage::ImageRgba8(resized.to_rgba8())
+                .save_with_format(item_dir.join(&file), ImageFormat::WebP)?;
+            Ok(MediaVariant {
+                width,
+                height: resized.height(),
+                file,
+            })
+        })
+        .collect()
+}
+
+fn scaled_height(width: u32, height: u32, target_width: u32) -> u32 {
+    let scaled = (height as u64 * target_width as u64).div_ceil(width as u64);
+    scaled.max(1) as u32
+}
+
+/// Resolve an item directory, rejecting ids that aren't UUIDs
+///
+/// Ids come from URLs, so this prevents `..` or absolute paths from escaping
+/// the media directory.
+fn item_dir(root: &Path, id: &str) -> Result<PathBuf, MediaError> {
+    Uuid::parse_str(id).map_err(|_| MediaError::InvalidId(id.to_string()))?;
+    Ok(root.join(id))
+}
+
+fn read_item(root: &Path, id: &str) -> Result<MediaItem, MediaError> {
+    let path = item_dir(root, id)?.join(METADATA_FILE);
+    let contents = fs::read_to_string(&path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => MediaError::NotFound(id.to_string()),
+        _ => MediaError::Io(e),
+    })?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+fn write_item(root: &Path, item: &MediaItem) -> Result<(), MediaError> {
+    let path = item_dir(root, &item.id)?.join(METADATA_FILE);
+    fs::write(path, serde_json::to_string_pretty(item)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::persistence::with_data_dir_sync;
+    use image::{ImageBuffer, Rgb};
+    use serde_json::json;
+    use std::io::Cursor;
+
+    fn png_bytes(width: u32, height: u32) -> Vec<u8> {
+        let img = ImageBuffer::from_pixel(width, height, Rgb([200u8, 30, 30]));
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgb8(img)
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_variant_widths_never_upscale() {
+        assert_eq!(variant_widths(200), vec![200]);
+        assert_eq!(variant_widths(700), vec![320, 640, 700]);
+        assert_eq!(variant_widths(4000), vec![320, 640, 960, 1280, 1920, 4000]);
+    }
+
+    #[test]
+    fn test_store_upload_generates_webp_variants() {
+        let root = tempfile::tempdir().unwrap();
+        let item = store_upload_in(
+            root.path(),
+            "photo.png",
+            &png_bytes(700, 350),
+            "  A red rectangle ",
+            Utc::now(),
+        )
+        .unwrap();
+
+        assert_eq!((item.width, item.height), (700, 350));
+        assert_eq!(item.alt, "A red rectangle");
+        assert_eq!(item.original, "original.png");
+        let widths: Vec<u32> = item.variants.iter().map(|v| v.width).collect();
+        assert_eq!(widths, vec![320, 640, 700]);
+        assert_eq!(item.variants[0].height, 160);
+
+        for variant in &item.variants {
+            let path = root.path().join(&item.id).join(&variant.file);
+            let decoded = image::open(&path).unwrap();
+            assert_eq!(decoded.width(), variant.width);
+        }
+
+        assert_eq!(list_media_in(root.path()), vec![item.clone()]);
+        assert!(
+            item.srcset()
+                .starts_with(&format!("/media/{}/320.webp 320w", item.id))
+        );
+        assert_eq!(item.default_src(), format!("/media/{}/700.webp", item.id));
+    }
+
+    #[test]
+    fn test_store_upload_rejects_non_images() {
+        let root = tempfile::tempdir().unwrap();
+        let result = store_upload_in(root.path(), "notes.txt", b"hello", "", Utc::now());
+        assert!(matches!(result, Err(MediaError::InvalidImage(_))));
+        assert_eq!(fs::read_dir(root.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_update_and_delete_media() {
+        let root = tempfile::tempdir().unwrap();
+        let item =
+            store_upload_in(root.path(), "a.png", &png_bytes(10, 10), "", Utc::now()).unwrap();
+
+        let updated = update_media_in(
+            root.path(),
+            &item.id,
+            MediaUpdate {
+                alt: Some("Updated".to_string()),
+                focal_point: Some(FocalPoint { x: 1.5, y: -0.2 }),
+            },
+        )
+        .unwrap();
+        assert_eq!(updated.alt, "Updated");
+        assert_eq!(updated.focal_point, FocalPoint { x: 1.0, y: 0.0 });
+
+        delete_media_in(root.path(), &item.id).unwrap();
+        assert!(matches!(
+            read_item(root.path(), &item.id),
+            Err(MediaError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_item_ids_must_be_uuids() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            delete_media_in(root.path(), "../routes.json"),
+            Err(MediaError::InvalidId(_))
+        ));
+    }
+
+    const MEDIA_ID: &str = "550e8400-e29b-41d4-a716-446655440000";
+
+    fn image_block() -> Value {
+        json!({ "id": "img", "type": "Image", "props": { "media_id": MEDIA_ID } })
+    }
+
+    /// `media_usage` in a data directory holding `files` (path, JSON)
+    fn usage_with(files: &[(&str, Value)]) -> Vec<String> {
+        let dir = tempfile::tempdir().unwrap();
+        for (path, json) in files {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, json.to_string()).unwrap();
+        }
+        with_data_dir_sync(dir.path().to_path_buf(), || media_usage(MEDIA_ID))
+    }
+
+    #[test]
+    fn test_media_usage_finds_default_content() {
+        let content = json!({ "blocks": [image_block()] });
+        assert_eq!(
+            usage_with(&[("content/homepage.json", content)]),
+            vec!["homepage.json"]
+        );
+
+        let other = json!({ "blocks": [{ "id": "img", "type": "Image",
+                                         "props": { "media_id": "other" } }] });
+        assert!(usage_with(&[("content/homepage.json", other)]).is_empty());
+    }
+
+    #[test]
+    fn test_media_usage_finds_locale_variants() {
+        let variant = json!({ "blocks": [image_block()] });
+        assert_eq!(
+            usage_with(&[
+                ("content/homepage.json", json!({ "blocks": [] })),
+                ("content/homepage.de.json", variant),
+            ]),
+            vec!["homepage.de.json"]
+        );
+    }
+
+    #[test]
+    fn test_media_usage_finds_scheduled_drafts() {
+        let draft = json!({
+            "id": "0f9f2c1e-0000-4000-8000-000000000000",
+            "route": "homepage",
+            "publish_at": "2026-03-01T12:00:00Z",
+            "files": [{ "source": "homepage", "blocks": [image_block()] }]
+        });
+        assert_eq!(
+            usage_with(&[("scheduled/0f9f2c1e-0000-4000-8000-000000000000.json", draft)]),
+            vec!["scheduled draft for homepage"]
+        );
+    }
+
+    #[test]
+    fn test_media_usage_finds_invalid_blocks() {
+        // Kept as raw JSON by the lenient loader: not an Image block any more
+        let invalid = json!({ "blocks": [{ "id": "img", "type": "Image",
+                                           "props": { "media_id": MEDIA_ID, "alt": 42 } }] });
+        assert_eq!(
+            usage_with(&[("content/homepage.json", invalid)]),
+            vec!["homepage.json"]
+        );
+
+        // Truncated mid-write: not JSON at all
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("content")).unwrap();
+        let truncated = format!(
+            "{{ \"blocks\": [{{ \"props\": {{ \"media_id\": \"{}\"",
+            MEDIA_ID
+        );
+        fs::write(dir.path().join("content/broken.json"), truncated).unwrap();
+        let usage = with_data_dir_sync(dir.path().to_path_buf(), || media_usage(MEDIA_ID));
+        assert_eq!(usage, vec!["broken.json"]);
+    }
+
+    #[test]
+    fn test_media_usage_finds_the_site_logo() {
+        assert_eq!(
+            usage_with(&[("settings.json", json!({ "logo": MEDIA_ID }))]),
+            vec!["site logo"]
+        );
+    }
+}
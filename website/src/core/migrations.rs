@@ -0,0 +1,465 @@
+/// Content schema versioning and migrations for stored block JSON
+///
+/// Content files in `data/content/` outlive the Rust types that read them.
+/// When a props struct changes incompatibly, older files would stop
+/// deserializing and the page would render empty. Instead, every file
+/// carries a schema `version` and is upgraded through a chain of migration
+/// functions on the raw JSON before it is deserialized into `HomepageData`.
+///
+/// # Versions
+///
+/// - **0**: Files written before versioning (no `version` field). Blocks
+///   from before `BlockWithId` may have no `id`.
+/// - **1**: `version` field; every block has an `id`.
+///
+/// # Adding a Migration
+///
+/// 1. Bump `CONTENT_VERSION`
+/// 2. Append a `Migration` to `MIGRATIONS` whose `from` is the previous
+///    version, transforming the `serde_json::Value` in place
+/// 3. Add `core/fixtures/content/v{N}.json`, a file in the new format, and add it
+///    to `FIXTURES` in the tests (a test fails if any version is missing)
+///
+/// Migrations operate on `serde_json::Value`, not on the typed structs, so
+/// they keep compiling after the structs move on.
+///
+/// # Applying Migrations
+///
+/// `parse_content` migrates in memory on every load; nothing is written
+/// from a GET request. `website migrate` (`migrate_content_files`) rewrites
+/// all route content files, including their locale variants (see
+/// `core::i18n`), at the current version.
+///
+/// # Content References
+///
+/// routes.json used to list content files by path
+/// (`../data/content/homepage.json`); routes now list content ids (see
+/// `core::persistence`). `website migrate` first rewrites such entries with
+/// `migrate_content_references`. Paths outside the content directory can't
+/// be converted; they are reported and left for an admin to fix.
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::core::persistence::{
+    content_dir, content_file_path, legacy_content_id, load_routes, load_stored_routes,
+    routes_json_path,
+};
+use crate::core::settings::load_settings;
+use crate::pages::homepage::HomepageData;
+
+/// Current content schema version, written by `HomepageData::new`
+pub const CONTENT_VERSION: u32 = 1;
+
+/// Errors that can occur while reading or migrating content JSON
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error("invalid JSON: {0}")]
+    InvalidJson(serde_json::Error),
+
+    #[error("invalid content version: {0}")]
+    InvalidVersion(Value),
+
+    #[error("content version {0} is newer than this build supports (version {CONTENT_VERSION})")]
+    NewerVersion(u32),
+
+    #[error("migration from version {from} failed: {message}")]
+    Failed { from: u32, message: String },
+
+    #[error("content doesn't match schema version {CONTENT_VERSION}: {0}")]
+    Schema(String),
+
+    #[error("filesystem error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// One step in the migration chain
+pub struct Migration {
+    /// Version this migration upgrades from (to `from + 1`)
+    pub from: u32,
+    /// What changed, shown by `website migrate`
+    pub description: &'static str,
+    pub apply: fn(&mut Value) -> Result<(), String>,
+}
+
+/// Every migration, in order; `MIGRATIONS[n].from == n`
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    description: "add schema version and give every block an id",
+    apply: add_missing_block_ids,
+}];
+
+/// Content parsed from a file, with the version it was stored at
+#[derive(Debug, Clone)]
+pub struct MigratedContent {
+    pub data: HomepageData,
+    /// Version found in the file (`CONTENT_VERSION` if no migration ran)
+    pub from_version: u32,
+}
+
+impl MigratedContent {
+    /// Whether the stored file is older than the current version
+    pub fn was_migrated(&self) -> bool {
+        self.from_version < CONTENT_VERSION
+    }
+}
+
+/// Parse a content file, migrating it to the current version
+///
+/// # Errors
+///
+/// - `InvalidJson` if the file isn't JSON
+/// - `InvalidVersion` / `NewerVersion` if `version` isn't usable
+/// - `Failed` if a migration step rejects the document
+/// - `Schema` if the migrated JSON doesn't match `HomepageData`; the message
+///   includes the path of the offending field (e.g. `blocks`). Individual
+///   blocks that don't parse are kept as `ContentBlock::Invalid` instead.
+pub fn parse_content(json: &str) -> Result<MigratedContent, MigrationError> {
+    let value: Value = serde_json::from_str(json).map_err(MigrationError::InvalidJson)?;
+    let (value, from_version) = migrate(value)?;
+    let data: HomepageData = serde_path_to_error::deserialize(value)
+        .map_err(|e| MigrationError::Schema(e.to_string()))?;
+    Ok(MigratedContent { data, from_version })
+}
+
+/// Upgrade a content document to `CONTENT_VERSION`
+///
+/// Returns the migrated document and the version it started at.
+pub fn migrate(mut value: Value) -> Result<(Value, u32), MigrationError> {
+    let from_version = match value.get("version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| MigrationError::InvalidVersion(version.clone()))?,
+    };
+    if from_version > CONTENT_VERSION {
+        return Err(MigrationError::NewerVersion(from_version));
+    }
+
+    for migration in &MIGRATIONS[from_version as usize..] {
+        (migration.apply)(&mut value).map_err(|message| MigrationError::Failed {
+            from: migration.from,
+            message,
+        })?;
+        if let Some(object) = value.as_object_mut() {
+            object.insert("version".to_string(), Value::from(migration.from + 1));
+        }
+    }
+
+    Ok((value, from_version))
+}
+
+// ============================================================================
+// Migrations
+// ============================================================================
+
+/// v0 → v1: blocks written before `BlockWithId` have no `id`
+fn add_missing_block_ids(value: &mut Value) -> Result<(), String> {
+    let blocks = value
+        .get_mut("blocks")
+        .and_then(Value::as_array_mut)
+        .ok_or("expected a \"blocks\" array")?;
+    for block in blocks {
+        let block = block
+            .as_object_mut()
+            .ok_or("expected every block to be an object")?;
+        let has_id = block
+            .get("id")
+            .and_then(Value::as_str)
+            .is_some_and(|id| !id.trim().is_empty());
+        if !has_id {
+            block.insert("id".to_string(), Value::from(Uuid::new_v4().to_string()));
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Migrating Files
+// ============================================================================
+
+/// Outcome of migrating one content file
+#[derive(Debug)]
+pub struct FileMigration {
+    pub path: PathBuf,
+    /// Version the file was at, or why it couldn't be migrated
+    pub result: Result<u32, MigrationError>,
+}
+
+/// Migrate every route content file to the current version in place
+///
+/// Files already at `CONTENT_VERSION` and files that don't exist yet are
+/// left untouched. With `dry_run`, nothing is written. A file that fails to
+/// migrate is reported and skipped; the others are still migrated. Variants
+/// for every configured locale are migrated along with their file. Content
+/// ids that don't resolve are skipped (see `migrate_content_references`).
+pub fn migrate_content_files(dry_run: bool) -> Vec<FileMigration> {
+    let locales = load_settings().i18n.locales;
+    let mut seen = HashSet::new();
+    load_routes()
+        .into_iter()
+        .flat_map(|route| route.block_ids)
+        .flat_map(|source| {
+            std::iter::once(None)
+                .chain(locales.iter().map(|locale| Some(locale.as_str())))
+                .filter_map(|locale| content_file_path(&source, locale).ok())
+                .collect::<Vec<_>>()
+        })
+        .filter(|path| path.exists() && seen.insert(path.clone()))
+        .map(|path| {
+            let result = migrate_file(&path, dry_run);
+            FileMigration { path, result }
+        })
+        .collect()
+}
+
+/// A `blockIds` entry of routes.json written as a path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceMigration {
+    pub route: String,
+    /// The entry as stored
+    pub from: String,
+    /// Its content id, or `None` if the path is outside the content directory
+    pub to: Option<String>,
+}
+
+/// Rewrite the path entries of every route's `blockIds` as content ids
+///
+/// Returns one `ReferenceMigration` per entry that isn't an id yet. Entries
+/// that can't be converted are kept as they are. With `dry_run`, or when
+/// there is no routes.json (the default routes already use ids), nothing is
+/// written.
+pub fn migrate_content_references(
+    dry_run: bool,
+) -> Result<Vec<ReferenceMigration>, MigrationError> {
+    let root = content_dir();
+    let mut routes = load_stored_routes();
+    let mut migrations = Vec::new();
+    for route in &mut routes {
+        for block_id in &mut route.block_ids {
+            let id = legacy_content_id(block_id, &root);
+            if id.as_deref() == Some(block_id.as_str()) {
+                continue;
+            }
+            migrations.push(ReferenceMigration {
+                route: route.name.clone(),
+                from: block_id.clone(),
+                to: id.clone(),
+            });
+            if let Some(id) = id {
+                *block_id = id;
+            }
+        }
+    }
+
+    let path = routes_json_path();
+    if !dry_run && path.exists() && migrations.iter().any(|m| m.to.is_some()) {
+        let json = serde_json::to_string_pretty(&routes).map_err(MigrationError::InvalidJson)?;
+        fs::write(path, json)?;
+    }
+    Ok(migrations)
+}
+
+fn migrate_file(path: &Path, dry_run: bool) -> Result<u32, MigrationError> {
+    let contents = fs::read_to_string(path)?;
+    let migrated = parse_content(&contents)?;
+    if migrated.was_migrated() && !dry_run {
+        let json = serde_json::to_string_pretty(&HomepageData::new(migrated.data.blocks))
+            .map_err(MigrationError::InvalidJson)?;
+        fs::write(path, json)?;
+    }
+    Ok(migrated.from_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::{ContentBlock, valid_blocks};
+    use crate::core::persistence::with_data_dir_sync;
+    use tempfile::TempDir;
+
+    /// One stored file per past and current version
+    const FIXTURES: &[(u32, &str)] = &[
+        (0, include_str!("fixtures/content/v0.json")),
+        (1, include_str!("fixtures/content/v1.json")),
+    ];
+
+    #[test]
+    fn test_migrations_form_a_chain() {
+        assert_eq!(MIGRATIONS.len(), CONTENT_VERSION as usize);
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.from as usize, index, "{}", migration.description);
+        }
+    }
+
+    #[test]
+    fn test_every_version_has_a_fixture() {
+        let versions: Vec<u32> = FIXTURES.iter().map(|(v, _)| *v).collect();
+        assert_eq!(versions, (0..=CONTENT_VERSION).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_fixtures_load_at_current_version() {
+        for (version, json) in FIXTURES {
+            let migrated = parse_content(json)
+                .unwrap_or_else(|e| panic!("fixture v{} failed to load: {}", version, e));
+            assert_eq!(migrated.from_version, *version);
+            assert!(!migrated.data.blocks.is_empty(), "fixture v{}", version);
+            let blocks = valid_blocks(&migrated.data.blocks);
+            assert_eq!(
+                blocks.len(),
+                migrated.data.blocks.len(),
+                "fixture v{} has invalid blocks",
+                version
+            );
+
+            let ids: HashSet<&str> = blocks.iter().map(|b| b.id.as_str()).collect();
+            assert_eq!(ids.len(), blocks.len(), "fixture v{}", version);
+            assert!(ids.iter().all(|id| !id.is_empty()), "fixture v{}", version);
+        }
+    }
+
+    #[test]
+    fn test_migration_keeps_existing_ids() {
+        let migrated = parse_content(FIXTURES[0].1).unwrap();
+        let blocks = valid_blocks(&migrated.data.blocks);
+        assert_eq!(blocks[0].id, "550e8400-e29b-41d4-a716-446655440001");
+        assert!(Uuid::parse_str(&blocks[1].id).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_newer_and_invalid_versions() {
+        let newer = format!(r#"{{"version": {}, "blocks": []}}"#, CONTENT_VERSION + 1);
+        assert!(matches!(
+            parse_content(&newer),
+            Err(MigrationError::NewerVersion(_))
+        ));
+        assert!(matches!(
+            parse_content(r#"{"version": "one", "blocks": []}"#),
+            Err(MigrationError::InvalidVersion(_))
+        ));
+        assert!(matches!(
+            parse_content(r#"{"blocks": {}}"#),
+            Err(MigrationError::Failed { from: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_schema_errors_include_field_path() {
+        let error = parse_content(r#"{"version": 1, "blocks": 5}"#)
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("blocks"), "{}", error);
+    }
+
+    #[test]
+    fn test_invalid_blocks_dont_fail_the_document() {
+        let json = r#"{"version": 1, "blocks": [
+            {"id": "a", "type": "Hero", "props": {"headline": 1}},
+            {"id": "b", "type": "Hero", "props": {"headline": "H", "subheadline": "S"}}
+        ]}"#;
+        let migrated = parse_content(json).unwrap();
+        assert!(matches!(
+            &migrated.data.blocks[0],
+            ContentBlock::Invalid(invalid) if invalid.error.starts_with("Hero block")
+        ));
+        assert_eq!(valid_blocks(&migrated.data.blocks)[0].id, "b");
+    }
+
+    #[test]
+    fn test_migrate_content_files_rewrites_old_files() {
+        let dir = TempDir::new().unwrap();
+        let content = dir.path().join("content");
+        fs::create_dir(&content).unwrap();
+        let old = content.join("old.json");
+        let current = content.join("current.json");
+        fs::write(&old, FIXTURES[0].1).unwrap();
+        fs::write(&current, FIXTURES[CONTENT_VERSION as usize].1).unwrap();
+        let routes = serde_json::json!([
+            { "path": "/", "name": "homepage", "blockIds": ["old"] },
+            { "path": "/b", "name": "b", "blockIds": ["current", "missing"] },
+        ]);
+        fs::write(dir.path().join("routes.json"), routes.to_string()).unwrap();
+        let current_before = fs::read_to_string(&current).unwrap();
+
+        let dry_run = with_data_dir_sync(dir.path().to_path_buf(), || migrate_content_files(true));
+        assert_eq!(dry_run.len(), 2);
+        assert_eq!(fs::read_to_string(&old).unwrap(), FIXTURES[0].1);
+
+        let results = with_data_dir_sync(dir.path().to_path_buf(), || migrate_content_files(false));
+        let versions: Vec<u32> = results.into_iter().map(|r| r.result.unwrap()).collect();
+        assert_eq!(versions, vec![0, CONTENT_VERSION]);
+
+        let rewritten: Value = serde_json::from_str(&fs::read_to_string(&old).unwrap()).unwrap();
+        assert_eq!(rewritten["version"], CONTENT_VERSION);
+        assert_eq!(fs::read_to_string(&current).unwrap(), current_before);
+    }
+
+    #[test]
+    fn test_migrate_content_files_includes_locale_variants() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("content")).unwrap();
+        let source = dir.path().join("content/homepage.json");
+        let variant = dir.path().join("content/homepage.de.json");
+        fs::write(&source, FIXTURES[0].1).unwrap();
+        fs::write(&variant, FIXTURES[0].1).unwrap();
+        let routes =
+            serde_json::json!([{ "path": "/", "name": "homepage", "blockIds": ["homepage"] }]);
+        fs::write(dir.path().join("routes.json"), routes.to_string()).unwrap();
+        fs::write(
+            dir.path().join("settings.json"),
+            r#"{"i18n": {"default_locale": "en", "locales": ["de"]}}"#,
+        )
+        .unwrap();
+
+        let results = with_data_dir_sync(dir.path().to_path_buf(), || migrate_content_files(false));
+        let paths: Vec<_> = results.iter().map(|r| r.path.clone()).collect();
+        assert_eq!(paths, vec![source, variant.clone()]);
+
+        let rewritten: Value =
+            serde_json::from_str(&fs::read_to_string(&variant).unwrap()).unwrap();
+        assert_eq!(rewritten["version"], CONTENT_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_content_references_rewrites_paths() {
+        let dir = TempDir::new().unwrap();
+        let routes = serde_json::json!([
+            { "path": "/", "name": "homepage", "blockIds": ["../data/content/homepage.json"] },
+            { "path": "/foo", "name": "foo", "blockIds": [
+                "header", dir.path().join("content/foo.json"), "/etc/passwd.json"
+            ]},
+        ]);
+        let routes_path = dir.path().join("routes.json");
+        fs::write(&routes_path, routes.to_string()).unwrap();
+        let migrate = |dry_run| {
+            with_data_dir_sync(dir.path().to_path_buf(), || {
+                migrate_content_references(dry_run).unwrap()
+            })
+        };
+
+        let dry_run = migrate(true);
+        let targets: Vec<_> = dry_run.iter().map(|m| m.to.as_deref()).collect();
+        assert_eq!(targets, vec![Some("homepage"), Some("foo"), None]);
+        assert_eq!(
+            fs::read_to_string(&routes_path).unwrap(),
+            routes.to_string()
+        );
+
+        migrate(false);
+        let rewritten: Value =
+            serde_json::from_str(&fs::read_to_string(&routes_path).unwrap()).unwrap();
+        assert_eq!(rewritten[0]["blockIds"], serde_json::json!(["homepage"]));
+        assert_eq!(
+            rewritten[1]["blockIds"],
+            serde_json::json!(["header", "foo", "/etc/passwd.json"])
+        );
+        let again = migrate(false);
+        assert_eq!(again.len(), 1, "only the unconvertible entry is left");
+    }
+}
@@ -2,9 +2,26 @@
 ///
 /// This module provides the foundational types and traits used across all features:
 ///
+/// - **a11y**: Accessibility linter for rendered markup
+/// - **archive**: Whole-site export/import as a versioned `.tar.gz` archive
+/// - **arbitrary**: Proptest strategies for every content type (tests only)
+/// - **audit**: Append-only log of admin actions with before/after file hashes
 /// - **block**: Type-safe content block system with enum variants
+/// - **i18n**: Content locales, locale negotiation and translated content files
+/// - **limits**: Request size limits, timeouts and per-client rate limiting
+/// - **media**: Media library storage, image variants and metadata
+/// - **migrations**: Content schema versions and the migrations between them
+/// - **options**: Per-block style variants and layout options
 /// - **persistence**: JSON file operations for homepage data
+/// - **presence**: Who is editing each route, and live publish notifications
 /// - **render**: Trait for components that render to Maud Markup
+/// - **schedule**: Scheduled publishing and expiry of routes and blocks
+/// - **security_headers**: Security response headers and the per-request CSP nonce
+/// - **sessions**: Signed-in admin sessions
+/// - **settings**: Site-wide settings and design tokens (CSS custom properties)
+/// - **tokens**: Scoped API tokens for scripts and CI, stored hashed
+/// - **totp**: Time-based one-time passwords and recovery codes for two-factor sign-in
+/// - **users**: Admin users, roles, per-route grants and password hashes
 ///
 /// # Philosophy
 ///
@@ -23,12 +40,32 @@
 ///
 /// Features depend on core, but core never depends on features (it imports
 /// feature schemas only to re-export them in the Block enum).
+pub mod a11y;
+#[cfg(test)]
+pub mod arbitrary;
+pub mod archive;
+pub mod audit;
 pub mod block;
+pub mod i18n;
+pub mod limits;
+pub mod media;
+pub mod migrations;
+pub mod options;
 pub mod persistence;
+pub mod presence;
 pub mod render;
+pub mod schedule;
+pub mod security_headers;
+pub mod sessions;
+pub mod settings;
+pub mod tokens;
+pub mod totp;
+pub mod users;
 
 // Re-export commonly used types for convenience
 // Props are re-exported from block module (which imports them from features)
-pub use block::{render_block, BlockWithId};
-pub use persistence::{load_blocks, load_homepage_blocks, load_routes, save_blocks, Route};
+pub use block::{BlockWithId, ContentBlock, render_block, render_content_block};
+pub use persistence::{
+    Route, load_blocks, load_content, load_homepage_blocks, load_routes, save_blocks,
+};
 pub use render::Render;
@@ -0,0 +1,416 @@
+/// Per-block style variants and layout options
+///
+/// Every block instance carries an optional `options` envelope next to its
+/// `type` and `props`. Options are presentation-only and shared by all block
+/// types, so each template honors them through the same helpers instead of
+/// growing its own ad-hoc props.
+///
+/// # JSON Format
+///
+/// ```json
+/// {
+///   "id": "550e8400-e29b-41d4-a716-446655440002",
+///   "type": "Hero",
+///   "props": { "...": "..." },
+///   "options": {
+///     "variant": "inverted",
+///     "alignment": "start",
+///     "background": "primary",
+///     "spacing": "large",
+///     "anchor": "about",
+///     "visibility": "desktop_only"
+///   }
+/// }
+/// ```
+///
+/// All fields are optional; omitted fields use the template's defaults and
+/// an all-default envelope is not written back to disk.
+///
+/// # Rendering
+///
+/// Templates call `BlockOptions::classes` for the root element's `class`
+/// attribute and `BlockOptions::anchor` for its `id`. The utility classes are
+/// defined once in `/assets/styles.css` in terms of the theme tokens.
+/// `Visibility::Hidden` blocks are skipped entirely by `render_block`.
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use strum::{Display, EnumIter, EnumString, IntoEnumIterator, VariantNames};
+
+/// Visual treatment of a block
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    VariantNames,
+    EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BlockVariant {
+    #[default]
+    Default,
+    /// Light surface with dark text (the inverse of the default dark blocks)
+    Inverted,
+    /// Subdued background for secondary content
+    Muted,
+    /// Brand color background for emphasis
+    Accent,
+}
+
+/// Horizontal alignment of block content
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    VariantNames,
+    EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Alignment {
+    /// Use the template's own alignment
+    #[default]
+    Default,
+    Start,
+    Center,
+    End,
+}
+
+/// Background color drawn from the theme tokens
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    VariantNames,
+    EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ColorToken {
+    Background,
+    Surface,
+    Primary,
+    Text,
+}
+
+/// Vertical padding around the block
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    VariantNames,
+    EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Spacing {
+    None,
+    Small,
+    /// Use the template's own padding
+    #[default]
+    Default,
+    Large,
+}
+
+/// When the block is shown
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    EnumString,
+    Display,
+    VariantNames,
+    EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Visibility {
+    #[default]
+    Visible,
+    /// Kept in content but not rendered publicly
+    Hidden,
+    MobileOnly,
+    DesktopOnly,
+}
+
+/// Class-name fragment for an options value (`mobile_only` → `mobile-only`)
+fn css(value: impl std::fmt::Display) -> String {
+    value.to_string().replace('_', "-")
+}
+
+/// Typed options envelope shared by every block type
+///
+/// Unknown keys are refused, as the schema's `additionalProperties: false`
+/// says. A stored block with one loads as an invalid block (see
+/// `core::block`), so the key is kept as written instead of being dropped on
+/// the next save.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BlockOptions {
+    pub variant: BlockVariant,
+    pub alignment: Alignment,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background: Option<ColorToken>,
+    pub spacing: Spacing,
+    /// Fragment identifier for in-page links (`#about`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
+    pub visibility: Visibility,
+}
+
+impl BlockOptions {
+    /// Whether every option has its default value
+    ///
+    /// Used to omit the envelope when serializing.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the block should be rendered at all
+    pub fn is_rendered(&self) -> bool {
+        self.visibility != Visibility::Hidden
+    }
+
+    /// Class attribute for a block's root element
+    ///
+    /// Always includes `base` and the shared `block` class, followed by one
+    /// modifier class per non-default option:
+    ///
+    /// ```text
+    /// hero-block block block--variant-inverted block--align-start block--bg-primary
+    /// ```
+    pub fn classes(&self, base: &str) -> String {
+        let mut classes = vec![base.to_string(), "block".to_string()];
+        if self.variant != BlockVariant::Default {
+            classes.push(format!("block--variant-{}", css(self.variant)));
+        }
+        if self.alignment != Alignment::Default {
+            classes.push(format!("block--align-{}", css(self.alignment)));
+        }
+        if let Some(background) = self.background {
+            classes.push(format!("block--bg-{}", css(background)));
+        }
+        if self.spacing != Spacing::Default {
+            classes.push(format!("block--spacing-{}", css(self.spacing)));
+        }
+        match self.visibility {
+            Visibility::MobileOnly | Visibility::DesktopOnly => {
+                classes.push(format!("block--{}", css(self.visibility)));
+            }
+            Visibility::Visible | Visibility::Hidden => {}
+        }
+        classes.join(" ")
+    }
+
+    /// Sanitized anchor id, if one is set and valid
+    ///
+    /// Valid anchors start with an ASCII letter and contain only ASCII
+    /// letters, digits, `-` and `_`. Invalid anchors are ignored rather than
+    /// emitted, so they can never break out of the attribute or collide with
+    /// generated ids.
+    pub fn anchor(&self) -> Option<&str> {
+        self.anchor.as_deref().filter(|a| is_valid_anchor(a))
+    }
+
+    /// Preview options for each style variant, used by the story system
+    pub fn variant_previews() -> Vec<(&'static str, BlockOptions)> {
+        BlockVariant::VARIANTS
+            .iter()
+            .zip(BlockVariant::iter())
+            .map(|(id, variant)| {
+                (
+                    *id,
+                    BlockOptions {
+                        variant,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+fn is_valid_anchor(anchor: &str) -> bool {
+    let mut chars = anchor.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn enum_schema<T: VariantNames>(description: &str) -> Value {
+    json!({
+        "type": "string",
+        "enum": T::VARIANTS,
+        "description": description,
+    })
+}
+
+/// JSON Schema (draft 2020-12) for the `options` envelope
+///
+/// Served at `/admin/api/schema/block-options` so editors and tooling can
+/// validate and offer the allowed values. Enum values are generated from the
+/// Rust types, and `BlockOptions` refuses keys the schema doesn't list.
+pub fn block_options_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "BlockOptions",
+        "type": "object",
+        "additionalProperties": false,
+        "properties": {
+            "variant": enum_schema::<BlockVariant>("Visual treatment of the block"),
+            "alignment": enum_schema::<Alignment>("Horizontal alignment of the content"),
+            "background": enum_schema::<ColorToken>("Background color from the theme palette"),
+            "spacing": enum_schema::<Spacing>("Vertical padding around the block"),
+            "anchor": {
+                "type": "string",
+                "pattern": "^[A-Za-z][A-Za-z0-9_-]*$",
+                "description": "Element id for in-page links"
+            },
+            "visibility": enum_schema::<Visibility>("When the block is shown"),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::ContentBlock;
+
+    #[test]
+    fn test_default_options_only_add_shared_class() {
+        assert_eq!(
+            BlockOptions::default().classes("hero-block"),
+            "hero-block block"
+        );
+    }
+
+    #[test]
+    fn test_classes_for_all_options() {
+        let options = BlockOptions {
+            variant: BlockVariant::Inverted,
+            alignment: Alignment::Start,
+            background: Some(ColorToken::Primary),
+            spacing: Spacing::Large,
+            anchor: Some("about".to_string()),
+            visibility: Visibility::MobileOnly,
+        };
+        assert_eq!(
+            options.classes("hero-block"),
+            "hero-block block block--variant-inverted block--align-start \
+             block--bg-primary block--spacing-large block--mobile-only"
+        );
+        assert_eq!(options.anchor(), Some("about"));
+    }
+
+    #[test]
+    fn test_invalid_anchor_is_dropped() {
+        for anchor in ["", "1st", "a b", "x\" onclick=\"y", "caf\u{e9}"] {
+            let options = BlockOptions {
+                anchor: Some(anchor.to_string()),
+                ..Default::default()
+            };
+            assert_eq!(options.anchor(), None, "anchor {:?}", anchor);
+        }
+    }
+
+    #[test]
+    fn test_options_round_trip_snake_case() {
+        let json = r#"{"variant": "accent", "visibility": "desktop_only"}"#;
+        let options: BlockOptions = serde_json::from_str(json).unwrap();
+        assert_eq!(options.variant, BlockVariant::Accent);
+        assert_eq!(options.visibility, Visibility::DesktopOnly);
+        assert_eq!(options.alignment, Alignment::Default);
+
+        let written = serde_json::to_value(&options).unwrap();
+        assert_eq!(written["visibility"], "desktop_only");
+        assert!(written.get("anchor").is_none());
+    }
+
+    #[test]
+    fn test_schema_enums_match_serde() {
+        let schema = block_options_schema();
+        for variant in BlockVariant::iter() {
+            let id = serde_json::to_value(variant).unwrap();
+            let allowed = schema["properties"]["variant"]["enum"].as_array().unwrap();
+            assert!(allowed.contains(&id));
+        }
+        let visibility = schema["properties"]["visibility"]["enum"]
+            .as_array()
+            .unwrap();
+        assert_eq!(visibility.len(), Visibility::iter().count());
+    }
+
+    #[test]
+    fn test_schema_properties_match_fields() {
+        let options = BlockOptions {
+            background: Some(ColorToken::Surface),
+            anchor: Some("about".to_string()),
+            ..Default::default()
+        };
+        let fields = serde_json::to_value(options).unwrap();
+        let fields: Vec<_> = fields.as_object().unwrap().keys().collect();
+        let schema = block_options_schema();
+        let properties: Vec<_> = schema["properties"].as_object().unwrap().keys().collect();
+        assert_eq!(fields, properties);
+    }
+
+    #[test]
+    fn test_unknown_options_keep_the_block_as_written() {
+        let unknown = r#"{"variant": "accent", "shadow": "large"}"#;
+        let error = serde_json::from_str::<BlockOptions>(unknown).unwrap_err();
+        assert!(error.to_string().contains("unknown field `shadow`"));
+
+        let raw = serde_json::json!({
+            "id": "b1", "type": "Hero",
+            "props": { "headline": "Hi", "subheadline": "" },
+            "options": { "shadow": "large" }
+        });
+        let block: ContentBlock = serde_json::from_value(raw.clone()).unwrap();
+        assert!(block.as_valid().is_none());
+        assert_eq!(serde_json::to_value(&block).unwrap(), raw);
+    }
+
+    #[test]
+    fn test_strum_names_match_serde() {
+        for visibility in Visibility::iter() {
+            let json = serde_json::to_value(visibility).unwrap();
+            assert_eq!(json, visibility.to_string());
+            assert_eq!(visibility.to_string().parse::<Visibility>(), Ok(visibility));
+        }
+        assert_eq!(
+            Visibility::VARIANTS,
+            ["visible", "hidden", "mobile_only", "desktop_only"]
+        );
+    }
+}
@@ -13,17 +13,54 @@
 /// # File Locations
 ///
 /// All paths are absolute, computed from the workspace root using `CARGO_MANIFEST_DIR`:
-/// - Route content: `{workspace_root}/data/content/{route_name}.json`
+/// - Route content: `{workspace_root}/data/content/{id}.json`, where `id` is
+///   a content id from the route's `blockIds` (see "Content Ids" below)
 /// - Routes data: `{workspace_root}/data/routes.json`
+/// - Media library: `{workspace_root}/data/media/` (see `core::media`)
 ///
 /// This ensures the application works regardless of the current working directory.
 /// The workspace root is determined at compile time (zero runtime overhead).
+///
+/// # Data Directory Override
+///
+/// The router built by `app::app` serves every request inside
+/// `with_data_dir(state.data_dir, ...)`, so `data_dir()` (and every path
+/// derived from it) points at the directory in `AppState`. Outside such a
+/// scope it falls back to `{workspace_root}/data`. The override is a tokio
+/// task-local, so concurrent apps with different directories (e.g. the
+/// integration tests) don't interfere; blocking work moved to another thread
+/// must re-enter the scope with `with_data_dir_sync`.
+///
+/// # Content Ids
+///
+/// A route's `blockIds` name its content files by id, e.g. `homepage` or
+/// `shared-header`, never by path. `content_file_path` resolves an id to
+/// `{id}.json` inside the content directory and refuses anything that could
+/// reach outside it:
+/// - Ids are file names without the extension: ASCII letters, digits, `-`
+///   and `_`, starting with a letter or digit. Absolute paths, `/`, `..`,
+///   subdirectories and `.` (reserved for locale variants such as
+///   `homepage.de.json`) are rejected (`ContentError::InvalidContentId`).
+/// - The resolved file is canonicalized, so a symlink in the content
+///   directory pointing elsewhere is rejected too
+///   (`ContentError::OutsideContentRoot`).
+///
+/// Older routes.json files listed paths such as
+/// `../data/content/homepage.json`. `load_routes` converts paths to `.json`
+/// files in the content directory to their ids in memory, and
+/// `website migrate` rewrites the file (see `core::migrations`).
 use std::fs;
+use std::future::Future;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
 
+use uuid::Uuid;
 
-use crate::core::block::BlockWithId;
+use crate::core::block::{BlockWithId, ContentBlock, valid_blocks};
+use crate::core::i18n::localized_path;
+use crate::core::migrations::parse_content;
+use crate::core::schedule::Schedule;
 use crate::pages::homepage::HomepageData;
 
 /// Get the workspace root directory (one level up from the website package)
@@ -49,11 +86,51 @@ fn workspace_root() -> PathBuf {
         .to_path_buf()
 }
 
+tokio::task_local! {
+    static DATA_DIR: PathBuf;
+}
+
+/// Get the default data directory (`{workspace_root}/data`)
+pub fn default_data_dir() -> PathBuf {
+    workspace_root().join("data")
+}
+
+/// Get the data directory for the current request
+///
+/// Everything the admin can change lives under this directory: routes.json,
+/// route content files and uploaded media. Returns the directory set by
+/// `with_data_dir` when called inside one, `default_data_dir()` otherwise.
+pub fn data_dir() -> PathBuf {
+    DATA_DIR
+        .try_with(Clone::clone)
+        .unwrap_or_else(|_| default_data_dir())
+}
+
+/// Run a future with `data_dir()` pointing at `dir`
+pub async fn with_data_dir<F: Future>(dir: PathBuf, future: F) -> F::Output {
+    DATA_DIR.scope(dir, future).await
+}
+
+/// Run a closure with `data_dir()` pointing at `dir`
+///
+/// For work handed to `spawn_blocking`, which doesn't inherit the scope.
+pub fn with_data_dir_sync<R>(dir: PathBuf, f: impl FnOnce() -> R) -> R {
+    DATA_DIR.sync_scope(dir, f)
+}
+
 /// Get the path to routes.json in the workspace data directory
-fn routes_json_path() -> PathBuf {
-    workspace_root().join("data").join("routes.json")
+pub fn routes_json_path() -> PathBuf {
+    data_dir().join("routes.json")
 }
 
+/// Directory holding route content files; content ids resolve inside it
+pub fn content_dir() -> PathBuf {
+    data_dir().join(CONTENT_DIR)
+}
+
+/// Name of the content directory inside the data directory
+const CONTENT_DIR: &str = "content";
+
 /// Route definition
 ///
 /// Represents a route in the application with its path, name, and associated content.
@@ -63,7 +140,10 @@ fn routes_json_path() -> PathBuf {
 ///
 /// - `path`: The URL path (acts as primary key), e.g., "/", "/foo"
 /// - `name`: The route name used in admin URLs, e.g., "homepage", "foo"
-/// - `block_ids`: Array of content file paths for this route, e.g., ["../data/content/homepage.json"]
+/// - `block_ids`: Content ids of this route's content files, e.g., ["homepage"]
+/// - `noindex`: Ask search engines not to index this route (omitted from sitemap.xml)
+/// - `exclude_from_sitemap`: Leave the route out of sitemap.xml while still allowing indexing
+/// - `schedule`: Publish window; outside it the route is not found (see `core::schedule`)
 ///
 /// # Example
 ///
@@ -71,53 +151,185 @@ fn routes_json_path() -> PathBuf {
 /// {
 ///   "path": "/",
 ///   "name": "homepage",
-///   "blockIds": ["../data/content/homepage.json"]
+///   "blockIds": ["homepage"]
+/// }
+/// ```
+///
+/// The SEO flags are optional and only written when set:
+///
+/// ```json
+/// {
+///   "path": "/foo",
+///   "name": "foo",
+///   "blockIds": ["foo"],
+///   "noindex": true,
+///   "excludeFromSitemap": true,
+///   "schedule": { "publish_at": "2026-03-01T09:00:00Z" }
 /// }
 /// ```
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize, PartialEq, Eq)]
 pub struct Route {
     pub path: String,
     pub name: String,
 
-    /// Content file paths associated with this route
+    /// Content ids of the files this route is composed from, in order
     /// Uses camelCase "blockIds" in JSON for consistency with frontend conventions
     #[serde(rename = "blockIds")]
     pub block_ids: Vec<String>,
+
+    /// Emit `<meta name="robots" content="noindex">` and skip the sitemap
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub noindex: bool,
+
+    /// Skip this route when generating sitemap.xml
+    #[serde(
+        default,
+        rename = "excludeFromSitemap",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub exclude_from_sitemap: bool,
+
+    /// Publish window of the whole route
+    #[serde(default, skip_serializing_if = "Schedule::is_default")]
+    pub schedule: Schedule,
 }
 
-/// Get the content file path for a given route name
+/// Get the content ids for a given route name
 ///
-/// Looks up the route in routes.json and returns the first blockId (content file path).
+/// Looks up the route in routes.json and returns its blockIds (content ids)
+/// in order. The ids aren't resolved; see `content_file_path`.
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Route name is not found in routes.json
 /// - Route has no blockIds
-/// - File path is invalid
-fn get_content_path(route_name: &str) -> Result<PathBuf, String> {
-    let routes = load_routes();
-
-    let route = routes
-        .iter()
+pub fn get_content_ids(route_name: &str) -> Result<Vec<String>, ContentError> {
+    let route = load_routes()
+        .into_iter()
         .find(|r| r.name == route_name)
-        .ok_or_else(|| format!("Route '{}' not found in routes.json", route_name))?;
+        .ok_or_else(|| ContentError::UnknownRoute(route_name.to_string()))?;
+
+    if route.block_ids.is_empty() {
+        return Err(ContentError::NoContentFiles(route_name.to_string()));
+    }
+
+    Ok(route.block_ids)
+}
 
-    let content_path = route
+/// Find a route by its URL path
+///
+/// Returns `None` when no route in routes.json matches the path exactly.
+pub fn find_route_by_path(path: &str) -> Option<Route> {
+    load_routes().into_iter().find(|r| r.path == path)
+}
+
+/// Get the most recent modification time of a route's content files
+///
+/// Used for `<lastmod>` in sitemap.xml. Files that don't exist or whose
+/// metadata can't be read (or whose id doesn't resolve) are ignored;
+/// returns `None` if no file has a readable modification time.
+pub fn content_last_modified(route: &Route) -> Option<SystemTime> {
+    route
         .block_ids
-        .first()
-        .ok_or_else(|| format!("Route '{}' has no blockIds", route_name))?;
+        .iter()
+        .filter_map(|id| content_file_path(id, None).ok())
+        .filter_map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        .max()
+}
+
+/// The blocks stored in one of a route's content files
+///
+/// A route composes its page from every file in `blockIds`, in order (e.g. a
+/// shared header file followed by the page body). The editor shows and
+/// saves content grouped this way so each block goes back to its own file.
+///
+/// # JSON Format
+///
+/// ```json
+/// { "source": "header", "blocks": [ ... ] }
+/// ```
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ContentFile {
+    /// The file's content id, as listed in the route's `blockIds`
+    pub source: String,
+    pub blocks: Vec<ContentBlock>,
+}
+
+/// Errors that can occur while looking up or saving route content
+#[derive(Debug, thiserror::Error)]
+pub enum ContentError {
+    #[error("Route '{0}' not found in routes.json")]
+    UnknownRoute(String),
+
+    #[error("Route '{0}' has no blockIds")]
+    NoContentFiles(String),
+
+    #[error("Route '{route}' has {count} content files; save each file's blocks separately")]
+    MultipleContentFiles { route: String, count: usize },
+
+    #[error("'{file}' is not a content file of route '{route}'")]
+    UnknownContentFile { route: String, file: String },
+
+    #[error("Content file '{0}' is listed more than once")]
+    DuplicateContentFile(String),
+
+    #[error(
+        "'{0}' is not a content id (use letters, digits, '-' and '_', without a path or extension)"
+    )]
+    InvalidContentId(String),
+
+    #[error("Content file '{0}' resolves outside the content directory")]
+    OutsideContentRoot(String),
 
-    // Paths in routes.json are relative to the website/ directory where cargo runs from
-    // (e.g., "../data/content/homepage.json" goes up to project root, then into data/content/)
-    // When running `cd website && cargo run`, the working directory is website/, so we use ../data/
-    Ok(PathBuf::from(content_path))
+    #[error("failed to serialize content: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("failed to write content: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Load the stored blocks of every content file of a route, in order
+///
+/// Each entry in the route's `blockIds` yields one `ContentFile`; a file that
+/// is missing or can't be parsed yields an empty block list (see
+/// `load_content` for the fallback behavior).
+pub fn load_content_files(route_name: &str) -> Vec<ContentFile> {
+    load_content_files_in(route_name, None)
+}
+
+/// Load a route's content files in a locale (`None` for the default locale)
+///
+/// Reads each file's locale variant (see `core::i18n`) without falling back
+/// to the default locale; `source` is still the id listed in `blockIds`. An
+/// id that doesn't resolve is logged and yields an empty block list.
+pub fn load_content_files_in(route_name: &str, locale: Option<&str>) -> Vec<ContentFile> {
+    match get_content_ids(route_name) {
+        Ok(ids) => ids
+            .into_iter()
+            .map(|source| ContentFile {
+                blocks: match content_file_path(&source, locale) {
+                    Ok(path) => load_content_file(&path),
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        vec![]
+                    }
+                },
+                source,
+            })
+            .collect(),
+        Err(e) => {
+            eprintln!("{}", e);
+            vec![]
+        }
+    }
 }
 
-/// Load blocks for any route by name
+/// Load the stored blocks for any route by name, including invalid ones
 ///
 /// This is the generic version of load_homepage_blocks() that works for any route
-/// defined in routes.json.
+/// defined in routes.json. Blocks from all of the route's content files are
+/// concatenated in `blockIds` order.
 ///
 /// # Parameters
 ///
@@ -128,27 +340,42 @@ fn get_content_path(route_name: &str) -> Result<PathBuf, String> {
 /// - Route not found: Returns empty vec, logs error to stderr
 /// - File not found: Returns empty vec
 /// - Invalid JSON: Returns empty vec, logs error to stderr
-/// - Valid JSON: Returns parsed blocks
+/// - Valid JSON: Returns parsed blocks; blocks that don't parse are kept as
+///   `ContentBlock::Invalid` (logged to stderr) so the rest still render
+///
+/// Files from older content schema versions are migrated in memory (see
+/// `core::migrations`); run `website migrate` to rewrite them on disk.
 ///
 /// # Examples
 ///
 /// ```
-/// let homepage_blocks = load_blocks("homepage");
-/// let foo_blocks = load_blocks("foo");
+/// let homepage_blocks = load_content("homepage");
+/// let foo_blocks = load_content("foo");
 /// ```
-pub fn load_blocks(route_name: &str) -> Vec<BlockWithId> {
-    // Get the content file path for this route
-    let content_path = match get_content_path(route_name) {
-        Ok(path) => path,
-        Err(e) => {
-            eprintln!("{}", e);
-            return vec![];
-        }
-    };
+pub fn load_content(route_name: &str) -> Vec<ContentBlock> {
+    load_content_files(route_name)
+        .into_iter()
+        .flat_map(|file| file.blocks)
+        .collect()
+}
 
-    match fs::read_to_string(&content_path) {
-        Ok(contents) => match serde_json::from_str::<HomepageData>(&contents) {
-            Ok(data) => data.blocks,
+/// Load the stored blocks of a single content file
+fn load_content_file(content_path: &Path) -> Vec<ContentBlock> {
+    match fs::read_to_string(content_path) {
+        Ok(contents) => match parse_content(&contents) {
+            Ok(migrated) => {
+                for block in &migrated.data.blocks {
+                    if let ContentBlock::Invalid(invalid) = block {
+                        eprintln!(
+                            "Skipping invalid block {} in {}: {}",
+                            invalid.id().unwrap_or("(no id)"),
+                            content_path.display(),
+                            invalid.error
+                        );
+                    }
+                }
+                migrated.data.blocks
+            }
             Err(e) => {
                 eprintln!("Failed to parse {}: {}", content_path.display(), e);
                 vec![]
@@ -164,36 +391,212 @@ pub fn load_blocks(route_name: &str) -> Vec<BlockWithId> {
     }
 }
 
+/// Load the valid blocks for any route by name
+///
+/// Same as `load_content` without the blocks that failed to parse, for
+/// callers that only work with typed blocks (media usage, linting).
+pub fn load_blocks(route_name: &str) -> Vec<BlockWithId> {
+    valid_blocks(&load_content(route_name))
+}
+
 /// Save blocks for any route by name
 ///
 /// This is the generic version of save_homepage_blocks() that works for any route
-/// defined in routes.json.
+/// defined in routes.json. The route must have a single content file; routes
+/// composed from several files are saved with `save_content_files`.
 ///
 /// # Parameters
 ///
 /// - `route_name`: The route name (e.g., "homepage", "foo")
 /// - `blocks`: The blocks to save
+/// - `locale`: Write the file's variant for this locale (`None` for the
+///   default locale, see `core::i18n`)
 ///
 /// # Errors
 ///
 /// Returns an error if:
 /// - Route name is not found in routes.json
+/// - Route has no or several content files
+/// - The content id doesn't resolve inside the content directory
 /// - JSON serialization fails
 /// - File write fails (disk full, permissions, etc.)
 ///
 /// # Examples
 ///
 /// ```
-/// save_blocks("homepage", &homepage_blocks)?;
-/// save_blocks("foo", &foo_blocks)?;
+/// save_blocks("homepage", &homepage_blocks, None)?;
+/// save_blocks("foo", &foo_blocks, Some("de"))?;
 /// ```
 pub fn save_blocks(
     route_name: &str,
-    blocks: &[BlockWithId],
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Get the content file path for this route
-    let content_path = get_content_path(route_name)?;
+    blocks: &[ContentBlock],
+    locale: Option<&str>,
+) -> Result<(), ContentError> {
+    let ids = get_content_ids(route_name)?;
+    match ids.as_slice() {
+        [source] => write_content_file(&content_file_path(source, locale)?, blocks),
+        _ => Err(ContentError::MultipleContentFiles {
+            route: route_name.to_string(),
+            count: ids.len(),
+        }),
+    }
+}
 
+/// Save the blocks of some or all of a route's content files
+///
+/// Every `source` must be one of the route's `blockIds`; files of the route
+/// that aren't listed are left untouched. Nothing is written unless every
+/// source is valid. With a `locale`, each file's variant for that locale is
+/// written instead.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Route name is not found in routes.json
+/// - A source isn't one of the route's content files, or is listed twice
+/// - A content id doesn't resolve inside the content directory
+/// - JSON serialization fails
+/// - File write fails (disk full, permissions, etc.)
+pub fn save_content_files(
+    route_name: &str,
+    files: &[ContentFile],
+    locale: Option<&str>,
+) -> Result<(), ContentError> {
+    check_content_files(route_name, files)?;
+    let paths = files
+        .iter()
+        .map(|file| content_file_path(&file.source, locale))
+        .collect::<Result<Vec<_>, _>>()?;
+    for (file, path) in files.iter().zip(paths) {
+        write_content_file(&path, &file.blocks)?;
+    }
+    Ok(())
+}
+
+/// Check that every `source` is one of the route's `blockIds`, listed once
+pub fn check_content_files(route_name: &str, files: &[ContentFile]) -> Result<(), ContentError> {
+    let ids = get_content_ids(route_name)?;
+    for (index, file) in files.iter().enumerate() {
+        if !ids.contains(&file.source) {
+            return Err(ContentError::UnknownContentFile {
+                route: route_name.to_string(),
+                file: file.source.clone(),
+            });
+        }
+        if files[..index].iter().any(|f| f.source == file.source) {
+            return Err(ContentError::DuplicateContentFile(file.source.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// Path of a content file, or of its variant for a non-default locale
+///
+/// Resolves the content id inside the content directory (see "Content Ids"
+/// in the module docs). The file doesn't have to exist yet.
+///
+/// # Errors
+///
+/// - `InvalidContentId`: `id` (or `locale`) isn't a plain file name
+/// - `OutsideContentRoot`: the file is a symlink leading out of the content
+///   directory
+/// - `Io`: the file or directory exists but can't be resolved
+pub fn content_file_path(id: &str, locale: Option<&str>) -> Result<PathBuf, ContentError> {
+    let locale_is_valid = locale.is_none_or(|locale| {
+        !locale.is_empty()
+            && locale
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    if !is_content_id(id) || !locale_is_valid {
+        return Err(ContentError::InvalidContentId(id.to_string()));
+    }
+
+    let root = content_dir();
+    let path = root.join(content_file_name(id, locale));
+    if escapes_root(&root, &path)? {
+        return Err(ContentError::OutsideContentRoot(id.to_string()));
+    }
+    Ok(path)
+}
+
+/// File name of a content id in the content directory, e.g. `homepage.de.json`
+pub fn content_file_name(id: &str, locale: Option<&str>) -> String {
+    match locale {
+        Some(locale) => localized_path(&format!("{}.json", id), locale),
+        None => format!("{}.json", id),
+    }
+}
+
+/// Whether `id` is a valid content id
+///
+/// A file name without the `.json` extension: ASCII letters, digits, `-`
+/// and `_`, starting with a letter or digit. No `.`, which separates the
+/// locale in variant file names: an id `homepage.de` would be stored as
+/// `homepage.de.json`, the German variant of `homepage`.
+pub fn is_content_id(id: &str) -> bool {
+    id.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+}
+
+/// Whether `path`, a file directly inside `root`, resolves outside it
+///
+/// A file that doesn't exist can't escape (its parent is `root` itself),
+/// except a dangling symlink, which could be created through.
+fn escapes_root(root: &Path, path: &Path) -> io::Result<bool> {
+    let resolved = match path.canonicalize() {
+        Ok(resolved) => resolved,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            return Ok(path.symlink_metadata().is_ok());
+        }
+        Err(e) => return Err(e),
+    };
+    Ok(!resolved.starts_with(root.canonicalize()?))
+}
+
+/// Content id of a `blockIds` entry written as a file path
+///
+/// Before content ids, routes.json listed paths relative to `website/` (e.g.
+/// `../data/content/homepage.json`), absolute paths, or in archives paths
+/// like `content/homepage.json`. A path to a `.json` file directly in
+/// `content_root` converts to its id; entries that already are ids are
+/// returned unchanged. Any other path returns `None`.
+pub fn legacy_content_id(entry: &str, content_root: &Path) -> Option<String> {
+    if is_content_id(entry) {
+        return Some(entry.to_string());
+    }
+
+    let path = Path::new(entry);
+    let file = if path.is_absolute() {
+        path.strip_prefix(content_root).ok()?.to_path_buf()
+    } else {
+        let relative: PathBuf = path
+            .components()
+            .skip_while(|c| matches!(c, Component::ParentDir | Component::CurDir))
+            .collect();
+        let relative = relative.strip_prefix("data").unwrap_or(&relative);
+        relative.strip_prefix(CONTENT_DIR).ok()?.to_path_buf()
+    };
+    let id = file.to_str()?.strip_suffix(".json")?;
+    is_content_id(id).then(|| id.to_string())
+}
+
+/// Convert every path entry of the routes' `blockIds` to its content id
+///
+/// Entries that don't convert are kept, and fail when resolved.
+fn convert_legacy_ids(routes: &mut [Route]) {
+    let root = content_dir();
+    for block_id in routes.iter_mut().flat_map(|r| r.block_ids.iter_mut()) {
+        if let Some(id) = legacy_content_id(block_id, &root) {
+            *block_id = id;
+        }
+    }
+}
+
+/// Write blocks to a content file at the current schema version
+fn write_content_file(content_path: &Path, blocks: &[ContentBlock]) -> Result<(), ContentError> {
     // Ensure the parent directory exists (rust-error-handling: graceful degradation)
     // This handles the case where data/content/ doesn't exist yet
     if let Some(parent) = content_path.parent() {
@@ -202,13 +605,30 @@ pub fn save_blocks(
 
     let data = HomepageData::new(blocks.to_vec());
     let json = serde_json::to_string_pretty(&data)?;
-    fs::write(&content_path, json)?;
+    fs::write(content_path, json)?;
     Ok(())
 }
 
+/// Replace the file at `path` with `contents` in one step
+///
+/// The contents go to a temporary file in the same directory, which is then
+/// renamed over `path`, so readers see either the old file or the new one,
+/// never a partial write, even if the process dies midway.
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    let parent = path.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(parent)?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = parent.join(format!(".{name}.{}.tmp", Uuid::new_v4().simple()));
+    let result = fs::write(&temp, contents).and_then(|()| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
 /// Load homepage blocks from JSON file
 ///
-/// This is a convenience wrapper around load_blocks("homepage") for backwards compatibility.
+/// This is a convenience wrapper around load_content("homepage") for backwards compatibility.
 ///
 /// If the file doesn't exist or contains invalid JSON, this function falls back
 /// to the default blocks defined in HomepageData::default_blocks().
@@ -218,12 +638,15 @@ pub fn save_blocks(
 /// - File not found: Returns default blocks
 /// - Invalid JSON: Returns default blocks, logs error to stderr
 /// - Valid JSON: Returns parsed blocks
-pub fn load_homepage_blocks() -> Vec<BlockWithId> {
-    let blocks = load_blocks("homepage");
+pub fn load_homepage_blocks() -> Vec<ContentBlock> {
+    let blocks = load_content("homepage");
 
     // If no blocks were loaded (file not found or error), return defaults
     if blocks.is_empty() {
         HomepageData::default_blocks()
+            .into_iter()
+            .map(ContentBlock::from)
+            .collect()
     } else {
         blocks
     }
@@ -244,8 +667,8 @@ pub fn load_homepage_blocks() -> Vec<BlockWithId> {
 ///
 /// The caller should map this error to an appropriate HTTP status code.
 #[allow(dead_code)]
-pub fn save_homepage_blocks(blocks: &[BlockWithId]) -> Result<(), Box<dyn std::error::Error>> {
-    save_blocks("homepage", blocks)
+pub fn save_homepage_blocks(blocks: &[ContentBlock]) -> Result<(), ContentError> {
+    save_blocks("homepage", blocks, None)
 }
 
 /// Load routes from JSON file
@@ -257,13 +680,24 @@ pub fn save_homepage_blocks(blocks: &[BlockWithId]) -> Result<(), Box<dyn std::e
 ///
 /// - File not found: Returns default routes
 /// - Invalid JSON: Returns default routes, logs error to stderr
-/// - Valid JSON: Returns parsed routes
+/// - Valid JSON: Returns parsed routes, with legacy `blockIds` paths
+///   converted to content ids (see `legacy_content_id`)
 ///
 /// # Default Routes
 ///
 /// The default routes include:
 /// - `{ "path": "/", "name": "homepage" }`
 pub fn load_routes() -> Vec<Route> {
+    let mut routes = load_stored_routes();
+    convert_legacy_ids(&mut routes);
+    routes
+}
+
+/// Load routes exactly as stored, without converting legacy `blockIds`
+///
+/// Falls back like `load_routes`. Used by `website migrate` to find the
+/// entries it needs to rewrite.
+pub fn load_stored_routes() -> Vec<Route> {
     let routes_path = routes_json_path();
     match fs::read_to_string(&routes_path) {
         Ok(contents) => {
@@ -287,21 +721,98 @@ pub fn load_routes() -> Vec<Route> {
     }
 }
 
-/// Save routes to JSON file
-///
-/// This serializes routes to pretty-printed JSON and writes atomically
-/// to the filesystem.
-///
-/// # Errors
+/// Errors that make a list of routes unfit for routes.json
+#[derive(Debug, thiserror::Error)]
+pub enum RoutesError {
+    #[error("invalid route name '{0}': use letters, digits, '-' and '_'")]
+    InvalidName(String),
+
+    #[error("route '{name}' has an invalid path '{path}': it must start with '/'")]
+    InvalidPath { name: String, path: String },
+
+    #[error("route name '{0}' is used more than once")]
+    DuplicateName(String),
+
+    #[error("route path '{0}' is used more than once")]
+    DuplicatePath(String),
+
+    #[error("route '{name}': {message}")]
+    InvalidSchedule { name: String, message: String },
+
+    #[error("route '{name}': {source}")]
+    Content { name: String, source: ContentError },
+
+    #[error("failed to write routes.json: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("failed to serialize routes: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Check routes before they replace routes.json
 ///
-/// Returns an error if:
-/// - JSON serialization fails
-/// - File write fails (disk full, permissions, etc.)
+/// Names and paths must be unique, paths must start with `/`, and every
+/// `blockIds` entry must be a content id (see `is_content_id`) listed once.
+/// Content files that don't exist yet are fine; they start empty.
+pub fn check_routes(routes: &[Route]) -> Result<(), RoutesError> {
+    let mut names = std::collections::HashSet::new();
+    let mut paths = std::collections::HashSet::new();
+    for route in routes {
+        let name = &route.name;
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        {
+            return Err(RoutesError::InvalidName(name.clone()));
+        }
+        if !route.path.starts_with('/') {
+            return Err(RoutesError::InvalidPath {
+                name: name.clone(),
+                path: route.path.clone(),
+            });
+        }
+        if !names.insert(name) {
+            return Err(RoutesError::DuplicateName(name.clone()));
+        }
+        if !paths.insert(&route.path) {
+            return Err(RoutesError::DuplicatePath(route.path.clone()));
+        }
+        route
+            .schedule
+            .validate()
+            .map_err(|message| RoutesError::InvalidSchedule {
+                name: name.clone(),
+                message,
+            })?;
+
+        let content = |source| RoutesError::Content {
+            name: name.clone(),
+            source,
+        };
+        let mut ids = std::collections::HashSet::new();
+        for id in &route.block_ids {
+            if !is_content_id(id) {
+                return Err(content(ContentError::InvalidContentId(id.clone())));
+            }
+            if !ids.insert(id) {
+                return Err(content(ContentError::DuplicateContentFile(id.clone())));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check routes and save them to routes.json
 ///
-/// The caller should map this error to an appropriate HTTP status code.
-#[allow(dead_code)]
-pub fn save_routes(routes: &[Route]) -> Result<(), Box<dyn std::error::Error>> {
+/// Serializes the routes to pretty-printed JSON, replacing the whole file.
+/// Nothing is written if `check_routes` fails.
+pub fn save_routes(routes: &[Route]) -> Result<(), RoutesError> {
+    check_routes(routes)?;
     let routes_path = routes_json_path();
+    if let Some(parent) = routes_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
     let json = serde_json::to_string_pretty(&routes)?;
     fs::write(&routes_path, json)?;
     Ok(())
@@ -311,20 +822,12 @@ pub fn save_routes(routes: &[Route]) -> Result<(), Box<dyn std::error::Error>> {
 ///
 /// Returns the initial set of routes used when routes.json doesn't exist
 /// or is invalid.
-///
-/// Uses absolute paths based on workspace root for consistency.
 fn default_routes() -> Vec<Route> {
-    let homepage_path = workspace_root()
-        .join("data")
-        .join("content")
-        .join("homepage.json")
-        .to_string_lossy()
-        .to_string();
-
     vec![Route {
         path: "/".to_string(),
         name: "homepage".to_string(),
-        block_ids: vec![homepage_path],
+        block_ids: vec!["homepage".to_string()],
+        ..Default::default()
     }]
 }
 
@@ -332,10 +835,57 @@ fn default_routes() -> Vec<Route> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_check_routes_refuses_duplicates_and_bad_ids() {
+        let route = |name: &str, path: &str, ids: &[&str]| Route {
+            name: name.to_string(),
+            path: path.to_string(),
+            block_ids: ids.iter().map(|id| id.to_string()).collect(),
+            ..Default::default()
+        };
+        assert!(
+            check_routes(&[
+                route("homepage", "/", &["homepage"]),
+                route("foo", "/foo", &[])
+            ])
+            .is_ok()
+        );
+        assert!(matches!(
+            check_routes(&[route("homepage", "/", &[]), route("homepage", "/b", &[])]),
+            Err(RoutesError::DuplicateName(_))
+        ));
+        assert!(matches!(
+            check_routes(&[route("a", "/", &[]), route("b", "/", &[])]),
+            Err(RoutesError::DuplicatePath(_))
+        ));
+        assert!(matches!(
+            check_routes(&[route("a", "a", &[])]),
+            Err(RoutesError::InvalidPath { .. })
+        ));
+        assert!(matches!(
+            check_routes(&[route("a/b", "/", &[])]),
+            Err(RoutesError::InvalidName(_))
+        ));
+        assert!(matches!(
+            check_routes(&[route("a", "/", &["../secrets"])]),
+            Err(RoutesError::Content {
+                source: ContentError::InvalidContentId(_),
+                ..
+            })
+        ));
+        assert!(matches!(
+            check_routes(&[route("a", "/", &["a", "a"])]),
+            Err(RoutesError::Content {
+                source: ContentError::DuplicateContentFile(_),
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_default_blocks_serialization() {
         let blocks = HomepageData::default_blocks();
-        let data = HomepageData::new(blocks);
+        let data = HomepageData::new(blocks.into_iter().map(ContentBlock::from).collect());
         let json = serde_json::to_string_pretty(&data).unwrap();
 
         // Should be able to round-trip
@@ -343,34 +893,46 @@ mod tests {
         assert_eq!(parsed.blocks.len(), 2);
     }
 
+    #[test]
+    fn test_block_options_round_trip() {
+        let json = r#"{
+            "blocks": [
+                {"id": "a", "type": "Hero", "props": {"headline": "H", "subheadline": "S"}},
+                {"id": "b", "type": "Hero", "props": {"headline": "H", "subheadline": "S"},
+                 "options": {"variant": "inverted", "anchor": "about"}}
+            ]
+        }"#;
+        let parsed: HomepageData = serde_json::from_str(json).unwrap();
+        let blocks = valid_blocks(&parsed.blocks);
+        assert!(blocks[0].options.is_default());
+        assert_eq!(blocks[1].options.anchor(), Some("about"));
+
+        // Default options are omitted; custom options survive a round-trip
+        let written = serde_json::to_value(&parsed).unwrap();
+        assert!(written["blocks"][0].get("options").is_none());
+        assert_eq!(written["blocks"][1]["options"]["variant"], "inverted");
+        assert_eq!(written["blocks"][1]["type"], "Hero");
+    }
+
     #[test]
     fn test_route_serialization() {
         // Test that routes can be serialized and deserialized correctly
-        // Using realistic absolute paths that would be generated by workspace_root()
-        let homepage_path = workspace_root()
-            .join("data")
-            .join("content")
-            .join("homepage.json")
-            .to_string_lossy()
-            .to_string();
-
-        let foo_path = workspace_root()
-            .join("data")
-            .join("content")
-            .join("foo.json")
-            .to_string_lossy()
-            .to_string();
+        let homepage_path = "homepage".to_string();
+        let foo_path = "foo".to_string();
 
         let routes = vec![
             Route {
                 path: "/".to_string(),
                 name: "homepage".to_string(),
                 block_ids: vec![homepage_path.clone()],
+                ..Default::default()
             },
             Route {
                 path: "/foo".to_string(),
                 name: "foo".to_string(),
                 block_ids: vec![foo_path.clone()],
+                noindex: true,
+                ..Default::default()
             },
         ];
 
@@ -384,6 +946,21 @@ mod tests {
         assert_eq!(parsed[1].path, "/foo");
         assert_eq!(parsed[1].name, "foo");
         assert_eq!(parsed[1].block_ids, vec![foo_path]);
+        assert!(parsed[1].noindex);
+        assert!(!parsed[1].exclude_from_sitemap);
+    }
+
+    #[test]
+    fn test_route_seo_flags_are_optional() {
+        let json = r#"{"path": "/", "name": "homepage", "blockIds": []}"#;
+        let route: Route = serde_json::from_str(json).unwrap();
+        assert!(!route.noindex);
+        assert!(!route.exclude_from_sitemap);
+
+        // Unset flags are not written back out
+        let written = serde_json::to_string(&route).unwrap();
+        assert!(!written.contains("noindex"));
+        assert!(!written.contains("excludeFromSitemap"));
     }
 
     #[test]
@@ -392,7 +969,225 @@ mod tests {
         assert_eq!(routes.len(), 1);
         assert_eq!(routes[0].path, "/");
         assert_eq!(routes[0].name, "homepage");
-        // Check that the path is absolute (starts with / on Unix or drive letter on Windows)
-        assert!(routes[0].block_ids[0].contains("data/content/homepage.json"));
+        assert_eq!(routes[0].block_ids, vec!["homepage"]);
+    }
+
+    #[test]
+    fn test_route_content_spans_all_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let content = dir.path().join("content");
+        fs::create_dir(&content).unwrap();
+        let header = content.join("header.json");
+        let body = content.join("body.json");
+        let hero = |id: &str| {
+            serde_json::json!({
+                "id": id, "type": "Hero", "props": { "headline": id, "subheadline": "" }
+            })
+        };
+        fs::write(
+            &header,
+            serde_json::json!({ "blocks": [hero("h")] }).to_string(),
+        )
+        .unwrap();
+        fs::write(
+            &body,
+            serde_json::json!({ "blocks": [hero("b1"), hero("b2")] }).to_string(),
+        )
+        .unwrap();
+        let routes = serde_json::json!([{ "path": "/", "name": "homepage", "blockIds": ["header", "body"] }]);
+        fs::write(dir.path().join("routes.json"), routes.to_string()).unwrap();
+
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let ids: Vec<String> = load_blocks("homepage").into_iter().map(|b| b.id).collect();
+            assert_eq!(ids, vec!["h", "b1", "b2"]);
+
+            let files = load_content_files("homepage");
+            assert_eq!(files.len(), 2);
+            assert_eq!(files[1].source, "body");
+            assert_eq!(files[1].blocks.len(), 2);
+
+            assert!(matches!(
+                save_blocks("homepage", &[], None),
+                Err(ContentError::MultipleContentFiles { count: 2, .. })
+            ));
+            let stray = ContentFile {
+                source: "other".to_string(),
+                blocks: vec![],
+            };
+            assert!(matches!(
+                save_content_files("homepage", &[files[0].clone(), stray], None),
+                Err(ContentError::UnknownContentFile { .. })
+            ));
+            assert!(!content.join("other.json").exists());
+
+            let emptied = ContentFile {
+                source: files[1].source.clone(),
+                blocks: vec![],
+            };
+            save_content_files("homepage", &[emptied], None).unwrap();
+            let ids: Vec<String> = load_blocks("homepage").into_iter().map(|b| b.id).collect();
+            assert_eq!(ids, vec!["h"], "the header file is untouched");
+        });
+    }
+
+    #[test]
+    fn test_content_file_path_rejects_paths() {
+        let dir = tempfile::TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            assert_eq!(
+                content_file_path("homepage", None).unwrap(),
+                dir.path().join("content/homepage.json")
+            );
+            assert_eq!(
+                content_file_path("homepage", Some("de-CH")).unwrap(),
+                dir.path().join("content/homepage.de-CH.json")
+            );
+            for id in [
+                "",
+                "..",
+                "../routes",
+                "../data/content/homepage.json",
+                "/etc/passwd",
+                "shared/header",
+                ".hidden",
+                "homepage.json",
+            ] {
+                assert!(
+                    matches!(
+                        content_file_path(id, None),
+                        Err(ContentError::InvalidContentId(_))
+                    ),
+                    "{id}"
+                );
+            }
+            assert!(matches!(
+                content_file_path("homepage", Some("../x")),
+                Err(ContentError::InvalidContentId(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_content_ids_cannot_collide_with_locale_variants() {
+        let dir = tempfile::TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let variant = content_file_path("homepage", Some("de")).unwrap();
+            assert_eq!(variant, dir.path().join("content/homepage.de.json"));
+            assert!(matches!(
+                content_file_path("homepage.de", None),
+                Err(ContentError::InvalidContentId(_))
+            ));
+            assert!(matches!(
+                check_routes(&[Route {
+                    path: "/".to_string(),
+                    name: "homepage".to_string(),
+                    block_ids: vec!["homepage".to_string(), "homepage.de".to_string()],
+                    ..Default::default()
+                }]),
+                Err(RoutesError::Content {
+                    source: ContentError::InvalidContentId(_),
+                    ..
+                })
+            ));
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_content_file_path_rejects_symlink_escapes() {
+        use std::os::unix::fs::symlink;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        let content = dir.path().join("content");
+        fs::create_dir(&content).unwrap();
+        fs::write(outside.path().join("secret.json"), "{}").unwrap();
+        fs::write(content.join("real.json"), "{}").unwrap();
+        symlink(
+            outside.path().join("secret.json"),
+            content.join("escape.json"),
+        )
+        .unwrap();
+        symlink(
+            outside.path().join("missing.json"),
+            content.join("dangling.json"),
+        )
+        .unwrap();
+        symlink(content.join("real.json"), content.join("alias.json")).unwrap();
+
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            for id in ["escape", "dangling"] {
+                assert!(
+                    matches!(
+                        content_file_path(id, None),
+                        Err(ContentError::OutsideContentRoot(_))
+                    ),
+                    "{id}"
+                );
+            }
+            assert!(content_file_path("alias", None).is_ok());
+            assert!(content_file_path("new", None).is_ok());
+        });
+    }
+
+    #[test]
+    fn test_legacy_content_id() {
+        let root = Path::new("/srv/site/data/content");
+        assert_eq!(
+            legacy_content_id("../data/content/homepage.json", root).as_deref(),
+            Some("homepage")
+        );
+        assert_eq!(
+            legacy_content_id("/srv/site/data/content/foo.json", root).as_deref(),
+            Some("foo")
+        );
+        assert_eq!(legacy_content_id("foo", root).as_deref(), Some("foo"));
+        for entry in [
+            "/etc/passwd",
+            "/srv/other/content/foo.json",
+            "../../secret.json",
+            "../data/content/nested/foo.json",
+            "../data/content/foo.txt",
+            "content/homepage.de.json",
+        ] {
+            assert_eq!(legacy_content_id(entry, root), None, "{entry}");
+        }
+    }
+
+    #[test]
+    fn test_load_routes_converts_legacy_paths() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let routes = serde_json::json!([{ "path": "/", "name": "homepage", "blockIds": [
+            "../data/content/homepage.json",
+            dir.path().join("content/header.json"),
+            "/etc/passwd",
+        ]}]);
+        fs::write(dir.path().join("routes.json"), routes.to_string()).unwrap();
+
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            assert_eq!(
+                load_routes()[0].block_ids,
+                vec!["homepage", "header", "/etc/passwd"]
+            );
+            assert_eq!(
+                load_stored_routes()[0].block_ids[0],
+                "../data/content/homepage.json"
+            );
+            let files = load_content_files("homepage");
+            assert!(files[2].blocks.is_empty(), "unresolvable ids load nothing");
+        });
+    }
+
+    #[test]
+    fn test_write_atomic_replaces_the_file_without_leftovers() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("nested/users.json");
+
+        write_atomic(&path, "[1]").unwrap();
+        write_atomic(&path, "[2]").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[2]");
+        let entries = fs::read_dir(path.parent().unwrap()).unwrap().count();
+        assert_eq!(entries, 1, "no temporary files are left behind");
     }
 }
@@ -0,0 +1,266 @@
+/// Live presence and publish notifications for route editors
+///
+/// Every open page editor and preview tab of a route subscribes to that
+/// route's channel. Editors also announce themselves, so each editor can
+/// show who else has the route open. When someone publishes, the saved
+/// content is broadcast on the channel: other editors load it or warn about
+/// a conflict, and preview tabs refresh.
+///
+/// # Messages
+///
+/// Events are serialized as JSON objects tagged with `type`:
+///
+/// ```json
+/// {"type":"welcome","session":"1b4e…"}
+/// {"type":"editors","editors":[{"session":"1b4e…","name":"Ana","locale":null}]}
+/// {"type":"published","by":"Ana","session":"1b4e…","locale":null,"files":[…]}
+/// ```
+///
+/// `welcome` is sent only to the connection it describes; the others go to
+/// every subscriber of the route.
+///
+/// # Architecture
+///
+/// Following rust-core-patterns:
+/// - **In-process**: Channels are `tokio::sync::broadcast` senders in a map
+///   shared through `AppState`; presence isn't persisted or shared between
+///   server processes
+/// - **RAII membership**: `join` returns a guard that removes the editor
+///   (and tells the others) when the connection's task drops it
+/// - **No framework types**: The WebSocket handling lives in
+///   `pages::admin::presence`
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::core::persistence::ContentFile;
+
+/// Events buffered per route before a slow subscriber starts missing them
+const CHANNEL_CAPACITY: usize = 32;
+
+/// An editor with a route open
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Editor {
+    /// Id of the editor's connection, unique per open tab
+    pub session: String,
+    pub name: String,
+    /// Locale being edited, `None` for the default locale
+    pub locale: Option<String>,
+}
+
+/// A message to the editors and previews of a route
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PresenceEvent {
+    /// Sent to a new connection with its session id
+    Welcome { session: String },
+    /// Everyone editing the route, sent whenever someone joins or leaves
+    Editors { editors: Vec<Editor> },
+    /// Content was saved; `files` are the files as written
+    Published {
+        by: String,
+        /// Session of the editor that published, if it had one open
+        session: Option<String>,
+        locale: Option<String>,
+        files: Vec<ContentFile>,
+    },
+}
+
+#[derive(Debug)]
+struct Channel {
+    sender: broadcast::Sender<PresenceEvent>,
+    editors: Vec<Editor>,
+}
+
+impl Channel {
+    fn new() -> Self {
+        Self {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+            editors: Vec::new(),
+        }
+    }
+
+    fn is_unused(&self) -> bool {
+        self.editors.is_empty() && self.sender.receiver_count() == 0
+    }
+
+    fn announce_editors(&self) {
+        // No receivers is fine: nobody is left to tell
+        let _ = self.sender.send(PresenceEvent::Editors {
+            editors: self.editors.clone(),
+        });
+    }
+}
+
+/// Presence channels of every route, keyed by route name
+#[derive(Debug, Clone, Default)]
+pub struct Presence {
+    channels: Arc<Mutex<HashMap<String, Channel>>>,
+}
+
+impl Presence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_channels<T>(&self, f: impl FnOnce(&mut HashMap<String, Channel>) -> T) -> T {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        let result = f(&mut channels);
+        channels.retain(|_, channel| !channel.is_unused());
+        result
+    }
+
+    /// Receive every event broadcast on `route` from now on
+    pub fn subscribe(&self, route: &str) -> broadcast::Receiver<PresenceEvent> {
+        let mut channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels
+            .entry(route.to_string())
+            .or_insert_with(Channel::new)
+            .sender
+            .subscribe()
+    }
+
+    /// Add `editor` to `route` until the returned guard is dropped
+    ///
+    /// Every subscriber, including the editor's own, is sent the new list.
+    pub fn join(&self, route: &str, editor: Editor) -> PresenceGuard {
+        let session = editor.session.clone();
+        self.with_channels(|channels| {
+            let channel = channels
+                .entry(route.to_string())
+                .or_insert_with(Channel::new);
+            channel.editors.push(editor);
+            channel.announce_editors();
+        });
+        PresenceGuard {
+            presence: self.clone(),
+            route: route.to_string(),
+            session,
+        }
+    }
+
+    fn leave(&self, route: &str, session: &str) {
+        self.with_channels(|channels| {
+            if let Some(channel) = channels.get_mut(route) {
+                channel.editors.retain(|editor| editor.session != session);
+                channel.announce_editors();
+            }
+        });
+    }
+
+    /// Editors currently on `route`, in the order they joined
+    pub fn editors(&self, route: &str) -> Vec<Editor> {
+        let channels = self.channels.lock().unwrap_or_else(|e| e.into_inner());
+        channels
+            .get(route)
+            .map(|channel| channel.editors.clone())
+            .unwrap_or_default()
+    }
+
+    /// Send `event` to everyone subscribed to `route`
+    pub fn broadcast(&self, route: &str, event: PresenceEvent) {
+        self.with_channels(|channels| {
+            if let Some(channel) = channels.get(route) {
+                let _ = channel.sender.send(event);
+            }
+        });
+    }
+}
+
+/// Membership of an editor in a route's channel; leaves when dropped
+#[derive(Debug)]
+pub struct PresenceGuard {
+    presence: Presence,
+    route: String,
+    session: String,
+}
+
+impl Drop for PresenceGuard {
+    fn drop(&mut self) {
+        self.presence.leave(&self.route, &self.session);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(session: &str, name: &str) -> Editor {
+        Editor {
+            session: session.to_string(),
+            name: name.to_string(),
+            locale: None,
+        }
+    }
+
+    fn names(event: PresenceEvent) -> Vec<String> {
+        match event {
+            PresenceEvent::Editors { editors } => editors.into_iter().map(|e| e.name).collect(),
+            other => panic!("expected editors, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_join_and_leave_announce_editors() {
+        let presence = Presence::new();
+        let mut events = presence.subscribe("homepage");
+
+        let ana = presence.join("homepage", editor("a", "Ana"));
+        assert_eq!(names(events.try_recv().unwrap()), vec!["Ana"]);
+        let ben = presence.join("homepage", editor("b", "Ben"));
+        assert_eq!(names(events.try_recv().unwrap()), vec!["Ana", "Ben"]);
+        assert!(presence.editors("foo").is_empty(), "channels are per route");
+
+        drop(ana);
+        assert_eq!(names(events.try_recv().unwrap()), vec!["Ben"]);
+        drop(ben);
+        assert!(names(events.try_recv().unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_broadcast_reaches_every_subscriber() {
+        let presence = Presence::new();
+        let mut editor_events = presence.subscribe("foo");
+        let mut preview_events = presence.subscribe("foo");
+        let published = PresenceEvent::Published {
+            by: "Ana".to_string(),
+            session: None,
+            locale: Some("de".to_string()),
+            files: vec![],
+        };
+
+        let json = |event: PresenceEvent| serde_json::to_value(event).unwrap();
+
+        presence.broadcast("foo", published.clone());
+        presence.broadcast("homepage", published.clone());
+        assert_eq!(
+            json(editor_events.try_recv().unwrap()),
+            json(published.clone())
+        );
+        assert_eq!(json(preview_events.try_recv().unwrap()), json(published));
+        assert!(editor_events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_unused_channels_are_removed() {
+        let presence = Presence::new();
+        let events = presence.subscribe("foo");
+        drop(presence.join("foo", editor("a", "Ana")));
+        drop(events);
+        presence.broadcast("foo", PresenceEvent::Editors { editors: vec![] });
+        assert!(presence.channels.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_event_json() {
+        let event = PresenceEvent::Welcome {
+            session: "s1".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "type": "welcome", "session": "s1" })
+        );
+    }
+}
@@ -0,0 +1,683 @@
+/// Scheduled publishing and expiry of content
+///
+/// Announcements can be lined up ahead of time in two ways:
+///
+/// - **Routes** and **blocks** carry an optional `schedule` envelope. Public
+///   pages hide a route (404) or a block outside its window; nothing is
+///   written when the window opens or closes.
+/// - **Scheduled drafts** are whole saves queued for later: the editor posts
+///   the usual save request with a future `publish_at`, the draft is stored
+///   in `data/scheduled/{id}.json`, and a background task writes it to the
+///   route's content files once it is due.
+///
+/// # JSON Format
+///
+/// The same envelope is used on a block (next to `options`) and on a route
+/// in routes.json. Both timestamps are optional RFC 3339 instants:
+///
+/// ```json
+/// "schedule": {
+///   "publish_at": "2026-03-01T09:00:00Z",
+///   "unpublish_at": "2026-03-08T00:00:00Z"
+/// }
+/// ```
+///
+/// # Time
+///
+/// Everything that depends on "now" takes the current instant from a
+/// `Clock` (see `AppState::clock`), so tests can move time forward with a
+/// `ManualClock` instead of sleeping.
+///
+/// # Architecture
+///
+/// Following rust-core-patterns:
+/// - **Pure checks**: `Schedule::is_live` and `live_blocks` take `now` as an
+///   argument; only the background task and handlers read the clock
+/// - **Leave failures queued**: a draft that can't be written (e.g. its route
+///   was removed) stays queued and is shown as overdue until it is cancelled
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::audit::{AuditAction, AuditEntry, SCHEDULER, record_file_changes};
+use crate::core::block::ContentBlock;
+use crate::core::i18n::has_variant;
+use crate::core::persistence::{
+    ContentError, ContentFile, check_content_files, content_file_path, data_dir,
+    load_content_files_in, load_routes, save_content_files,
+};
+use crate::core::presence::{Presence, PresenceEvent};
+use crate::core::settings::load_settings;
+
+/// Directory under the data directory holding scheduled drafts
+const SCHEDULED_DIR: &str = "scheduled";
+
+// ============================================================================
+// Clock
+// ============================================================================
+
+/// Source of the current time
+pub trait Clock: Send + Sync + fmt::Debug {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when told to, for tests
+///
+/// Clones share the same time, so a test can keep one and hand another to
+/// the app.
+#[derive(Debug, Clone)]
+pub struct ManualClock(Arc<Mutex<DateTime<Utc>>>);
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self(Arc::new(Mutex::new(now)))
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.0.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, by: chrono::Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().unwrap()
+    }
+}
+
+// ============================================================================
+// Publish Windows
+// ============================================================================
+
+/// When a route or block is publicly visible
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Hidden before this instant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<DateTime<Utc>>,
+    /// Hidden from this instant on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unpublish_at: Option<DateTime<Utc>>,
+}
+
+impl Schedule {
+    /// Whether neither timestamp is set (the envelope isn't written to disk)
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the content is visible at `now`
+    pub fn is_live(&self, now: DateTime<Utc>) -> bool {
+        self.publish_at.is_none_or(|at| at <= now) && self.unpublish_at.is_none_or(|at| now < at)
+    }
+
+    /// Check that the window isn't empty
+    pub fn validate(&self) -> Result<(), String> {
+        match (self.publish_at, self.unpublish_at) {
+            (Some(publish), Some(unpublish)) if unpublish <= publish => Err(format!(
+                "unpublish_at ({}) must be after publish_at ({})",
+                unpublish, publish
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The blocks visible at `now`
+///
+/// Invalid blocks are kept: they render as hidden placeholders anyway and
+/// have no schedule that could be read.
+pub fn live_blocks(blocks: Vec<ContentBlock>, now: DateTime<Utc>) -> Vec<ContentBlock> {
+    blocks
+        .into_iter()
+        .filter(|block| block.as_valid().is_none_or(|b| b.schedule.is_live(now)))
+        .collect()
+}
+
+// ============================================================================
+// Scheduled Drafts
+// ============================================================================
+
+/// A save of a route's content, queued until `publish_at`
+///
+/// # JSON Format
+///
+/// ```json
+/// {
+///   "id": "0b6c4f0e-...",
+///   "route": "homepage",
+///   "locale": "de",
+///   "publish_at": "2026-03-01T09:00:00Z",
+///   "files": [{ "source": "../data/content/homepage.json", "blocks": [ ... ] }]
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledDraft {
+    pub id: String,
+    pub route: String,
+    /// Locale whose variant files are written; `None` for the default locale
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    pub publish_at: DateTime<Utc>,
+    pub files: Vec<ContentFile>,
+}
+
+/// Errors that can occur while queueing or cancelling a scheduled draft
+#[derive(Debug, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("Scheduled draft '{0}' not found")]
+    NotFound(String),
+
+    #[error(transparent)]
+    Content(#[from] ContentError),
+
+    #[error("failed to serialize scheduled draft: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("failed to write scheduled draft: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Directory holding the scheduled draft files
+pub(crate) fn scheduled_dir() -> PathBuf {
+    data_dir().join(SCHEDULED_DIR)
+}
+
+/// Path of a draft file, or `None` if `id` isn't a UUID
+pub fn draft_path(id: &str) -> Option<PathBuf> {
+    Uuid::parse_str(id)
+        .ok()
+        .map(|id| scheduled_dir().join(format!("{}.json", id)))
+}
+
+/// Queue `files` to be saved to `route` at `publish_at`
+///
+/// The files are checked against the route's `blockIds` now, so a draft
+/// that could never be written is rejected up front.
+pub fn schedule_draft(
+    route: &str,
+    locale: Option<&str>,
+    publish_at: DateTime<Utc>,
+    files: Vec<ContentFile>,
+) -> Result<ScheduledDraft, ScheduleError> {
+    check_content_files(route, &files)?;
+
+    let draft = ScheduledDraft {
+        id: Uuid::new_v4().to_string(),
+        route: route.to_string(),
+        locale: locale.map(str::to_string),
+        publish_at,
+        files,
+    };
+    let path = draft_path(&draft.id).expect("new draft ids are UUIDs");
+    fs::create_dir_all(scheduled_dir())?;
+    fs::write(path, serde_json::to_string_pretty(&draft)?)?;
+    Ok(draft)
+}
+
+/// Every queued draft, soonest first
+///
+/// Files that can't be read or parsed are logged and skipped.
+pub fn scheduled_drafts() -> Vec<ScheduledDraft> {
+    let entries = match fs::read_dir(scheduled_dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return vec![],
+        Err(e) => {
+            eprintln!("Failed to list scheduled drafts: {}", e);
+            return vec![];
+        }
+    };
+
+    let mut drafts: Vec<ScheduledDraft> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let parsed = fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+            parsed
+                .inspect_err(|e| eprintln!("Skipping scheduled draft {}: {}", path.display(), e))
+                .ok()
+        })
+        .collect();
+    drafts.sort_by(|a, b| (a.publish_at, &a.id).cmp(&(b.publish_at, &b.id)));
+    drafts
+}
+
+/// Remove a queued draft without publishing it
+pub fn cancel_draft(id: &str) -> Result<(), ScheduleError> {
+    let path = draft_path(id).ok_or_else(|| ScheduleError::NotFound(id.to_string()))?;
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            Err(ScheduleError::NotFound(id.to_string()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write every draft that is due at `now` and remove it from the queue
+///
+/// Returns each due draft with the result of saving it. Drafts that fail to
+/// save stay queued. Published files are recorded in the audit log as the
+/// `scheduler` actor.
+pub fn promote_due_drafts(now: DateTime<Utc>) -> Vec<(ScheduledDraft, Result<(), ContentError>)> {
+    scheduled_drafts()
+        .into_iter()
+        .filter(|draft| draft.publish_at <= now)
+        .map(|draft| {
+            let locale = draft.locale.as_deref();
+            // A file that doesn't resolve fails the save below
+            let paths: Vec<_> = draft
+                .files
+                .iter()
+                .filter_map(|file| content_file_path(&file.source, locale).ok())
+                .collect();
+            let entry = AuditEntry::new(now, SCHEDULER, AuditAction::DraftPublish, None)
+                .route(&draft.route);
+            let result = record_file_changes(&entry, &paths, || {
+                save_content_files(&draft.route, &draft.files, locale)
+            });
+            if result.is_ok()
+                && let Some(path) = draft_path(&draft.id)
+                && let Err(e) = fs::remove_file(&path)
+            {
+                eprintln!("Failed to remove promoted draft {}: {}", path.display(), e);
+            }
+            (draft, result)
+        })
+        .collect()
+}
+
+/// Promote due drafts every `interval`, forever
+///
+/// Spawned by the server (see `app::spawn_scheduler`) inside the data
+/// directory scope it should publish to. Published drafts are announced to
+/// the route's open editors and previews through `presence`.
+pub async fn run_scheduler(clock: Arc<dyn Clock>, interval: Duration, presence: Presence) {
+    let mut ticks = tokio::time::interval(interval);
+    loop {
+        ticks.tick().await;
+        for (draft, result) in promote_due_drafts(clock.now()) {
+            match result {
+                Ok(()) => {
+                    println!("Published scheduled draft {} of {}", draft.id, draft.route);
+                    presence.broadcast(
+                        &draft.route,
+                        PresenceEvent::Published {
+                            by: SCHEDULER.to_string(),
+                            session: None,
+                            locale: draft.locale,
+                            files: draft.files,
+                        },
+                    );
+                }
+                Err(e) => eprintln!(
+                    "Failed to publish scheduled draft {} of {}: {}",
+                    draft.id, draft.route, e
+                ),
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Calendar
+// ============================================================================
+
+/// A future change to the public site
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledChange {
+    pub at: DateTime<Utc>,
+    pub route: String,
+    /// Locale of the content; `None` for the default locale and for routes
+    pub locale: Option<String>,
+    pub kind: ChangeKind,
+}
+
+/// What happens at a `ScheduledChange`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    RoutePublish,
+    RouteUnpublish,
+    BlockPublish {
+        block_id: String,
+        type_name: String,
+    },
+    BlockUnpublish {
+        block_id: String,
+        type_name: String,
+    },
+    /// A queued draft; overdue if it is still queued after `at`
+    Draft {
+        id: String,
+    },
+}
+
+impl ScheduledChange {
+    /// Calendar day of the change (UTC)
+    pub fn day(&self) -> NaiveDate {
+        self.at.date_naive()
+    }
+}
+
+/// Every change after `now`, plus all queued drafts, in time order
+///
+/// Blocks are read from the default locale's content files and from every
+/// locale variant that exists.
+pub fn upcoming_changes(now: DateTime<Utc>) -> Vec<ScheduledChange> {
+    let locales = load_settings().i18n.locales;
+    let mut changes = Vec::new();
+
+    for route in load_routes() {
+        let windows = [
+            (route.schedule.publish_at, ChangeKind::RoutePublish),
+            (route.schedule.unpublish_at, ChangeKind::RouteUnpublish),
+        ];
+        for (at, kind) in windows {
+            if let Some(at) = at.filter(|at| *at > now) {
+                changes.push(ScheduledChange {
+                    at,
+                    route: route.name.clone(),
+                    locale: None,
+                    kind,
+                });
+            }
+        }
+
+        let variants = locales.iter().filter(|locale| {
+            route
+                .block_ids
+                .iter()
+                .any(|source| has_variant(source, locale))
+        });
+        for locale in std::iter::once(None).chain(variants.map(|l| Some(l.as_str()))) {
+            let blocks = load_content_files_in(&route.name, locale)
+                .into_iter()
+                .flat_map(|file| file.blocks);
+            for block in blocks.filter_map(|block| block.as_valid().cloned()) {
+                let type_name = block.block.type_name().to_string();
+                let windows = [
+                    (block.schedule.publish_at, true),
+                    (block.schedule.unpublish_at, false),
+                ];
+                for (at, publish) in windows {
+                    let Some(at) = at.filter(|at| *at > now) else {
+                        continue;
+                    };
+                    let (block_id, type_name) = (block.id.clone(), type_name.clone());
+                    changes.push(ScheduledChange {
+                        at,
+                        route: route.name.clone(),
+                        locale: locale.map(str::to_string),
+                        kind: if publish {
+                            ChangeKind::BlockPublish {
+                                block_id,
+                                type_name,
+                            }
+                        } else {
+                            ChangeKind::BlockUnpublish {
+                                block_id,
+                                type_name,
+                            }
+                        },
+                    });
+                }
+            }
+        }
+    }
+
+    changes.extend(scheduled_drafts().into_iter().map(|draft| ScheduledChange {
+        at: draft.publish_at,
+        route: draft.route,
+        locale: draft.locale,
+        kind: ChangeKind::Draft { id: draft.id },
+    }));
+    changes.sort_by_key(|change| change.at);
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::{Block, BlockWithId};
+    use crate::core::persistence::{load_content, with_data_dir_sync};
+    use crate::features::hero::HeroProps;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, hour, 0, 0).unwrap()
+    }
+
+    fn hero(id: &str, schedule: Schedule) -> ContentBlock {
+        ContentBlock::Valid(BlockWithId {
+            id: id.to_string(),
+            block: Block::Hero(HeroProps {
+                headline: id.to_string(),
+                subheadline: String::new(),
+            }),
+            options: Default::default(),
+            schedule,
+        })
+    }
+
+    /// A data directory with a homepage route whose content file is empty
+    fn site() -> (TempDir, String) {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir(dir.path().join("content")).unwrap();
+        let content = "homepage".to_string();
+        let routes = serde_json::json!([
+            { "path": "/", "name": "homepage", "blockIds": [&content],
+              "schedule": { "publish_at": at(8), "unpublish_at": at(20) } }
+        ]);
+        fs::write(dir.path().join("routes.json"), routes.to_string()).unwrap();
+        (dir, content)
+    }
+
+    #[test]
+    fn test_schedule_window() {
+        let window = Schedule {
+            publish_at: Some(at(9)),
+            unpublish_at: Some(at(17)),
+        };
+        assert!(!window.is_live(at(8)));
+        assert!(window.is_live(at(9)));
+        assert!(window.is_live(at(16)));
+        assert!(!window.is_live(at(17)));
+        assert!(Schedule::default().is_live(at(0)));
+
+        assert!(window.validate().is_ok());
+        let empty = Schedule {
+            publish_at: Some(at(9)),
+            unpublish_at: Some(at(9)),
+        };
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_live_blocks_hide_scheduled_and_expired() {
+        let blocks = vec![
+            hero("always", Schedule::default()),
+            hero(
+                "later",
+                Schedule {
+                    publish_at: Some(at(12)),
+                    unpublish_at: None,
+                },
+            ),
+            hero(
+                "expired",
+                Schedule {
+                    publish_at: None,
+                    unpublish_at: Some(at(10)),
+                },
+            ),
+        ];
+        let ids = |now| -> Vec<String> {
+            live_blocks(blocks.clone(), now)
+                .iter()
+                .filter_map(|b| b.id().map(str::to_string))
+                .collect()
+        };
+        assert_eq!(ids(at(9)), vec!["always", "expired"]);
+        assert_eq!(ids(at(12)), vec!["always", "later"]);
+    }
+
+    #[test]
+    fn test_schedule_serializes_only_when_set() {
+        let json = serde_json::to_value(hero("a", Schedule::default())).unwrap();
+        assert!(json.get("schedule").is_none());
+
+        let scheduled = hero(
+            "a",
+            Schedule {
+                publish_at: Some(at(9)),
+                unpublish_at: None,
+            },
+        );
+        let json = serde_json::to_value(&scheduled).unwrap();
+        assert_eq!(json["schedule"]["publish_at"], "2026-03-01T09:00:00Z");
+        let back: ContentBlock = serde_json::from_value(json).unwrap();
+        assert_eq!(back.as_valid().unwrap().schedule.publish_at, Some(at(9)));
+    }
+
+    #[test]
+    fn test_manual_clock_is_shared_between_clones() {
+        let clock = ManualClock::new(at(9));
+        let app_clock: Arc<dyn Clock> = Arc::new(clock.clone());
+        clock.advance(chrono::Duration::hours(2));
+        assert_eq!(app_clock.now(), at(11));
+    }
+
+    #[test]
+    fn test_due_drafts_are_promoted_once() {
+        let (dir, content) = site();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let files = vec![ContentFile {
+                source: content.clone(),
+                blocks: vec![hero("new", Schedule::default())],
+            }];
+            let draft = schedule_draft("homepage", None, at(12), files).unwrap();
+            assert_eq!(scheduled_drafts().len(), 1);
+
+            assert!(promote_due_drafts(at(11)).is_empty());
+            assert!(load_content("homepage").is_empty());
+
+            let promoted = promote_due_drafts(at(12));
+            assert_eq!(promoted.len(), 1);
+            assert_eq!(promoted[0].0.id, draft.id);
+            assert!(promoted[0].1.is_ok());
+            assert_eq!(load_content("homepage")[0].id(), Some("new"));
+            assert!(scheduled_drafts().is_empty());
+            assert!(promote_due_drafts(at(13)).is_empty());
+        });
+    }
+
+    #[test]
+    fn test_draft_for_unknown_file_is_rejected() {
+        let (dir, _) = site();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let files = vec![ContentFile {
+                source: "/etc/passwd".to_string(),
+                blocks: vec![],
+            }];
+            let err = schedule_draft("homepage", None, at(12), files).unwrap_err();
+            assert!(matches!(
+                err,
+                ScheduleError::Content(ContentError::UnknownContentFile { .. })
+            ));
+            assert!(scheduled_drafts().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_cancel_draft() {
+        let (dir, content) = site();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let files = vec![ContentFile {
+                source: content,
+                blocks: vec![],
+            }];
+            let draft = schedule_draft("homepage", None, at(12), files).unwrap();
+            cancel_draft(&draft.id).unwrap();
+            assert!(scheduled_drafts().is_empty());
+            assert!(matches!(
+                cancel_draft(&draft.id),
+                Err(ScheduleError::NotFound(_))
+            ));
+            assert!(matches!(
+                cancel_draft("../routes"),
+                Err(ScheduleError::NotFound(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_upcoming_changes_in_time_order() {
+        let (dir, content) = site();
+        let blocks = serde_json::json!({ "blocks": [{
+            "id": "promo", "type": "Hero",
+            "props": { "headline": "Sale", "subheadline": "" },
+            "schedule": { "publish_at": at(10), "unpublish_at": at(18) }
+        }]});
+        fs::write(dir.path().join("content/homepage.json"), blocks.to_string()).unwrap();
+
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let files = vec![ContentFile {
+                source: content.clone(),
+                blocks: vec![],
+            }];
+            let draft = schedule_draft("homepage", None, at(14), files).unwrap();
+
+            let changes = upcoming_changes(at(9));
+            let kinds: Vec<(u32, &ChangeKind)> = changes
+                .iter()
+                .map(|c| (c.at.format("%H").to_string().parse().unwrap(), &c.kind))
+                .collect();
+            let promo = || ("promo".to_string(), "Hero".to_string());
+            assert_eq!(
+                kinds,
+                vec![
+                    (
+                        10,
+                        &ChangeKind::BlockPublish {
+                            block_id: promo().0,
+                            type_name: promo().1
+                        }
+                    ),
+                    (14, &ChangeKind::Draft { id: draft.id }),
+                    (
+                        18,
+                        &ChangeKind::BlockUnpublish {
+                            block_id: promo().0,
+                            type_name: promo().1
+                        }
+                    ),
+                    (20, &ChangeKind::RouteUnpublish),
+                ]
+            );
+        });
+    }
+}
@@ -0,0 +1,280 @@
+/// Security response headers and the per-request CSP nonce
+///
+/// Every response carries a `HeaderSet`: one for public pages and a stricter
+/// one for `/admin`. The Content Security Policy only allows scripts and
+/// styles from the site itself, plus inline `<script>`/`<style>` elements
+/// carrying the request's nonce.
+///
+/// # Nonces
+///
+/// The middleware in `app` generates a fresh nonce for each request and
+/// runs the handler inside `with_csp_nonce`. Templates read it with
+/// `csp_nonce()` and attach it to the elements they render:
+///
+/// ```ignore
+/// html! {
+///     style nonce=[csp_nonce()] { ... }
+///     script type="module" nonce=[csp_nonce()] src="/features/..." {}
+/// }
+/// ```
+///
+/// Outside a request (unit and snapshot tests) `csp_nonce()` is `None` and
+/// the attribute is left out. Admin pages also render
+/// `<meta property="csp-nonce" nonce="…">` (see `csp_nonce_meta`) so scripts
+/// that create `<style>` elements at runtime, such as Monaco, can nonce them.
+///
+/// Nonces don't apply to `style=` attributes, which the policy blocks:
+/// templates use classes, or a nonced `<style>` element for values computed
+/// per block (see the image component).
+///
+/// # Configuration
+///
+/// `SecurityHeaders::default()` holds the sets described on `HeaderSet::public`
+/// and `HeaderSet::admin`. Sites override them through
+/// `AppState::with_security_headers`, e.g. to drop HSTS behind a proxy that
+/// already sends it:
+///
+/// ```ignore
+/// let headers = SecurityHeaders {
+///     public: HeaderSet::public().without("strict-transport-security"),
+///     ..SecurityHeaders::default()
+/// };
+/// let state = AppState::default().with_security_headers(headers);
+/// ```
+///
+/// # Architecture
+///
+/// Following rust-core-patterns:
+/// - **No framework types**: Headers are plain name/value strings; the
+///   middleware in `app` converts them and skips any a handler already set
+/// - **Task-local nonce**: Like `persistence::data_dir`, the nonce is scoped
+///   to the request's task rather than threaded through every template
+use std::future::Future;
+
+use maud::{Markup, html};
+use uuid::Uuid;
+
+/// Placeholder replaced with the request's nonce in header values
+pub const NONCE_PLACEHOLDER: &str = "{nonce}";
+
+tokio::task_local! {
+    static CSP_NONCE: String;
+}
+
+/// Generate a nonce: 122 random bits from a v4 UUID, as hex
+pub fn generate_nonce() -> String {
+    Uuid::new_v4().simple().to_string()
+}
+
+/// Run a future with `csp_nonce()` returning `nonce`
+pub async fn with_csp_nonce<F: Future>(nonce: String, future: F) -> F::Output {
+    CSP_NONCE.scope(nonce, future).await
+}
+
+/// Nonce of the current request, or `None` outside a request
+pub fn csp_nonce() -> Option<String> {
+    CSP_NONCE.try_with(Clone::clone).ok()
+}
+
+/// `<meta property="csp-nonce">` carrying the request's nonce
+///
+/// Scripts read it as `document.querySelector('meta[property="csp-nonce"]').nonce`;
+/// browsers hide the attribute value from CSS selectors and `getAttribute`.
+pub fn csp_nonce_meta() -> Markup {
+    html! {
+        @if let Some(nonce) = csp_nonce() {
+            meta property="csp-nonce" nonce=(nonce);
+        }
+    }
+}
+
+/// Response headers sent with every response of one kind of page
+///
+/// Header names are lowercase. `{nonce}` in a value is replaced with the
+/// request's nonce.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderSet {
+    headers: Vec<(String, String)>,
+}
+
+impl HeaderSet {
+    /// An empty set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set `name` to `value`, replacing an existing value
+    pub fn with(mut self, name: &str, value: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        self.headers.retain(|(existing, _)| *existing != name);
+        self.headers.push((name, value.to_string()));
+        self
+    }
+
+    /// Remove `name` from the set
+    pub fn without(mut self, name: &str) -> Self {
+        let name = name.to_ascii_lowercase();
+        self.headers.retain(|(existing, _)| *existing != name);
+        self
+    }
+
+    /// The value of `name`, with the placeholder still in place
+    pub fn get(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(existing, _)| *existing == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Headers with `{nonce}` replaced by `nonce`, in insertion order
+    pub fn render(&self, nonce: &str) -> Vec<(String, String)> {
+        self.headers
+            .iter()
+            .map(|(name, value)| (name.clone(), value.replace(NONCE_PLACEHOLDER, nonce)))
+            .collect()
+    }
+
+    /// Headers for public pages, assets and media
+    ///
+    /// Pages may be framed by the same origin only (the admin live preview
+    /// frames them).
+    pub fn public() -> Self {
+        Self::new()
+            .with(
+                "content-security-policy",
+                "default-src 'self'; \
+                 script-src 'self' 'nonce-{nonce}'; \
+                 style-src 'self' 'nonce-{nonce}'; \
+                 img-src 'self' data:; \
+                 font-src 'self'; \
+                 object-src 'none'; \
+                 base-uri 'self'; \
+                 form-action 'self'; \
+                 frame-ancestors 'self'",
+            )
+            .with(
+                "strict-transport-security",
+                "max-age=31536000; includeSubDomains",
+            )
+            .with("x-content-type-options", "nosniff")
+            .with("x-frame-options", "SAMEORIGIN")
+            .with("referrer-policy", "strict-origin-when-cross-origin")
+    }
+
+    /// Headers for everything under `/admin`
+    ///
+    /// Stricter than `public`: no `<base>`, connections (fetch, WebSockets)
+    /// only to the site, no referrer and no caching. Same-origin framing is
+    /// still allowed for the settings preview.
+    pub fn admin() -> Self {
+        Self::public()
+            .with(
+                "content-security-policy",
+                "default-src 'self'; \
+                 script-src 'self' 'nonce-{nonce}'; \
+                 style-src 'self' 'nonce-{nonce}'; \
+                 img-src 'self' data: blob:; \
+                 font-src 'self' data:; \
+                 connect-src 'self'; \
+                 worker-src 'self' blob:; \
+                 object-src 'none'; \
+                 base-uri 'none'; \
+                 form-action 'self'; \
+                 frame-ancestors 'self'",
+            )
+            .with("referrer-policy", "no-referrer")
+            .with("cache-control", "no-store")
+    }
+}
+
+/// The header sets of the site
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecurityHeaders {
+    /// Sent with everything outside `/admin`
+    pub public: HeaderSet,
+    /// Sent with `/admin` and everything under it
+    pub admin: HeaderSet,
+}
+
+impl Default for SecurityHeaders {
+    fn default() -> Self {
+        Self {
+            public: HeaderSet::public(),
+            admin: HeaderSet::admin(),
+        }
+    }
+}
+
+impl SecurityHeaders {
+    /// The set for a request path
+    pub fn for_path(&self, path: &str) -> &HeaderSet {
+        if path == "/admin" || path.starts_with("/admin/") {
+            &self.admin
+        } else {
+            &self.public
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_replaces_nonce() {
+        let set = HeaderSet::new()
+            .with("Content-Security-Policy", "script-src 'nonce-{nonce}'")
+            .with("x-frame-options", "DENY");
+        assert_eq!(
+            set.render("abc"),
+            vec![
+                (
+                    "content-security-policy".to_string(),
+                    "script-src 'nonce-abc'".to_string()
+                ),
+                ("x-frame-options".to_string(), "DENY".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_with_and_without_replace_by_name() {
+        let set = HeaderSet::public()
+            .with("X-Frame-Options", "DENY")
+            .without("Strict-Transport-Security");
+        assert_eq!(set.get("x-frame-options"), Some("DENY"));
+        assert_eq!(set.get("strict-transport-security"), None);
+        assert_eq!(
+            set.render("n").len(),
+            HeaderSet::public().render("n").len() - 1
+        );
+    }
+
+    #[test]
+    fn test_admin_paths_get_the_admin_set() {
+        let headers = SecurityHeaders::default();
+        assert_eq!(headers.for_path("/admin"), &headers.admin);
+        assert_eq!(headers.for_path("/admin/route/"), &headers.admin);
+        assert_eq!(headers.for_path("/administrator"), &headers.public);
+        assert_eq!(headers.for_path("/"), &headers.public);
+        for set in [&headers.public, &headers.admin] {
+            let csp = set.get("content-security-policy").unwrap();
+            assert!(!csp.contains("unsafe-inline"), "{csp}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nonce_is_scoped_to_the_task() {
+        assert_eq!(csp_nonce(), None);
+        assert!(csp_nonce_meta().into_string().is_empty());
+        let nonce = generate_nonce();
+        let inside = with_csp_nonce(nonce.clone(), async {
+            (csp_nonce(), csp_nonce_meta().into_string())
+        })
+        .await;
+        assert_eq!(inside.0.as_deref(), Some(nonce.as_str()));
+        assert!(inside.1.contains(&format!("nonce=\"{nonce}\"")));
+        assert_ne!(generate_nonce(), nonce);
+    }
+}
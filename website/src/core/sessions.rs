@@ -0,0 +1,231 @@
+/// Signed-in admin sessions
+///
+/// Signing in creates a session: a random token that the admin stores in a
+/// cookie and that maps to a user name. Sessions live in memory, so a
+/// restart signs everyone out. A session expires after `SESSION_IDLE` without
+/// requests; each request pushes the expiry back.
+///
+/// The session only remembers who signed in. The user's role and grants are
+/// read from users.json on each request (see `core::users`), so changes
+/// apply to open sessions at once.
+///
+/// # Two-Factor Challenges
+///
+/// A user with two-factor sign-in gets no session for their password alone.
+/// They get a challenge: a short-lived token naming the user, exchanged for
+/// a session once they enter a valid code. A challenge lasts
+/// `CHALLENGE_TTL` and allows `CHALLENGE_ATTEMPTS` wrong codes, after which
+/// the password must be entered again.
+///
+/// # Architecture
+///
+/// Following rust-core-patterns:
+/// - **Shared handle**: Like `Presence`, `Sessions` is cloned into
+///   `AppState`; clones share the same sessions
+/// - **Injected time**: Methods take `now` from the app's clock, so tests
+///   can expire sessions with a `ManualClock`
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+/// How long a session lasts without requests
+pub const SESSION_IDLE: Duration = Duration::hours(12);
+
+/// How long a two-factor challenge waits for its code
+pub const CHALLENGE_TTL: Duration = Duration::minutes(5);
+
+/// Wrong codes allowed per two-factor challenge
+pub const CHALLENGE_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Clone)]
+struct Session {
+    user: String,
+    expires: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone)]
+struct Challenge {
+    user: String,
+    expires: DateTime<Utc>,
+    attempts_left: u32,
+}
+
+/// The open sessions and two-factor challenges, by token
+#[derive(Debug, Clone, Default)]
+pub struct Sessions {
+    sessions: Arc<Mutex<HashMap<String, Session>>>,
+    challenges: Arc<Mutex<HashMap<String, Challenge>>>,
+}
+
+impl Sessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a session for `user` and return its token
+    pub fn create(&self, user: &str, now: DateTime<Utc>) -> String {
+        let token = new_token();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, session| session.expires > now);
+        sessions.insert(
+            token.clone(),
+            Session {
+                user: user.to_string(),
+                expires: now + SESSION_IDLE,
+            },
+        );
+        token
+    }
+
+    /// The user of an unexpired session, extending it
+    pub fn user(&self, token: &str, now: DateTime<Utc>) -> Option<String> {
+        let mut sessions = self.sessions.lock().unwrap();
+        match sessions.get_mut(token) {
+            Some(session) if session.expires > now => {
+                session.expires = now + SESSION_IDLE;
+                Some(session.user.clone())
+            }
+            Some(_) => {
+                sessions.remove(token);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// End one session
+    pub fn remove(&self, token: &str) {
+        self.sessions.lock().unwrap().remove(token);
+    }
+
+    /// End every session of `user` except `keep` (e.g. the current one)
+    pub fn remove_user(&self, user: &str, keep: Option<&str>) {
+        self.sessions
+            .lock()
+            .unwrap()
+            .retain(|token, session| session.user != user || Some(token.as_str()) == keep);
+    }
+
+    /// Start a two-factor challenge for `user`, whose password was right
+    pub fn create_challenge(&self, user: &str, now: DateTime<Utc>) -> String {
+        let token = new_token();
+        let mut challenges = self.challenges.lock().unwrap();
+        challenges.retain(|_, challenge| challenge.expires > now);
+        challenges.insert(
+            token.clone(),
+            Challenge {
+                user: user.to_string(),
+                expires: now + CHALLENGE_TTL,
+                attempts_left: CHALLENGE_ATTEMPTS,
+            },
+        );
+        token
+    }
+
+    /// The user of an unexpired challenge
+    pub fn challenge_user(&self, token: &str, now: DateTime<Utc>) -> Option<String> {
+        let mut challenges = self.challenges.lock().unwrap();
+        match challenges.get(token) {
+            Some(challenge) if challenge.expires > now => Some(challenge.user.clone()),
+            Some(_) => {
+                challenges.remove(token);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Count a wrong code; returns whether the challenge may still be answered
+    pub fn fail_challenge(&self, token: &str) -> bool {
+        let mut challenges = self.challenges.lock().unwrap();
+        let Some(challenge) = challenges.get_mut(token) else {
+            return false;
+        };
+        challenge.attempts_left = challenge.attempts_left.saturating_sub(1);
+        if challenge.attempts_left == 0 {
+            challenges.remove(token);
+            return false;
+        }
+        true
+    }
+
+    /// End a challenge (answered, or no longer needed)
+    pub fn remove_challenge(&self, token: &str) {
+        self.challenges.lock().unwrap().remove(token);
+    }
+}
+
+/// A random token of 244 bits (two v4 UUIDs)
+fn new_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn noon() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_sessions_expire_when_idle() {
+        let sessions = Sessions::new();
+        let token = sessions.create("ada", noon());
+        assert_eq!(token.len(), 64);
+        assert_eq!(sessions.user(&token, noon()).as_deref(), Some("ada"));
+        assert_eq!(sessions.user("other", noon()), None);
+
+        // Each request pushes the expiry back
+        let later = noon() + SESSION_IDLE - Duration::minutes(1);
+        assert!(sessions.user(&token, later).is_some());
+        assert!(sessions.user(&token, later + SESSION_IDLE).is_none());
+        assert!(
+            sessions.user(&token, later).is_none(),
+            "expired sessions are removed"
+        );
+    }
+
+    #[test]
+    fn test_remove_user_keeps_the_current_session() {
+        let sessions = Sessions::new();
+        let current = sessions.create("ada", noon());
+        let other = sessions.create("ada", noon());
+        let mia = sessions.create("mia", noon());
+
+        sessions.remove_user("ada", Some(&current));
+        assert!(sessions.user(&current, noon()).is_some());
+        assert!(sessions.user(&other, noon()).is_none());
+        assert!(sessions.user(&mia, noon()).is_some());
+
+        sessions.remove(&mia);
+        assert!(sessions.user(&mia, noon()).is_none());
+    }
+
+    #[test]
+    fn test_challenges_expire_and_limit_attempts() {
+        let sessions = Sessions::new();
+        let token = sessions.create_challenge("ada", noon());
+        assert_eq!(
+            sessions.challenge_user(&token, noon()).as_deref(),
+            Some("ada")
+        );
+        assert!(sessions.user(&token, noon()).is_none(), "not a session");
+
+        for _ in 1..CHALLENGE_ATTEMPTS {
+            assert!(sessions.fail_challenge(&token));
+        }
+        assert!(!sessions.fail_challenge(&token));
+        assert!(sessions.challenge_user(&token, noon()).is_none());
+
+        let token = sessions.create_challenge("ada", noon());
+        assert!(
+            sessions
+                .challenge_user(&token, noon() + CHALLENGE_TTL)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,515 @@
+/// Site-wide settings and design tokens
+///
+/// Colors, fonts, the fluid type scale and the spacing scale used to live only
+/// in `website/assets/styles.css`. They are now stored in `data/settings.json`
+/// next to the route content and rendered as CSS custom properties in every
+/// public page's `<head>`, so rebranding no longer requires a deploy.
+///
+/// # Token Names
+///
+/// The generated variables match the names the feature stylesheets already
+/// reference (with fallbacks):
+///
+/// - Colors: `--color-primary`, `--color-text`, `--color-surface`, ...
+/// - Fonts: `--font-display`, `--font-body`
+/// - Type scale: `--step--4` … `--step-9` (Utopia-style fluid `clamp()`)
+/// - Spacing: `--space-3xs` … `--space-3xl`
+///
+/// # Safety
+///
+/// The CSS is injected into a `<style>` element, so every value is validated
+/// (`SiteSettings::validate`) or chosen from a closed set (`FontChoice`)
+/// before it can be saved. Free-form strings such as the site name never end
+/// up in CSS.
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use maud::{Markup, PreEscaped, html};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumMessage};
+
+use crate::core::i18n::LocaleSettings;
+use crate::core::persistence::data_dir;
+use crate::core::security_headers::csp_nonce;
+use crate::core::users::Role;
+
+/// Get the path to settings.json in the workspace data directory
+pub fn settings_json_path() -> PathBuf {
+    data_dir().join("settings.json")
+}
+
+/// Top-level site settings entity
+///
+/// # Example JSON
+///
+/// ```json
+/// {
+///   "site_name": "Eng Manager",
+///   "logo": null,
+///   "palette": { "primary": "#0066cc", "...": "..." },
+///   "fonts": { "display": "monument_extended", "body": "system_sans" },
+///   "type_scale": { "min_size": 18.0, "max_size": 20.0, "min_ratio": 1.2, "max_ratio": 1.25 },
+///   "spacing": { "min_size": 18.0, "max_size": 20.0 },
+///   "i18n": { "default_locale": "en", "locales": ["de"], "fallback": "default_locale" },
+///   "two_factor_roles": ["admin", "publisher"]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SiteSettings {
+    /// Used for `<title>` and OpenGraph metadata
+    pub site_name: String,
+    /// Optional media library id of the site logo
+    pub logo: Option<String>,
+    pub palette: Palette,
+    pub fonts: FontSettings,
+    pub type_scale: FluidScale,
+    pub spacing: FluidSpace,
+    /// Content locales (see `core::i18n`)
+    pub i18n: LocaleSettings,
+    /// Roles whose users must sign in with a second factor (see `core::totp`)
+    pub two_factor_roles: Vec<Role>,
+}
+
+impl Default for SiteSettings {
+    fn default() -> Self {
+        Self {
+            site_name: "Eng Manager".to_string(),
+            logo: None,
+            palette: Palette::default(),
+            fonts: FontSettings::default(),
+            type_scale: FluidScale::default(),
+            spacing: FluidSpace::default(),
+            i18n: LocaleSettings::default(),
+            two_factor_roles: vec![],
+        }
+    }
+}
+
+/// Color tokens, as `#rgb` or `#rrggbb` hex strings
+///
+/// Defaults match the fallbacks in the feature stylesheets, so an empty
+/// settings file renders the site exactly as before.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    /// Page and section background (header, hero)
+    pub background: String,
+    /// Text on `background`
+    pub on_background: String,
+    /// Form and card surfaces
+    pub surface: String,
+    pub text: String,
+    pub text_secondary: String,
+    pub primary: String,
+    pub primary_hover: String,
+    pub primary_active: String,
+    /// Text on `primary`
+    pub on_primary: String,
+    pub border: String,
+    pub border_hover: String,
+    pub error: String,
+    pub focus: String,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            background: "#0a0a0a".to_string(),
+            on_background: "#ffffff".to_string(),
+            surface: "#ffffff".to_string(),
+            text: "#1a1a1a".to_string(),
+            text_secondary: "#666666".to_string(),
+            primary: "#0066cc".to_string(),
+            primary_hover: "#0052a3".to_string(),
+            primary_active: "#003d7a".to_string(),
+            on_primary: "#ffffff".to_string(),
+            border: "#cccccc".to_string(),
+            border_hover: "#999999".to_string(),
+            error: "#cc0000".to_string(),
+            focus: "#0066cc".to_string(),
+        }
+    }
+}
+
+impl Palette {
+    /// (token name, value) pairs in a stable order
+    pub fn tokens(&self) -> [(&'static str, &str); 13] {
+        [
+            ("background", &self.background),
+            ("on-background", &self.on_background),
+            ("surface", &self.surface),
+            ("text", &self.text),
+            ("text-secondary", &self.text_secondary),
+            ("primary", &self.primary),
+            ("primary-hover", &self.primary_hover),
+            ("primary-active", &self.primary_active),
+            ("on-primary", &self.on_primary),
+            ("border", &self.border),
+            ("border-hover", &self.border_hover),
+            ("error", &self.error),
+            ("focus", &self.focus),
+        ]
+    }
+}
+
+/// Font families available to the theme
+///
+/// Only bundled webfonts and system stacks are offered, so a typo can't
+/// silently fall back to Times New Roman.
+///
+/// Displays as its serialized id; the human-readable label is the variant's
+/// strum message.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumIter, EnumMessage,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FontChoice {
+    /// Monument Extended Black (bundled in /assets)
+    #[strum(message = "Monument Extended")]
+    MonumentExtended,
+    #[strum(message = "System Sans")]
+    SystemSans,
+    #[strum(message = "System Serif")]
+    SystemSerif,
+    #[strum(message = "System Mono")]
+    SystemMono,
+}
+
+impl FontChoice {
+    /// CSS `font-family` stack
+    pub fn font_stack(self) -> &'static str {
+        match self {
+            FontChoice::MonumentExtended => "'monument_extendedblack', sans-serif",
+            FontChoice::SystemSans => "system-ui, -apple-system, 'Segoe UI', Roboto, sans-serif",
+            FontChoice::SystemSerif => "Georgia, 'Times New Roman', serif",
+            FontChoice::SystemMono => "ui-monospace, 'SFMono-Regular', Menlo, monospace",
+        }
+    }
+
+    /// Human-readable label
+    pub fn label(self) -> &'static str {
+        self.get_message().unwrap_or_default()
+    }
+}
+
+/// Font assignments for display (headings) and body text
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FontSettings {
+    pub display: FontChoice,
+    pub body: FontChoice,
+}
+
+impl Default for FontSettings {
+    fn default() -> Self {
+        Self {
+            display: FontChoice::MonumentExtended,
+            body: FontChoice::SystemSans,
+        }
+    }
+}
+
+/// Smallest and largest viewport widths the fluid scales interpolate between
+const MIN_VIEWPORT_PX: f64 = 360.0;
+const MAX_VIEWPORT_PX: f64 = 2560.0;
+
+/// Root font size used to convert px to rem
+const ROOT_FONT_PX: f64 = 16.0;
+
+/// Fluid modular type scale (Utopia-style)
+///
+/// Step 0 grows from `min_size` to `max_size` pixels between the min and max
+/// viewports; step `n` multiplies by `min_ratio^n` / `max_ratio^n`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FluidScale {
+    pub min_size: f64,
+    pub max_size: f64,
+    pub min_ratio: f64,
+    pub max_ratio: f64,
+}
+
+impl Default for FluidScale {
+    fn default() -> Self {
+        Self {
+            min_size: 18.0,
+            max_size: 20.0,
+            min_ratio: 1.2,
+            max_ratio: 1.25,
+        }
+    }
+}
+
+/// Type scale steps emitted as `--step-{n}`
+const TYPE_STEPS: std::ops::RangeInclusive<i32> = -4..=9;
+
+/// Fluid spacing scale
+///
+/// The base unit (`--space-s`) grows from `min_size` to `max_size` pixels;
+/// the other sizes are fixed multiples of it.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FluidSpace {
+    pub min_size: f64,
+    pub max_size: f64,
+}
+
+impl Default for FluidSpace {
+    fn default() -> Self {
+        Self {
+            min_size: 18.0,
+            max_size: 20.0,
+        }
+    }
+}
+
+/// Spacing sizes emitted as `--space-{name}` with their base-unit multipliers
+const SPACE_SIZES: [(&str, f64); 9] = [
+    ("3xs", 0.25),
+    ("2xs", 0.5),
+    ("xs", 0.75),
+    ("s", 1.0),
+    ("m", 1.5),
+    ("l", 2.0),
+    ("xl", 3.0),
+    ("2xl", 4.0),
+    ("3xl", 6.0),
+];
+
+impl SiteSettings {
+    /// Check that every value is safe to emit as CSS
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the first invalid field.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.site_name.trim().is_empty() {
+            return Err("site_name must not be empty".to_string());
+        }
+        for (name, value) in self.palette.tokens() {
+            if !is_hex_color(value) {
+                return Err(format!(
+                    "palette.{} must be a hex color like #0066cc, got '{}'",
+                    name.replace('-', "_"),
+                    value
+                ));
+            }
+        }
+        let scales = [
+            ("type_scale.min_size", self.type_scale.min_size, 8.0, 48.0),
+            ("type_scale.max_size", self.type_scale.max_size, 8.0, 48.0),
+            ("type_scale.min_ratio", self.type_scale.min_ratio, 1.0, 2.0),
+            ("type_scale.max_ratio", self.type_scale.max_ratio, 1.0, 2.0),
+            ("spacing.min_size", self.spacing.min_size, 4.0, 64.0),
+            ("spacing.max_size", self.spacing.max_size, 4.0, 64.0),
+        ];
+        for (name, value, min, max) in scales {
+            if !(min..=max).contains(&value) {
+                return Err(format!(
+                    "{} must be between {} and {}, got {}",
+                    name, min, max, value
+                ));
+            }
+        }
+        self.i18n.validate()
+    }
+
+    /// Render all design tokens as a `:root { ... }` CSS rule
+    pub fn css_variables(&self) -> String {
+        let mut css = String::from(":root {\n");
+        for (name, value) in self.palette.tokens() {
+            css.push_str(&format!("    --color-{}: {};\n", name, value));
+        }
+        css.push_str(&format!(
+            "    --font-display: {};\n    --font-body: {};\n",
+            self.fonts.display.font_stack(),
+            self.fonts.body.font_stack()
+        ));
+        for step in TYPE_STEPS {
+            let min = self.type_scale.min_size * self.type_scale.min_ratio.powi(step);
+            let max = self.type_scale.max_size * self.type_scale.max_ratio.powi(step);
+            css.push_str(&format!(
+                "    --step-{}: {};\n",
+                step,
+                fluid_clamp(min, max)
+            ));
+        }
+        for (name, multiplier) in SPACE_SIZES {
+            let min = self.spacing.min_size * multiplier;
+            let max = self.spacing.max_size * multiplier;
+            css.push_str(&format!(
+                "    --space-{}: {};\n",
+                name,
+                fluid_clamp(min, max)
+            ));
+        }
+        css.push('}');
+        css
+    }
+}
+
+/// Render the theme as a `<style>` element for the page `<head>`
+///
+/// Place it after `/assets/styles.css` so the tokens override the static
+/// defaults defined there. The element carries the request's CSP nonce (see
+/// `core::security_headers`).
+pub fn theme_style(settings: &SiteSettings) -> Markup {
+    html! {
+        style id="theme-tokens" nonce=[csp_nonce()] { (PreEscaped(settings.css_variables())) }
+    }
+}
+
+/// Build a `clamp()` expression interpolating linearly between viewports
+///
+/// Sizes are in px and emitted in rem; the preferred value is
+/// `intercept + slope * 100vi`, matching the Utopia calculator output.
+fn fluid_clamp(min_px: f64, max_px: f64) -> String {
+    let slope = (max_px - min_px) / (MAX_VIEWPORT_PX - MIN_VIEWPORT_PX);
+    let intercept = min_px - slope * MIN_VIEWPORT_PX;
+    let (lower, upper) = if min_px <= max_px {
+        (min_px, max_px)
+    } else {
+        (max_px, min_px)
+    };
+    format!(
+        "clamp({}rem, {}rem + {}vi, {}rem)",
+        round4(lower / ROOT_FONT_PX),
+        round4(intercept / ROOT_FONT_PX),
+        round4(slope * 100.0),
+        round4(upper / ROOT_FONT_PX)
+    )
+}
+
+fn round4(value: f64) -> f64 {
+    (value * 10_000.0).round() / 10_000.0
+}
+
+/// Whether a string is a `#rgb` or `#rrggbb` hex color
+fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Load site settings from JSON file
+///
+/// # Fallback Behavior
+///
+/// - File not found: Returns defaults
+/// - Invalid JSON or values: Returns defaults, logs error to stderr
+pub fn load_settings() -> SiteSettings {
+    let path = settings_json_path();
+    match fs::read_to_string(&path) {
+        Ok(contents) => match serde_json::from_str::<SiteSettings>(&contents) {
+            Ok(settings) => match settings.validate() {
+                Ok(()) => settings,
+                Err(e) => {
+                    eprintln!("Invalid settings in {}: {}", path.display(), e);
+                    SiteSettings::default()
+                }
+            },
+            Err(e) => {
+                eprintln!("Failed to parse {}: {}", path.display(), e);
+                SiteSettings::default()
+            }
+        },
+        Err(e) => {
+            // Only log if error is not "file not found" (expected on first run)
+            if e.kind() != io::ErrorKind::NotFound {
+                eprintln!("Failed to read {}: {}", path.display(), e);
+            }
+            SiteSettings::default()
+        }
+    }
+}
+
+/// Save site settings to JSON file
+///
+/// # Errors
+///
+/// Returns an error if validation fails, or if serialization or the file
+/// write fails. The caller should map this error to an appropriate HTTP
+/// status code.
+pub fn save_settings(settings: &SiteSettings) -> Result<(), Box<dyn std::error::Error>> {
+    settings.validate()?;
+    let path = settings_json_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_string_pretty(settings)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_default_type_scale_matches_static_stylesheet() {
+        // Values from the Utopia block in website/assets/styles.css
+        let css = SiteSettings::default().css_variables();
+        assert!(css.contains("--step-0: clamp(1.125rem, 1.1045rem + 0.0909vi, 1.25rem);"));
+        assert!(css.contains("--step--4: clamp(0.512rem, 0.5475rem + -0.0222vi, 0.5425rem);"));
+        assert!(css.contains("--step-9: clamp(5.8048rem, 5.2306rem + 2.5516vi, 9.3132rem);"));
+        assert!(css.contains("--color-primary: #0066cc;"));
+        assert!(css.contains("--font-display: 'monument_extendedblack', sans-serif;"));
+        assert!(css.contains("--space-m: clamp("));
+    }
+
+    #[test]
+    fn test_partial_settings_fill_in_defaults() {
+        let settings: SiteSettings =
+            serde_json::from_str(r##"{"palette": {"primary": "#ff0000"}}"##).unwrap();
+        assert_eq!(settings.palette.primary, "#ff0000");
+        assert_eq!(settings.palette.text, Palette::default().text);
+        assert_eq!(settings.site_name, "Eng Manager");
+        assert_eq!(settings.i18n, LocaleSettings::default());
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_checks_locales() {
+        let mut settings = SiteSettings::default();
+        settings.i18n.locales = vec!["de".to_string(), "../x".to_string()];
+        assert!(settings.validate().unwrap_err().contains("../x"));
+    }
+
+    #[test]
+    fn test_validate_rejects_css_injection() {
+        let mut settings = SiteSettings::default();
+        settings.palette.primary = "red; } body { display: none".to_string();
+        let err = settings.validate().unwrap_err();
+        assert!(err.contains("palette.primary"));
+
+        let mut settings = SiteSettings::default();
+        settings.type_scale.max_ratio = 10.0;
+        assert!(
+            settings
+                .validate()
+                .unwrap_err()
+                .contains("type_scale.max_ratio")
+        );
+    }
+
+    #[test]
+    fn test_font_choice_ids_match_serde() {
+        for font in FontChoice::iter() {
+            let json = serde_json::to_string(&font).unwrap();
+            assert_eq!(json, format!("\"{font}\""));
+            assert!(!font.label().is_empty(), "{font} has a label");
+        }
+    }
+
+    #[test]
+    fn test_is_hex_color() {
+        assert!(is_hex_color("#fff"));
+        assert!(is_hex_color("#0066CC"));
+        assert!(!is_hex_color("0066cc"));
+        assert!(!is_hex_color("#0066c"));
+        assert!(!is_hex_color("#gggggg"));
+    }
+}
@@ -0,0 +1,493 @@
+/// Scoped API tokens for scripts and CI
+///
+/// A token lets a script call `/admin/api/*` without a browser session, by
+/// sending `Authorization: Bearer wst_…`. Tokens are stored in
+/// `data/tokens.json`; only a SHA-256 hash of each token is kept, so the
+/// token itself is shown once, when it is created:
+///
+/// ```json
+/// [
+///   {
+///     "id": "3f2b…",
+///     "name": "changelog sync",
+///     "owner": "mia",
+///     "scopes": ["read_content", "write_content"],
+///     "routes": ["changelog"],
+///     "hash": "sha256:9f86…",
+///     "created": "2026-03-01T09:00:00Z",
+///     "expires": "2026-05-30T09:00:00Z",
+///     "last_used": "2026-03-02T18:12:00Z"
+///   }
+/// ]
+/// ```
+///
+/// # Scopes
+///
+/// | Scope           | Grants                                           |
+/// |-----------------|--------------------------------------------------|
+/// | `read_content`  | View: read routes, content and read-only APIs    |
+/// | `write_content` | View, Edit and Publish on `routes` (every route when absent) |
+/// | `manage_routes` | View and Manage routes: replace routes.json      |
+///
+/// A token belongs to the user who created it and never grants more than
+/// that user may do now: a request is allowed only if both the owner's role
+/// (see `core::users`) and the token's scopes allow it. Deleting the owner
+/// disables their tokens. Tokens never grant Administer.
+///
+/// # Expiry and Last Use
+///
+/// A token may expire; an expired token is refused like an unknown one.
+/// `last_used` is updated on use, at most once per `LAST_USED_INTERVAL`, so a
+/// busy script doesn't rewrite tokens.json on every request.
+///
+/// # Architecture
+///
+/// Following rust-core-patterns:
+/// - **No framework types**: Like `core::users`, tokens are plain values;
+///   the admin middleware turns a valid token into the request's user
+/// - **Injected time**: Creating and checking tokens take `now` from the
+///   app's clock, so tests can expire them with a `ManualClock`
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use strum::{Display, EnumIter};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::core::load_routes;
+use crate::core::persistence::{data_dir, write_atomic};
+use crate::core::users::Permission;
+
+/// File name of the tokens file in the data directory
+const TOKENS_FILE: &str = "tokens.json";
+
+/// Start of every token, so leaked tokens are easy to recognize
+pub const TOKEN_PREFIX: &str = "wst_";
+
+/// Longest token name, in characters
+const MAX_NAME_CHARS: usize = 60;
+
+/// Least time between two `last_used` updates of a token
+pub const LAST_USED_INTERVAL: Duration = Duration::minutes(1);
+
+/// Serializes every load, change and save of tokens.json, so concurrent
+/// requests can't undo each other's changes
+static TOKENS_LOCK: Mutex<()> = Mutex::new(());
+
+/// What a token may be used for
+///
+/// Variants are in the order shown in the tokens screen and stored on a
+/// token; each displays as its serialized name.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TokenScope {
+    ReadContent,
+    WriteContent,
+    ManageRoutes,
+}
+
+impl TokenScope {
+    /// The owner permission needed to create a token with this scope
+    pub fn permission(self) -> Permission {
+        match self {
+            TokenScope::ReadContent => Permission::View,
+            TokenScope::WriteContent => Permission::Edit,
+            TokenScope::ManageRoutes => Permission::ManageRoutes,
+        }
+    }
+
+    /// Whether the scope grants `permission` (before the token's routes)
+    pub fn allows(self, permission: Permission) -> bool {
+        matches!(
+            (self, permission),
+            (_, Permission::View)
+                | (
+                    TokenScope::WriteContent,
+                    Permission::Edit | Permission::Publish
+                )
+                | (TokenScope::ManageRoutes, Permission::ManageRoutes)
+        )
+    }
+}
+
+/// An API token as stored in tokens.json
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    /// What the token is for, chosen by its owner
+    pub name: String,
+    /// Name of the user who created the token
+    pub owner: String,
+    pub scopes: Vec<TokenScope>,
+    /// Routes `write_content` applies to; every route when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routes: Option<Vec<String>>,
+    /// Hash of the token (see `hash_token`)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub hash: String,
+    pub created: DateTime<Utc>,
+    #[serde(default)]
+    pub expires: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    /// Whether the token has expired at `now`
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// Whether a scope of the token grants `permission`
+    pub fn allows(&self, permission: Permission) -> bool {
+        self.scopes.iter().any(|scope| scope.allows(permission))
+    }
+
+    /// Whether the token grants `permission` on `route`
+    pub fn allows_on_route(&self, permission: Permission, route: &str) -> bool {
+        let granted = match (&self.routes, permission) {
+            (Some(routes), Permission::Edit | Permission::Publish) => {
+                routes.iter().any(|granted| granted == route)
+            }
+            _ => true,
+        };
+        self.allows(permission) && granted
+    }
+
+    /// The token without its hash, for request extensions and pages
+    pub fn without_hash(&self) -> Self {
+        Self {
+            hash: String::new(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Why a tokens change or load failed
+#[derive(Debug, Error)]
+pub enum TokenError {
+    #[error("token name must be 1-{MAX_NAME_CHARS} characters")]
+    InvalidName,
+
+    #[error("choose at least one scope")]
+    NoScopes,
+
+    #[error("unknown route '{0}'")]
+    UnknownRoute(String),
+
+    #[error("token '{0}' not found")]
+    NotFound(String),
+
+    #[error("tokens.json is invalid: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("failed to access tokens.json: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Path of tokens.json in the current data directory
+pub fn tokens_path() -> PathBuf {
+    data_dir().join(TOKENS_FILE)
+}
+
+/// Every token; none if tokens.json doesn't exist
+pub fn load_tokens() -> Result<Vec<ApiToken>, TokenError> {
+    match fs::read_to_string(tokens_path()) {
+        Ok(json) => Ok(serde_json::from_str(&json)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Hold `TOKENS_LOCK` from loading tokens.json until it is saved
+fn lock_tokens() -> MutexGuard<'static, ()> {
+    TOKENS_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Write tokens.json atomically, since every token request reads it
+fn save_tokens(tokens: &[ApiToken]) -> Result<(), TokenError> {
+    write_atomic(&tokens_path(), &serde_json::to_string_pretty(tokens)?)?;
+    Ok(())
+}
+
+/// A token to create
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewToken {
+    pub name: String,
+    pub owner: String,
+    pub scopes: Vec<TokenScope>,
+    /// Routes `write_content` applies to; every route when `None`
+    pub routes: Option<Vec<String>>,
+    pub expires: Option<DateTime<Utc>>,
+}
+
+/// Create a token, returning it (without its hash) and the token to show
+///
+/// The returned token string is not stored anywhere; it can't be shown
+/// again.
+pub fn create_token(new: NewToken, now: DateTime<Utc>) -> Result<(ApiToken, String), TokenError> {
+    let name = new.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+        return Err(TokenError::InvalidName);
+    }
+    if new.scopes.is_empty() {
+        return Err(TokenError::NoScopes);
+    }
+    let known: Vec<String> = load_routes().into_iter().map(|route| route.name).collect();
+    if let Some(unknown) = new
+        .routes
+        .iter()
+        .flatten()
+        .find(|route| !known.contains(route))
+    {
+        return Err(TokenError::UnknownRoute(unknown.clone()));
+    }
+
+    let secret = format!(
+        "{TOKEN_PREFIX}{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    );
+    let mut scopes = new.scopes;
+    scopes.sort();
+    scopes.dedup();
+    let token = ApiToken {
+        id: Uuid::new_v4().simple().to_string(),
+        name: name.to_string(),
+        owner: new.owner,
+        scopes,
+        routes: new.routes,
+        hash: hash_token(&secret),
+        created: now,
+        expires: new.expires,
+        last_used: None,
+    };
+
+    let _guard = lock_tokens();
+    let mut tokens = load_tokens()?;
+    tokens.push(token.clone());
+    save_tokens(&tokens)?;
+    Ok((token.without_hash(), secret))
+}
+
+/// Delete a token, returning it
+pub fn revoke_token(id: &str) -> Result<ApiToken, TokenError> {
+    let _guard = lock_tokens();
+    let mut tokens = load_tokens()?;
+    let index = tokens
+        .iter()
+        .position(|token| token.id == id)
+        .ok_or_else(|| TokenError::NotFound(id.to_string()))?;
+    let token = tokens.remove(index);
+    save_tokens(&tokens)?;
+    Ok(token.without_hash())
+}
+
+/// The unexpired token matching `secret`, recording its use at `now`
+pub fn authenticate_token(
+    secret: &str,
+    now: DateTime<Utc>,
+) -> Result<Option<ApiToken>, TokenError> {
+    if !secret.starts_with(TOKEN_PREFIX) {
+        return Ok(None);
+    }
+    let hash = hash_token(secret);
+    let _guard = lock_tokens();
+    let mut tokens = load_tokens()?;
+    let Some(index) = tokens.iter().position(|token| token.hash == hash) else {
+        return Ok(None);
+    };
+    if tokens[index].is_expired(now) {
+        return Ok(None);
+    }
+
+    let stale = tokens[index]
+        .last_used
+        .is_none_or(|last_used| now - last_used >= LAST_USED_INTERVAL);
+    if stale {
+        tokens[index].last_used = Some(now);
+        save_tokens(&tokens)?;
+    }
+    Ok(Some(tokens[index].without_hash()))
+}
+
+/// Stored hash of a token
+///
+/// Tokens are long random strings, so a single SHA-256 is enough; unlike
+/// passwords they can't be guessed from a dictionary.
+fn hash_token(secret: &str) -> String {
+    format!("sha256:{:x}", Sha256::digest(secret.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::persistence::with_data_dir_sync;
+    use chrono::TimeZone;
+    use strum::IntoEnumIterator;
+    use tempfile::TempDir;
+
+    fn noon() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn new_token(scopes: Vec<TokenScope>) -> NewToken {
+        NewToken {
+            name: "ci".to_string(),
+            owner: "mia".to_string(),
+            scopes,
+            routes: None,
+            expires: Some(noon() + Duration::days(30)),
+        }
+    }
+
+    #[test]
+    fn test_scopes_display_as_serde_names_and_are_stored_in_order() {
+        for scope in TokenScope::iter() {
+            let json = serde_json::to_string(&scope).unwrap();
+            assert_eq!(json, format!("\"{scope}\""));
+        }
+
+        let dir = TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let scopes = vec![
+                TokenScope::ManageRoutes,
+                TokenScope::ReadContent,
+                TokenScope::ManageRoutes,
+            ];
+            let (token, _) = create_token(new_token(scopes), noon()).unwrap();
+            assert_eq!(
+                token.scopes,
+                vec![TokenScope::ReadContent, TokenScope::ManageRoutes]
+            );
+        });
+    }
+
+    #[test]
+    fn test_tokens_are_stored_hashed_and_expire() {
+        let dir = TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let (token, secret) =
+                create_token(new_token(vec![TokenScope::ReadContent]), noon()).unwrap();
+            assert!(secret.starts_with(TOKEN_PREFIX));
+            assert!(token.hash.is_empty());
+            let stored = fs::read_to_string(tokens_path()).unwrap();
+            assert!(!stored.contains(&secret));
+            assert!(stored.contains(&hash_token(&secret)));
+
+            let found = authenticate_token(&secret, noon()).unwrap().unwrap();
+            assert_eq!(found.id, token.id);
+            assert_eq!(found.last_used, Some(noon()));
+            assert!(authenticate_token("wst_other", noon()).unwrap().is_none());
+
+            let expired = noon() + Duration::days(30);
+            assert!(authenticate_token(&secret, expired).unwrap().is_none());
+
+            revoke_token(&token.id).unwrap();
+            assert!(authenticate_token(&secret, noon()).unwrap().is_none());
+            assert!(matches!(
+                revoke_token(&token.id),
+                Err(TokenError::NotFound(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_last_used_is_updated_at_most_once_per_interval() {
+        let dir = TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let (_, secret) =
+                create_token(new_token(vec![TokenScope::ReadContent]), noon()).unwrap();
+            authenticate_token(&secret, noon()).unwrap();
+
+            let soon = noon() + Duration::seconds(30);
+            let found = authenticate_token(&secret, soon).unwrap().unwrap();
+            assert_eq!(found.last_used, Some(noon()));
+            let later = noon() + LAST_USED_INTERVAL;
+            let found = authenticate_token(&secret, later).unwrap().unwrap();
+            assert_eq!(found.last_used, Some(later));
+        });
+    }
+
+    #[test]
+    fn test_revoking_during_authentication_stays_revoked() {
+        const USES: u32 = 8;
+        let dir = TempDir::new().unwrap();
+        let data_dir = dir.path().to_path_buf();
+        for _ in 0..50 {
+            let (token, secret) = with_data_dir_sync(data_dir.clone(), || {
+                create_token(new_token(vec![TokenScope::ReadContent]), noon()).unwrap()
+            });
+            let start = std::sync::Barrier::new(USES as usize + 1);
+            std::thread::scope(|scope| {
+                for minute in 0..USES {
+                    let (data_dir, secret, start) = (data_dir.clone(), &secret, &start);
+                    scope.spawn(move || {
+                        // Each use is stale, so each one saves tokens.json
+                        let now = noon() + LAST_USED_INTERVAL * minute as i32;
+                        start.wait();
+                        with_data_dir_sync(data_dir, || authenticate_token(secret, now).unwrap());
+                    });
+                }
+                start.wait();
+                with_data_dir_sync(data_dir.clone(), || revoke_token(&token.id).unwrap());
+            });
+
+            with_data_dir_sync(data_dir.clone(), || {
+                assert!(authenticate_token(&secret, noon()).unwrap().is_none());
+                assert!(load_tokens().unwrap().is_empty());
+            });
+        }
+    }
+
+    #[test]
+    fn test_scopes_limit_permissions_and_routes() {
+        let dir = TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let new = NewToken {
+                routes: Some(vec!["homepage".to_string()]),
+                ..new_token(vec![TokenScope::WriteContent])
+            };
+            let (token, _) = create_token(new, noon()).unwrap();
+            assert!(token.allows_on_route(Permission::Publish, "homepage"));
+            assert!(!token.allows_on_route(Permission::Edit, "foo"));
+            assert!(token.allows_on_route(Permission::View, "foo"));
+            assert!(!token.allows(Permission::ManageRoutes));
+            assert!(!token.allows(Permission::Administer));
+
+            let unknown = NewToken {
+                routes: Some(vec!["nope".to_string()]),
+                ..new_token(vec![TokenScope::WriteContent])
+            };
+            assert!(matches!(
+                create_token(unknown, noon()),
+                Err(TokenError::UnknownRoute(_))
+            ));
+            assert!(matches!(
+                create_token(new_token(vec![]), noon()),
+                Err(TokenError::NoScopes)
+            ));
+        });
+        assert!(TokenScope::ManageRoutes.allows(Permission::ManageRoutes));
+        assert!(!TokenScope::ReadContent.allows(Permission::Edit));
+    }
+}
@@ -0,0 +1,349 @@
+/// Time-based one-time passwords (TOTP) for two-factor sign-in
+///
+/// Implements RFC 6238 with the parameters every authenticator app
+/// supports: HMAC-SHA1, 6 digits, 30-second steps. A user enrolls by
+/// scanning a QR code of the `otpauth://` provisioning URI (or typing the
+/// base32 secret) and confirming one code; after that, signing in needs a
+/// current code as well as the password.
+///
+/// # Verification
+///
+/// A code is accepted for the current step and one step either side, to
+/// allow for clock drift. Each step is accepted once: `TwoFactor::last_step`
+/// remembers the last step used, so an observed code can't be replayed.
+///
+/// # Recovery Codes
+///
+/// Enrolling creates `RECOVERY_CODES` single-use codes (`xxxxx-xxxxx`) for
+/// when the authenticator is lost. They are shown once and stored as
+/// SHA-256 hashes; like API tokens they are random, so a fast hash is enough.
+///
+/// # Architecture
+///
+/// Following rust-core-patterns:
+/// - **No framework types**: Codes and enrollments are plain values; the
+///   sign-in and account handlers call them and map failures to responses
+/// - **Injected time**: Every check takes `now`, so tests use fixed times
+///   (including the RFC 6238 test vectors)
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use qrcode::QrCode;
+use qrcode::render::svg;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+/// Seconds per code
+pub const STEP_SECONDS: i64 = 30;
+
+/// Digits per code
+pub const DIGITS: u32 = 6;
+
+/// Steps accepted before and after the current one
+const DRIFT_STEPS: i64 = 1;
+
+/// Recovery codes created on enrollment
+pub const RECOVERY_CODES: usize = 10;
+
+/// RFC 4648 base32 alphabet, used for secrets in provisioning URIs
+const BASE32: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// A user's two-factor enrollment, as stored in users.json
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TwoFactor {
+    /// Base32 TOTP secret
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub secret: String,
+    /// Hashes of the unused recovery codes (see `hash_recovery_code`)
+    #[serde(default)]
+    pub recovery_codes: Vec<String>,
+    /// Last step a code was accepted for
+    #[serde(default)]
+    pub last_step: i64,
+}
+
+/// Which kind of code was accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecondFactor {
+    Totp,
+    /// A recovery code, now used up; `remaining` are left
+    RecoveryCode {
+        remaining: usize,
+    },
+}
+
+impl TwoFactor {
+    /// A new enrollment with `secret`, returning it and its recovery codes
+    pub fn new(secret: &str, now: DateTime<Utc>) -> (Self, Vec<String>) {
+        let codes = generate_recovery_codes();
+        let enrollment = Self {
+            secret: secret.to_string(),
+            recovery_codes: codes.iter().map(|code| hash_recovery_code(code)).collect(),
+            last_step: step_at(now),
+        };
+        (enrollment, codes)
+    }
+
+    /// Accept a TOTP code or an unused recovery code, recording its use
+    pub fn verify(&mut self, code: &str, now: DateTime<Utc>) -> Option<SecondFactor> {
+        if let Some(step) = matching_step(&self.secret, code, now)
+            && step > self.last_step
+        {
+            self.last_step = step;
+            return Some(SecondFactor::Totp);
+        }
+
+        let hash = hash_recovery_code(code);
+        let index = self
+            .recovery_codes
+            .iter()
+            .position(|stored| *stored == hash)?;
+        self.recovery_codes.remove(index);
+        Some(SecondFactor::RecoveryCode {
+            remaining: self.recovery_codes.len(),
+        })
+    }
+
+    /// Replace the recovery codes, returning the new ones
+    pub fn reset_recovery_codes(&mut self) -> Vec<String> {
+        let codes = generate_recovery_codes();
+        self.recovery_codes = codes.iter().map(|code| hash_recovery_code(code)).collect();
+        codes
+    }
+}
+
+/// A new random secret (160 bits, as RFC 4226 recommends), in base32
+pub fn generate_secret() -> String {
+    let random = [Uuid::new_v4(), Uuid::new_v4()];
+    let bytes: Vec<u8> = random.iter().flat_map(|uuid| *uuid.as_bytes()).collect();
+    base32_encode(&bytes[..20])
+}
+
+/// Whether `secret` is a usable base32 secret
+pub fn is_secret(secret: &str) -> bool {
+    base32_decode(secret).is_some_and(|bytes| bytes.len() >= 16)
+}
+
+/// The code for `secret` at `at`
+pub fn code_at(secret: &str, at: DateTime<Utc>) -> Option<String> {
+    let key = base32_decode(secret)?;
+    Some(hotp(&key, step_at(at)))
+}
+
+/// The step `code` is valid for at `now`, allowing for clock drift
+pub fn matching_step(secret: &str, code: &str, now: DateTime<Utc>) -> Option<i64> {
+    let code: String = code.chars().filter(|c| !c.is_whitespace()).collect();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let key = base32_decode(secret)?;
+    let current = step_at(now);
+    (current - DRIFT_STEPS..=current + DRIFT_STEPS)
+        .filter(|step| *step >= 0)
+        .find(|step| constant_time_eq(hotp(&key, *step).as_bytes(), code.as_bytes()))
+}
+
+/// The `otpauth://` URI authenticator apps read from the QR code
+pub fn provisioning_uri(issuer: &str, account: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        secret,
+        percent_encode(issuer),
+        DIGITS,
+        STEP_SECONDS
+    )
+}
+
+/// The provisioning URI as an inline SVG QR code
+pub fn qr_code_svg(uri: &str) -> Option<String> {
+    let svg = QrCode::new(uri.as_bytes())
+        .ok()?
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .build();
+    // Drop the XML declaration so the SVG can be inlined in HTML
+    svg.find("<svg").map(|start| svg[start..].to_string())
+}
+
+fn step_at(at: DateTime<Utc>) -> i64 {
+    at.timestamp().div_euclid(STEP_SECONDS)
+}
+
+/// RFC 4226 HOTP value of `counter`, as a zero-padded decimal code
+fn hotp(key: &[u8], counter: i64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = usize::from(hash[hash.len() - 1] & 0x0f);
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(DIGITS),
+        width = DIGITS as usize
+    )
+}
+
+fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODES)
+        .map(|_| {
+            let code = base32_encode(Uuid::new_v4().as_bytes()).to_lowercase();
+            format!("{}-{}", &code[..5], &code[5..10])
+        })
+        .collect()
+}
+
+/// Stored hash of a recovery code; case and spacing don't matter
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    format!("sha256:{:x}", Sha256::digest(normalized.as_bytes()))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(char::from(BASE32[((buffer >> bits) & 31) as usize]));
+        }
+    }
+    if bits > 0 {
+        out.push(char::from(BASE32[((buffer << (5 - bits)) & 31) as usize]));
+    }
+    out
+}
+
+/// Decode base32, ignoring case, spaces and padding
+fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in text.chars().filter(|c| !c.is_whitespace() && *c != '=') {
+        let value = BASE32
+            .iter()
+            .position(|&b| char::from(b) == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    (!out.is_empty()).then_some(out)
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                char::from(byte).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// The RFC 6238 SHA-1 test secret, "12345678901234567890"
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(seconds, 0).unwrap()
+    }
+
+    #[test]
+    fn test_codes_match_rfc_6238_vectors() {
+        assert_eq!(base32_encode(b"12345678901234567890"), RFC_SECRET);
+        assert_eq!(
+            base32_decode(&RFC_SECRET.to_lowercase()).unwrap(),
+            b"12345678901234567890"
+        );
+        // The RFC lists 8-digit codes; these are their last 6 digits
+        for (seconds, code) in [
+            (59, "287082"),
+            (1_111_111_109, "081804"),
+            (1_111_111_111, "050471"),
+            (1_234_567_890, "005924"),
+            (2_000_000_000, "279037"),
+        ] {
+            assert_eq!(code_at(RFC_SECRET, at(seconds)).unwrap(), code, "{seconds}");
+        }
+    }
+
+    #[test]
+    fn test_codes_allow_one_step_of_drift() {
+        let now = at(1_111_111_111);
+        let code = code_at(RFC_SECRET, now).unwrap();
+        assert!(matching_step(RFC_SECRET, &code, now).is_some());
+        assert!(matching_step(RFC_SECRET, &code, at(1_111_111_111 + 30)).is_some());
+        assert!(matching_step(RFC_SECRET, &code, at(1_111_111_111 + 90)).is_none());
+        assert!(matching_step(RFC_SECRET, "050 471", now).is_some());
+        assert!(matching_step(RFC_SECRET, "12345", now).is_none());
+        assert!(matching_step(RFC_SECRET, "abcdef", now).is_none());
+    }
+
+    #[test]
+    fn test_each_step_and_recovery_code_is_used_once() {
+        let start = at(1_700_000_000);
+        let (mut enrollment, codes) = TwoFactor::new(&generate_secret(), start);
+        assert_eq!(codes.len(), RECOVERY_CODES);
+        assert!(is_secret(&enrollment.secret));
+
+        // The step used to confirm enrollment can't sign in again
+        let code = code_at(&enrollment.secret, start).unwrap();
+        assert_eq!(enrollment.verify(&code, start), None);
+
+        let later = start + chrono::Duration::seconds(60);
+        let code = code_at(&enrollment.secret, later).unwrap();
+        assert_eq!(enrollment.verify(&code, later), Some(SecondFactor::Totp));
+        assert_eq!(enrollment.verify(&code, later), None, "replayed");
+
+        let recovery = codes[3].to_uppercase();
+        assert_eq!(
+            enrollment.verify(&recovery, later),
+            Some(SecondFactor::RecoveryCode {
+                remaining: RECOVERY_CODES - 1
+            })
+        );
+        assert_eq!(enrollment.verify(&recovery, later), None);
+
+        let fresh = enrollment.reset_recovery_codes();
+        assert_eq!(enrollment.recovery_codes.len(), RECOVERY_CODES);
+        assert_eq!(enrollment.verify(&codes[0], later), None);
+        assert!(enrollment.verify(&fresh[0], later).is_some());
+    }
+
+    #[test]
+    fn test_provisioning_uri_and_qr_code() {
+        let uri = provisioning_uri("Eng Manager", "ada@example.com", RFC_SECRET);
+        assert_eq!(
+            uri,
+            "otpauth://totp/Eng%20Manager:ada%40example.com?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ\
+             &issuer=Eng%20Manager&algorithm=SHA1&digits=6&period=30"
+        );
+        let svg = qr_code_svg(&uri).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+    }
+}
@@ -0,0 +1,751 @@
+/// Admin users, roles and per-route grants
+///
+/// Users are stored in `data/users.json`. Each has a role, and editors and
+/// publishers may be limited to some routes:
+///
+/// ```json
+/// [
+///   { "name": "ada", "role": "admin", "password": "$pbkdf2-sha256$…" },
+///   { "name": "mia", "role": "editor", "routes": ["homepage"], "password": "$pbkdf2-sha256$…" }
+/// ]
+/// ```
+///
+/// # Roles
+///
+/// | Role        | View | Edit | Publish | Manage routes | Administer |
+/// |-------------|------|------|---------|---------------|------------|
+/// | `viewer`    | ✓    |      |         |               |            |
+/// | `editor`    | ✓    | ✓    |         |               |            |
+/// | `publisher` | ✓    | ✓    | ✓       |               |            |
+/// | `admin`     | ✓    | ✓    | ✓       | ✓             | ✓          |
+///
+/// - **View**: admin pages, previews, story pages and read-only APIs
+/// - **Edit**: save route content, upload and edit media
+/// - **Publish**: schedule saves, cancel scheduled drafts, delete media
+/// - **Manage routes**: replace routes.json through the routes API
+/// - **Administer**: settings, archive export/import, the audit log and users
+///
+/// `routes` limits Edit and Publish to the listed routes; without it a user
+/// may edit every route. Admins ignore it.
+///
+/// # Open Mode
+///
+/// Until the first user is created, `users.json` is missing or empty and the
+/// admin is open: every request acts as an anonymous admin, as it did before
+/// users existed. The first user must be an admin, and the last admin can't
+/// be removed or demoted, so the admin can't be locked by accident. Deleting
+/// `users.json` reopens it.
+///
+/// # Passwords
+///
+/// Passwords are stored as PBKDF2-HMAC-SHA256 hashes (the `pbkdf2` crate)
+/// with a salt from the OS random generator, in PHC string format:
+/// `$pbkdf2-sha256$i=600000,l=32${salt}${hash}`. The iteration count is part
+/// of the hash, so it can be raised without invalidating old hashes.
+///
+/// # Two-Factor Authentication
+///
+/// A user may enroll a TOTP authenticator (see `core::totp`); the enrollment
+/// is stored with the user under `two_factor`. Enrolled users need a code
+/// after their password to sign in. The site settings can require it for
+/// some roles, in which case those users must enroll before using the admin.
+///
+/// # Architecture
+///
+/// Following rust-core-patterns:
+/// - **No framework types**: Checks are plain functions of a user; the
+///   admin handlers map a failed check to 403
+/// - **Read per request**: Users are loaded from disk on every request, so a
+///   changed role or grant applies immediately
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use pbkdf2::password_hash::rand_core::OsRng;
+use pbkdf2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use pbkdf2::{Params, Pbkdf2};
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter, EnumString};
+use thiserror::Error;
+
+use chrono::{DateTime, Utc};
+
+use crate::core::audit::ANONYMOUS;
+use crate::core::load_routes;
+use crate::core::persistence::{data_dir, write_atomic};
+use crate::core::totp::{self, SecondFactor, TwoFactor};
+
+/// File name of the users file in the data directory
+const USERS_FILE: &str = "users.json";
+
+/// Shortest password accepted, in characters
+pub const MIN_PASSWORD_CHARS: usize = 10;
+
+/// Longest user name, in characters
+const MAX_NAME_CHARS: usize = 40;
+
+/// PBKDF2 iterations for new hashes (OWASP's recommendation for SHA-256)
+#[cfg(not(test))]
+const PASSWORD_ITERATIONS: u32 = 600_000;
+
+/// Unit tests hash many passwords; `test_verify_password_known_answers`
+/// checks the real iteration count
+#[cfg(test)]
+const PASSWORD_ITERATIONS: u32 = 1_000;
+
+/// Serializes every load, check and save of users.json, so a code can't be
+/// used twice and concurrent changes can't undo each other
+static USERS_LOCK: Mutex<()> = Mutex::new(());
+
+// ============================================================================
+// Roles and Permissions
+// ============================================================================
+
+/// What a user may do, from least to most
+///
+/// Variants are in the order shown in the users screen; each displays and
+/// parses as its serialized name.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Role {
+    Viewer,
+    Editor,
+    Publisher,
+    Admin,
+}
+
+impl Role {
+    /// Whether the role grants `permission` (before route grants)
+    pub fn allows(self, permission: Permission) -> bool {
+        let needed = match permission {
+            Permission::View => Role::Viewer,
+            Permission::Edit => Role::Editor,
+            Permission::Publish => Role::Publisher,
+            Permission::ManageRoutes | Permission::Administer => Role::Admin,
+        };
+        self >= needed
+    }
+}
+
+/// A kind of admin action, checked by the admin handlers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    View,
+    Edit,
+    Publish,
+    ManageRoutes,
+    Administer,
+}
+
+impl Permission {
+    /// Lowercase verb for messages ("edit", "publish", …)
+    pub fn verb(self) -> &'static str {
+        match self {
+            Permission::View => "view",
+            Permission::Edit => "edit",
+            Permission::Publish => "publish",
+            Permission::ManageRoutes => "manage routes",
+            Permission::Administer => "administer",
+        }
+    }
+}
+
+// ============================================================================
+// Users
+// ============================================================================
+
+/// An admin user as stored in users.json
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct User {
+    pub name: String,
+    pub role: Role,
+    /// Routes this user may edit and publish; every route when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routes: Option<Vec<String>>,
+    /// Password hash (see `hash_password`)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub password: String,
+    /// TOTP enrollment; two-factor sign-in is off when absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub two_factor: Option<TwoFactor>,
+}
+
+impl User {
+    /// The anonymous admin every request acts as in open mode
+    pub fn anonymous() -> Self {
+        Self {
+            name: ANONYMOUS.to_string(),
+            role: Role::Admin,
+            routes: None,
+            password: String::new(),
+            two_factor: None,
+        }
+    }
+
+    /// Whether the user may do `permission` anywhere (ignoring route grants)
+    ///
+    /// For Edit and Publish this means "on at least one route".
+    pub fn can(&self, permission: Permission) -> bool {
+        self.role.allows(permission)
+            && (self.role == Role::Admin
+                || !matches!(permission, Permission::Edit | Permission::Publish)
+                || self.routes.as_ref().is_none_or(|routes| !routes.is_empty()))
+    }
+
+    /// Whether the user may do `permission` on `route`
+    pub fn can_on_route(&self, permission: Permission, route: &str) -> bool {
+        let granted = match (&self.routes, permission) {
+            _ if self.role == Role::Admin => true,
+            (Some(routes), Permission::Edit | Permission::Publish) => {
+                routes.iter().any(|granted| granted == route)
+            }
+            _ => true,
+        };
+        self.role.allows(permission) && granted
+    }
+
+    /// Whether signing in needs a second factor
+    pub fn has_two_factor(&self) -> bool {
+        self.two_factor.is_some()
+    }
+
+    /// Whether the user must enroll before using the admin, given the roles
+    /// that require two-factor sign-in
+    pub fn must_enroll_two_factor(&self, required: &[Role]) -> bool {
+        !self.has_two_factor() && required.contains(&self.role)
+    }
+
+    /// The user without their password hash or TOTP secret, for request
+    /// extensions and pages
+    pub fn without_password(&self) -> Self {
+        Self {
+            password: String::new(),
+            two_factor: self.two_factor.as_ref().map(|two_factor| TwoFactor {
+                secret: String::new(),
+                ..two_factor.clone()
+            }),
+            ..self.clone()
+        }
+    }
+}
+
+/// Why a users change or load failed
+#[derive(Debug, Error)]
+pub enum UserError {
+    #[error("invalid user name '{0}': use 1-40 letters, digits, '.', '_', '-' or '@'")]
+    InvalidName(String),
+
+    #[error("password must be at least {MIN_PASSWORD_CHARS} characters")]
+    PasswordTooShort,
+
+    #[error("a new user needs a password")]
+    PasswordRequired,
+
+    #[error("unknown route '{0}'")]
+    UnknownRoute(String),
+
+    #[error("user '{0}' not found")]
+    NotFound(String),
+
+    #[error("there must be at least one admin")]
+    LastAdmin,
+
+    #[error("the code is wrong or was already used")]
+    InvalidCode,
+
+    #[error("users.json is invalid: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("failed to access users.json: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Path of users.json in the current data directory
+pub fn users_path() -> PathBuf {
+    data_dir().join(USERS_FILE)
+}
+
+/// Every user; none if users.json doesn't exist
+///
+/// A users.json that can't be read is an error rather than an empty list,
+/// so a damaged file never reopens the admin.
+pub fn load_users() -> Result<Vec<User>, UserError> {
+    match fs::read_to_string(users_path()) {
+        Ok(json) => Ok(serde_json::from_str(&json)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Hold `USERS_LOCK` from loading users.json until it is saved
+fn lock_users() -> MutexGuard<'static, ()> {
+    USERS_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Write users.json atomically, since every admin request reads it
+fn save_users(users: &[User]) -> Result<(), UserError> {
+    write_atomic(&users_path(), &serde_json::to_string_pretty(users)?)?;
+    Ok(())
+}
+
+/// Whether `name` can be used as a user name
+pub fn is_user_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().count() <= MAX_NAME_CHARS
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | '@'))
+}
+
+/// A new user, or changes to an existing one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserUpdate {
+    pub name: String,
+    pub role: Role,
+    /// Routes the user may edit and publish; every route when `None`
+    pub routes: Option<Vec<String>>,
+    /// New password; an existing user's is kept when `None`
+    pub password: Option<String>,
+}
+
+/// Create a user, or change an existing user's role, routes or password
+///
+/// Returns whether the user was created.
+pub fn save_user(update: UserUpdate) -> Result<bool, UserError> {
+    if !is_user_name(&update.name) {
+        return Err(UserError::InvalidName(update.name));
+    }
+    if let Some(password) = &update.password
+        && password.chars().count() < MIN_PASSWORD_CHARS
+    {
+        return Err(UserError::PasswordTooShort);
+    }
+    let known: Vec<String> = load_routes().into_iter().map(|route| route.name).collect();
+    if let Some(unknown) = update
+        .routes
+        .iter()
+        .flatten()
+        .find(|route| !known.contains(route))
+    {
+        return Err(UserError::UnknownRoute(unknown.clone()));
+    }
+
+    // Hash before locking: it is slow on purpose
+    let password = update.password.as_deref().map(hash_password);
+    let _guard = lock_users();
+    let mut users = load_users()?;
+    let created = match users.iter_mut().find(|user| user.name == update.name) {
+        Some(user) => {
+            user.role = update.role;
+            user.routes = update.routes;
+            if let Some(password) = password {
+                user.password = password;
+            }
+            false
+        }
+        None => {
+            users.push(User {
+                name: update.name,
+                role: update.role,
+                routes: update.routes,
+                password: password.ok_or(UserError::PasswordRequired)?,
+                two_factor: None,
+            });
+            true
+        }
+    };
+
+    ensure_admin(&users)?;
+    save_users(&users)?;
+    Ok(created)
+}
+
+/// Delete a user
+pub fn delete_user(name: &str) -> Result<(), UserError> {
+    let _guard = lock_users();
+    let mut users = load_users()?;
+    let count = users.len();
+    users.retain(|user| user.name != name);
+    if users.len() == count {
+        return Err(UserError::NotFound(name.to_string()));
+    }
+    if !users.iter().any(|user| user.role == Role::Admin) {
+        return Err(UserError::LastAdmin);
+    }
+    save_users(&users)
+}
+
+fn ensure_admin(users: &[User]) -> Result<(), UserError> {
+    if users.is_empty() || users.iter().any(|user| user.role == Role::Admin) {
+        Ok(())
+    } else {
+        Err(UserError::LastAdmin)
+    }
+}
+
+/// The user with `name` if `password` is theirs
+pub fn authenticate(name: &str, password: &str) -> Result<Option<User>, UserError> {
+    let users = load_users()?;
+    match users.into_iter().find(|user| user.name == name) {
+        Some(user) if verify_password(password, &user.password) => Ok(Some(user)),
+        Some(_) => Ok(None),
+        None => {
+            // Take as long as a wrong password, so names can't be probed
+            hash_password(password);
+            Ok(None)
+        }
+    }
+}
+
+// ============================================================================
+// Two-Factor Authentication
+// ============================================================================
+
+/// Enroll `name` with a TOTP `secret`, confirmed by a current `code`
+///
+/// Returns the new recovery codes, which are only available now.
+pub fn enable_two_factor(
+    name: &str,
+    secret: &str,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<Vec<String>, UserError> {
+    let step = totp::matching_step(secret, code, now).ok_or(UserError::InvalidCode)?;
+    update_user(name, |user| {
+        let (mut two_factor, codes) = TwoFactor::new(secret, now);
+        two_factor.last_step = step;
+        user.two_factor = Some(two_factor);
+        Ok(codes)
+    })
+}
+
+/// Turn two-factor sign-in off for `name`
+pub fn disable_two_factor(name: &str) -> Result<(), UserError> {
+    update_user(name, |user| {
+        user.two_factor = None;
+        Ok(())
+    })
+}
+
+/// Replace the recovery codes of an enrolled user, returning the new ones
+pub fn reset_recovery_codes(name: &str) -> Result<Vec<String>, UserError> {
+    update_user(name, |user| match &mut user.two_factor {
+        Some(two_factor) => Ok(two_factor.reset_recovery_codes()),
+        None => Err(UserError::InvalidCode),
+    })
+}
+
+/// Check a TOTP or recovery code of an enrolled user, recording its use
+pub fn verify_second_factor(
+    name: &str,
+    code: &str,
+    now: DateTime<Utc>,
+) -> Result<SecondFactor, UserError> {
+    update_user(name, |user| {
+        user.two_factor
+            .as_mut()
+            .and_then(|two_factor| two_factor.verify(code, now))
+            .ok_or(UserError::InvalidCode)
+    })
+}
+
+/// Change one user and save users.json if `change` succeeds
+///
+/// Holds the users lock throughout, so `change` sees the latest user.
+fn update_user<T>(
+    name: &str,
+    change: impl FnOnce(&mut User) -> Result<T, UserError>,
+) -> Result<T, UserError> {
+    let _guard = lock_users();
+    let mut users = load_users()?;
+    let user = users
+        .iter_mut()
+        .find(|user| user.name == name)
+        .ok_or_else(|| UserError::NotFound(name.to_string()))?;
+    let result = change(user)?;
+    save_users(&users)?;
+    Ok(result)
+}
+
+// ============================================================================
+// Password Hashing
+// ============================================================================
+
+/// Hash a password with a fresh random salt, as a PHC string
+pub fn hash_password(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    let params = Params {
+        rounds: PASSWORD_ITERATIONS,
+        output_length: 32,
+    };
+    Pbkdf2
+        .hash_password_customized(password.as_bytes(), None, None, params, &salt)
+        .expect("PBKDF2 hashes any password with a generated salt")
+        .to_string()
+}
+
+/// Whether `password` matches a hash from `hash_password`
+///
+/// The comparison is constant-time; malformed hashes never match.
+pub fn verify_password(password: &str, stored: &str) -> bool {
+    PasswordHash::new(stored)
+        .is_ok_and(|hash| Pbkdf2.verify_password(password.as_bytes(), &hash).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::persistence::with_data_dir_sync;
+    use strum::IntoEnumIterator;
+    use tempfile::TempDir;
+
+    fn user(role: Role, routes: Option<&[&str]>) -> User {
+        User {
+            name: "u".to_string(),
+            role,
+            routes: routes.map(|routes| routes.iter().map(|r| r.to_string()).collect()),
+            password: String::new(),
+            two_factor: None,
+        }
+    }
+
+    #[test]
+    fn test_role_names_match_serde() {
+        for role in Role::iter() {
+            let json = serde_json::to_string(&role).unwrap();
+            assert_eq!(json, format!("\"{role}\""));
+            assert_eq!(role.to_string().parse::<Role>(), Ok(role));
+        }
+    }
+
+    #[test]
+    fn test_roles_are_cumulative() {
+        let viewer = user(Role::Viewer, None);
+        assert!(viewer.can(Permission::View));
+        assert!(!viewer.can(Permission::Edit));
+
+        let publisher = user(Role::Publisher, None);
+        assert!(publisher.can(Permission::Publish));
+        assert!(!publisher.can(Permission::Administer));
+        assert!(user(Role::Admin, None).can(Permission::Administer));
+    }
+
+    #[test]
+    fn test_route_grants_limit_edit_and_publish() {
+        let editor = user(Role::Editor, Some(&["homepage"]));
+        assert!(editor.can_on_route(Permission::Edit, "homepage"));
+        assert!(!editor.can_on_route(Permission::Edit, "foo"));
+        assert!(editor.can_on_route(Permission::View, "foo"));
+        assert!(!editor.can_on_route(Permission::Publish, "homepage"));
+
+        // No routes at all: may view but edit nothing
+        let editor = user(Role::Editor, Some(&[]));
+        assert!(!editor.can(Permission::Edit));
+        assert!(editor.can(Permission::View));
+
+        // Admins ignore grants
+        let admin = user(Role::Admin, Some(&[]));
+        assert!(admin.can_on_route(Permission::Publish, "foo"));
+    }
+
+    #[test]
+    fn test_password_hash_round_trip() {
+        let hash = hash_password("correct horse");
+        assert!(hash.starts_with(&format!("$pbkdf2-sha256$i={PASSWORD_ITERATIONS},l=32$")));
+        assert!(verify_password("correct horse", &hash));
+        assert!(!verify_password("correct horsf", &hash));
+        assert_ne!(hash, hash_password("correct horse"), "salted");
+        assert!(!verify_password("", ""));
+        assert!(!verify_password("x", "pbkdf2-sha256$1$00$00"));
+    }
+
+    #[test]
+    fn test_verify_password_known_answers() {
+        // RFC 7914 section 11, PBKDF2-HMAC-SHA256 vectors (first 32 bytes)
+        let first = "$pbkdf2-sha256$i=1,l=32$c2FsdA$VawEblbjCJ/sFpHCJUS2BflBhSFt3gRl5oudV8INrLw";
+        assert!(verify_password("passwd", first));
+        assert!(!verify_password("passwe", first));
+
+        let second =
+            "$pbkdf2-sha256$i=80000,l=32$TmFDbA$TdzY9guYviGDDO5e8icB+WQaRBjQTAQUrv8Ih2s0q1Y";
+        assert!(verify_password("Password", second));
+        assert!(!verify_password("password", second));
+
+        // The production parameters, computed with Python's hashlib
+        let production = "$pbkdf2-sha256$i=600000,l=32$c2FsdFNBTFRzYWx0$\
+            uSQT8DRWu06OtKoMD7erwSOi5CM13j0FCChzozXeU1E";
+        assert!(verify_password("correct horse battery staple", production));
+    }
+
+    #[test]
+    fn test_save_and_delete_keep_an_admin() {
+        let dir = TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            assert!(load_users().unwrap().is_empty());
+            let update = |name: &str, role, password: Option<&str>| UserUpdate {
+                name: name.to_string(),
+                role,
+                routes: None,
+                password: password.map(str::to_string),
+            };
+
+            // The first user must be an admin
+            assert!(matches!(
+                save_user(update("mia", Role::Editor, Some("long enough pw"))),
+                Err(UserError::LastAdmin)
+            ));
+            assert!(save_user(update("ada", Role::Admin, Some("long enough pw"))).unwrap());
+            assert!(save_user(update("mia", Role::Editor, Some("long enough pw"))).unwrap());
+            assert!(matches!(
+                save_user(update("bob", Role::Editor, None)),
+                Err(UserError::PasswordRequired)
+            ));
+            assert!(matches!(
+                save_user(update("bob", Role::Editor, Some("short"))),
+                Err(UserError::PasswordTooShort)
+            ));
+            assert!(matches!(
+                save_user(update("no spaces", Role::Editor, Some("long enough pw"))),
+                Err(UserError::InvalidName(_))
+            ));
+
+            // Demoting or deleting the last admin is refused
+            assert!(matches!(
+                save_user(update("ada", Role::Publisher, None)),
+                Err(UserError::LastAdmin)
+            ));
+            assert!(matches!(delete_user("ada"), Err(UserError::LastAdmin)));
+
+            // Changing a role keeps the password
+            assert!(!save_user(update("mia", Role::Publisher, None)).unwrap());
+            let mia = authenticate("mia", "long enough pw").unwrap().unwrap();
+            assert_eq!(mia.role, Role::Publisher);
+            assert!(authenticate("mia", "wrong password").unwrap().is_none());
+            assert!(authenticate("nobody", "long enough pw").unwrap().is_none());
+
+            delete_user("mia").unwrap();
+            assert!(matches!(delete_user("mia"), Err(UserError::NotFound(_))));
+            assert_eq!(load_users().unwrap().len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_grants_must_name_known_routes() {
+        let dir = TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            let result = save_user(UserUpdate {
+                name: "ada".to_string(),
+                role: Role::Admin,
+                routes: Some(vec!["nope".to_string()]),
+                password: Some("long enough pw".to_string()),
+            });
+            assert!(matches!(result, Err(UserError::UnknownRoute(route)) if route == "nope"));
+        });
+    }
+
+    #[test]
+    fn test_two_factor_enrollment_and_verification() {
+        let dir = TempDir::new().unwrap();
+        with_data_dir_sync(dir.path().to_path_buf(), || {
+            save_user(UserUpdate {
+                name: "ada".to_string(),
+                role: Role::Admin,
+                routes: None,
+                password: Some("long enough pw".to_string()),
+            })
+            .unwrap();
+            // The RFC 6238 test secret, whose code at this time is 050471
+            let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+            let now = DateTime::from_timestamp(1_111_111_111, 0).unwrap();
+            let code = "050471";
+
+            assert!(matches!(
+                enable_two_factor("ada", secret, "050472", now),
+                Err(UserError::InvalidCode)
+            ));
+            let recovery = enable_two_factor("ada", secret, code, now).unwrap();
+            let ada = authenticate("ada", "long enough pw").unwrap().unwrap();
+            assert!(ada.has_two_factor());
+            assert!(ada.without_password().two_factor.unwrap().secret.is_empty());
+            assert!(!ada.must_enroll_two_factor(&[Role::Admin]));
+
+            // The enrollment code can't be used again to sign in
+            assert!(matches!(
+                verify_second_factor("ada", code, now),
+                Err(UserError::InvalidCode)
+            ));
+            let later = now + chrono::Duration::seconds(30);
+            let code = totp::code_at(secret, later).unwrap();
+            assert_eq!(
+                verify_second_factor("ada", &code, later).unwrap(),
+                SecondFactor::Totp
+            );
+            assert!(matches!(
+                verify_second_factor("ada", &recovery[0], later).unwrap(),
+                SecondFactor::RecoveryCode { .. }
+            ));
+            assert!(verify_second_factor("ada", &recovery[0], later).is_err());
+
+            disable_two_factor("ada").unwrap();
+            let ada = authenticate("ada", "long enough pw").unwrap().unwrap();
+            assert!(ada.must_enroll_two_factor(&[Role::Admin]));
+            assert!(!ada.must_enroll_two_factor(&[Role::Editor]));
+            assert!(matches!(
+                disable_two_factor("bob"),
+                Err(UserError::NotFound(_))
+            ));
+        });
+    }
+
+    #[test]
+    fn test_a_code_is_accepted_once_even_in_parallel() {
+        const USES: usize = 8;
+        let dir = TempDir::new().unwrap();
+        let secret = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+        let start = DateTime::from_timestamp(1_111_111_111, 0).unwrap();
+        let ada = User {
+            name: "ada".to_string(),
+            two_factor: Some(TwoFactor::new(secret, start).0),
+            ..user(Role::Admin, None)
+        };
+        with_data_dir_sync(dir.path().to_path_buf(), || save_users(&[ada]).unwrap());
+
+        for round in 1..=50 {
+            let now = start + chrono::Duration::seconds(totp::STEP_SECONDS * round);
+            let code = totp::code_at(secret, now).unwrap();
+            let barrier = std::sync::Barrier::new(USES);
+            let accepted = std::thread::scope(|scope| {
+                let uses: Vec<_> = (0..USES)
+                    .map(|_| {
+                        let (dir, code, barrier) = (dir.path().to_path_buf(), &code, &barrier);
+                        scope.spawn(move || {
+                            barrier.wait();
+                            with_data_dir_sync(dir, || verify_second_factor("ada", code, now))
+                        })
+                    })
+                    .collect();
+                uses.into_iter()
+                    .map(|handle| handle.join().unwrap())
+                    .filter(Result::is_ok)
+                    .count()
+            });
+            assert_eq!(accepted, 1, "round {round}");
+        }
+    }
+}
@@ -0,0 +1,113 @@
+// Accessibility Panel Web Component
+// Lists accessibility linter warnings for the content being edited
+// Using web-components-architecture and javascript-pragmatic-rules skills
+
+class A11yPanel extends HTMLElement {
+  #abortController = null;
+  #timeout = 5_000;
+  #summary = null;
+  #list = null;
+
+  // Lifecycle callback from web-components-architecture
+  connectedCallback() {
+    // Using handleEvent pattern from web-components-architecture
+    this.addEventListener('click', this);
+
+    this.setAttribute('role', 'region');
+    this.setAttribute('aria-label', 'Accessibility warnings');
+
+    // Principle 1: Zero DOM Selection - EXCEPTION for known child structure
+    this.#summary = this.querySelector('.a11y-panel__summary');
+    this.#list = this.querySelector('.a11y-panel__list');
+  }
+
+  // Rule 4 from javascript-pragmatic-rules: Clean up resources
+  disconnectedCallback() {
+    this.removeEventListener('click', this);
+
+    if (this.#abortController) {
+      this.#abortController.abort();
+      this.#abortController = null;
+    }
+  }
+
+  // Principle 3: HandleEvent pattern from web-components-architecture
+  handleEvent(e) {
+    if (e.type !== 'click') return;
+
+    const button = e.target.closest('[data-action="check"]');
+    if (!button) return;
+
+    // Principle 4: Events are the ONLY output from web-components-architecture
+    // The editor owns the current content, so it answers by calling check()
+    this.dispatchEvent(new CustomEvent('a11y-check-requested', {
+      bubbles: true,
+      composed: true
+    }));
+  }
+
+  // Public API: Lint the given page JSON and show the warnings
+  // Returns the issues, or null if the check could not run
+  // Rule 2 from javascript-pragmatic-rules: Time-bound async operations
+  async check(jsonData) {
+    if (this.#abortController) {
+      this.#abortController.abort();
+    }
+
+    this.#abortController = new AbortController();
+    const timeoutId = setTimeout(() => this.#abortController?.abort(), this.#timeout);
+
+    // Rule 1 from javascript-pragmatic-rules: Handle promise rejections
+    try {
+      const response = await fetch('/admin/api/a11y', {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: jsonData,
+        signal: this.#abortController.signal
+      });
+      if (!response.ok) {
+        throw new Error(`HTTP ${response.status}: ${await response.text()}`);
+      }
+      const issues = await response.json();
+      this.#render(issues);
+      return issues;
+    } catch (error) {
+      if (error.name !== 'AbortError') {
+        this.#summary.textContent = `Accessibility check failed: ${error.message}`;
+        console.error('Accessibility check error:', { error: error.message });
+      }
+      return null;
+    } finally {
+      clearTimeout(timeoutId);
+      this.#abortController = null;
+    }
+  }
+
+  #render(issues) {
+    this.#summary.textContent = issues.length === 0
+      ? 'No accessibility warnings.'
+      : `${issues.length} accessibility warning(s).`;
+
+    const items = issues.map((issue) => {
+      const item = document.createElement('li');
+      item.className = 'a11y-panel__issue';
+
+      const rule = document.createElement('code');
+      rule.textContent = issue.rule;
+      item.append(rule, ` ${issue.message}`);
+
+      if (issue.block_id) {
+        const block = document.createElement('small');
+        block.textContent = ` (block ${issue.block_id})`;
+        item.append(block);
+      }
+      return item;
+    });
+    this.#list.replaceChildren(...items);
+  }
+}
+
+// Register the custom element
+customElements.define('a11y-panel', A11yPanel);
+
+export { A11yPanel };
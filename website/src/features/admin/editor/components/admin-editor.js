@@ -11,6 +11,10 @@ class AdminEditor extends HTMLElement {
   #blockList = null;
   #jsonEditor = null;
   #messageBanner = null;
+  #a11yPanel = null;
+  #acknowledgedJson = null;
+  #presence = null;
+  #savedJson = null;
   #cleanup = [];
 
   // Principle 2: Attribute-Driven State from web-components-architecture
@@ -25,6 +29,8 @@ class AdminEditor extends HTMLElement {
     this.addEventListener('tab-changed', this);
     this.addEventListener('blocks-changed', this);
     this.addEventListener('json-valid', this);
+    this.addEventListener('a11y-check-requested', this);
+    this.addEventListener('content-published', this);
 
     // Set ARIA attributes for accessibility
     this.setAttribute('role', 'main');
@@ -43,6 +49,8 @@ class AdminEditor extends HTMLElement {
     this.removeEventListener('tab-changed', this);
     this.removeEventListener('blocks-changed', this);
     this.removeEventListener('json-valid', this);
+    this.removeEventListener('a11y-check-requested', this);
+    this.removeEventListener('content-published', this);
 
     // Cancel any pending requests
     if (this.#abortController) {
@@ -79,6 +87,12 @@ class AdminEditor extends HTMLElement {
       case 'json-valid':
         this.#handleJsonValid(e);
         break;
+      case 'a11y-check-requested':
+        this.#handleA11yCheck();
+        break;
+      case 'content-published':
+        this.#handleContentPublished(e);
+        break;
     }
   }
 
@@ -90,6 +104,8 @@ class AdminEditor extends HTMLElement {
     this.#blockList = this.querySelector('block-list');
     this.#jsonEditor = this.querySelector('monaco-json-editor');
     this.#messageBanner = this.querySelector('message-banner');
+    this.#a11yPanel = this.querySelector('a11y-panel');
+    this.#presence = this.querySelector('editor-presence');
 
     if (!this.#tabSwitcher || !this.#blockList || !this.#jsonEditor || !this.#messageBanner) {
       console.warn('Admin editor: Not all child components found', {
@@ -116,6 +132,7 @@ class AdminEditor extends HTMLElement {
       }
 
       const parsed = JSON.parse(initialValue);
+      this.#savedJson = JSON.stringify(parsed);
 
       // Sync to block list
       if (this.#blockList) {
@@ -172,9 +189,8 @@ class AdminEditor extends HTMLElement {
     }
   }
 
-  async #handleSubmit(e) {
-    e.preventDefault();
-
+  // Sync the inactive view and return the current editor content
+  #currentJson() {
     // Get active tab to determine which data to use
     const activeTab = this.#tabSwitcher?.getAttribute('active-tab') || 'list';
 
@@ -185,15 +201,52 @@ class AdminEditor extends HTMLElement {
       this.#syncJsonToList();
     }
 
+    // Call getValue() method to get current editor content (not the initial attribute value)
+    return this.#jsonEditor?.getValue() ?? null;
+  }
+
+  // Another editor published this route: take their content if nothing here
+  // is unsaved, otherwise warn before this editor overwrites it
+  #handleContentPublished(e) {
+    const { by, content } = e.detail;
+
+    let current = null;
+    try {
+      current = JSON.stringify(JSON.parse(this.#currentJson()));
+    } catch {
+      // Unparseable JSON counts as unsaved changes
+    }
+
+    if (current !== null && current === this.#savedJson) {
+      this.#jsonEditor?.setFormattedValue(content);
+      this.#blockList?.setBlocksData(content);
+      this.#savedJson = JSON.stringify(content);
+      this.#showMessage(`${by} published changes - the editor now shows them`, 'success');
+    } else {
+      this.#showMessage(
+        `⚠ ${by} published changes while you were editing. Publishing now will overwrite them; reload to see their version.`,
+        'error'
+      );
+    }
+  }
+
+  async #handleA11yCheck() {
+    const jsonValue = this.#currentJson();
+    if (jsonValue === null || !this.#a11yPanel) return;
+
+    await this.#a11yPanel.check(jsonValue);
+  }
+
+  async #handleSubmit(e) {
+    e.preventDefault();
+
     // Get JSON data
-    if (!this.#jsonEditor) {
+    const jsonValue = this.#currentJson();
+    if (jsonValue === null) {
       this.#showMessage('Editor not initialized', 'error');
       return;
     }
 
-    // Call getValue() method to get current editor content (not the initial attribute value)
-    const jsonValue = this.#jsonEditor.getValue();
-
     // Validate JSON before submission
     // Rule 1 from javascript-pragmatic-rules: Handle errors with context
     try {
@@ -203,8 +256,32 @@ class AdminEditor extends HTMLElement {
       return;
     }
 
-    // Submit to server
-    await this.#submitToServer(jsonValue);
+    // Check accessibility before publishing; warnings don't block, but the
+    // first publish of content with warnings asks for confirmation
+    if (this.#a11yPanel && jsonValue !== this.#acknowledgedJson) {
+      const issues = await this.#a11yPanel.check(jsonValue);
+      if (issues?.length) {
+        this.#acknowledgedJson = jsonValue;
+        this.#showMessage(
+          `${issues.length} accessibility warning(s) - review them below, then publish again to confirm`,
+          'error'
+        );
+        return;
+      }
+    }
+
+    // Submit to server, scheduled if "Publish at" is filled in
+    await this.#submitToServer(this.#withPublishAt(jsonValue, e.target));
+  }
+
+  // Add the form's "Publish at" time (local) to the request as a UTC instant
+  #withPublishAt(jsonValue, form) {
+    const publishAt = form.elements?.publish_at?.value;
+    if (!publishAt) return jsonValue;
+
+    const request = JSON.parse(jsonValue);
+    request.publish_at = new Date(publishAt).toISOString();
+    return JSON.stringify(request);
   }
 
   // Rule 2 from javascript-pragmatic-rules: Time-bound async operations
@@ -223,6 +300,7 @@ class AdminEditor extends HTMLElement {
         method: 'POST',
         headers: {
           'Content-Type': 'application/json',
+          ...(this.#presence?.session && { 'X-Editor-Session': this.#presence.session }),
         },
         body: jsonData,
         signal: this.#abortController.signal
@@ -242,6 +320,8 @@ class AdminEditor extends HTMLElement {
       // Rule 1 from javascript-pragmatic-rules: Handle errors
       try {
         const parsed = JSON.parse(jsonData);
+        delete parsed.publish_at;
+        this.#savedJson = JSON.stringify(parsed);
         if (this.#blockList) {
           this.#blockList.setBlocksData(parsed);
         }
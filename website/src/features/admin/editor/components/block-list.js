@@ -7,6 +7,7 @@ class BlockList extends HTMLElement {
   #blocksData = { blocks: [] };
   #listContainer = null;
   #blockTypeSelect = null;
+  #fileSelect = null;
 
   // Block type templates
   #blockDefaults = {
@@ -29,6 +30,13 @@ class BlockList extends HTMLElement {
         headline: '',
         subheadline: ''
       }
+    },
+    Image: {
+      id: '',
+      type: 'Image',
+      props: {
+        media_id: ''
+      }
     }
   };
 
@@ -77,9 +85,10 @@ class BlockList extends HTMLElement {
     // Principle 1: Zero DOM Selection - use event delegation from web-components-architecture
     const deleteBtn = e.target.closest('[data-action="delete"]');
     if (deleteBtn) {
+      const file = parseInt(deleteBtn.getAttribute('data-file'), 10);
       const index = parseInt(deleteBtn.getAttribute('data-index'), 10);
-      if (!isNaN(index)) {
-        this.#deleteBlock(index);
+      if (!isNaN(file) && !isNaN(index)) {
+        this.#deleteBlock(file, index);
       }
       return;
     }
@@ -121,6 +130,25 @@ class BlockList extends HTMLElement {
     }
   }
 
+  // Blocks grouped by content file: `{ files: [{ source, blocks }] }` from
+  // the page editor, or a single unnamed group for plain `{ blocks }` data
+  #files() {
+    if (Array.isArray(this.#blocksData.files)) {
+      return this.#blocksData.files;
+    }
+    if (!Array.isArray(this.#blocksData.blocks)) {
+      this.#blocksData.blocks = [];
+    }
+    return [{ source: null, blocks: this.#blocksData.blocks }];
+  }
+
+  #getSelectedFile() {
+    const files = this.#files();
+    if (!this.#fileSelect || files.length < 2) return files.length - 1;
+    const file = parseInt(this.#fileSelect.value, 10);
+    return isNaN(file) ? files.length - 1 : file;
+  }
+
   #getSelectedBlockType() {
     // Principle 1: Zero DOM Selection - use stored reference from web-components-architecture
     if (!this.#blockTypeSelect) return null;
@@ -135,7 +163,13 @@ class BlockList extends HTMLElement {
 
     // Rule 5 from javascript-pragmatic-rules: Prefer immutability
     const newBlock = JSON.parse(JSON.stringify(this.#blockDefaults[blockType]));
-    this.#blocksData.blocks.push(newBlock);
+    const files = this.#files();
+    if (files.length === 0) {
+      console.error('Route has no content files');
+      return;
+    }
+    const file = this.#getSelectedFile();
+    files[file].blocks.push(newBlock);
 
     this.#updateBlocksAttribute();
     this.#renderBlocks();
@@ -147,19 +181,21 @@ class BlockList extends HTMLElement {
       detail: {
         action: 'add',
         blockType,
+        file,
         blocks: this.#blocksData
       }
     }));
   }
 
-  #deleteBlock(index) {
-    if (index < 0 || index >= this.#blocksData.blocks.length) {
-      console.error(`Invalid block index: ${index}`);
+  #deleteBlock(file, index) {
+    const blocks = this.#files()[file]?.blocks;
+    if (!blocks || index < 0 || index >= blocks.length) {
+      console.error(`Invalid block index: ${file}/${index}`);
       return;
     }
 
-    const deletedBlock = this.#blocksData.blocks[index];
-    this.#blocksData.blocks.splice(index, 1);
+    const deletedBlock = blocks[index];
+    blocks.splice(index, 1);
 
     this.#updateBlocksAttribute();
     this.#renderBlocks();
@@ -170,6 +206,7 @@ class BlockList extends HTMLElement {
       composed: true,
       detail: {
         action: 'delete',
+        file,
         index,
         deletedBlock,
         blocks: this.#blocksData
@@ -225,7 +262,14 @@ class BlockList extends HTMLElement {
     addButton.setAttribute('data-action', 'add');
     addButton.textContent = 'Add Block';
 
+    // Target file for new blocks, shown when the route has several files
+    this.#fileSelect = document.createElement('select');
+    this.#fileSelect.className = 'block-file-select';
+    this.#fileSelect.setAttribute('aria-label', 'Select content file for the new block');
+    this.#fileSelect.hidden = true;
+
     controls.appendChild(this.#blockTypeSelect);
+    controls.appendChild(this.#fileSelect);
     controls.appendChild(addButton);
 
     // Create list container
@@ -250,9 +294,10 @@ class BlockList extends HTMLElement {
     // Clear existing blocks
     this.#listContainer.textContent = '';
 
-    const blocks = this.#blocksData.blocks || [];
+    const files = this.#files();
+    this.#renderFileOptions(files);
 
-    if (blocks.length === 0) {
+    if (files.every((file) => file.blocks.length === 0)) {
       const emptyMessage = document.createElement('li');
       emptyMessage.className = 'block-list-empty';
       emptyMessage.textContent = 'No blocks added yet. Add a block to get started.';
@@ -260,15 +305,48 @@ class BlockList extends HTMLElement {
       return;
     }
 
-    // Render each block
-    for (let i = 0; i < blocks.length; i++) {
-      const block = blocks[i];
-      const listItem = this.#createBlockItem(block, i);
-      this.#listContainer.appendChild(listItem);
+    // Render each file's blocks under the file they are saved to
+    for (let f = 0; f < files.length; f++) {
+      const { source, blocks } = files[f];
+      if (source !== null) {
+        this.#listContainer.appendChild(this.#createSourceItem(source));
+      }
+      for (let i = 0; i < blocks.length; i++) {
+        const listItem = this.#createBlockItem(blocks[i], f, i);
+        this.#listContainer.appendChild(listItem);
+      }
     }
   }
 
-  #createBlockItem(block, index) {
+  #renderFileOptions(files) {
+    if (!this.#fileSelect) return;
+
+    const selected = this.#fileSelect.value;
+    this.#fileSelect.textContent = '';
+    for (let f = 0; f < files.length; f++) {
+      const option = document.createElement('option');
+      option.value = String(f);
+      option.textContent = this.#fileName(files[f].source);
+      this.#fileSelect.appendChild(option);
+    }
+    this.#fileSelect.value = selected || String(files.length - 1);
+    this.#fileSelect.hidden = files.length < 2;
+  }
+
+  #createSourceItem(source) {
+    const li = document.createElement('li');
+    li.className = 'block-source';
+    li.setAttribute('role', 'listitem');
+    li.title = source;
+    li.textContent = this.#fileName(source);
+    return li;
+  }
+
+  #fileName(source) {
+    return source ? source.split(/[\\/]/).pop() : 'content';
+  }
+
+  #createBlockItem(block, file, index) {
     const li = document.createElement('li');
     li.className = 'block-item';
     li.setAttribute('role', 'listitem');
@@ -295,6 +373,7 @@ class BlockList extends HTMLElement {
     deleteBtn.textContent = 'Delete';
     deleteBtn.setAttribute('type', 'button');
     deleteBtn.setAttribute('data-action', 'delete');
+    deleteBtn.setAttribute('data-file', String(file));
     deleteBtn.setAttribute('data-index', String(index));
     deleteBtn.setAttribute('aria-label', `Delete ${block.type} block`);
 
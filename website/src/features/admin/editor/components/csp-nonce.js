@@ -0,0 +1,22 @@
+// CSP Nonce Helpers
+// The admin's Content Security Policy only runs <style> elements carrying
+// the page's nonce (see core::security_headers in the Rust crate)
+// Using javascript-pragmatic-rules skill
+
+// The page's nonce, from <meta property="csp-nonce">. Browsers hide the
+// attribute value, so it is read from the `nonce` property.
+export const cspNonce = () =>
+  document.querySelector('meta[property="csp-nonce"]')?.nonce ?? '';
+
+// Parse server-rendered HTML into a fragment whose <style> elements carry
+// this page's nonce. Markup fetched from the API was rendered with that
+// request's nonce, which this page's policy doesn't accept.
+export const parseWithNonce = (html) => {
+  const template = document.createElement('template');
+  template.innerHTML = html;
+  const nonce = cspNonce();
+  for (const style of template.content.querySelectorAll('style')) {
+    style.nonce = nonce;
+  }
+  return template.content;
+};
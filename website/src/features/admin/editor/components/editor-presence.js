@@ -0,0 +1,150 @@
+// Editor Presence Web Component
+// Shows who else is editing the route and announces their publishes
+// Using web-components-architecture and javascript-pragmatic-rules skills
+
+const NAME_KEY = 'editor-presence-name';
+
+class EditorPresence extends HTMLElement {
+  #socket = null;
+  #session = null;
+  #list = null;
+  #nameInput = null;
+  #reconnectDelay = 1_000;
+  #reconnectTimer = null;
+  #closed = false;
+
+  // Public API: this tab's session id, sent with saves as X-Editor-Session
+  get session() {
+    return this.#session;
+  }
+
+  // Lifecycle callback from web-components-architecture
+  connectedCallback() {
+    this.#closed = false;
+    this.#list = this.querySelector('.editor-presence__list');
+    this.#nameInput = this.querySelector('input[name="editor_name"]');
+    if (this.#nameInput) {
+      this.#nameInput.value = localStorage.getItem(NAME_KEY) ?? '';
+      this.#nameInput.addEventListener('change', this);
+    }
+    this.#connect();
+  }
+
+  // Rule 4 from javascript-pragmatic-rules: Clean up resources
+  disconnectedCallback() {
+    this.#closed = true;
+    clearTimeout(this.#reconnectTimer);
+    this.#nameInput?.removeEventListener('change', this);
+    this.#socket?.removeEventListener('message', this);
+    this.#socket?.removeEventListener('open', this);
+    this.#socket?.removeEventListener('close', this);
+    this.#socket?.close();
+    this.#socket = null;
+  }
+
+  // Principle 3: HandleEvent pattern from web-components-architecture
+  handleEvent(e) {
+    switch (e.type) {
+      case 'message':
+        this.#handleMessage(e);
+        break;
+      case 'open':
+        this.#reconnectDelay = 1_000;
+        break;
+      case 'close':
+        this.#scheduleReconnect();
+        break;
+      case 'change':
+        this.#handleNameChange();
+        break;
+    }
+  }
+
+  #connect() {
+    const name = localStorage.getItem(NAME_KEY) ?? '';
+    const params = new URLSearchParams({ role: 'editor', name });
+    if (this.dataset.locale) params.set('locale', this.dataset.locale);
+
+    const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
+    const route = encodeURIComponent(this.dataset.routeName);
+    this.#socket = new WebSocket(
+      `${protocol}//${window.location.host}/admin/ws/route/${route}?${params}`
+    );
+    this.#socket.addEventListener('message', this);
+    this.#socket.addEventListener('open', this);
+    this.#socket.addEventListener('close', this);
+  }
+
+  // Rule 2 from javascript-pragmatic-rules: Back off instead of retrying in a loop
+  #scheduleReconnect() {
+    this.#session = null;
+    if (this.#closed) return;
+
+    this.#reconnectTimer = setTimeout(() => this.#connect(), this.#reconnectDelay);
+    this.#reconnectDelay = Math.min(this.#reconnectDelay * 2, 30_000);
+  }
+
+  // Display name kept per browser until the admin has sign-in; reconnect
+  // so the other editors see the new name
+  #handleNameChange() {
+    localStorage.setItem(NAME_KEY, this.#nameInput.value.trim());
+    this.#socket?.close();
+  }
+
+  #handleMessage(e) {
+    // Rule 1 from javascript-pragmatic-rules: Handle errors with context
+    let event;
+    try {
+      event = JSON.parse(e.data);
+    } catch (error) {
+      console.error('Invalid presence message:', { data: e.data, error: error.message });
+      return;
+    }
+
+    switch (event.type) {
+      case 'welcome':
+        this.#session = event.session;
+        break;
+      case 'editors':
+        this.#renderEditors(event.editors);
+        break;
+      case 'published':
+        this.#handlePublished(event);
+        break;
+    }
+  }
+
+  #renderEditors(editors) {
+    if (!this.#list) return;
+
+    const others = editors.filter((editor) => editor.session !== this.#session);
+    this.#list.textContent = others.length
+      ? `Also editing: ${others
+        .map((editor) => (editor.locale ? `${editor.name} (${editor.locale})` : editor.name))
+        .join(', ')}`
+      : '';
+  }
+
+  #handlePublished(event) {
+    // Our own publish, or a different locale's files
+    if (event.session && event.session === this.#session) return;
+    if ((event.locale ?? '') !== (this.dataset.locale ?? '')) return;
+
+    // Principle 4: Events are the ONLY output from web-components-architecture
+    this.dispatchEvent(new CustomEvent('content-published', {
+      bubbles: true,
+      composed: true,
+      detail: {
+        by: event.by,
+        content: event.locale
+          ? { locale: event.locale, files: event.files }
+          : { files: event.files }
+      }
+    }));
+  }
+}
+
+// Register the custom element
+customElements.define('editor-presence', EditorPresence);
+
+export { EditorPresence };
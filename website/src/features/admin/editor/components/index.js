@@ -9,6 +9,13 @@ import { JsonEditor } from './json-editor.js';
 import { MonacoJsonEditor } from './monaco-json-editor.js';
 import { BlockList } from './block-list.js';
 import { AdminEditor } from './admin-editor.js';
+import { MediaLibrary } from './media-library.js';
+import { SettingsEditor } from './settings-editor.js';
+import { PropsPlayground } from './props-playground.js';
+import { A11yPanel } from './a11y-panel.js';
+import { ScheduleCalendar } from './schedule-calendar.js';
+import { EditorPresence } from './editor-presence.js';
+import { LivePreview } from './live-preview.js';
 
 // Rule 8 from javascript-pragmatic-rules: Global error handlers
 window.addEventListener('error', (event) => {
@@ -36,7 +43,14 @@ export {
   JsonEditor,
   MonacoJsonEditor,
   BlockList,
-  AdminEditor
+  AdminEditor,
+  MediaLibrary,
+  SettingsEditor,
+  PropsPlayground,
+  A11yPanel,
+  ScheduleCalendar,
+  EditorPresence,
+  LivePreview
 };
 
 // Log successful registration
@@ -46,5 +60,12 @@ console.log('Admin editor components registered:', {
   'json-editor': customElements.get('json-editor'),
   'monaco-json-editor': customElements.get('monaco-json-editor'),
   'block-list': customElements.get('block-list'),
-  'admin-editor': customElements.get('admin-editor')
+  'admin-editor': customElements.get('admin-editor'),
+  'media-library': customElements.get('media-library'),
+  'settings-editor': customElements.get('settings-editor'),
+  'props-playground': customElements.get('props-playground'),
+  'a11y-panel': customElements.get('a11y-panel'),
+  'schedule-calendar': customElements.get('schedule-calendar'),
+  'editor-presence': customElements.get('editor-presence'),
+  'live-preview': customElements.get('live-preview')
 });
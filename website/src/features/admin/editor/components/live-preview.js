@@ -0,0 +1,89 @@
+// Live Preview Web Component
+// Reloads the framed public page whenever its route is published
+// Using web-components-architecture and javascript-pragmatic-rules skills
+
+class LivePreview extends HTMLElement {
+  #socket = null;
+  #frame = null;
+  #status = null;
+  #reconnectDelay = 1_000;
+  #reconnectTimer = null;
+  #closed = false;
+
+  // Lifecycle callback from web-components-architecture
+  connectedCallback() {
+    this.#closed = false;
+    this.#frame = this.querySelector('iframe');
+    this.#status = this.querySelector('[role="status"]');
+    this.#connect();
+  }
+
+  // Rule 4 from javascript-pragmatic-rules: Clean up resources
+  disconnectedCallback() {
+    this.#closed = true;
+    clearTimeout(this.#reconnectTimer);
+    this.#socket?.removeEventListener('message', this);
+    this.#socket?.removeEventListener('open', this);
+    this.#socket?.removeEventListener('close', this);
+    this.#socket?.close();
+    this.#socket = null;
+  }
+
+  // Principle 3: HandleEvent pattern from web-components-architecture
+  handleEvent(e) {
+    switch (e.type) {
+      case 'message':
+        this.#handleMessage(e);
+        break;
+      case 'open':
+        this.#reconnectDelay = 1_000;
+        break;
+      case 'close':
+        this.#scheduleReconnect();
+        break;
+    }
+  }
+
+  #connect() {
+    const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
+    const route = encodeURIComponent(this.dataset.routeName);
+    this.#socket = new WebSocket(
+      `${protocol}//${window.location.host}/admin/ws/route/${route}?role=preview`
+    );
+    this.#socket.addEventListener('message', this);
+    this.#socket.addEventListener('open', this);
+    this.#socket.addEventListener('close', this);
+  }
+
+  // Rule 2 from javascript-pragmatic-rules: Back off instead of retrying in a loop
+  #scheduleReconnect() {
+    if (this.#closed) return;
+
+    this.#reconnectTimer = setTimeout(() => this.#connect(), this.#reconnectDelay);
+    this.#reconnectDelay = Math.min(this.#reconnectDelay * 2, 30_000);
+  }
+
+  #handleMessage(e) {
+    // Rule 1 from javascript-pragmatic-rules: Handle errors with context
+    let event;
+    try {
+      event = JSON.parse(e.data);
+    } catch (error) {
+      console.error('Invalid presence message:', { data: e.data, error: error.message });
+      return;
+    }
+
+    if (event.type !== 'published') return;
+    if ((event.locale ?? '') !== (this.dataset.locale ?? '')) return;
+
+    this.#frame?.contentWindow?.location.reload();
+    if (this.#status) {
+      this.#status.textContent = `Updated ${new Date().toLocaleTimeString()} - published by ${event.by}`;
+    }
+  }
+}
+
+// Register the custom element
+customElements.define('live-preview', LivePreview);
+
+export { LivePreview };
@@ -0,0 +1,142 @@
+// Media Library Web Component
+// Handles uploads, metadata edits and deletion on the media admin page
+// Using web-components-architecture and javascript-pragmatic-rules skills
+
+class MediaLibrary extends HTMLElement {
+  #abortController = null;
+  #timeout = 60_000; // Uploads include server-side image processing
+  #messageBanner = null;
+
+  // Lifecycle callback from web-components-architecture
+  connectedCallback() {
+    // Using handleEvent pattern from web-components-architecture
+    this.addEventListener('submit', this);
+    this.addEventListener('click', this);
+
+    this.setAttribute('role', 'region');
+    this.setAttribute('aria-label', 'Media library');
+
+    this.#messageBanner = this.querySelector('message-banner');
+  }
+
+  // Rule 4 from javascript-pragmatic-rules: Clean up resources
+  disconnectedCallback() {
+    this.removeEventListener('submit', this);
+    this.removeEventListener('click', this);
+
+    if (this.#abortController) {
+      this.#abortController.abort();
+      this.#abortController = null;
+    }
+  }
+
+  // Principle 3: HandleEvent pattern from web-components-architecture
+  handleEvent(e) {
+    switch (e.type) {
+      case 'submit':
+        this.#handleSubmit(e);
+        break;
+      case 'click':
+        this.#handleClick(e);
+        break;
+    }
+  }
+
+  #handleSubmit(e) {
+    const form = e.target.closest('form[data-action]');
+    if (!form) return;
+    e.preventDefault();
+
+    switch (form.dataset.action) {
+      case 'upload':
+        this.#upload(form);
+        break;
+      case 'update':
+        this.#update(form);
+        break;
+    }
+  }
+
+  #handleClick(e) {
+    // Principle 1: Zero DOM Selection - use event delegation from web-components-architecture
+    const deleteBtn = e.target.closest('[data-action="delete"]');
+    if (!deleteBtn) return;
+
+    const id = deleteBtn.dataset.mediaId;
+    if (id && window.confirm('Delete this image? This cannot be undone.')) {
+      this.#delete(id);
+    }
+  }
+
+  async #upload(form) {
+    const response = await this.#request('/admin/api/media', {
+      method: 'POST',
+      body: new FormData(form)
+    });
+    if (response) window.location.reload();
+  }
+
+  async #update(form) {
+    const data = new FormData(form);
+    const body = JSON.stringify({
+      alt: data.get('alt') ?? '',
+      focal_point: {
+        x: Number(data.get('focal_x')),
+        y: Number(data.get('focal_y'))
+      }
+    });
+
+    const response = await this.#request(`/admin/api/media/${form.dataset.mediaId}`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body
+    });
+    if (response) this.#showMessage('✓ Media updated', 'success');
+  }
+
+  async #delete(id) {
+    const response = await this.#request(`/admin/api/media/${id}`, { method: 'DELETE' });
+    if (response) window.location.reload();
+  }
+
+  // Rule 2 from javascript-pragmatic-rules: Time-bound async operations
+  async #request(url, options) {
+    if (this.#abortController) {
+      this.#abortController.abort();
+    }
+
+    this.#abortController = new AbortController();
+    const timeoutId = setTimeout(() => this.#abortController?.abort(), this.#timeout);
+
+    // Rule 1 from javascript-pragmatic-rules: Handle promise rejections
+    try {
+      const response = await fetch(url, { ...options, signal: this.#abortController.signal });
+      if (!response.ok) {
+        const errorText = await response.text();
+        throw new Error(`HTTP ${response.status}: ${errorText}`);
+      }
+      return response;
+    } catch (error) {
+      const message = error.name === 'AbortError'
+        ? `Request timed out after ${this.#timeout / 1_000} seconds`
+        : error.message;
+      this.#showMessage(message, 'error');
+      console.error('Media request error:', { url, error: error.message });
+      return null;
+    } finally {
+      clearTimeout(timeoutId);
+      this.#abortController = null;
+    }
+  }
+
+  #showMessage(text, type) {
+    if (!this.#messageBanner) return;
+
+    this.#messageBanner.showMessage(text, type);
+  }
+}
+
+// Register the custom element
+customElements.define('media-library', MediaLibrary);
+
+export { MediaLibrary };
@@ -9,6 +9,8 @@
 // - Better editing experience
 // - Monaco Editor features (find/replace, etc.)
 
+import { cspNonce } from './csp-nonce.js';
+
 // Monaco Editor 0.55 - loaded from local assets
 // Configure MonacoEnvironment to prevent worker creation entirely
 // This prevents all worker-related errors by forcing synchronous mode
@@ -97,6 +99,8 @@ class MonacoJsonEditor extends HTMLElement {
         // Create a <style> element with high specificity
         const style = document.createElement('style');
         style.id = 'monaco-codicon-font-override';
+        // The admin CSP only applies <style> elements carrying the page's nonce
+        style.nonce = cspNonce();
         style.textContent = `
             /* Monaco codicon font override - must use absolute path */
             /* Loaded AFTER Monaco's CSS to override via cascade order */
@@ -179,16 +183,13 @@ class MonacoJsonEditor extends HTMLElement {
 
             // Show error message in UI
             this.#container.innerHTML = `
-        <div style="padding: 1rem; color: #ef4444; background: #1a1a1a; border-radius: 4px;">
+        <div class="monaco-fallback__error">
           <strong>Failed to load Monaco Editor</strong><br>
           ${error.message}<br>
           <small>Falling back to basic JSON editing</small>
         </div>
         <textarea
-          style="width: 100%; min-height: 350px; margin-top: 1rem;
-                 padding: 1rem; font-family: monospace; font-size: 14px;
-                 background: #1a1a1a; color: #fff; border: 1px solid #333;
-                 border-radius: 4px;"
+          class="monaco-fallback__textarea"
           aria-label="JSON content (fallback editor)"
         >${this.getAttribute('value') || '{}'}</textarea>
       `;
@@ -0,0 +1,164 @@
+// Props Playground Web Component
+// Serializes story controls into props JSON and renders it server-side
+// Using web-components-architecture and javascript-pragmatic-rules skills
+
+import { parseWithNonce } from './csp-nonce.js';
+
+class PropsPlayground extends HTMLElement {
+  #abortController = null;
+  #timeout = 5_000;
+  #debounceMs = 250;
+  #debounceId = null;
+  #form = null;
+  #jsonField = null;
+  #preview = null;
+  #error = null;
+
+  // Lifecycle callback from web-components-architecture
+  connectedCallback() {
+    // Using handleEvent pattern from web-components-architecture
+    this.addEventListener('input', this);
+    this.addEventListener('change', this);
+    this.addEventListener('submit', this);
+
+    this.setAttribute('role', 'region');
+    this.setAttribute('aria-label', 'Props playground');
+
+    // Principle 1: Zero DOM Selection - EXCEPTION for known child structure
+    this.#form = this.querySelector('form');
+    this.#jsonField = this.querySelector('textarea[name="props-json"]');
+    this.#preview = this.querySelector('.props-playground__preview');
+    this.#error = this.querySelector('.props-playground__error');
+  }
+
+  // Rule 4 from javascript-pragmatic-rules: Clean up resources
+  disconnectedCallback() {
+    this.removeEventListener('input', this);
+    this.removeEventListener('change', this);
+    this.removeEventListener('submit', this);
+
+    if (this.#debounceId) {
+      clearTimeout(this.#debounceId);
+      this.#debounceId = null;
+    }
+    if (this.#abortController) {
+      this.#abortController.abort();
+      this.#abortController = null;
+    }
+  }
+
+  // Principle 3: HandleEvent pattern from web-components-architecture
+  handleEvent(e) {
+    switch (e.type) {
+      case 'input':
+      case 'change':
+        // Editing the raw JSON renders it as-is; editing a control rewrites the JSON
+        if (e.target !== this.#jsonField) {
+          this.#syncJson();
+        }
+        this.#scheduleRender();
+        break;
+      case 'submit':
+        e.preventDefault();
+        break;
+    }
+  }
+
+  // Public API: Current control values as a props object
+  getProps() {
+    const props = {};
+    for (const field of this.#form.elements) {
+      if (!field.name) continue;
+      this.#assignPath(props, field.name.split('.'), this.#fieldValue(field));
+    }
+    return props;
+  }
+
+  #fieldValue(field) {
+    switch (field.dataset.type) {
+      case 'boolean':
+        return field.checked;
+      case 'number':
+        return Number(field.value);
+      case 'nullable':
+        return field.value === '' ? null : field.value;
+      case 'json':
+        // Rule 1 from javascript-pragmatic-rules: Handle errors explicitly
+        try {
+          return JSON.parse(field.value);
+        } catch {
+          return field.value;
+        }
+      default:
+        return field.value;
+    }
+  }
+
+  #assignPath(target, path, value) {
+    const [head, ...rest] = path;
+    if (rest.length === 0) {
+      target[head] = value;
+      return;
+    }
+    target[head] ??= {};
+    this.#assignPath(target[head], rest, value);
+  }
+
+  #syncJson() {
+    if (!this.#jsonField) return;
+
+    this.#jsonField.value = JSON.stringify(this.getProps(), null, 2);
+  }
+
+  #scheduleRender() {
+    if (this.#debounceId) clearTimeout(this.#debounceId);
+    this.#debounceId = setTimeout(() => this.#render(), this.#debounceMs);
+  }
+
+  // Rule 2 from javascript-pragmatic-rules: Time-bound async operations
+  async #render() {
+    if (this.#abortController) {
+      this.#abortController.abort();
+    }
+
+    this.#abortController = new AbortController();
+    const timeoutId = setTimeout(() => this.#abortController?.abort(), this.#timeout);
+    const url = `/admin/api/features/${encodeURIComponent(this.dataset.story)}/render`;
+
+    // Rule 1 from javascript-pragmatic-rules: Handle promise rejections
+    try {
+      const response = await fetch(url, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: this.#jsonField.value,
+        signal: this.#abortController.signal
+      });
+      const text = await response.text();
+      if (!response.ok) {
+        throw new Error(text || `HTTP ${response.status}`);
+      }
+      this.#showError(null);
+      this.#preview.replaceChildren(parseWithNonce(text));
+    } catch (error) {
+      if (error.name !== 'AbortError') {
+        this.#showError(error.message);
+        console.error('Playground render error:', { url, error: error.message });
+      }
+    } finally {
+      clearTimeout(timeoutId);
+      this.#abortController = null;
+    }
+  }
+
+  #showError(message) {
+    if (!this.#error) return;
+
+    this.#error.textContent = message ?? '';
+    this.#error.hidden = message === null;
+  }
+}
+
+// Register the custom element
+customElements.define('props-playground', PropsPlayground);
+
+export { PropsPlayground };
@@ -0,0 +1,88 @@
+// Schedule Calendar Web Component
+// Cancels scheduled drafts on the schedule admin page
+// Using web-components-architecture and javascript-pragmatic-rules skills
+
+class ScheduleCalendar extends HTMLElement {
+  #abortController = null;
+  #timeout = 5_000;
+  #messageBanner = null;
+
+  // Lifecycle callback from web-components-architecture
+  connectedCallback() {
+    // Using handleEvent pattern from web-components-architecture
+    this.addEventListener('click', this);
+
+    this.setAttribute('role', 'region');
+    this.setAttribute('aria-label', 'Scheduled changes');
+
+    this.#messageBanner = this.querySelector('message-banner');
+  }
+
+  // Rule 4 from javascript-pragmatic-rules: Clean up resources
+  disconnectedCallback() {
+    this.removeEventListener('click', this);
+
+    if (this.#abortController) {
+      this.#abortController.abort();
+      this.#abortController = null;
+    }
+  }
+
+  // Principle 3: HandleEvent pattern from web-components-architecture
+  handleEvent(e) {
+    if (e.type !== 'click') return;
+
+    // Principle 1: Zero DOM Selection - use event delegation from web-components-architecture
+    const cancelBtn = e.target.closest('[data-action="cancel"]');
+    if (!cancelBtn) return;
+
+    const id = cancelBtn.dataset.draftId;
+    if (id && window.confirm('Cancel this scheduled draft? Its content will be discarded.')) {
+      this.#cancel(id, cancelBtn.closest('li'));
+    }
+  }
+
+  // Rule 2 from javascript-pragmatic-rules: Time-bound async operations
+  async #cancel(id, item) {
+    if (this.#abortController) {
+      this.#abortController.abort();
+    }
+
+    this.#abortController = new AbortController();
+    const timeoutId = setTimeout(() => this.#abortController?.abort(), this.#timeout);
+
+    // Rule 1 from javascript-pragmatic-rules: Handle promise rejections
+    try {
+      const response = await fetch(`/admin/api/scheduled/${id}`, {
+        method: 'DELETE',
+        signal: this.#abortController.signal
+      });
+      if (!response.ok) {
+        const errorText = await response.text();
+        throw new Error(`HTTP ${response.status}: ${errorText}`);
+      }
+      item?.remove();
+      this.#showMessage('✓ Scheduled draft cancelled', 'success');
+    } catch (error) {
+      const message = error.name === 'AbortError'
+        ? `Request timed out after ${this.#timeout / 1_000} seconds`
+        : error.message;
+      this.#showMessage(message, 'error');
+      console.error('Cancel draft error:', { id, error: error.message });
+    } finally {
+      clearTimeout(timeoutId);
+      this.#abortController = null;
+    }
+  }
+
+  #showMessage(text, type) {
+    if (!this.#messageBanner) return;
+
+    this.#messageBanner.showMessage(text, type);
+  }
+}
+
+// Register the custom element
+customElements.define('schedule-calendar', ScheduleCalendar);
+
+export { ScheduleCalendar };
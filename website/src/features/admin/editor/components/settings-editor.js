@@ -0,0 +1,177 @@
+// Settings Editor Web Component
+// Serializes the site settings form, refreshes the live preview and saves
+// Using web-components-architecture and javascript-pragmatic-rules skills
+
+import { cspNonce } from './csp-nonce.js';
+
+class SettingsEditor extends HTMLElement {
+  // One controller per endpoint, so a preview never cancels a save
+  #previewController = null;
+  #saveController = null;
+  #timeout = 5_000;
+  #debounceMs = 300;
+  #debounceId = null;
+  #form = null;
+  #previewFrame = null;
+  #messageBanner = null;
+
+  // Lifecycle callback from web-components-architecture
+  connectedCallback() {
+    // Using handleEvent pattern from web-components-architecture
+    this.addEventListener('input', this);
+    this.addEventListener('change', this);
+    this.addEventListener('submit', this);
+
+    this.setAttribute('role', 'region');
+    this.setAttribute('aria-label', 'Site settings editor');
+
+    // Principle 1: Zero DOM Selection - EXCEPTION for known child structure
+    this.#form = this.querySelector('form');
+    this.#previewFrame = this.querySelector('iframe');
+    this.#messageBanner = this.querySelector('message-banner');
+  }
+
+  // Rule 4 from javascript-pragmatic-rules: Clean up resources
+  disconnectedCallback() {
+    this.removeEventListener('input', this);
+    this.removeEventListener('change', this);
+    this.removeEventListener('submit', this);
+
+    if (this.#debounceId) {
+      clearTimeout(this.#debounceId);
+      this.#debounceId = null;
+    }
+    if (this.#previewController) {
+      this.#previewController.abort();
+      this.#previewController = null;
+    }
+  }
+
+  // Principle 3: HandleEvent pattern from web-components-architecture
+  handleEvent(e) {
+    switch (e.type) {
+      case 'input':
+      case 'change':
+        this.#schedulePreview();
+        break;
+      case 'submit':
+        e.preventDefault();
+        this.#save();
+        break;
+    }
+  }
+
+  // Public API: Current form state as a settings object
+  getSettings() {
+    const settings = {};
+    for (const field of this.#form.elements) {
+      if (!field.name) continue;
+
+      let value = field.value;
+      if (field.dataset.type === 'number') {
+        value = Number(value);
+      } else if (field.dataset.type === 'list') {
+        value = value.split(',').map((item) => item.trim()).filter(Boolean);
+      } else if (field.name === 'logo') {
+        value = value.trim() || null;
+      }
+      this.#assignPath(settings, field.name.split('.'), value);
+    }
+    return settings;
+  }
+
+  #assignPath(target, path, value) {
+    const [head, ...rest] = path;
+    if (rest.length === 0) {
+      target[head] = value;
+      return;
+    }
+    target[head] ??= {};
+    this.#assignPath(target[head], rest, value);
+  }
+
+  #schedulePreview() {
+    if (this.#debounceId) clearTimeout(this.#debounceId);
+    this.#debounceId = setTimeout(() => this.#refreshPreview(), this.#debounceMs);
+  }
+
+  async #refreshPreview() {
+    // A newer preview supersedes the one in flight
+    this.#previewController?.abort();
+    const controller = new AbortController();
+    this.#previewController = controller;
+
+    const html = await this.#post('/admin/api/settings/preview', controller);
+    if (this.#previewController === controller) {
+      this.#previewController = null;
+    }
+    if (html !== null && this.#previewFrame) {
+      // A srcdoc frame inherits this page's policy, so its theme <style>
+      // needs this page's nonce rather than the preview request's
+      const doc = new DOMParser().parseFromString(html, 'text/html');
+      for (const style of doc.querySelectorAll('style')) {
+        style.setAttribute('nonce', cspNonce());
+      }
+      this.#previewFrame.srcdoc = `<!DOCTYPE html>${doc.documentElement.outerHTML}`;
+    }
+  }
+
+  async #save() {
+    // Saves are never aborted by later requests; one at a time is enough
+    if (this.#saveController) return;
+    const controller = new AbortController();
+    this.#saveController = controller;
+
+    const message = await this.#post('/admin/api/settings', controller);
+    this.#saveController = null;
+    if (message !== null) {
+      this.#showMessage(`✓ ${message}`, 'success');
+    }
+  }
+
+  // Rule 2 from javascript-pragmatic-rules: Time-bound async operations
+  async #post(url, controller) {
+    let timedOut = false;
+    const timeoutId = setTimeout(() => {
+      timedOut = true;
+      controller.abort();
+    }, this.#timeout);
+
+    // Rule 1 from javascript-pragmatic-rules: Handle promise rejections
+    try {
+      const response = await fetch(url, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json' },
+        body: JSON.stringify(this.getSettings()),
+        signal: controller.signal
+      });
+      const text = await response.text();
+      if (!response.ok) {
+        throw new Error(text || `HTTP ${response.status}`);
+      }
+      return text;
+    } catch (error) {
+      // Superseded previews are aborted on purpose; timeouts are not
+      if (timedOut) {
+        this.#showMessage('The server did not respond in time', 'error');
+      } else if (error.name !== 'AbortError') {
+        this.#showMessage(error.message, 'error');
+        console.error('Settings request error:', { url, error: error.message });
+      }
+      return null;
+    } finally {
+      clearTimeout(timeoutId);
+    }
+  }
+
+  #showMessage(text, type) {
+    if (!this.#messageBanner) return;
+
+    this.#messageBanner.showMessage(text, type);
+  }
+}
+
+// Register the custom element
+customElements.define('settings-editor', SettingsEditor);
+
+export { SettingsEditor };
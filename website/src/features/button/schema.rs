@@ -32,7 +32,7 @@
 use maud::Markup;
 use serde::{Deserialize, Serialize};
 
-use crate::features::story::ComponentStory;
+use crate::features::story::{ComponentStory, StoryVariant, register_story};
 
 /// Button component props
 ///
@@ -42,7 +42,8 @@ use crate::features::story::ComponentStory;
 ///
 /// - `href`: The URL the button navigates to
 /// - `text`: The visible button label
-/// - `aria_label`: Accessible description for screen readers
+/// - `aria_label`: Accessible description for screen readers; leave empty
+///   when the visible text is descriptive enough (the attribute is then omitted)
 ///
 /// # Example JSON
 ///
@@ -60,6 +61,16 @@ pub struct ButtonProps {
     pub aria_label: String,
 }
 
+impl ButtonProps {
+    /// The `aria-label` to render, if any
+    ///
+    /// An empty `aria-label` would hide the visible text from screen readers,
+    /// so blank labels are omitted instead.
+    pub fn accessible_label(&self) -> Option<&str> {
+        Some(self.aria_label.as_str()).filter(|label| !label.trim().is_empty())
+    }
+}
+
 /// ComponentStory implementation for Button
 ///
 /// Following rust-core-patterns for trait-based abstraction, this implementation
@@ -82,6 +93,27 @@ impl ComponentStory for ButtonProps {
         }
     }
 
+    fn story_variants() -> Vec<StoryVariant<Self>> {
+        vec![
+            StoryVariant::new("default", Self::story_fixture()),
+            StoryVariant::new(
+                "text-only",
+                ButtonProps {
+                    text: "Read the case study".to_string(),
+                    aria_label: String::new(),
+                    ..Self::story_fixture()
+                },
+            ),
+            StoryVariant::new(
+                "long-text",
+                ButtonProps {
+                    text: "Schedule a thirty minute introductory call with our team".to_string(),
+                    ..Self::story_fixture()
+                },
+            ),
+        ]
+    }
+
     fn render_story(&self) -> Markup {
         // Import the template function here to avoid circular dependencies
         crate::features::button::template::button(self)
@@ -89,3 +121,5 @@ impl ComponentStory for ButtonProps {
 
     // No additional stylesheets needed - using default implementation
 }
+
+register_story!(ButtonProps);
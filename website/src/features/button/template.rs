@@ -29,7 +29,7 @@ pub fn button(props: &ButtonProps) -> Markup {
     html! {
         a
             href=(props.href)
-            aria-label=(props.aria_label)
+            aria-label=[props.accessible_label()]
             class="cta-button"
         {
             (props.text)
@@ -28,6 +28,7 @@
 /// let props = CheckboxProps {
 ///     label: "I agree to the terms".to_string(),
 ///     name: "terms".to_string(),
+///     id: None,
 ///     value: Some("agreed".to_string()),
 ///     checked: false,
 ///     required: true,
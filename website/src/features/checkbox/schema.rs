@@ -14,6 +14,7 @@
 /// let checkbox = CheckboxProps {
 ///     label: "Subscribe to newsletter".to_string(),
 ///     name: "subscribe".to_string(),
+///     id: None,
 ///     value: Some("yes".to_string()),
 ///     checked: false,
 ///     required: false,
@@ -35,7 +36,7 @@
 use maud::Markup;
 use serde::{Deserialize, Serialize};
 
-use crate::features::story::ComponentStory;
+use crate::features::story::{ComponentStory, StoryVariant, register_story};
 
 /// Checkbox component props
 ///
@@ -45,6 +46,8 @@ use crate::features::story::ComponentStory;
 ///
 /// - `label`: The visible label text for the checkbox
 /// - `name`: The form field name attribute
+/// - `id`: Optional element id (defaults to `name`); set it when several
+///   checkboxes on one page share a name
 /// - `value`: Optional value attribute (defaults to "on" if not provided)
 /// - `checked`: Whether the checkbox is initially checked
 /// - `required`: Whether the field is required
@@ -66,6 +69,8 @@ use crate::features::story::ComponentStory;
 pub struct CheckboxProps {
     pub label: String,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub value: Option<String>,
     pub checked: bool,
@@ -74,6 +79,13 @@ pub struct CheckboxProps {
     pub aria_describedby: Option<String>,
 }
 
+impl CheckboxProps {
+    /// The element id: `id` if set, otherwise `name`
+    pub fn element_id(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.name)
+    }
+}
+
 /// ComponentStory implementation for Checkbox
 ///
 /// Following rust-core-patterns for trait-based abstraction, this implementation
@@ -92,6 +104,7 @@ impl ComponentStory for CheckboxProps {
         CheckboxProps {
             label: "Send me product updates and announcements".to_string(),
             name: "newsletter".to_string(),
+            id: None,
             value: Some("subscribe".to_string()),
             checked: false,
             required: false,
@@ -99,6 +112,42 @@ impl ComponentStory for CheckboxProps {
         }
     }
 
+    fn story_variants() -> Vec<StoryVariant<Self>> {
+        let fixture = Self::story_fixture();
+        vec![
+            StoryVariant::new("default", fixture.clone()),
+            StoryVariant::new(
+                "checked",
+                CheckboxProps {
+                    checked: true,
+                    ..fixture.clone()
+                },
+            ),
+            StoryVariant::new(
+                "required",
+                CheckboxProps {
+                    label: "I agree to the terms and conditions".to_string(),
+                    name: "terms".to_string(),
+                    value: Some("agreed".to_string()),
+                    required: true,
+                    ..fixture.clone()
+                },
+            ),
+            StoryVariant::new(
+                "checked-required",
+                CheckboxProps {
+                    label: "I agree to the terms and conditions".to_string(),
+                    name: "terms".to_string(),
+                    value: Some("agreed".to_string()),
+                    id: None,
+                    checked: true,
+                    required: true,
+                    aria_describedby: None,
+                },
+            ),
+        ]
+    }
+
     fn render_story(&self) -> Markup {
         // Import the template function here to avoid circular dependencies
         crate::features::checkbox::template::checkbox(self)
@@ -106,3 +155,5 @@ impl ComponentStory for CheckboxProps {
 
     // No additional stylesheets needed - using default implementation
 }
+
+register_story!(CheckboxProps);
@@ -16,7 +16,7 @@
 ///
 /// This component has an associated stylesheet at:
 /// `/features/checkbox/styles.css`
-use maud::{html, Markup};
+use maud::{Markup, html};
 
 use crate::core::Render;
 use crate::features::checkbox::CheckboxProps;
@@ -32,14 +32,14 @@ pub fn checkbox(props: &CheckboxProps) -> Markup {
             label class="checkbox-label" {
                 input
                     type="checkbox"
-                    id=(props.name)
+                    id=(props.element_id())
                     name=(props.name)
                     class="checkbox-input"
                     value=[props.value.as_deref()]
                     checked[props.checked]
                     required[props.required]
                     aria-describedby=[props.aria_describedby.as_deref()]
-                {}
+                ;
                 span class="checkbox-label-text" {
                     (props.label)
                     @if props.required {
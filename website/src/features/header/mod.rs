@@ -15,6 +15,7 @@
 /// # Usage
 ///
 /// ```rust
+/// use crate::core::options::BlockOptions;
 /// use crate::features::header::{HeaderProps, render_header};
 /// use crate::features::button::ButtonProps;
 ///
@@ -27,7 +28,7 @@
 ///     },
 /// };
 ///
-/// let markup = render_header(&props);
+/// let markup = render_header(&props, &BlockOptions::default());
 /// ```
 pub mod schema;
 pub mod template;
@@ -23,8 +23,9 @@
 use maud::Markup;
 use serde::{Deserialize, Serialize};
 
+use crate::core::options::BlockOptions;
 use crate::features::button::ButtonProps;
-use crate::features::story::ComponentStory;
+use crate::features::story::{ComponentStory, StoryVariant, register_story};
 
 /// Header component props
 ///
@@ -78,9 +79,31 @@ impl ComponentStory for HeaderProps {
         }
     }
 
+    fn story_variants() -> Vec<StoryVariant<Self>> {
+        vec![
+            StoryVariant::new("default", Self::story_fixture()),
+            StoryVariant::new(
+                "long-headline",
+                HeaderProps {
+                    headline: "Engineering leadership for teams that ship, scale and stay together"
+                        .to_string(),
+                    ..Self::story_fixture()
+                },
+            ),
+        ]
+    }
+
     fn render_story(&self) -> Markup {
+        self.render_story_with_options(&BlockOptions::default())
+    }
+
+    fn block_type() -> Option<&'static str> {
+        Some("Header")
+    }
+
+    fn render_story_with_options(&self, options: &BlockOptions) -> Markup {
         // Import the template function here to avoid circular dependencies
-        crate::features::header::template::header(self)
+        crate::features::header::template::header(self, options)
     }
 
     fn additional_stylesheets() -> Vec<&'static str> {
@@ -90,3 +113,5 @@ impl ComponentStory for HeaderProps {
         ]
     }
 }
+
+register_story!(HeaderProps);
@@ -16,21 +16,27 @@
 /// `/features/header/styles.css`
 ///
 /// The stylesheet is loaded in the page <head>, not inline with the component.
+///
+/// # Block Options
+///
+/// The root `header` honors the shared `BlockOptions` envelope (variant,
+/// alignment, background, spacing, anchor, visibility classes).
 use maud::{Markup, html};
 
 use crate::core::Render;
+use crate::core::options::BlockOptions;
 use crate::features::header::HeaderProps;
 
-/// Render the Header component with the given props
+/// Render the Header component with the given props and block options
 ///
 /// This is a pure function that takes HeaderProps and returns Markup.
 /// It can be called directly or via the Render trait implementation.
 ///
 /// Demonstrates component composition by using the Button component
 /// as a primitive building block via the Render trait.
-pub fn header(props: &HeaderProps) -> Markup {
+pub fn header(props: &HeaderProps, options: &BlockOptions) -> Markup {
     html! {
-        header class="header-block" {
+        header class=(options.classes("header-block")) id=[options.anchor()] {
             div class="container" {
                 h1 { (props.headline) }
                 (props.button.render())
@@ -45,6 +51,6 @@ pub fn header(props: &HeaderProps) -> Markup {
 /// that implement Render, enabling composition and reusability.
 impl Render for HeaderProps {
     fn render(&self) -> Markup {
-        header(self)
+        header(self, &BlockOptions::default())
     }
 }
@@ -14,6 +14,7 @@
 /// # Usage
 ///
 /// ```rust
+/// use crate::core::options::BlockOptions;
 /// use crate::features::hero::{HeroProps, render_hero};
 ///
 /// let props = HeroProps {
@@ -21,7 +22,7 @@
 ///     subheadline: "Build amazing things with confidence".to_string(),
 /// };
 ///
-/// let markup = render_hero(&props);
+/// let markup = render_hero(&props, &BlockOptions::default());
 /// ```
 pub mod schema;
 pub mod template;
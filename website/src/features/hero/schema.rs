@@ -9,8 +9,17 @@
 /// This schema is intentionally separate from the template logic to enable:
 /// - **Type safety**: Serde validation ensures data integrity
 /// - **Clear boundaries**: Schema defines the contract, template implements the presentation
+///
+/// # Story Support
+///
+/// HeroProps implements ComponentStory trait to provide story/preview functionality
+/// directly in the schema, eliminating the need for a separate story.rs file.
+use maud::Markup;
 use serde::{Deserialize, Serialize};
 
+use crate::core::options::BlockOptions;
+use crate::features::story::{ComponentStory, StoryVariant, register_story};
+
 /// Hero component props
 ///
 /// Represents the data required to render the hero section.
@@ -33,3 +42,62 @@ pub struct HeroProps {
     pub headline: String,
     pub subheadline: String,
 }
+
+/// ComponentStory implementation for Hero
+///
+/// Following rust-core-patterns for trait-based abstraction, this implementation
+/// provides all story functionality (name, description, fixture, rendering) directly
+/// on the Props type.
+impl ComponentStory for HeroProps {
+    fn story_name() -> &'static str {
+        "hero"
+    }
+
+    fn story_description() -> &'static str {
+        "Full-width hero section with headline and supporting subheadline."
+    }
+
+    fn story_fixture() -> Self {
+        HeroProps {
+            headline: "Build Amazing Things".to_string(),
+            subheadline: "With the tools and expertise you need to succeed".to_string(),
+        }
+    }
+
+    fn story_variants() -> Vec<StoryVariant<Self>> {
+        vec![
+            StoryVariant::new("default", Self::story_fixture()),
+            StoryVariant::new(
+                "long-copy",
+                HeroProps {
+                    headline: "Helping engineering managers grow teams that deliver".to_string(),
+                    subheadline: "Coaching, hiring loops, delivery rituals and the occasional \
+                                  hard conversation, all grounded in a decade of shipping \
+                                  software with teams of every size."
+                        .to_string(),
+                },
+            ),
+        ]
+    }
+
+    fn render_story(&self) -> Markup {
+        self.render_story_with_options(&BlockOptions::default())
+    }
+
+    fn block_type() -> Option<&'static str> {
+        Some("Hero")
+    }
+
+    fn render_story_with_options(&self, options: &BlockOptions) -> Markup {
+        // Import the template function here to avoid circular dependencies
+        crate::features::hero::template::hero(self, options)
+    }
+
+    fn additional_stylesheets() -> Vec<&'static str> {
+        vec![
+            "/assets/styles.css", // Global styles for base typography
+        ]
+    }
+}
+
+register_story!(HeroProps);
@@ -16,18 +16,24 @@
 /// `/features/hero/styles.css`
 ///
 /// The stylesheet is loaded in the page <head>, not inline with the component.
+///
+/// # Block Options
+///
+/// The root `section` honors the shared `BlockOptions` envelope (variant,
+/// alignment, background, spacing, anchor, visibility classes).
 use maud::{Markup, html};
 
 use crate::core::Render;
+use crate::core::options::BlockOptions;
 use crate::features::hero::HeroProps;
 
-/// Render the Hero component with the given props
+/// Render the Hero component with the given props and block options
 ///
 /// This is a pure function that takes HeroProps and returns Markup.
 /// It can be called directly or via the Render trait implementation.
-pub fn hero(props: &HeroProps) -> Markup {
+pub fn hero(props: &HeroProps, options: &BlockOptions) -> Markup {
     html! {
-        section class="hero-block" {
+        section class=(options.classes("hero-block")) id=[options.anchor()] {
             div class="container" {
                 h2 { (props.headline) }
                 p class="subheadline" { (props.subheadline) }
@@ -42,6 +48,6 @@ pub fn hero(props: &HeroProps) -> Markup {
 /// that implement Render, enabling composition and reusability.
 impl Render for HeroProps {
     fn render(&self) -> Markup {
-        hero(self)
+        hero(self, &BlockOptions::default())
     }
 }
@@ -0,0 +1,41 @@
+/// Image feature module
+///
+/// The Image block displays a picture from the media library:
+/// - A responsive `<img>` with `srcset`/`sizes` built from the WebP variants
+/// - Alt text from the media item (overridable per block)
+/// - An optional caption
+///
+/// # Architecture
+///
+/// Following the feature-based architecture pattern:
+/// - **Schema**: Data shape defined in schema.rs (ImageProps)
+/// - **Template**: Maud rendering logic in template.rs
+/// - **Styles**: Component-scoped CSS in styles.css
+///
+/// Unlike other features, the props only reference a media item by id. The
+/// template resolves the item from the media library (see `core::media`) so
+/// that dimensions and variants always reflect what is stored on disk.
+///
+/// # Usage
+///
+/// ```rust
+/// use crate::core::options::BlockOptions;
+/// use crate::features::image::{ImageProps, render_image};
+///
+/// let props = ImageProps {
+///     media_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+///     alt: None,
+///     sizes: Some("(min-width: 1200px) 1200px, 100vw".to_string()),
+///     caption: Some("The team at the 2025 offsite".to_string()),
+/// };
+///
+/// let markup = render_image(&props, &BlockOptions::default());
+/// ```
+pub mod schema;
+pub mod template;
+
+// Re-export schema types for easy importing
+pub use schema::ImageProps;
+
+// Re-export the main rendering function for convenience
+pub use template::image as render_image;
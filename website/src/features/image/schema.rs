@@ -0,0 +1,154 @@
+/// Image component schema
+///
+/// This module defines the data shape (schema) for the Image component.
+/// Following rust-core-patterns, props are type-safe domain types that
+/// enforce validation at compile time.
+///
+/// # Architecture
+///
+/// This schema is intentionally separate from the template logic to enable:
+/// - **Type safety**: Serde validation ensures data integrity
+/// - **Clear boundaries**: Schema defines the contract, template implements the presentation
+/// - **Single source of truth**: Dimensions and variants live with the media item, not the block
+///
+/// # Story Support
+///
+/// ImageProps implements ComponentStory trait. Story fixtures reference
+/// `STORY_MEDIA_ID`, a placeholder item that exists only in memory so the
+/// rendered markup doesn't depend on the media library; any other id is
+/// looked up in the library as usual.
+use chrono::DateTime;
+use maud::Markup;
+use serde::{Deserialize, Serialize};
+
+use crate::core::media::{FocalPoint, MediaItem, MediaVariant, find_media};
+use crate::core::options::BlockOptions;
+use crate::features::story::{ComponentStory, StoryVariant, register_story};
+
+/// Media id used by the story fixtures (see `story_media`)
+pub const STORY_MEDIA_ID: &str = "00000000-0000-4000-8000-000000000000";
+
+/// Image component props
+///
+/// Represents the data required to render an image from the media library.
+///
+/// # Fields
+///
+/// - `media_id`: Id of the media item to display
+/// - `alt`: Optional alt text override (defaults to the media item's alt text)
+/// - `sizes`: Optional `sizes` attribute (defaults to `100vw`)
+/// - `caption`: Optional caption rendered below the image
+///
+/// # Example JSON
+///
+/// ```json
+/// {
+///   "media_id": "550e8400-e29b-41d4-a716-446655440000",
+///   "sizes": "(min-width: 1200px) 1200px, 100vw",
+///   "caption": "The team at the 2025 offsite"
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageProps {
+    pub media_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sizes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+}
+
+/// In-memory media item for `STORY_MEDIA_ID`: a 1920×1080 image with the
+/// standard variant widths
+fn story_media() -> MediaItem {
+    MediaItem {
+        id: STORY_MEDIA_ID.to_string(),
+        filename: "story-placeholder.jpg".to_string(),
+        original: "original.jpg".to_string(),
+        width: 1920,
+        height: 1080,
+        alt: "Placeholder image for the component story".to_string(),
+        focal_point: FocalPoint::default(),
+        variants: [320, 640, 960, 1280, 1920]
+            .into_iter()
+            .map(|width| MediaVariant {
+                width,
+                height: width * 9 / 16,
+                file: format!("{}.webp", width),
+            })
+            .collect(),
+        uploaded_at: DateTime::UNIX_EPOCH,
+    }
+}
+
+/// ComponentStory implementation for Image
+///
+/// Following rust-core-patterns for trait-based abstraction, this implementation
+/// provides all story functionality (name, description, fixture, rendering) directly
+/// on the Props type.
+impl ComponentStory for ImageProps {
+    fn story_name() -> &'static str {
+        "image"
+    }
+
+    fn story_description() -> &'static str {
+        "Responsive image from the media library with srcset, focal point and optional caption."
+    }
+
+    fn story_fixture() -> Self {
+        ImageProps {
+            media_id: STORY_MEDIA_ID.to_string(),
+            alt: None,
+            sizes: None,
+            caption: None,
+        }
+    }
+
+    fn story_variants() -> Vec<StoryVariant<Self>> {
+        vec![
+            StoryVariant::new("default", Self::story_fixture()),
+            StoryVariant::new(
+                "with-caption",
+                ImageProps {
+                    alt: Some("The team gathered around a whiteboard".to_string()),
+                    sizes: Some("(min-width: 1200px) 1200px, 100vw".to_string()),
+                    caption: Some("The team at the 2025 offsite".to_string()),
+                    ..Self::story_fixture()
+                },
+            ),
+            StoryVariant::new(
+                "missing-media",
+                ImageProps {
+                    media_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+                    ..Self::story_fixture()
+                },
+            ),
+        ]
+    }
+
+    fn render_story(&self) -> Markup {
+        self.render_story_with_options(&BlockOptions::default())
+    }
+
+    fn block_type() -> Option<&'static str> {
+        Some("Image")
+    }
+
+    fn render_story_with_options(&self, options: &BlockOptions) -> Markup {
+        let media = if self.media_id == STORY_MEDIA_ID {
+            Some(story_media())
+        } else {
+            find_media(&self.media_id)
+        };
+        crate::features::image::template::image_with_media(self, options, media.as_ref())
+    }
+
+    fn additional_stylesheets() -> Vec<&'static str> {
+        vec![
+            "/assets/styles.css", // Global styles and block option utilities
+        ]
+    }
+}
+
+register_story!(ImageProps);
@@ -0,0 +1,98 @@
+/// Image component Maud template
+///
+/// This module contains the rendering logic for the Image component.
+/// Following maud-components-patterns, templates are separated from props
+/// to maintain clean separation of concerns.
+///
+/// # Component Structure
+///
+/// The image renders as a figure with:
+/// - An `<img>` with `srcset`, `sizes`, intrinsic `width`/`height` and lazy loading
+/// - `object-position` from the media item's focal point, set by a `<style>`
+///   rule carrying the request's CSP nonce (the policy blocks `style=`
+///   attributes, see `core::security_headers`)
+/// - An optional `<figcaption>`
+///
+/// The root `figure` honors the shared `BlockOptions` envelope.
+///
+/// If the referenced media item no longer exists, nothing visible is rendered
+/// (an HTML comment marks the gap) so a missing upload never breaks the page.
+///
+/// # Asset References
+///
+/// This component has an associated stylesheet at:
+/// `/features/image/styles.css`
+use maud::{Markup, PreEscaped, html};
+
+use crate::core::Render;
+use crate::core::media::{MediaItem, find_media};
+use crate::core::options::BlockOptions;
+use crate::core::security_headers::csp_nonce;
+use crate::features::image::ImageProps;
+
+/// Default `sizes` attribute when the block doesn't specify one
+const DEFAULT_SIZES: &str = "100vw";
+
+/// Render the Image component with the given props and block options
+///
+/// Resolves the media item from the library, then delegates to
+/// `image_with_media`.
+pub fn image(props: &ImageProps, options: &BlockOptions) -> Markup {
+    image_with_media(props, options, find_media(&props.media_id).as_ref())
+}
+
+/// Render the Image component with an already-resolved media item
+///
+/// This is a pure function, useful when the caller has the item at hand.
+pub fn image_with_media(
+    props: &ImageProps,
+    options: &BlockOptions,
+    media: Option<&MediaItem>,
+) -> Markup {
+    let Some(media) = media else {
+        return html! {
+            (PreEscaped(format!("<!-- missing media item: {} -->", props.media_id.replace("--", ""))))
+        };
+    };
+
+    let alt = props.alt.as_deref().unwrap_or(&media.alt);
+    let sizes = props.sizes.as_deref().unwrap_or(DEFAULT_SIZES);
+    let x = (media.focal_point.x * 100.0).round();
+    let y = (media.focal_point.y * 100.0).round();
+    let focal = format!("{}-{}", x, y);
+    // Numbers only, so the rule needs no escaping
+    let object_position = format!(
+        ".image-block__img[data-focal=\"{}\"] {{ object-position: {}% {}%; }}",
+        focal, x, y
+    );
+
+    html! {
+        figure class=(options.classes("image-block")) id=[options.anchor()] {
+            style nonce=[csp_nonce()] { (PreEscaped(object_position)) }
+            img
+                class="image-block__img"
+                src=(media.default_src())
+                srcset=(media.srcset())
+                sizes=(sizes)
+                width=(media.width)
+                height=(media.height)
+                alt=(alt)
+                loading="lazy"
+                decoding="async"
+                data-focal=(focal);
+            @if let Some(caption) = &props.caption {
+                figcaption class="image-block__caption" { (caption) }
+            }
+        }
+    }
+}
+
+/// Implement Render trait for ImageProps
+///
+/// This allows ImageProps to be used polymorphically with other components
+/// that implement Render, enabling composition and reusability.
+impl Render for ImageProps {
+    fn render(&self) -> Markup {
+        image(self, &BlockOptions::default())
+    }
+}
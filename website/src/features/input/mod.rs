@@ -27,11 +27,13 @@
 /// let props = InputProps {
 ///     label: "Email".to_string(),
 ///     name: "email".to_string(),
+///     id: None,
 ///     input_type: "email".to_string(),
 ///     placeholder: Some("you@example.com".to_string()),
 ///     value: None,
 ///     required: true,
 ///     aria_describedby: None,
+///     error: None,
 /// };
 ///
 /// let markup = render_input(&props);
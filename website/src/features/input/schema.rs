@@ -14,11 +14,13 @@
 /// let input = InputProps {
 ///     label: "Email Address".to_string(),
 ///     name: "email".to_string(),
+///     id: None,
 ///     input_type: "email".to_string(),
 ///     placeholder: Some("you@example.com".to_string()),
 ///     value: None,
 ///     required: true,
 ///     aria_describedby: None,
+///     error: None,
 /// };
 /// ```
 ///
@@ -36,7 +38,7 @@
 use maud::Markup;
 use serde::{Deserialize, Serialize};
 
-use crate::features::story::ComponentStory;
+use crate::features::story::{ComponentStory, StoryVariant, register_story};
 
 /// Input component props
 ///
@@ -46,11 +48,15 @@ use crate::features::story::ComponentStory;
 ///
 /// - `label`: The visible label text for the input
 /// - `name`: The form field name attribute
+/// - `id`: Optional element id (defaults to `name`); set it when several
+///   inputs on one page share a name
 /// - `input_type`: The input type (text, email, password, etc.)
 /// - `placeholder`: Optional placeholder text
 /// - `value`: Optional default value
 /// - `required`: Whether the field is required
 /// - `aria_describedby`: Optional ID of an element that describes the input
+/// - `error`: Optional validation message; marks the input invalid and is
+///   announced via `aria-describedby`
 ///
 /// # Example JSON
 ///
@@ -69,6 +75,8 @@ use crate::features::story::ComponentStory;
 pub struct InputProps {
     pub label: String,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
     #[serde(rename = "type")]
     pub input_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -78,6 +86,15 @@ pub struct InputProps {
     pub required: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub aria_describedby: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl InputProps {
+    /// The element id: `id` if set, otherwise `name`
+    pub fn element_id(&self) -> &str {
+        self.id.as_deref().unwrap_or(&self.name)
+    }
 }
 
 /// ComponentStory implementation for Input
@@ -98,14 +115,51 @@ impl ComponentStory for InputProps {
         InputProps {
             label: "Email Address".to_string(),
             name: "email".to_string(),
+            id: None,
             input_type: "email".to_string(),
             placeholder: Some("you@example.com".to_string()),
             value: None,
             required: true,
             aria_describedby: None,
+            error: None,
         }
     }
 
+    fn story_variants() -> Vec<StoryVariant<Self>> {
+        let fixture = Self::story_fixture();
+        vec![
+            StoryVariant::new("default", fixture.clone()),
+            StoryVariant::new(
+                "with-value",
+                InputProps {
+                    value: Some("jane@example.com".to_string()),
+                    ..fixture.clone()
+                },
+            ),
+            StoryVariant::new(
+                "optional",
+                InputProps {
+                    label: "Company".to_string(),
+                    name: "company".to_string(),
+                    input_type: "text".to_string(),
+                    placeholder: None,
+                    required: false,
+                    ..fixture.clone()
+                },
+            ),
+            StoryVariant::new(
+                "error",
+                InputProps {
+                    value: Some("jane@".to_string()),
+                    error: Some(
+                        "Enter a complete email address, like jane@example.com".to_string(),
+                    ),
+                    ..fixture.clone()
+                },
+            ),
+        ]
+    }
+
     fn render_story(&self) -> Markup {
         // Import the template function here to avoid circular dependencies
         crate::features::input::template::input(self)
@@ -113,3 +167,5 @@ impl ComponentStory for InputProps {
 
     // No additional stylesheets needed - using default implementation
 }
+
+register_story!(InputProps);
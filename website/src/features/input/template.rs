@@ -10,13 +10,14 @@
 /// - Label element for accessibility
 /// - Input element with appropriate attributes
 /// - Required indicator when applicable
+/// - Error message (linked via `aria-describedby`) when `error` is set
 /// - CSS class for styling
 ///
 /// # Asset References
 ///
 /// This component has an associated stylesheet at:
 /// `/features/input/styles.css`
-use maud::{html, Markup};
+use maud::{Markup, html};
 
 use crate::core::Render;
 use crate::features::input::InputProps;
@@ -27,9 +28,18 @@ use crate::features::input::InputProps;
 /// It can be called directly or via the Render trait implementation.
 #[allow(dead_code)] // Available for direct use, though typically accessed via Render trait
 pub fn input(props: &InputProps) -> Markup {
+    let id = props.element_id();
+    let error_id = format!("{}-error", id);
+    let describedby = match (props.aria_describedby.as_deref(), props.error.is_some()) {
+        (Some(id), true) => Some(format!("{} {}", id, error_id)),
+        (None, true) => Some(error_id.clone()),
+        (Some(id), false) => Some(id.to_string()),
+        (None, false) => None,
+    };
+
     html! {
         div class="form-field" {
-            label for=(props.name) class="form-label" {
+            label for=(id) class="form-label" {
                 (props.label)
                 @if props.required {
                     span class="required-indicator" aria-label="required" { " *" }
@@ -37,14 +47,18 @@ pub fn input(props: &InputProps) -> Markup {
             }
             input
                 type=(props.input_type)
-                id=(props.name)
+                id=(id)
                 name=(props.name)
                 class="form-input"
                 placeholder=[props.placeholder.as_deref()]
                 value=[props.value.as_deref()]
                 required[props.required]
-                aria-describedby=[props.aria_describedby.as_deref()]
-            {}
+                aria-invalid=[props.error.as_ref().map(|_| "true")]
+                aria-describedby=[describedby]
+            ;
+            @if let Some(error) = &props.error {
+                p class="form-error" id=(error_id) { (error) }
+            }
         }
     }
 }
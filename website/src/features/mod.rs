@@ -17,11 +17,13 @@
 /// - **checkbox**: Checkbox input field component (primitive)
 /// - **header**: Page header with headline and CTA button
 /// - **hero**: Hero section with headline and subheadline
+/// - **image**: Responsive image from the media library
 /// - **input**: Text input field component (primitive)
 ///
 /// # Story System
 ///
-/// - **story**: ComponentStory trait for the component preview system
+/// - **story**: ComponentStory trait and registry for the component preview system
+/// - **snapshot_tests**: Snapshot tests over every registered story and block type
 ///
 /// # Adding a New Feature
 ///
@@ -31,12 +33,17 @@
 /// 4. Create styles: `src/features/my_feature/styles.css`
 /// 5. Create module: `src/features/my_feature/mod.rs` (re-export schema and template)
 /// 6. Add feature to this file: `pub mod my_feature;`
-/// 7. (Optional) Implement ComponentStory trait in schema.rs for preview system
+/// 7. Implement ComponentStory in schema.rs and `register_story!` it
+///    (required for block types; a test fails otherwise)
 /// 8. Add variant to `Block` enum in `core/block.rs` (if block-based)
 /// 9. Update render dispatcher in pages
+/// 10. Run `INSTA_UPDATE=always cargo test snapshot` and commit the new snapshots
 pub mod button;
 pub mod checkbox;
 pub mod header;
 pub mod hero;
+pub mod image;
 pub mod input;
+#[cfg(test)]
+mod snapshot_tests;
 pub mod story;
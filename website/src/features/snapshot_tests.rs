@@ -0,0 +1,145 @@
+/// Snapshot tests for rendered component HTML
+///
+/// Renders every registered story variant and every `Block` type through
+/// `render_block`, normalizes the HTML to one tag or text node per line, and
+/// compares it against the snapshots committed in `src/features/snapshots/`.
+/// A template change that alters the markup fails `cargo test` with a diff.
+///
+/// # Reviewing Changes
+///
+/// When a markup change is intended, accept the new output with either:
+///
+/// ```bash
+/// cargo insta review                      # interactive (cargo install cargo-insta)
+/// INSTA_UPDATE=always cargo test snapshot # accept everything non-interactively
+/// ```
+///
+/// New stories and variants get a snapshot on their first run; commit the
+/// generated `.snap` files alongside the template change.
+use insta::assert_snapshot;
+
+use crate::core::block::{Block, BlockWithId, render_block};
+use crate::core::options::{Alignment, BlockOptions, BlockVariant, ColorToken, Spacing};
+use crate::features::story::all_stories;
+use strum::VariantNames;
+
+/// Elements that never have a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Pretty-print HTML with one tag, comment or text node per line
+///
+/// Whitespace between tags is dropped and runs of whitespace inside text are
+/// collapsed, so snapshots only change when the rendered structure or content
+/// does.
+fn normalize_html(html: &str) -> String {
+    let mut lines = Vec::new();
+    let mut depth = 0usize;
+    let mut rest = html;
+
+    while !rest.is_empty() {
+        let (token, remainder) = if rest.starts_with("<!--") {
+            let end = rest.find("-->").map_or(rest.len(), |i| i + 3);
+            rest.split_at(end)
+        } else if rest.starts_with('<') {
+            let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+            rest.split_at(end)
+        } else {
+            rest.split_at(rest.find('<').unwrap_or(rest.len()))
+        };
+        rest = remainder;
+
+        if token.starts_with("</") {
+            depth = depth.saturating_sub(1);
+            lines.push(format!("{}{}", "  ".repeat(depth), token));
+        } else if token.starts_with("<!") {
+            lines.push(format!("{}{}", "  ".repeat(depth), token));
+        } else if let Some(tag) = token.strip_prefix('<') {
+            lines.push(format!("{}{}", "  ".repeat(depth), token));
+            let name: String = tag
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+                .collect();
+            if !VOID_ELEMENTS.contains(&name.to_ascii_lowercase().as_str())
+                && !token.ends_with("/>")
+            {
+                depth += 1;
+            }
+        } else {
+            let text = token.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !text.is_empty() {
+                lines.push(format!("{}{}", "  ".repeat(depth), text));
+            }
+        }
+    }
+
+    lines.join("\n")
+}
+
+#[test]
+fn test_normalize_html() {
+    let html = "<div class=\"a\"><label>Name <span>*</span></label><input id=\"x\">\n  <!-- note --><img src=\"y\"></div>";
+    assert_eq!(
+        normalize_html(html),
+        "<div class=\"a\">\n  <label>\n    Name\n    <span>\n      *\n    </span>\n  </label>\n  \
+         <input id=\"x\">\n  <!-- note -->\n  <img src=\"y\">\n</div>"
+    );
+}
+
+#[test]
+fn snapshot_story_variants() {
+    for story in all_stories() {
+        for variant in story.variants() {
+            assert_snapshot!(
+                format!("story__{}__{}", story.name(), variant.name),
+                normalize_html(&variant.markup.into_string())
+            );
+        }
+    }
+}
+
+#[test]
+fn snapshot_block_types() {
+    let options = BlockOptions {
+        variant: BlockVariant::Inverted,
+        alignment: Alignment::Center,
+        background: Some(ColorToken::Surface),
+        spacing: Spacing::Large,
+        anchor: Some("section".to_string()),
+        ..Default::default()
+    };
+
+    for block_type in Block::VARIANTS {
+        let story = all_stories()
+            .into_iter()
+            .find(|story| story.block_type() == Some(*block_type))
+            .expect("every block type has a story");
+        let block: Block = serde_json::from_value(serde_json::json!({
+            "type": block_type,
+            "props": story.props_shape(),
+        }))
+        .expect("story props are valid block props");
+
+        // Image blocks resolve their media from the library like on the live
+        // site, so here they render the missing-media comment; the
+        // `story__image__*` snapshots cover the figure markup.
+        let mut block = BlockWithId {
+            id: "00000000-0000-4000-8000-000000000001".to_string(),
+            block,
+            options: BlockOptions::default(),
+            schedule: Default::default(),
+        };
+        assert_snapshot!(
+            format!("block__{}", block_type),
+            normalize_html(&render_block(&block).into_string())
+        );
+
+        block.options = options.clone();
+        assert_snapshot!(
+            format!("block__{}__with_options", block_type),
+            normalize_html(&render_block(&block).into_string())
+        );
+    }
+}
@@ -51,18 +51,61 @@
 /// }
 /// ```
 ///
-/// Then in your admin/features.rs, you can use this trait:
+/// Then register it once, next to the impl:
 ///
 /// ```rust
-/// use crate::features::story::ComponentStory;
-/// use crate::features::button::ButtonProps;
-///
-/// let name = ButtonProps::story_name();
-/// let description = ButtonProps::story_description();
-/// let fixture = ButtonProps::story_fixture();
-/// let markup = fixture.render_story();
+/// register_story!(ButtonProps);
 /// ```
+///
+/// # Registry
+///
+/// `register_story!` submits a type-erased `StoryEntry` to a link-time
+/// registry (the `inventory` crate). The stories index, detail pages,
+/// isolated variants, playground and theme preview all iterate
+/// `all_stories()` / `find_story()`, so adding a feature never requires
+/// editing the admin pages.
+///
+/// # Variants
+///
+/// A story can show several named states of the same component (e.g. a
+/// checkbox that is both checked and required) by overriding
+/// `story_variants`. Each variant gets an anchor on the story page and an
+/// isolated URL at `/admin/features/{name}/{variant}` for iframing and
+/// screenshot testing. The default is a single `"default"` variant built from
+/// `story_fixture`.
 use maud::Markup;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::core::options::BlockOptions;
+
+/// A named set of props shown on a story page
+///
+/// Variant names are URL path segments, so they must be lowercase
+/// kebab-case (`checked-required`).
+#[derive(Debug, Clone)]
+pub struct StoryVariant<T> {
+    pub name: &'static str,
+    pub props: T,
+}
+
+impl<T> StoryVariant<T> {
+    pub fn new(name: &'static str, props: T) -> Self {
+        Self { name, props }
+    }
+}
+
+/// Whether a variant name is a valid URL slug (`[a-z0-9]+(-[a-z0-9]+)*`)
+pub fn is_valid_variant_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.split('-').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        })
+}
 
 /// Trait for component types that can be previewed in the story system
 ///
@@ -75,8 +118,9 @@ use maud::Markup;
 /// - Static methods for metadata (name, description) that don't need an instance
 /// - Instance method for rendering to allow flexibility with props
 /// - Default implementation for additional_stylesheets (most components don't need it)
+/// - Serde bounds let the props playground edit any story's props as JSON
 /// - Follows rust-core-patterns for trait-based abstraction
-pub trait ComponentStory: Sized {
+pub trait ComponentStory: Sized + Serialize + DeserializeOwned {
     /// The story identifier (e.g., "button", "header")
     ///
     /// Used in URLs and for component identification.
@@ -92,11 +136,36 @@ pub trait ComponentStory: Sized {
     /// Returns sample data that demonstrates the component's functionality.
     fn story_fixture() -> Self;
 
+    /// Named variants shown on the story page
+    ///
+    /// The first variant is the primary preview. Defaults to a single
+    /// `"default"` variant built from `story_fixture`.
+    fn story_variants() -> Vec<StoryVariant<Self>> {
+        vec![StoryVariant::new("default", Self::story_fixture())]
+    }
+
     /// Render the component with this instance's data
     ///
     /// Takes self to allow rendering with fixture data or custom props.
     fn render_story(&self) -> Markup;
 
+    /// The `Block` type name this component renders (e.g. `"Hero"`)
+    ///
+    /// Block stories additionally preview every style variant
+    /// (`BlockOptions::variant_previews`). Defaults to `None` for primitives.
+    fn block_type() -> Option<&'static str> {
+        None
+    }
+
+    /// Render the component with block options applied
+    ///
+    /// Block components override this to pass the options to their template.
+    /// The default ignores the options and calls `render_story`.
+    fn render_story_with_options(&self, options: &BlockOptions) -> Markup {
+        let _ = options;
+        self.render_story()
+    }
+
     /// Additional stylesheets beyond the main feature stylesheet
     ///
     /// Convention: All features have `/features/{feature_name}/styles.css`
@@ -108,3 +177,320 @@ pub trait ComponentStory: Sized {
         Vec::new()
     }
 }
+
+/// Rendered output of one named variant
+#[derive(Debug, Clone)]
+pub struct RenderedVariant {
+    pub name: &'static str,
+    pub markup: Markup,
+}
+
+/// Type-erased story registered with `register_story!`
+///
+/// Holds function pointers to the generic `ComponentStory` methods so pages
+/// can work with any story by name. Construct with `StoryEntry::of`.
+pub struct StoryEntry {
+    name: fn() -> &'static str,
+    description: fn() -> &'static str,
+    block_type: fn() -> Option<&'static str>,
+    stylesheets: fn() -> Vec<String>,
+    variants: fn() -> Vec<RenderedVariant>,
+    style_previews: fn() -> Vec<(&'static str, Markup)>,
+    render_json: fn(&str) -> Result<Markup, String>,
+    props_shape: fn() -> Value,
+}
+
+inventory::collect!(StoryEntry);
+
+impl StoryEntry {
+    pub const fn of<T: ComponentStory>() -> Self {
+        Self {
+            name: T::story_name,
+            description: T::story_description,
+            block_type: T::block_type,
+            stylesheets: stylesheets_of::<T>,
+            variants: variants_of::<T>,
+            style_previews: style_previews_of::<T>,
+            render_json: render_json_of::<T>,
+            props_shape: props_shape_of::<T>,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        (self.name)()
+    }
+
+    pub fn description(&self) -> &'static str {
+        (self.description)()
+    }
+
+    /// The `Block` type this story covers, if it is a page block
+    pub fn block_type(&self) -> Option<&'static str> {
+        (self.block_type)()
+    }
+
+    /// Stylesheets in load order
+    ///
+    /// Additional stylesheets come first (e.g., global styles, dependencies)
+    /// and the feature's own stylesheet (convention:
+    /// `/features/{name}/styles.css`) last so it wins the cascade.
+    pub fn stylesheets(&self) -> Vec<String> {
+        (self.stylesheets)()
+    }
+
+    /// Every named variant, rendered
+    pub fn variants(&self) -> Vec<RenderedVariant> {
+        (self.variants)()
+    }
+
+    /// One named variant, rendered
+    pub fn variant(&self, name: &str) -> Option<RenderedVariant> {
+        self.variants().into_iter().find(|v| v.name == name)
+    }
+
+    /// The primary variant once per block style (empty for non-blocks)
+    pub fn style_previews(&self) -> Vec<(&'static str, Markup)> {
+        (self.style_previews)()
+    }
+
+    /// Deserialize props JSON into the story's type and render it
+    ///
+    /// Errors include the path to the offending field (e.g.
+    /// `button.text: invalid type: ...`).
+    pub fn render_json(&self, json: &str) -> Result<Markup, String> {
+        (self.render_json)(json)
+    }
+
+    /// The editable props shape
+    ///
+    /// Values from the first variant, plus `null` for every field that only
+    /// appears in other variants.
+    pub fn props_shape(&self) -> Value {
+        (self.props_shape)()
+    }
+}
+
+/// Register a `ComponentStory` implementation with the story registry
+///
+/// ```rust
+/// register_story!(HeroProps);
+/// ```
+macro_rules! register_story {
+    ($props:ty) => {
+        inventory::submit! {
+            $crate::features::story::StoryEntry::of::<$props>()
+        }
+    };
+}
+pub(crate) use register_story;
+
+/// All registered stories, sorted by name
+pub fn all_stories() -> Vec<&'static StoryEntry> {
+    let mut stories: Vec<&'static StoryEntry> = inventory::iter::<StoryEntry>.into_iter().collect();
+    stories.sort_by_key(|story| story.name());
+    stories
+}
+
+/// Look up a registered story by name
+pub fn find_story(name: &str) -> Option<&'static StoryEntry> {
+    inventory::iter::<StoryEntry>
+        .into_iter()
+        .find(|story| story.name() == name)
+}
+
+fn stylesheets_of<T: ComponentStory>() -> Vec<String> {
+    let mut stylesheets: Vec<String> = T::additional_stylesheets()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    stylesheets.push(format!("/features/{}/styles.css", T::story_name()));
+    stylesheets
+}
+
+fn variants_of<T: ComponentStory>() -> Vec<RenderedVariant> {
+    T::story_variants()
+        .into_iter()
+        .map(|variant| RenderedVariant {
+            name: variant.name,
+            markup: variant.props.render_story(),
+        })
+        .collect()
+}
+
+fn style_previews_of<T: ComponentStory>() -> Vec<(&'static str, Markup)> {
+    if T::block_type().is_none() {
+        return Vec::new();
+    }
+    let Some(primary) = T::story_variants().into_iter().next() else {
+        return Vec::new();
+    };
+    BlockOptions::variant_previews()
+        .into_iter()
+        .map(|(style, options)| (style, primary.props.render_story_with_options(&options)))
+        .collect()
+}
+
+fn render_json_of<T: ComponentStory>(json: &str) -> Result<Markup, String> {
+    let deserializer = &mut serde_json::Deserializer::from_str(json);
+    let props: T = serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let path = e.path().to_string();
+        if path == "." {
+            e.inner().to_string()
+        } else {
+            format!("{}: {}", path, e.inner())
+        }
+    })?;
+    Ok(props.render_story())
+}
+
+fn props_shape_of<T: ComponentStory>() -> Value {
+    let mut variants = T::story_variants()
+        .into_iter()
+        .filter_map(|variant| serde_json::to_value(&variant.props).ok());
+    let Some(mut shape) = variants.next() else {
+        return Value::Object(Map::new());
+    };
+    for other in variants {
+        add_missing_fields(&mut shape, &other);
+    }
+    shape
+}
+
+/// Add fields present in `other` but missing from `shape` as `null`
+fn add_missing_fields(shape: &mut Value, other: &Value) {
+    let (Value::Object(shape), Value::Object(other)) = (shape, other) else {
+        return;
+    };
+    for (key, value) in other {
+        match shape.get_mut(key) {
+            Some(existing) => add_missing_fields(existing, value),
+            None => {
+                shape.insert(key.clone(), Value::Null);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::block::Block;
+    use crate::features::header::HeaderProps;
+    use crate::features::input::InputProps;
+    use strum::VariantNames;
+
+    #[test]
+    fn test_variant_name_validation() {
+        assert!(is_valid_variant_name("default"));
+        assert!(is_valid_variant_name("checked-required"));
+        assert!(is_valid_variant_name("h2"));
+        for name in [
+            "",
+            "Default",
+            "with space",
+            "-leading",
+            "trailing-",
+            "a--b",
+            "../x",
+        ] {
+            assert!(!is_valid_variant_name(name), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_story_variants_are_unique_slugs() {
+        for story in all_stories() {
+            let variants = story.variants();
+            assert!(!variants.is_empty(), "{} has no variants", story.name());
+
+            let mut seen = Vec::new();
+            for variant in &variants {
+                assert!(
+                    is_valid_variant_name(variant.name),
+                    "{}: invalid variant name {:?}",
+                    story.name(),
+                    variant.name
+                );
+                assert!(
+                    !seen.contains(&variant.name),
+                    "{}: duplicate variant {:?}",
+                    story.name(),
+                    variant.name
+                );
+                seen.push(variant.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_story_names_are_unique() {
+        let names: Vec<_> = all_stories().iter().map(|story| story.name()).collect();
+        let mut deduped = names.clone();
+        deduped.dedup();
+        assert_eq!(names, deduped);
+    }
+
+    #[test]
+    fn test_every_block_type_has_a_story() {
+        let stories = all_stories();
+        for block_type in Block::VARIANTS {
+            let story = stories
+                .iter()
+                .find(|story| story.block_type() == Some(*block_type))
+                .unwrap_or_else(|| panic!("Block::{} has no registered story", block_type));
+
+            // The story's props must be valid props for that block type
+            let block: Block = serde_json::from_value(serde_json::json!({
+                "type": block_type,
+                "props": story.props_shape(),
+            }))
+            .unwrap_or_else(|e| {
+                panic!(
+                    "{} story props aren't a valid {}: {}",
+                    story.name(),
+                    block_type,
+                    e
+                )
+            });
+            assert_eq!(block.type_name(), *block_type);
+        }
+    }
+
+    #[test]
+    fn test_block_stories_have_style_previews() {
+        for story in all_stories() {
+            assert_eq!(
+                story.style_previews().is_empty(),
+                story.block_type().is_none(),
+                "{}",
+                story.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_render_json_reports_field_path() {
+        let header = find_story("header").unwrap();
+        let json = r#"{
+            "headline": "Hi",
+            "button": { "href": "/go", "text": 3, "aria_label": "Go" }
+        }"#;
+        let error = header.render_json(json).unwrap_err();
+        assert!(error.starts_with("button.text: invalid type"), "{}", error);
+
+        let error = header.render_json("{not json").unwrap_err();
+        assert!(error.contains("line 1"), "{}", error);
+
+        let json = serde_json::to_string(&HeaderProps::story_fixture()).unwrap();
+        assert!(header.render_json(&json).is_ok());
+    }
+
+    #[test]
+    fn test_shape_includes_fields_from_other_variants() {
+        let shape = StoryEntry::of::<InputProps>().props_shape();
+        assert_eq!(shape["label"], "Email Address");
+        assert_eq!(shape["required"], true);
+        // Only the "error" variant sets an error; it becomes an empty optional field
+        assert_eq!(shape["error"], Value::Null);
+    }
+}
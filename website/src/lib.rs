@@ -0,0 +1,43 @@
+/// Eng Manager Website - Feature-based Architecture
+///
+/// This application demonstrates a production-quality feature-based architecture
+/// for Axum + Maud web applications. Key principles:
+///
+/// - **Feature-based organization**: Code organized by feature, not layer
+/// - **Separation of concerns**: Templates, styles, and scripts in separate files
+/// - **Type-safe blocks**: Content composition using enum variants
+/// - **Clean module boundaries**: Core, features, and pages are independent
+///
+/// # Architecture
+///
+/// ```
+/// src/
+/// ├── core/           # Shared types and operations
+/// │   ├── block.rs    # Block enum and props
+/// │   ├── persistence.rs # JSON file operations
+/// │   └── render.rs   # Render trait
+/// ├── features/       # Feature modules (vertical slices)
+/// │   ├── header/     # Header component
+/// │   ├── hero/       # Hero component
+/// │   └── admin/      # Admin interface
+/// ├── pages/          # Route handlers
+/// │   └── homepage.rs # Homepage composition
+/// ├── app.rs          # AppState and router
+/// ├── cli.rs          # Maintenance commands (export, import, migrate)
+/// ├── lib.rs          # Library crate root
+/// └── main.rs         # Server binary
+/// ```
+///
+/// # Skills Applied
+///
+/// - **axum-web-framework**: Router setup, asset serving, state management
+/// - **maud-axum-integration**: IntoResponse, templates, layouts
+/// - **maud-components-patterns**: Render trait, component composition
+/// - **rust-core-patterns**: Type-safe domain modeling with enums
+pub mod app;
+pub mod cli;
+pub mod core;
+pub mod features;
+pub mod pages;
+
+pub use app::{AppState, app, spawn_scheduler};
@@ -1,76 +1,57 @@
-/// Eng Manager Website - Feature-based Architecture
+/// Eng Manager Website server binary
 ///
-/// This application demonstrates a production-quality feature-based architecture
-/// for Axum + Maud web applications. Key principles:
-///
-/// - **Feature-based organization**: Code organized by feature, not layer
-/// - **Separation of concerns**: Templates, styles, and scripts in separate files
-/// - **Type-safe blocks**: Content composition using enum variants
-/// - **Clean module boundaries**: Core, features, and pages are independent
-///
-/// # Architecture
-///
-/// ```
-/// src/
-/// ├── core/           # Shared types and operations
-/// │   ├── block.rs    # Block enum and props
-/// │   ├── persistence.rs # JSON file operations
-/// │   └── render.rs   # Render trait
-/// ├── features/       # Feature modules (vertical slices)
-/// │   ├── header/     # Header component
-/// │   ├── hero/       # Hero component
-/// │   └── admin/      # Admin interface
-/// ├── pages/          # Route handlers
-/// │   └── homepage.rs # Homepage composition
-/// └── main.rs         # App setup, router, server
-/// ```
+/// Serves the router from `website::app` with the default `AppState`
+/// (workspace data directory and this package's assets). Maintenance
+/// commands such as `export` and `import` are dispatched to `website::cli`.
+/// The scheduled publishing task runs alongside the server.
 ///
 /// # Skills Applied
 ///
-/// - **axum-web-framework**: Router setup, asset serving, state management
-/// - **maud-axum-integration**: IntoResponse, templates, layouts
-/// - **maud-components-patterns**: Render trait, component composition
-/// - **rust-core-patterns**: Type-safe domain modeling with enums
-use axum::{routing::get, routing::post, Router};
+/// - **axum-web-framework**: Server startup, host and port configuration
 use std::net::SocketAddr;
 use tokio::net::TcpListener;
-use tower_http::services::ServeDir;
 
+use website::cli::{self, Command};
+use website::core::limits::RequestLimits;
+use website::{AppState, app, spawn_scheduler};
 
-// Module declarations
-mod core;
-mod features;
-mod pages;
 // Server configuration constants
 const PORT_ENV_VAR: &str = "PORT";
 const DEFAULT_PORT: u16 = 3000;
 const PRODUCTION_HOST: [u8; 4] = [0, 0, 0, 0]; // 0.0.0.0 - accept external connections
 const DEV_HOST: [u8; 4] = [127, 0, 0, 1]; // 127.0.0.1 - localhost only
 
-// Asset serving paths
-const ASSETS_DIR: &str = "website/assets";
-const FEATURES_DIR: &str = "website/src/features";
-
 #[tokio::main]
 async fn main() {
-    // Build application with routes
-    // Following axum-web-framework patterns for router composition
-    let app = Router::new()
-        // Public pages
-        .route("/", get(pages::homepage))
-        .route("/health", get(|| async { "OK" }))
-        // Admin pages (route handlers in pages::admin)
-        .route("/admin", get(pages::admin::admin_index))
-        .route("/admin/route/", get(pages::admin::admin_route_index))
-        .route("/admin/route/{name}/", get(pages::admin::admin_route_page))
-        // Admin features (component story system)
-        .route("/admin/features/", get(pages::admin::features_index))
-        .route("/admin/features/{name}/", get(pages::admin::feature_story))
-        // Admin API endpoints
-        .route("/admin/api/homepage", post(pages::admin::update_homepage))
-        .route("/admin/api/{route_name}", post(pages::admin::update_route))
-        .nest_service("/assets", ServeDir::new(ASSETS_DIR))
-        .nest_service("/features", ServeDir::new(FEATURES_DIR));
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match Command::parse(&args) {
+        Ok(Command::Serve) => {}
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            return;
+        }
+        Ok(command) => {
+            if let Err(e) = cli::run(command) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    }
+
+    // Build application with routes (see app.rs)
+    // Render.io's proxy sets X-Forwarded-For; clients can't reach the port directly
+    let limits = RequestLimits {
+        trust_forwarded_for: std::env::var(PORT_ENV_VAR).is_ok(),
+        ..RequestLimits::default()
+    };
+    let state = AppState::default().with_limits(limits);
+    spawn_scheduler(&state);
+    let app = app(state);
 
     // Get port from environment (Render.io sets PORT) or use default for dev
     let port = std::env::var(PORT_ENV_VAR)
@@ -97,7 +78,12 @@ async fn main() {
         }
     };
 
-    if let Err(e) = axum::serve(listener, app.into_make_service()).await {
+    if let Err(e) = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    {
         eprintln!("Server error: {}", e);
         std::process::exit(1);
     }
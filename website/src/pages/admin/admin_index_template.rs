@@ -10,6 +10,14 @@
 /// - "ADMIN" heading
 /// - "Routes" link to /admin/route/
 /// - "Features" link to /admin/features/
+/// - "Media" link to /admin/media/
+/// - "Settings" link to /admin/settings/
+/// - "Schedule" link to /admin/schedule/
+/// - "Audit Log" link to /admin/audit/
+/// - "Users" link to /admin/users/
+///
+/// Links the user may not use are left out (see `core::users::Permission`),
+/// and a signed-in user gets a sign-out button.
 ///
 /// # Asset References
 ///
@@ -19,17 +27,32 @@
 /// The stylesheet is loaded in the page <head>, not inline with the component.
 use maud::{Markup, html};
 
+use crate::core::users::Permission;
+use crate::pages::admin::auth::{CurrentUser, render_account_bar};
+
 /// Render the Admin Index component
 ///
 /// This is a pure function that returns Markup for the admin index page.
 /// The component displays a centered layout with a black circle, heading, and links.
-pub fn render_admin_index() -> Markup {
+pub fn render_admin_index(user: &CurrentUser) -> Markup {
+    let admin = user.can(Permission::Administer);
     html! {
+        (render_account_bar(user))
         div class="admin-index" {
             div class="admin-index__circle" {}
             h1 class="admin-index__heading" { "ADMIN" }
             a class="admin-index__link" href="/admin/route/" { "Routes" }
             a class="admin-index__link" href="/admin/features/" { "Features" }
+            a class="admin-index__link" href="/admin/media/" { "Media" }
+            @if admin {
+                a class="admin-index__link" href="/admin/settings/" { "Settings" }
+            }
+            a class="admin-index__link" href="/admin/schedule/" { "Schedule" }
+            @if admin {
+                a class="admin-index__link" href="/admin/audit/" { "Audit Log" }
+                a class="admin-index__link" href="/admin/users/" { "Users" }
+            }
+            a class="admin-index__link" href="/admin/tokens/" { "API Tokens" }
         }
     }
 }
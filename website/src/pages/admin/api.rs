@@ -9,11 +9,130 @@ use axum::Json;
 /// - Failures return Result<T, String> which Axum maps to 500 Internal Server Error
 ///
 /// In production, this should use proper error types with IntoResponse.
-use axum::extract::Path;
-use uuid::Uuid;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
-use crate::core::{BlockWithId, save_blocks};
-use crate::pages::homepage::HomepageData;
+use crate::app::AppState;
+use crate::core::a11y::{BlockIssue, lint_blocks};
+use crate::core::audit::{AuditAction, hash_file, record, record_file_changes};
+use crate::core::block::{BlockWithId, ContentBlock, assign_block_ids};
+use crate::core::i18n::localized_files;
+use crate::core::limits::{LimitError, RequestLimits};
+use crate::core::options::block_options_schema;
+use crate::core::persistence::{
+    ContentError, ContentFile, content_file_path, get_content_ids, load_content_files_in,
+    save_content_files,
+};
+use crate::core::presence::{Presence, PresenceEvent};
+use crate::core::schedule::{ScheduleError, draft_path, schedule_draft};
+use crate::core::settings::load_settings;
+use crate::core::users::Permission;
+use crate::core::{load_content, load_routes};
+use crate::pages::admin::AuditContext;
+use crate::pages::admin::auth::{CurrentUser, Forbidden};
+use crate::pages::admin::presence::EDITOR_SESSION_HEADER;
+
+/// Request body for saving a route's content
+///
+/// Either `blocks`, for a route with a single content file, or `files`, one
+/// entry per content file as the page editor sends it. Other fields (such
+/// as `version`) are ignored; saved files always get the current version.
+///
+/// `locale` selects which locale's variant of the content files to write
+/// (see `core::i18n`); without it the default locale's files are written.
+/// A future `publish_at` queues the save as a scheduled draft instead (see
+/// `core::schedule`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publish_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocks: Option<Vec<ContentBlock>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<Vec<ContentFile>>,
+}
+
+impl SaveRequest {
+    /// Every submitted block, in page order
+    pub fn all_blocks(&self) -> impl Iterator<Item = &ContentBlock> {
+        let files = self.files.iter().flatten().flat_map(|file| &file.blocks);
+        self.blocks.iter().flatten().chain(files)
+    }
+}
+
+/// Query parameters for `GET /admin/api/:route_name`
+#[derive(Debug, Default, Deserialize)]
+pub struct ReadQuery {
+    /// Locale to read; the default locale when absent
+    pub locale: Option<String>,
+}
+
+/// GET /admin/api/:route_name
+///
+/// Returns the route's content in the shape `POST` accepts, one entry per
+/// content file, so a script can read, change and save it back:
+///
+/// ```json
+/// { "files": [{ "source": "homepage", "blocks": [ ... ] }] }
+/// ```
+///
+/// With `?locale=de` the translation is returned (with `"locale": "de"`);
+/// files not translated yet hold the default locale's blocks, as in the
+/// page editor.
+///
+/// # Response
+///
+/// - **200 OK**: The content
+/// - **403 Forbidden**: The user (or token) may not view content
+/// - **404 Not Found**: The route isn't in routes.json, or `locale` isn't
+///   configured
+pub async fn read_route(
+    user: CurrentUser,
+    Path(route_name): Path<String>,
+    Query(query): Query<ReadQuery>,
+) -> Result<Json<SaveRequest>, (StatusCode, String)> {
+    user.require(Permission::View).map_err(forbidden)?;
+    if !load_routes().iter().any(|route| route.name == route_name) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            format!("Route '{}' not found in routes.json", route_name),
+        ));
+    }
+
+    let i18n = load_settings().i18n;
+    let locale = match &query.locale {
+        Some(tag) => i18n
+            .variant(tag)
+            .map_err(|message| (StatusCode::NOT_FOUND, message))?,
+        None => None,
+    };
+    let files = match locale {
+        None => load_content_files_in(&route_name, None),
+        Some(locale) => localized_files(&route_name, locale)
+            .into_iter()
+            .map(|(file, _)| file)
+            .collect(),
+    };
+    Ok(Json(SaveRequest {
+        locale: locale.map(str::to_string),
+        files: Some(files),
+        ..Default::default()
+    }))
+}
+
+/// GET /admin/api/homepage
+///
+/// Same as `GET /admin/api/:route_name` for the homepage.
+pub async fn read_homepage(
+    user: CurrentUser,
+    query: Query<ReadQuery>,
+) -> Result<Json<SaveRequest>, (StatusCode, String)> {
+    read_route(user, Path("homepage".to_string()), query).await
+}
 
 /// POST /admin/api/:route_name
 ///
@@ -38,33 +157,306 @@ use crate::pages::homepage::HomepageData;
 /// }
 /// ```
 ///
-/// If a block's `id` is empty or missing, a new UUID v4 will be generated.
+/// Routes with several content files in `blockIds` are saved per file; each
+/// `source` must be one of the route's `blockIds`, and files left out are
+/// not changed:
+///
+/// ```json
+/// {
+///   "files": [
+///     { "source": "../data/content/header.json", "blocks": [ ... ] },
+///     { "source": "../data/content/foo.json", "blocks": [ ... ] }
+///   ]
+/// }
+/// ```
+///
+/// If a block's `id` is empty, missing, or repeats an earlier block's id
+/// (across all files), a new UUID v4 will be generated (see
+/// `assign_block_ids`).
+///
+/// Add `"locale": "de"` to save a translation: each file's `de` variant is
+/// written instead (e.g. `homepage.de.json`).
+///
+/// Add `"publish_at": "2026-03-01T09:00:00Z"` to schedule the save: nothing
+/// is written to the content files until the scheduler publishes it. A
+/// `publish_at` that has already passed publishes immediately.
+///
+/// Every file written is recorded in the audit log with its hash before and
+/// after the save; a scheduled save records the draft instead (see
+/// `core::audit`). A published save is pushed to the route's open editors
+/// and previews (see `core::presence`).
+///
+/// # Invalid Blocks
+///
+/// Blocks that fail to parse are accepted only if they are stored unchanged
+/// in the route's current content (in the default locale or in `locale`).
+/// This lets the editor re-save a page that contains an unknown or broken
+/// block without losing it, while new or edited blocks must still be valid.
+///
+/// # Permissions
+///
+/// Saving needs Edit on the route; a future `publish_at` needs Publish on it
+/// too (see `core::users`).
+///
+/// # Limits
+///
+/// A save may hold at most `RequestLimits::max_blocks_per_route` blocks
+/// across its files, and no string longer than `max_string_chars` (see
+/// `core::limits`). Larger saves are refused before anything is checked or
+/// written.
 ///
 /// # Response
 ///
-/// - **200 OK**: "Route updated successfully"
+/// - **200 OK**: "Route updated successfully", or when it is scheduled
+/// - **400 Bad Request**: A block's `schedule` ends before it starts
+/// - **403 Forbidden**: The user may not edit the route, or schedule on it
+/// - **404 Not Found**: The route isn't in routes.json
+/// - **413 Payload Too Large**: The body, block count or a string is over
+///   its limit
+/// - **422 Unprocessable Entity**: A block is invalid and isn't stored as-is,
+///   a `source` isn't one of the route's files, `locale` isn't configured, or
+///   `blocks` was sent for a route with several content files
 /// - **500 Internal Server Error**: Error message describing the failure
 pub async fn update_route(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    audit: AuditContext,
     Path(route_name): Path<String>,
-    Json(data): Json<HomepageData>,
-) -> Result<String, String> {
-    // Generate UUIDs for blocks that don't have IDs
-    let blocks_with_ids: Vec<BlockWithId> = data
-        .blocks
+    headers: HeaderMap,
+    Json(request): Json<SaveRequest>,
+) -> Result<String, (StatusCode, String)> {
+    user.require_on_route(Permission::Edit, &route_name)
+        .map_err(forbidden)?;
+    if request
+        .publish_at
+        .is_some_and(|publish_at| publish_at > state.clock.now())
+    {
+        user.require_on_route(Permission::Publish, &route_name)
+            .map_err(forbidden)?;
+    }
+    check_limits(&state.limits, &request)
+        .map_err(|e| (StatusCode::PAYLOAD_TOO_LARGE, e.to_string()))?;
+
+    let i18n = load_settings().i18n;
+    let locale = match &request.locale {
+        Some(tag) => i18n
+            .variant(tag)
+            .map_err(|message| (StatusCode::UNPROCESSABLE_ENTITY, message))?,
+        None => None,
+    };
+
+    check_invalid_blocks(&route_name, locale, request.all_blocks())
+        .map_err(|message| (StatusCode::UNPROCESSABLE_ENTITY, message))?;
+    for block in request.all_blocks().filter_map(ContentBlock::as_valid) {
+        block.schedule.validate().map_err(|message| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Block {}: {}", block.id, message),
+            )
+        })?;
+    }
+
+    // Generate UUIDs for blocks with missing or duplicate IDs
+    let files = match (request.blocks, request.files) {
+        (Some(blocks), None) => {
+            let source = single_content_file(&route_name).map_err(save_error)?;
+            vec![ContentFile {
+                source,
+                blocks: assign_block_ids(blocks),
+            }]
+        }
+        (None, Some(files)) => assign_file_block_ids(files),
+        _ => {
+            return Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "Expected either \"blocks\" or \"files\"".to_string(),
+            ));
+        }
+    };
+
+    match request.publish_at {
+        Some(publish_at) if publish_at > state.clock.now() => {
+            let draft =
+                schedule_draft(&route_name, locale, publish_at, files).map_err(|e| match e {
+                    ScheduleError::Content(e) => save_error(e),
+                    e => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+                })?;
+            let after = draft_path(&draft.id).and_then(|path| hash_file(&path));
+            record(
+                &audit
+                    .entry(AuditAction::ContentSchedule)
+                    .route(&route_name)
+                    .target(&draft.id)
+                    .hashes(None, after),
+            );
+            Ok(format!(
+                "{} scheduled for {}",
+                route_name,
+                publish_at.format("%Y-%m-%d %H:%M UTC")
+            ))
+        }
+        _ => {
+            // A file that doesn't resolve fails the save below
+            let paths: Vec<_> = files
+                .iter()
+                .filter_map(|file| content_file_path(&file.source, locale).ok())
+                .collect();
+            let entry = audit.entry(AuditAction::ContentSave).route(&route_name);
+            record_file_changes(&entry, &paths, || {
+                save_content_files(&route_name, &files, locale)
+            })
+            .map_err(save_error)?;
+            announce_publish(
+                &state.presence,
+                &route_name,
+                &headers,
+                &audit,
+                locale,
+                files,
+            );
+            Ok(format!("{} updated successfully", route_name))
+        }
+    }
+}
+
+fn forbidden(forbidden: Forbidden) -> (StatusCode, String) {
+    (StatusCode::FORBIDDEN, format!("Forbidden: {}", forbidden.0))
+}
+
+/// Tell the route's open editors and previews about a publish
+///
+/// The publishing editor sends its presence session in `X-Editor-Session`,
+/// so the event carries its name and it can ignore its own publish.
+fn announce_publish(
+    presence: &Presence,
+    route_name: &str,
+    headers: &HeaderMap,
+    audit: &AuditContext,
+    locale: Option<&str>,
+    files: Vec<ContentFile>,
+) {
+    let session = headers
+        .get(EDITOR_SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let by = session
+        .as_ref()
+        .and_then(|session| {
+            presence
+                .editors(route_name)
+                .into_iter()
+                .find(|editor| editor.session == *session)
+        })
+        .map(|editor| editor.name)
+        .unwrap_or_else(|| audit.actor.clone());
+    presence.broadcast(
+        route_name,
+        PresenceEvent::Published {
+            by,
+            session,
+            locale: locale.map(str::to_string),
+            files,
+        },
+    );
+}
+
+/// Check a save against the block count and string length limits
+fn check_limits(limits: &RequestLimits, request: &SaveRequest) -> Result<(), LimitError> {
+    let content = serde_json::to_value(request).unwrap_or_default();
+    limits.check_content(request.all_blocks().count(), &content)
+}
+
+/// The only content file of a route, for a save that sends `blocks`
+fn single_content_file(route_name: &str) -> Result<String, ContentError> {
+    let mut ids = get_content_ids(route_name)?;
+    match ids.len() {
+        1 => Ok(ids.remove(0)),
+        count => Err(ContentError::MultipleContentFiles {
+            route: route_name.to_string(),
+            count,
+        }),
+    }
+}
+
+/// Map a content error to a status code and message
+fn save_error(error: ContentError) -> (StatusCode, String) {
+    let status = match error {
+        ContentError::UnknownRoute(_) => StatusCode::NOT_FOUND,
+        ContentError::NoContentFiles(_)
+        | ContentError::MultipleContentFiles { .. }
+        | ContentError::UnknownContentFile { .. }
+        | ContentError::DuplicateContentFile(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        // routes.json lists an id that doesn't resolve inside the content directory
+        ContentError::InvalidContentId(_)
+        | ContentError::OutsideContentRoot(_)
+        | ContentError::Json(_)
+        | ContentError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, format!("Failed to save: {}", error))
+}
+
+/// Assign block IDs across all files at once, so IDs are unique per route
+fn assign_file_block_ids(files: Vec<ContentFile>) -> Vec<ContentFile> {
+    let lengths: Vec<usize> = files.iter().map(|file| file.blocks.len()).collect();
+    let mut sources = Vec::with_capacity(files.len());
+    let mut blocks = Vec::new();
+    for file in files {
+        sources.push(file.source);
+        blocks.extend(file.blocks);
+    }
+
+    let mut assigned = assign_block_ids(blocks).into_iter();
+    sources
         .into_iter()
-        .map(|mut block| {
-            // If the ID is empty or missing, generate a new UUID
-            if block.id.trim().is_empty() {
-                block.id = Uuid::new_v4().to_string();
-            }
-            block
+        .zip(lengths)
+        .map(|(source, len)| ContentFile {
+            source,
+            blocks: assigned.by_ref().take(len).collect(),
+        })
+        .collect()
+}
+
+/// Reject invalid blocks that aren't already stored, unchanged, for the route
+///
+/// Blocks stored in the default locale count too: a new translation starts
+/// as a copy of the default locale's content.
+fn check_invalid_blocks<'a>(
+    route_name: &str,
+    locale: Option<&str>,
+    blocks: impl IntoIterator<Item = &'a ContentBlock>,
+) -> Result<(), String> {
+    let submitted: Vec<_> = blocks
+        .into_iter()
+        .filter_map(|block| match block {
+            ContentBlock::Invalid(invalid) => Some(invalid),
+            ContentBlock::Valid(_) => None,
         })
         .collect();
+    if submitted.is_empty() {
+        return Ok(());
+    }
 
-    match save_blocks(&route_name, &blocks_with_ids) {
-        Ok(_) => Ok(format!("{} updated successfully", route_name)),
-        Err(e) => Err(format!("Failed to save: {}", e)),
+    let mut stored = load_content(route_name);
+    if locale.is_some() {
+        stored.extend(
+            load_content_files_in(route_name, locale)
+                .into_iter()
+                .flat_map(|file| file.blocks),
+        );
+    }
+    for invalid in submitted {
+        let unchanged = stored.iter().any(
+            |block| matches!(block, ContentBlock::Invalid(stored) if stored.raw == invalid.raw),
+        );
+        if !unchanged {
+            return Err(format!(
+                "Invalid block {}: {}",
+                invalid.id().unwrap_or("(no id)"),
+                invalid.error
+            ));
+        }
     }
+    Ok(())
 }
 
 /// POST /admin/api/homepage
@@ -90,7 +482,67 @@ pub async fn update_route(
 /// # Response
 ///
 /// - **200 OK**: "homepage updated successfully"
+/// - **403 Forbidden**: Same as `update_route`
+/// - **413 Payload Too Large**: Same as `update_route`
+/// - **422 Unprocessable Entity**: Same as `update_route`
 /// - **500 Internal Server Error**: Error message describing the failure
-pub async fn update_homepage(Json(data): Json<HomepageData>) -> Result<String, String> {
-    update_route(Path("homepage".to_string()), Json(data)).await
+pub async fn update_homepage(
+    state: State<AppState>,
+    user: CurrentUser,
+    audit: AuditContext,
+    headers: HeaderMap,
+    Json(request): Json<SaveRequest>,
+) -> Result<String, (StatusCode, String)> {
+    update_route(
+        state,
+        user,
+        audit,
+        Path("homepage".to_string()),
+        headers,
+        Json(request),
+    )
+    .await
+}
+
+/// GET /admin/api/schema/block-options
+///
+/// Returns the JSON Schema for the per-block `options` envelope, generated
+/// from the Rust option types so it always matches what the API accepts.
+pub async fn block_options_schema_json(
+    user: CurrentUser,
+) -> Result<Json<serde_json::Value>, Forbidden> {
+    user.require(Permission::View)?;
+    Ok(Json(block_options_schema()))
+}
+
+/// POST /admin/api/a11y
+///
+/// Runs the accessibility linter over unsaved blocks, rendered as they would
+/// be published. The editor calls this before publishing and shows the
+/// result in its warnings panel. Warnings never block saving. Invalid
+/// blocks aren't rendered, so they aren't linted.
+///
+/// # Request Body
+///
+/// Same shape as `POST /admin/api/:route_name`.
+///
+/// # Response
+///
+/// ```json
+/// [
+///   { "block_id": "550e...", "rule": "empty_link", "message": "<a href=\"/x\"> has no text or accessible name" },
+///   { "rule": "duplicate_id", "message": "id \"about\" is used by 2 elements" }
+/// ]
+/// ```
+pub async fn check_accessibility(
+    user: CurrentUser,
+    Json(request): Json<SaveRequest>,
+) -> Result<Json<Vec<BlockIssue>>, Forbidden> {
+    user.require(Permission::View)?;
+    let blocks: Vec<BlockWithId> = request
+        .all_blocks()
+        .filter_map(ContentBlock::as_valid)
+        .cloned()
+        .collect();
+    Ok(Json(lint_blocks(&blocks)))
 }
@@ -0,0 +1,145 @@
+/// Site export/import API
+///
+/// HTTP access to `core::archive` so a site can be downloaded from one
+/// environment and uploaded to another. The same operations are available
+/// from the command line (`website export` / `website import`, see `cli`).
+///
+/// # Routes
+///
+/// - `GET /admin/api/export` - Download the whole site as a `.tar.gz` archive
+/// - `POST /admin/api/import` - Import an archive (raw request body)
+///
+/// # Import Dry Run
+///
+/// `POST /admin/api/import?dry_run=true` validates the archive and returns
+/// the `ImportReport` of what would be created, overwritten or deleted
+/// without writing anything. Callers are expected to show that report and
+/// repeat the request without `dry_run` to apply it.
+///
+/// An applied import is recorded in the audit log once, with the hash of
+/// the archive as its `after` hash; it replaces too many files to hash each.
+///
+/// Both routes are for admins only (403 Forbidden for other roles).
+///
+/// Archive work is blocking file I/O plus (de)compression, so it runs inside
+/// `tokio::task::spawn_blocking` with the request's data directory.
+use axum::Json;
+use axum::body::Bytes;
+use axum::extract::Query;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::core::archive::{ArchiveError, export_site, import_site};
+use crate::core::audit::{AuditAction, hash_bytes, record};
+use crate::core::persistence::{data_dir, with_data_dir_sync};
+use crate::core::users::Permission;
+use crate::pages::admin::{AuditContext, CurrentUser};
+
+/// Query parameters for `POST /admin/api/import`
+#[derive(Debug, Default, Deserialize)]
+pub struct ImportQuery {
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Route handler: GET /admin/api/export
+///
+/// # Response
+///
+/// - **200 OK**: `application/gzip` attachment named
+///   `site-export-{timestamp}.tar.gz`
+/// - **500 Internal Server Error**: Reading the data directory failed
+pub async fn export_archive(user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    let dir = data_dir();
+    let result = tokio::task::spawn_blocking(move || {
+        with_data_dir_sync(dir, || {
+            let mut bytes = Vec::new();
+            export_site(&mut bytes).map(|_| bytes)
+        })
+    })
+    .await;
+
+    match result {
+        Ok(Ok(bytes)) => {
+            let filename = format!("site-export-{}.tar.gz", Utc::now().format("%Y%m%dT%H%M%SZ"));
+            (
+                [
+                    (header::CONTENT_TYPE, "application/gzip".to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        format!("attachment; filename=\"{}\"", filename),
+                    ),
+                ],
+                bytes,
+            )
+                .into_response()
+        }
+        Ok(Err(e)) => archive_error_response(e),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Export failed: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+/// Route handler: POST /admin/api/import
+///
+/// # Response
+///
+/// - **200 OK**: `ImportReport` JSON (`dry_run` echoes the query)
+/// - **400 Bad Request**: Malformed archive, unexpected files or invalid JSON
+/// - **422 Unprocessable Entity**: Archive from a newer format version
+/// - **500 Internal Server Error**: Writing the data directory failed
+pub async fn import_archive(
+    user: CurrentUser,
+    audit: AuditContext,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    let archive_hash = hash_bytes(&body);
+    let dir = data_dir();
+    let result = tokio::task::spawn_blocking(move || {
+        with_data_dir_sync(dir, || import_site(body.as_ref(), query.dry_run))
+    })
+    .await;
+
+    match result {
+        Ok(Ok(report)) => {
+            if !query.dry_run {
+                record(
+                    &audit
+                        .entry(AuditAction::SiteImport)
+                        .hashes(None, Some(archive_hash)),
+                );
+            }
+            Json(report).into_response()
+        }
+        Ok(Err(e)) => archive_error_response(e),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Import failed: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+/// Map an `ArchiveError` to an HTTP response
+fn archive_error_response(error: ArchiveError) -> Response {
+    let status = match &error {
+        ArchiveError::Invalid(_)
+        | ArchiveError::UnexpectedEntry(_)
+        | ArchiveError::InvalidJson { .. } => StatusCode::BAD_REQUEST,
+        ArchiveError::UnsupportedVersion(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        ArchiveError::Content(_) | ArchiveError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, error.to_string()).into_response()
+}
@@ -0,0 +1,309 @@
+/// Audit log viewer and export
+///
+/// Shows the append-only log of admin actions (see `core::audit`) and lets
+/// it be filtered by actor, action, route and date range.
+///
+/// # Routes
+///
+/// - `GET /admin/audit/` - Filterable table of audit entries, newest first
+/// - `GET /admin/api/audit/export` - The matching entries as JSON Lines
+///
+/// Both take the same query parameters: `actor`, `action`, `route`, `since`
+/// and `until` (`YYYY-MM-DD`, inclusive). Empty parameters are ignored, so
+/// the filter form can submit every field.
+///
+/// # Recording Actions
+///
+/// Mutating handlers take an `AuditContext` to learn who made the request,
+/// which request it was and when, and pass an entry built from it to
+/// `core::audit`. The actor is the signed-in user (see `auth`), or
+/// `anonymous` while the admin is open.
+use axum::extract::{FromRequestParts, Query};
+use axum::http::request::Parts;
+use axum::http::{StatusCode, header};
+use axum::response::{Html, IntoResponse, Response};
+use chrono::{DateTime, NaiveDate, Utc};
+use maud::{Markup, html};
+use serde::Deserialize;
+use strum::IntoEnumIterator;
+
+use crate::app::{AppState, RequestId};
+use crate::core::audit::{ANONYMOUS, AuditAction, AuditEntry, AuditFilter, read_entries, to_jsonl};
+use crate::core::security_headers::csp_nonce_meta;
+use crate::core::users::Permission;
+use crate::pages::admin::auth::CurrentUser;
+
+/// Most entries shown on the page; the export has no limit
+const PAGE_LIMIT: usize = 500;
+
+/// Who is making the current request, and when
+#[derive(Debug, Clone)]
+pub struct AuditContext {
+    pub actor: String,
+    pub request_id: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+impl AuditContext {
+    /// An audit entry for `action` attributed to this request
+    pub fn entry(&self, action: AuditAction) -> AuditEntry {
+        AuditEntry::new(self.at, self.actor.clone(), action, self.request_id.clone())
+    }
+}
+
+impl FromRequestParts<AppState> for AuditContext {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(Self {
+            actor: parts
+                .extensions
+                .get::<CurrentUser>()
+                .map_or(ANONYMOUS, |user| user.name())
+                .to_string(),
+            request_id: parts
+                .extensions
+                .get::<RequestId>()
+                .map(|RequestId(id)| id.clone()),
+            at: state.clock.now(),
+        })
+    }
+}
+
+/// Query parameters of the audit page and export
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditQuery {
+    #[serde(default)]
+    pub actor: String,
+    #[serde(default)]
+    pub action: String,
+    #[serde(default)]
+    pub route: String,
+    #[serde(default)]
+    pub since: String,
+    #[serde(default)]
+    pub until: String,
+}
+
+impl AuditQuery {
+    /// Parse into a filter, with a message for an unknown action or bad date
+    fn filter(&self) -> Result<AuditFilter, String> {
+        let text = |value: &str| Some(value.trim().to_string()).filter(|v| !v.is_empty());
+        let date = |name: &str, value: &str| match value.trim() {
+            "" => Ok(None),
+            value => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_| format!("Invalid {} date: {}", name, value)),
+        };
+        let action = match self.action.trim() {
+            "" => None,
+            id => Some(id.parse().map_err(|_| format!("Unknown action: {}", id))?),
+        };
+
+        Ok(AuditFilter {
+            actor: text(&self.actor),
+            action,
+            route: text(&self.route),
+            since: date("since", &self.since)?,
+            until: date("until", &self.until)?,
+        })
+    }
+}
+
+/// Route handler: GET /admin/audit/
+///
+/// # Response
+///
+/// - **200 OK**: The filter form and up to 500 matching entries
+/// - **400 Bad Request**: Unknown action or malformed date
+/// - **403 Forbidden**: The user isn't an admin
+pub async fn audit_page(user: CurrentUser, Query(query): Query<AuditQuery>) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    let filter = match query.filter() {
+        Ok(filter) => filter,
+        Err(message) => return (StatusCode::BAD_REQUEST, message).into_response(),
+    };
+    let entries = read_entries(&filter);
+    Html(render_audit_page(&query, &entries).into_string()).into_response()
+}
+
+/// Route handler: GET /admin/api/audit/export
+///
+/// # Response
+///
+/// - **200 OK**: `application/x-ndjson` attachment named `audit.jsonl`,
+///   oldest entry first
+/// - **400 Bad Request**: Unknown action or malformed date
+/// - **403 Forbidden**: The user isn't an admin
+pub async fn export_audit(user: CurrentUser, Query(query): Query<AuditQuery>) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    match query.filter() {
+        Ok(filter) => (
+            [
+                (header::CONTENT_TYPE, "application/x-ndjson"),
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"audit.jsonl\"",
+                ),
+            ],
+            to_jsonl(&read_entries(&filter)),
+        )
+            .into_response(),
+        Err(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+    }
+}
+
+/// Export link carrying the current filter
+fn export_href(query: &AuditQuery) -> String {
+    let params: Vec<String> = [
+        ("actor", &query.actor),
+        ("action", &query.action),
+        ("route", &query.route),
+        ("since", &query.since),
+        ("until", &query.until),
+    ]
+    .into_iter()
+    .filter(|(_, value)| !value.trim().is_empty())
+    .map(|(name, value)| format!("{}={}", name, encode_query_value(value.trim())))
+    .collect();
+
+    if params.is_empty() {
+        "/admin/api/audit/export".to_string()
+    } else {
+        format!("/admin/api/audit/export?{}", params.join("&"))
+    }
+}
+
+/// Percent-encode everything but unreserved characters
+pub(crate) fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// First 12 hex digits of a `sha256:` hash, for display
+fn short_hash(hash: &Option<String>) -> String {
+    match hash {
+        Some(hash) => hash
+            .strip_prefix("sha256:")
+            .unwrap_or(hash)
+            .chars()
+            .take(12)
+            .collect(),
+        None => "—".to_string(),
+    }
+}
+
+/// Render the filter form and entries, newest first
+fn render_audit_page(query: &AuditQuery, entries: &[AuditEntry]) -> Markup {
+    let shown: Vec<&AuditEntry> = entries.iter().rev().take(PAGE_LIMIT).collect();
+
+    html! {
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Audit Log - Admin" }
+                link rel="stylesheet" href="/features/admin/editor/styles.css";
+            }
+            body {
+                h1 { "Audit Log" }
+
+                form class="audit-filter" method="get" action="/admin/audit/" {
+                    label {
+                        "Actor"
+                        input type="text" name="actor" value=(query.actor);
+                    }
+                    label {
+                        "Action"
+                        select name="action" {
+                            option value="" { "Any" }
+                            @for action in AuditAction::iter() {
+                                @let id: &str = action.into();
+                                option value=(id) selected[query.action == id] { (id) }
+                            }
+                        }
+                    }
+                    label {
+                        "Route"
+                        input type="text" name="route" value=(query.route);
+                    }
+                    label {
+                        "From"
+                        input type="date" name="since" value=(query.since);
+                    }
+                    label {
+                        "To"
+                        input type="date" name="until" value=(query.until);
+                    }
+                    button type="submit" { "Filter" }
+                    a href=(export_href(query)) download="audit.jsonl" { "Export JSONL" }
+                }
+
+                @if entries.is_empty() {
+                    p class="block-list-empty" { "No audit entries match." }
+                } @else {
+                    p class="audit-count" {
+                        @if entries.len() > shown.len() {
+                            "Showing the latest " (shown.len()) " of " (entries.len()) " entries."
+                        } @else {
+                            (entries.len()) " " (if entries.len() == 1 { "entry" } else { "entries" }) "."
+                        }
+                    }
+                    table class="audit-table" {
+                        thead {
+                            tr {
+                                th scope="col" { "Time (UTC)" }
+                                th scope="col" { "Actor" }
+                                th scope="col" { "Action" }
+                                th scope="col" { "Route" }
+                                th scope="col" { "Target" }
+                                th scope="col" { "Before" }
+                                th scope="col" { "After" }
+                                th scope="col" { "Request" }
+                            }
+                        }
+                        tbody {
+                            @for entry in shown {
+                                tr {
+                                    td {
+                                        time datetime=(entry.timestamp.to_rfc3339()) {
+                                            (entry.timestamp.format("%Y-%m-%d %H:%M:%S"))
+                                        }
+                                    }
+                                    td { (entry.actor) }
+                                    td { code { (<&str>::from(entry.action)) } }
+                                    td { (entry.route.as_deref().unwrap_or("")) }
+                                    td { (entry.target.as_deref().unwrap_or("")) }
+                                    td title=[entry.before.as_deref()] { code { (short_hash(&entry.before)) } }
+                                    td title=[entry.after.as_deref()] { code { (short_hash(&entry.after)) } }
+                                    td { small { (entry.request_id.as_deref().unwrap_or("")) } }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div class="button-group" {
+                    a href="/admin" {
+                        button type="button" { "Back to Admin" }
+                    }
+                }
+            }
+        }
+    }
+}
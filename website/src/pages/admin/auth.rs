@@ -0,0 +1,697 @@
+/// Admin sign-in and permission checks
+///
+/// Once users.json lists a user (see `core::users`), everything under
+/// `/admin` needs a signed-in session; until then the admin is open and
+/// every request acts as an anonymous admin.
+///
+/// # Routes
+///
+/// - `GET /admin/login` - Sign-in form (`?next=` is where to go afterwards)
+/// - `POST /admin/login` - Sign in (form: `name`, `password`, `next`)
+/// - `POST /admin/login/two-factor` - Second sign-in step (form:
+///   `challenge`, `code`, `next`)
+/// - `POST /admin/logout` - Sign out
+///
+/// # Two-Factor Sign-In
+///
+/// For a user with two-factor sign-in (see `core::totp`), the right password
+/// doesn't start a session. It answers with a code form carrying a
+/// short-lived challenge (see `core::sessions`); the session starts once
+/// the user enters a current TOTP code or an unused recovery code.
+///
+/// If the site settings require two-factor sign-in for a user's role and
+/// they haven't set it up, `require_sign_in` sends their page requests to
+/// the two-factor account screen and refuses everything else with 403
+/// Forbidden, until they enroll. API tokens are not affected.
+///
+/// # Requests Without a Session
+///
+/// `require_sign_in` redirects page requests (GET outside `/admin/api` and
+/// `/admin/ws`) to the sign-in form and answers everything else with
+/// 401 Unauthorized. A signed-in request carries a `CurrentUser` extension.
+///
+/// # Checking Permissions
+///
+/// Every admin handler takes a `CurrentUser` and checks the permission its
+/// action needs before doing anything:
+///
+/// ```ignore
+/// pub async fn update_settings(user: CurrentUser, ...) -> Response {
+///     if let Err(forbidden) = user.require(Permission::Administer) {
+///         return forbidden.into_response();
+///     }
+///     ...
+/// }
+/// ```
+///
+/// A failed check is 403 Forbidden. Templates use `can` and `can_on_route`
+/// to hide the actions the user may not take.
+///
+/// # API Tokens
+///
+/// Requests to `/admin/api/*` may send `Authorization: Bearer wst_…` instead
+/// of a session (see `core::tokens`). The request then acts as the token's
+/// owner, limited to the token's scopes; an unknown, expired or ownerless
+/// token is 401 Unauthorized, even while the admin is open.
+///
+/// # Session Cookie
+///
+/// The session token is sent as `admin_session`, limited to `/admin`,
+/// `HttpOnly`, `Secure` and `SameSite=Strict`; the strict same-site rule
+/// keeps other sites from making signed-in requests.
+use std::sync::Arc;
+
+use axum::Form;
+use axum::extract::{FromRequestParts, Query, Request, State};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, Method, StatusCode, header};
+use axum::middleware::Next;
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use maud::{Markup, html};
+use serde::Deserialize;
+
+use crate::app::AppState;
+use crate::core::persistence::{data_dir, with_data_dir_sync};
+use crate::core::security_headers::csp_nonce_meta;
+use crate::core::settings::load_settings;
+use crate::core::tokens::{ApiToken, authenticate_token};
+use crate::core::users::{
+    Permission, User, UserError, authenticate, load_users, verify_second_factor,
+};
+use crate::pages::admin::audit::encode_query_value;
+use crate::pages::admin::two_factor::TWO_FACTOR_PATH;
+
+/// Cookie carrying the session token
+pub const SESSION_COOKIE: &str = "admin_session";
+
+/// Path of the sign-in form
+pub const LOGIN_PATH: &str = "/admin/login";
+
+/// Path the second sign-in step posts to
+pub const TWO_FACTOR_LOGIN_PATH: &str = "/admin/login/two-factor";
+
+/// Password checks that may run at once (see `AppState::sign_ins`)
+pub const MAX_CONCURRENT_SIGN_INS: usize = 4;
+
+/// The user making an admin request
+#[derive(Debug, Clone)]
+pub struct CurrentUser {
+    /// The user, without their password hash
+    pub user: User,
+    /// Session token, if the user signed in
+    pub session: Option<String>,
+    /// No users exist yet; the request acts as an anonymous admin
+    pub open: bool,
+    /// API token the request was made with; it limits what the user may do
+    pub token: Option<ApiToken>,
+}
+
+impl CurrentUser {
+    /// The anonymous admin of open mode
+    pub fn open() -> Self {
+        Self {
+            user: User::anonymous(),
+            session: None,
+            open: true,
+            token: None,
+        }
+    }
+
+    /// Name recorded in the audit log and shown to other editors
+    pub fn name(&self) -> &str {
+        &self.user.name
+    }
+
+    /// Whether the user may do `permission` (on at least one route)
+    pub fn can(&self, permission: Permission) -> bool {
+        self.user.can(permission)
+            && self
+                .token
+                .as_ref()
+                .is_none_or(|token| token.allows(permission))
+    }
+
+    /// Whether the user may do `permission` on `route`
+    pub fn can_on_route(&self, permission: Permission, route: &str) -> bool {
+        self.user.can_on_route(permission, route)
+            && self
+                .token
+                .as_ref()
+                .is_none_or(|token| token.allows_on_route(permission, route))
+    }
+
+    /// Who is asking, for error messages: "mia (editor)", plus the token
+    fn describe(&self) -> String {
+        match &self.token {
+            Some(token) => format!(
+                "{} ({}) with token '{}'",
+                self.user.name, self.user.role, token.name
+            ),
+            None => format!("{} ({})", self.user.name, self.user.role),
+        }
+    }
+
+    /// 403 unless the user may do `permission`
+    pub fn require(&self, permission: Permission) -> Result<(), Forbidden> {
+        if self.can(permission) {
+            Ok(())
+        } else {
+            Err(Forbidden(format!(
+                "{} may not {}",
+                self.describe(),
+                permission.verb()
+            )))
+        }
+    }
+
+    /// 403 unless the user may do `permission` on `route`
+    pub fn require_on_route(&self, permission: Permission, route: &str) -> Result<(), Forbidden> {
+        if self.can_on_route(permission, route) {
+            Ok(())
+        } else {
+            Err(Forbidden(format!(
+                "{} may not {} route '{}'",
+                self.describe(),
+                permission.verb(),
+                route
+            )))
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for CurrentUser {
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<CurrentUser>()
+            .cloned()
+            .ok_or((StatusCode::UNAUTHORIZED, "Sign in to use the admin"))
+    }
+}
+
+/// A failed permission check: 403 Forbidden with the reason
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forbidden(pub String);
+
+impl IntoResponse for Forbidden {
+    fn into_response(self) -> Response {
+        (StatusCode::FORBIDDEN, format!("Forbidden: {}", self.0)).into_response()
+    }
+}
+
+/// Middleware: resolve the signed-in user of `/admin` requests
+///
+/// Inserts a `CurrentUser` extension, or refuses the request if users
+/// exist and it has no valid session or API token. The sign-in form is
+/// always reachable. Users who must enroll in two-factor sign-in can only
+/// reach the two-factor screen and sign out.
+pub async fn require_sign_in(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if path != "/admin" && !path.starts_with("/admin/") {
+        return next.run(request).await;
+    }
+
+    let users = match load_users() {
+        Ok(users) => users,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to load users: {}", e),
+            )
+                .into_response();
+        }
+    };
+    if path.starts_with("/admin/api/")
+        && let Some(secret) = bearer_token(request.headers())
+    {
+        return match token_user(&state, &users, secret) {
+            Ok(Some(user)) => {
+                request.extensions_mut().insert(user);
+                next.run(request).await
+            }
+            Ok(None) => (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                "Invalid or expired API token",
+            )
+                .into_response(),
+            Err(e) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to check API token: {}", e),
+            )
+                .into_response(),
+        };
+    }
+
+    let current = if users.is_empty() {
+        Some(CurrentUser::open())
+    } else {
+        session_token(request.headers()).and_then(|token| {
+            let name = state.sessions.user(&token, state.clock.now())?;
+            let user = users.iter().find(|user| user.name == name)?;
+            Some(CurrentUser {
+                user: user.without_password(),
+                session: Some(token),
+                open: false,
+                token: None,
+            })
+        })
+    };
+
+    match current {
+        Some(user)
+            if !user.open
+                && path != "/admin/logout"
+                && !path.starts_with(TWO_FACTOR_PATH)
+                && user
+                    .user
+                    .must_enroll_two_factor(&load_settings().two_factor_roles) =>
+        {
+            two_factor_required(&request, &user)
+        }
+        Some(user) => {
+            request.extensions_mut().insert(user);
+            next.run(request).await
+        }
+        None if path == LOGIN_PATH || path == TWO_FACTOR_LOGIN_PATH => next.run(request).await,
+        None => sign_in_required(&request),
+    }
+}
+
+/// Whether a request is for an admin page rather than an API or socket
+fn is_page_request(request: &Request) -> bool {
+    let path = request.uri().path();
+    request.method() == Method::GET
+        && !path.starts_with("/admin/api/")
+        && !path.starts_with("/admin/ws/")
+}
+
+/// Redirect a page request to the sign-in form, or 401 for anything else
+fn sign_in_required(request: &Request) -> Response {
+    let path = request.uri().path();
+    if is_page_request(request) {
+        let next = request
+            .uri()
+            .path_and_query()
+            .map_or(path, |path| path.as_str());
+        Redirect::to(&format!("{}?next={}", LOGIN_PATH, encode_query_value(next))).into_response()
+    } else {
+        (
+            StatusCode::UNAUTHORIZED,
+            format!("Sign in at {} to use the admin", LOGIN_PATH),
+        )
+            .into_response()
+    }
+}
+
+/// Send a page request to the two-factor screen, or 403 for anything else
+fn two_factor_required(request: &Request, user: &CurrentUser) -> Response {
+    if is_page_request(request) {
+        Redirect::to(TWO_FACTOR_PATH).into_response()
+    } else {
+        Forbidden(format!(
+            "the {} role must use two-factor sign-in; set it up at {}",
+            user.user.role, TWO_FACTOR_PATH
+        ))
+        .into_response()
+    }
+}
+
+/// The user of a valid API token: its owner, limited to its scopes
+///
+/// In open mode tokens belong to the anonymous admin; once users exist, the
+/// owner must still be one of them.
+fn token_user(
+    state: &AppState,
+    users: &[User],
+    secret: &str,
+) -> Result<Option<CurrentUser>, crate::core::tokens::TokenError> {
+    let Some(token) = authenticate_token(secret, state.clock.now())? else {
+        return Ok(None);
+    };
+    let owner = if users.is_empty() {
+        Some(User::anonymous()).filter(|anonymous| anonymous.name == token.owner)
+    } else {
+        users
+            .iter()
+            .find(|user| user.name == token.owner)
+            .map(User::without_password)
+    };
+    Ok(owner.map(|user| CurrentUser {
+        user,
+        session: None,
+        open: false,
+        token: Some(token),
+    }))
+}
+
+/// The token of an `Authorization: Bearer` header
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// The session token from the request's cookies
+fn session_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, token)| token.to_string())
+        .filter(|token| !token.is_empty())
+}
+
+/// `Set-Cookie` value for a session token; `None` clears the cookie
+fn session_cookie(token: Option<&str>) -> String {
+    let attributes = "Path=/admin; HttpOnly; Secure; SameSite=Strict";
+    match token {
+        Some(token) => format!("{}={}; {}", SESSION_COOKIE, token, attributes),
+        None => format!("{}=; Max-Age=0; {}", SESSION_COOKIE, attributes),
+    }
+}
+
+/// Where to go after signing in: an admin path, else the admin index
+fn safe_next(next: Option<&str>) -> &str {
+    match next {
+        Some(next)
+            if (next == "/admin" || next.starts_with("/admin/") || next.starts_with("/admin?"))
+                && !next.contains('\\') =>
+        {
+            next
+        }
+        _ => "/admin",
+    }
+}
+
+/// Fields of the second sign-in step
+#[derive(Debug, Default, Deserialize)]
+pub struct TwoFactorSignInForm {
+    #[serde(default)]
+    pub challenge: String,
+    #[serde(default)]
+    pub code: String,
+    pub next: Option<String>,
+}
+
+/// Query parameters of the sign-in form
+#[derive(Debug, Default, Deserialize)]
+pub struct LoginQuery {
+    pub next: Option<String>,
+}
+
+/// Fields of the sign-in form
+#[derive(Debug, Default, Deserialize)]
+pub struct SignInForm {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub password: String,
+    pub next: Option<String>,
+}
+
+/// Route handler: GET /admin/login
+///
+/// Redirects to the admin index while no users exist.
+pub async fn login_page(Query(query): Query<LoginQuery>) -> Response {
+    match load_users() {
+        Ok(users) if users.is_empty() => Redirect::to("/admin").into_response(),
+        _ => Html(render_login_page(safe_next(query.next.as_deref()), None).into_string())
+            .into_response(),
+    }
+}
+
+/// Route handler: POST /admin/login
+///
+/// # Response
+///
+/// - **303 See Other**: Signed in; the session cookie is set and the
+///   browser is sent to `next`
+/// - **200 OK**: The password was right and the user has two-factor
+///   sign-in; the code form, which posts to `/admin/login/two-factor`
+/// - **401 Unauthorized**: Unknown name or wrong password (the form again)
+pub async fn sign_in(State(state): State<AppState>, Form(form): Form<SignInForm>) -> Response {
+    let next = safe_next(form.next.as_deref()).to_string();
+
+    // Password hashing is slow on purpose; keep it off the async workers,
+    // and run only a few at once so a burst of sign-ins can't take every
+    // core. Others wait for a permit (within the request timeout).
+    let permit = Arc::clone(&state.sign_ins)
+        .acquire_owned()
+        .await
+        .expect("the sign-in semaphore is never closed");
+    let dir = data_dir();
+    let (name, password) = (form.name, form.password);
+    let result = tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        with_data_dir_sync(dir, || authenticate(&name, &password))
+    })
+    .await;
+
+    match result {
+        Ok(Ok(Some(user))) if user.has_two_factor() => {
+            let challenge = state
+                .sessions
+                .create_challenge(&user.name, state.clock.now());
+            Html(render_two_factor_sign_in_page(&next, &challenge, None).into_string())
+                .into_response()
+        }
+        Ok(Ok(Some(user))) => start_session(&state, &user.name, &next),
+        Ok(Ok(None)) => (
+            StatusCode::UNAUTHORIZED,
+            Html(render_login_page(&next, Some("Wrong name or password.")).into_string()),
+        )
+            .into_response(),
+        Ok(Err(e)) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to sign in: {}", e),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to sign in: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+/// Route handler: POST /admin/login/two-factor
+///
+/// # Response
+///
+/// - **303 See Other**: Signed in; the session cookie is set and the
+///   browser is sent to `next`
+/// - **401 Unauthorized**: Wrong or reused code (the code form again), or
+///   the challenge expired or ran out of attempts (the sign-in form)
+pub async fn sign_in_two_factor(
+    State(state): State<AppState>,
+    Form(form): Form<TwoFactorSignInForm>,
+) -> Response {
+    let next = safe_next(form.next.as_deref()).to_string();
+    let now = state.clock.now();
+    let Some(name) = state.sessions.challenge_user(&form.challenge, now) else {
+        return (
+            StatusCode::UNAUTHORIZED,
+            Html(
+                render_login_page(&next, Some("Sign-in expired. Enter your password again."))
+                    .into_string(),
+            ),
+        )
+            .into_response();
+    };
+
+    match verify_second_factor(&name, &form.code, now) {
+        Ok(_) => {
+            state.sessions.remove_challenge(&form.challenge);
+            start_session(&state, &name, &next)
+        }
+        Err(UserError::InvalidCode) => {
+            let markup = if state.sessions.fail_challenge(&form.challenge) {
+                render_two_factor_sign_in_page(
+                    &next,
+                    &form.challenge,
+                    Some("Wrong or already used code."),
+                )
+            } else {
+                render_login_page(
+                    &next,
+                    Some("Too many wrong codes. Enter your password again."),
+                )
+            };
+            (StatusCode::UNAUTHORIZED, Html(markup.into_string())).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to sign in: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+/// Start a session for `name`, set its cookie and go to `next`
+fn start_session(state: &AppState, name: &str, next: &str) -> Response {
+    let token = state.sessions.create(name, state.clock.now());
+    (
+        [(header::SET_COOKIE, session_cookie(Some(&token)))],
+        Redirect::to(next),
+    )
+        .into_response()
+}
+
+/// Route handler: POST /admin/logout
+pub async fn sign_out(State(state): State<AppState>, user: CurrentUser) -> Response {
+    if let Some(token) = &user.session {
+        state.sessions.remove(token);
+    }
+    (
+        [(header::SET_COOKIE, session_cookie(None))],
+        Redirect::to(LOGIN_PATH),
+    )
+        .into_response()
+}
+
+/// "Signed in as …" with a sign-out button; nothing in open mode
+pub fn render_account_bar(user: &CurrentUser) -> Markup {
+    html! {
+        @if !user.open {
+            form class="account-bar" method="post" action="/admin/logout" {
+                "Signed in as "
+                strong { (user.name()) }
+                " (" (user.user.role) ") "
+                a href=(TWO_FACTOR_PATH) { "Two-factor sign-in" }
+                button type="submit" { "Sign out" }
+            }
+        }
+    }
+}
+
+fn render_login_page(next: &str, error: Option<&str>) -> Markup {
+    html! {
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Sign in - Admin" }
+                link rel="stylesheet" href="/features/admin/editor/styles.css";
+            }
+            body {
+                h1 { "Sign in" }
+                @if let Some(error) = error {
+                    p class="form-error" role="alert" { (error) }
+                }
+                form class="sign-in" method="post" action=(LOGIN_PATH) {
+                    input type="hidden" name="next" value=(next);
+                    div class="form-group" {
+                        label for="sign-in-name" { "Name" }
+                        input id="sign-in-name" type="text" name="name" autocomplete="username" required autofocus;
+                    }
+                    div class="form-group" {
+                        label for="sign-in-password" { "Password" }
+                        input id="sign-in-password" type="password" name="password" autocomplete="current-password" required;
+                    }
+                    div class="button-group" {
+                        button type="submit" { "Sign in" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn render_two_factor_sign_in_page(next: &str, challenge: &str, error: Option<&str>) -> Markup {
+    html! {
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Sign in - Admin" }
+                link rel="stylesheet" href="/features/admin/editor/styles.css";
+            }
+            body {
+                h1 { "Sign in" }
+                p { "Enter the code from your authenticator app, or one of your recovery codes." }
+                @if let Some(error) = error {
+                    p class="form-error" role="alert" { (error) }
+                }
+                form class="sign-in" method="post" action=(TWO_FACTOR_LOGIN_PATH) {
+                    input type="hidden" name="next" value=(next);
+                    input type="hidden" name="challenge" value=(challenge);
+                    div class="form-group" {
+                        label for="sign-in-code" { "Code" }
+                        input id="sign-in-code" type="text" name="code" autocomplete="one-time-code" required autofocus;
+                    }
+                    div class="button-group" {
+                        button type="submit" { "Sign in" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_session_token_is_read_from_cookies() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(session_token(&headers), None);
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; admin_session=abc123; other=x"),
+        );
+        assert_eq!(session_token(&headers).as_deref(), Some("abc123"));
+    }
+
+    #[test]
+    fn test_next_must_stay_in_the_admin() {
+        assert_eq!(safe_next(Some("/admin/route/foo/")), "/admin/route/foo/");
+        assert_eq!(safe_next(Some("/admin")), "/admin");
+        assert_eq!(safe_next(Some("//evil.example/admin/")), "/admin");
+        assert_eq!(safe_next(Some("https://evil.example/")), "/admin");
+        assert_eq!(safe_next(Some("/administrator")), "/admin");
+        assert_eq!(safe_next(Some("/admin/\\evil")), "/admin");
+        assert_eq!(safe_next(None), "/admin");
+    }
+
+    #[test]
+    fn test_forbidden_names_the_missing_permission() {
+        let user = CurrentUser {
+            user: User {
+                name: "mia".to_string(),
+                role: crate::core::users::Role::Editor,
+                routes: Some(vec!["homepage".to_string()]),
+                password: String::new(),
+                two_factor: None,
+            },
+            session: None,
+            open: false,
+            token: None,
+        };
+        assert!(user.require_on_route(Permission::Edit, "homepage").is_ok());
+        assert_eq!(
+            user.require_on_route(Permission::Edit, "foo"),
+            Err(Forbidden(
+                "mia (editor) may not edit route 'foo'".to_string()
+            ))
+        );
+        assert!(user.require(Permission::Administer).is_err());
+        assert!(CurrentUser::open().require(Permission::Administer).is_ok());
+    }
+}
@@ -11,7 +11,8 @@
 /// # Architecture
 ///
 /// Following rust-feature-architecture and axum-web-framework patterns:
-/// - Stories are manually registered (compile-time discovery via this registry)
+/// - Stories register themselves with `register_story!` next to their impl;
+///   this module only iterates the registry (`all_stories` / `find_story`)
 /// - Each Props type implements ComponentStory trait in its schema.rs
 /// - Stories are accessed via /admin/features and /admin/features/{name}
 /// - No separate story.rs files needed - all functionality lives in schema.rs
@@ -20,57 +21,56 @@
 ///
 /// - `GET /admin/features/` - List all available component stories
 /// - `GET /admin/features/{name}` - Render a specific component story
+/// - `GET /admin/features/{name}/{variant}` - Render one variant in isolation
+///   (no page chrome; suitable for iframes and screenshot tests)
+///
+/// Story pages also embed the props playground (see `playground.rs`).
 use axum::extract::Path;
-use axum::response::Html;
-use maud::{html, Markup};
-
-use crate::features::button::ButtonProps;
-use crate::features::checkbox::CheckboxProps;
-use crate::features::header::HeaderProps;
-use crate::features::input::InputProps;
-use crate::features::story::ComponentStory;
-
-/// Story metadata for listing
-///
-/// Following rust-core-patterns for type-safe domain modeling
-#[derive(Debug, Clone)]
-pub struct Story {
-    pub name: &'static str,
-    pub description: &'static str,
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use maud::{DOCTYPE, Markup, html};
+
+use crate::core::security_headers::{csp_nonce, csp_nonce_meta};
+use crate::core::users::Permission;
+use crate::features::story::{StoryEntry, all_stories, find_story, is_valid_variant_name};
+use crate::pages::admin::CurrentUser;
+use crate::pages::admin::playground::render_playground;
+
+/// Render one story's primary variant for a combined preview
+fn story_preview(story: &StoryEntry) -> Markup {
+    let name = story.name();
+    html! {
+        section class="story-preview" id=(name) {
+            h2 { (capitalize_first(name)) }
+            div class="story-component" {
+                @if let Some(primary) = story.variants().into_iter().next() {
+                    (primary.markup)
+                }
+            }
+        }
+    }
 }
 
-/// Get all registered stories
-///
-/// Manual registry of all component stories in the codebase.
-/// When adding a new feature that implements ComponentStory, add it here.
-///
-/// Following the pattern from rust-feature-architecture where features
-/// are self-contained and registered explicitly.
-///
-/// # Usage
-///
-/// Each entry uses the ComponentStory trait's static methods to provide metadata:
-/// - `story_name()` for the identifier
-/// - `story_description()` for the human-readable description
-pub fn get_all_stories() -> Vec<Story> {
-    vec![
-        Story {
-            name: ButtonProps::story_name(),
-            description: ButtonProps::story_description(),
-        },
-        Story {
-            name: CheckboxProps::story_name(),
-            description: CheckboxProps::story_description(),
-        },
-        Story {
-            name: HeaderProps::story_name(),
-            description: HeaderProps::story_description(),
-        },
-        Story {
-            name: InputProps::story_name(),
-            description: InputProps::story_description(),
-        },
-    ]
+/// Render every registered story's primary variant in sequence
+///
+/// Used by the site settings page to preview theme changes across all
+/// components at once. Returns the de-duplicated stylesheets and the markup.
+pub fn render_all_story_previews() -> (Vec<String>, Markup) {
+    let stories = all_stories();
+
+    let mut stylesheets: Vec<String> = Vec::new();
+    for sheet in stories.iter().flat_map(|story| story.stylesheets()) {
+        if !stylesheets.contains(&sheet) {
+            stylesheets.push(sheet);
+        }
+    }
+
+    let markup = html! {
+        @for story in &stories {
+            (story_preview(story))
+        }
+    };
+    (stylesheets, markup)
 }
 
 /// Route handler: GET /admin/features/
@@ -78,20 +78,23 @@ pub fn get_all_stories() -> Vec<Story> {
 /// Displays a list of all available component stories with links to preview them.
 ///
 /// Following maud-axum-integration patterns for HTML responses.
-pub async fn features_index() -> Html<String> {
-    let stories = get_all_stories();
-    let markup = render_features_index(&stories);
-    Html(markup.into_string())
+pub async fn features_index(user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    let markup = render_features_index(&all_stories());
+    Html(markup.into_string()).into_response()
 }
 
 /// Render the features index page
 ///
 /// Following maud-components-patterns for clean template functions.
-fn render_features_index(stories: &[Story]) -> Markup {
+fn render_features_index(stories: &[&StoryEntry]) -> Markup {
     html! {
         html {
             head {
                 meta charset="utf-8";
+                (csp_nonce_meta())
                 meta name="viewport" content="width=device-width, initial-scale=1";
                 title { "Component Stories - Admin" }
                 link rel="stylesheet" href="/assets/features/admin/editor/styles.css";
@@ -104,10 +107,14 @@ fn render_features_index(stories: &[Story]) -> Markup {
                     ul {
                         @for story in stories {
                             li {
-                                a href=(format!("/admin/features/{}/", story.name)) {
-                                    strong { (story.name) }
+                                a href=(format!("/admin/features/{}/", story.name())) {
+                                    strong { (story.name()) }
                                     " - "
-                                    span { (story.description) }
+                                    span { (story.description()) }
+                                }
+                                @if let Some(block_type) = story.block_type() {
+                                    " "
+                                    small { "(" (block_type) " block)" }
                                 }
                             }
                         }
@@ -126,28 +133,74 @@ fn render_features_index(stories: &[Story]) -> Markup {
 
 /// Route handler: GET /admin/features/{name}
 ///
-/// Renders a specific component story with its fixture data.
+/// Renders a specific component story with all of its variants.
 ///
 /// Following axum-web-framework patterns for path parameter extraction.
-pub async fn feature_story(Path(name): Path<String>) -> Html<String> {
-    let markup = match name.as_str() {
-        "button" => render_story_for::<ButtonProps>(),
-        "checkbox" => render_story_for::<CheckboxProps>(),
-        "header" => render_story_for::<HeaderProps>(),
-        "input" => render_story_for::<InputProps>(),
-        _ => render_story_not_found(&name),
+pub async fn feature_story(user: CurrentUser, Path(name): Path<String>) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    let markup = match find_story(&name) {
+        Some(story) => render_story(story),
+        None => render_story_not_found(&name),
     };
-    Html(markup.into_string())
+    Html(markup.into_string()).into_response()
 }
 
-/// Render a component story using ComponentStory trait
+/// Route handler: GET /admin/features/{name}/{variant}
 ///
-/// Single rendering function that works with any component implementing ComponentStory.
-/// This eliminates duplication while maintaining type safety and flexibility.
+/// Renders a single story variant with only the component's stylesheets and
+/// no surrounding page chrome, so the output can be iframed or screenshotted.
+/// Unknown stories or variants return 404.
+pub async fn feature_story_variant(
+    user: CurrentUser,
+    Path((name, variant)): Path<(String, String)>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    let markup = find_story(&name)
+        .filter(|_| is_valid_variant_name(&variant))
+        .and_then(|story| render_story_variant(story, &variant));
+    match markup {
+        Some(markup) => Html(markup.into_string()).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Html(render_story_not_found(&format!("{}/{}", name, variant)).into_string()),
+        )
+            .into_response(),
+    }
+}
+
+/// Render one named variant as a standalone document
 ///
-/// # Type Parameters
+/// Returns `None` if the story has no variant with that name.
+fn render_story_variant(story: &StoryEntry, variant: &str) -> Option<Markup> {
+    let found = story.variant(variant)?;
+    let name = story.name();
+
+    Some(html! {
+        (DOCTYPE)
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { (capitalize_first(name)) " - " (found.name) }
+                @for stylesheet in story.stylesheets() {
+                    link rel="stylesheet" href=(stylesheet);
+                }
+            }
+            body class="story-isolated" data-story=(name) data-variant=(found.name) {
+                (found.markup)
+            }
+        }
+    })
+}
+
+/// Render a component story page
 ///
-/// - `T`: A type that implements ComponentStory (e.g., ButtonProps, HeaderProps)
+/// Single rendering function that works with any registered story.
 ///
 /// # Convention
 ///
@@ -155,44 +208,67 @@ pub async fn feature_story(Path(name): Path<String>) -> Html<String> {
 /// Additional stylesheets can be provided via `additional_stylesheets()`.
 ///
 /// Following maud-components-patterns for clean, reusable template functions.
-fn render_story_for<T: ComponentStory>() -> Markup {
-    let name = T::story_name();
-    let description = T::story_description();
-    let fixture = T::story_fixture();
-    let component = fixture.render_story();
-    let additional_stylesheets = T::additional_stylesheets();
+fn render_story(story: &StoryEntry) -> Markup {
+    let name = story.name();
+    let variants = story.variants();
 
     html! {
         html {
             head {
                 meta charset="utf-8";
+                (csp_nonce_meta())
                 meta name="viewport" content="width=device-width, initial-scale=1";
                 title { (capitalize_first(name)) " Story - Component Preview" }
 
-                // Load additional stylesheets first (e.g., global styles, dependencies)
-                @for stylesheet in additional_stylesheets {
+                @for stylesheet in story.stylesheets() {
                     link rel="stylesheet" href=(stylesheet);
                 }
-
-                // Load main feature stylesheet last (convention: /features/{name}/styles.css)
-                link rel="stylesheet" href=(format!("/features/{}/styles.css", name));
+                link rel="stylesheet" href="/features/admin/editor/playground.css";
             }
             body {
                 h1 { (capitalize_first(name)) " Component" }
-                p { (description) }
+                p { (story.description()) }
+
+                nav class="story-variants" aria-label="Variants" {
+                    ul {
+                        @for variant in &variants {
+                            li { a href=(format!("#variant-{}", variant.name)) { (variant.name) } }
+                        }
+                    }
+                }
+
+                @for variant in &variants {
+                    div class="story-preview" id=(format!("variant-{}", variant.name)) {
+                        h2 {
+                            "Variant: " (variant.name)
+                            " "
+                            a href=(format!("/admin/features/{}/{}", name, variant.name)) { "(isolated)" }
+                        }
+                        div class="story-component" {
+                            (variant.markup)
+                        }
+                    }
+                }
 
-                div class="story-preview" {
-                    h2 { "Preview" }
-                    div class="story-component" {
-                        (component)
+                // Block components are additionally previewed once per style variant
+                @for (style, markup) in story.style_previews() {
+                    div class="story-preview" id=(format!("style-{}", style)) {
+                        h2 { "Style: " (style) }
+                        div class="story-component" {
+                            (markup)
+                        }
                     }
                 }
 
+                (render_playground(story))
+
                 div class="button-group" {
                     a href="/admin/features/" {
                         button type="button" { "Back to Stories" }
                     }
                 }
+
+                script type="module" nonce=[csp_nonce()] src="/features/admin/editor/components/index.js" {}
             }
         }
     }
@@ -217,6 +293,7 @@ fn render_story_not_found(name: &str) -> Markup {
         html {
             head {
                 meta charset="utf-8";
+                (csp_nonce_meta())
                 meta name="viewport" content="width=device-width, initial-scale=1";
                 title { "Story Not Found" }
             }
@@ -233,3 +310,49 @@ fn render_story_not_found(name: &str) -> Markup {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_lists_every_registered_story() {
+        let html = render_features_index(&all_stories()).into_string();
+        for name in ["button", "checkbox", "header", "hero", "image", "input"] {
+            assert!(html.contains(&format!(r#"href="/admin/features/{}/""#, name)));
+        }
+    }
+
+    #[test]
+    fn test_story_page_anchors_every_variant() {
+        let story = find_story("checkbox").unwrap();
+        let html = render_story(story).into_string();
+        for variant in story.variants() {
+            assert!(html.contains(&format!(r#"id="variant-{}""#, variant.name)));
+            assert!(html.contains(&format!(r##"href="#variant-{}""##, variant.name)));
+            assert!(html.contains(&format!(
+                r#"href="/admin/features/checkbox/{}""#,
+                variant.name
+            )));
+        }
+    }
+
+    #[test]
+    fn test_isolated_variant_renders_only_that_variant() {
+        let story = find_story("checkbox").unwrap();
+        let html = render_story_variant(story, "checked-required")
+            .unwrap()
+            .into_string();
+        assert!(html.contains(r#"data-variant="checked-required""#));
+        assert!(html.contains("checked"));
+        assert!(html.contains("required"));
+        assert!(html.contains(r#"href="/features/checkbox/styles.css""#));
+        assert!(!html.contains("story-preview"));
+    }
+
+    #[test]
+    fn test_unknown_variant_is_none() {
+        let story = find_story("input").unwrap();
+        assert!(render_story_variant(story, "missing").is_none());
+    }
+}
@@ -0,0 +1,284 @@
+/// Media library admin page and API
+///
+/// Lets editors upload images, edit their alt text and focal point, and
+/// delete them once no Image block references them.
+///
+/// # Routes
+///
+/// - `GET /admin/media/` - Media library page
+/// - `GET /admin/api/media` - List media items as JSON
+/// - `POST /admin/api/media` - Upload an image (multipart: `file`, optional `alt`)
+/// - `POST /admin/api/media/{id}` - Update alt text / focal point (JSON)
+/// - `DELETE /admin/api/media/{id}` - Delete an unused media item
+///
+/// # Error Handling
+///
+/// `MediaError` variants map to status codes:
+/// - `NotFound` → 404, `InvalidId` / `InvalidImage` → 400
+/// - `InUse` → 409 (message lists where the image is still used)
+/// - `Io` / `Metadata` → 500
+///
+/// Viewing needs View, uploading and editing need Edit, and deleting needs
+/// Publish (see `core::users`); the page hides the forms the user may not
+/// use.
+///
+/// Uploads, updates and deletes are recorded in the audit log with the hash
+/// of the item's `media.json` before and after the change.
+use axum::Json;
+use axum::extract::{Multipart, Path};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use maud::{Markup, html};
+
+use crate::core::audit::{AuditAction, hash_file, record, record_file_changes};
+use crate::core::media::{
+    self, FocalPoint, MediaError, MediaItem, MediaUpdate, delete_media, list_media, metadata_path,
+    store_upload, update_media,
+};
+use crate::core::persistence::{data_dir, with_data_dir_sync};
+use crate::core::security_headers::{csp_nonce, csp_nonce_meta};
+use crate::core::users::Permission;
+use crate::pages::admin::{AuditContext, CurrentUser};
+
+/// Route handler: GET /admin/media/
+pub async fn media_index(user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    let items = list_media();
+    Html(render_media_index(&user, &items).into_string()).into_response()
+}
+
+/// Route handler: GET /admin/api/media
+pub async fn list_media_api(user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    Json(list_media()).into_response()
+}
+
+/// Route handler: POST /admin/api/media
+///
+/// Accepts a multipart form with a `file` field and an optional `alt` field.
+/// Image decoding and variant generation run on the blocking thread pool.
+///
+/// # Response
+///
+/// - **201 Created**: The stored `MediaItem` as JSON
+/// - **400 Bad Request**: Missing file or undecodable image
+pub async fn upload_media(
+    user: CurrentUser,
+    audit: AuditContext,
+    mut multipart: Multipart,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::Edit) {
+        return forbidden.into_response();
+    }
+    let mut upload: Option<(String, Vec<u8>)> = None;
+    let mut alt = String::new();
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return (StatusCode::BAD_REQUEST, e.body_text()).into_response(),
+        };
+        match field.name() {
+            Some("file") => {
+                let filename = field.file_name().unwrap_or("upload").to_string();
+                match field.bytes().await {
+                    Ok(bytes) => upload = Some((filename, bytes.to_vec())),
+                    Err(e) => return (StatusCode::BAD_REQUEST, e.body_text()).into_response(),
+                }
+            }
+            Some("alt") => alt = field.text().await.unwrap_or_default(),
+            _ => {}
+        }
+    }
+
+    let Some((filename, bytes)) = upload else {
+        return (StatusCode::BAD_REQUEST, "Missing 'file' field").into_response();
+    };
+
+    // spawn_blocking runs outside the request's data directory scope
+    let dir = data_dir();
+    let result = tokio::task::spawn_blocking(move || {
+        with_data_dir_sync(dir, || store_upload(&filename, &bytes, &alt))
+    })
+    .await;
+    match result {
+        Ok(Ok(item)) => {
+            let after = metadata_path(&item.id)
+                .ok()
+                .and_then(|path| hash_file(&path));
+            record(
+                &audit
+                    .entry(AuditAction::MediaUpload)
+                    .target(&item.id)
+                    .hashes(None, after),
+            );
+            (StatusCode::CREATED, Json(item)).into_response()
+        }
+        Ok(Err(e)) => media_error_response(e),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Image processing failed: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+/// Route handler: POST /admin/api/media/{id}
+///
+/// # Request Body
+///
+/// ```json
+/// { "alt": "New alt text", "focal_point": { "x": 0.5, "y": 0.25 } }
+/// ```
+///
+/// Both fields are optional; omitted fields are left unchanged.
+pub async fn update_media_item(
+    user: CurrentUser,
+    audit: AuditContext,
+    Path(id): Path<String>,
+    Json(update): Json<MediaUpdate>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::Edit) {
+        return forbidden.into_response();
+    }
+    let paths: Vec<_> = metadata_path(&id).into_iter().collect();
+    let entry = audit.entry(AuditAction::MediaUpdate);
+    match record_file_changes(&entry, &paths, || update_media(&id, update)) {
+        Ok(item) => Json(item).into_response(),
+        Err(e) => media_error_response(e),
+    }
+}
+
+/// Route handler: DELETE /admin/api/media/{id}
+///
+/// Refuses with 409 Conflict while any route still uses the image.
+pub async fn delete_media_item(
+    user: CurrentUser,
+    audit: AuditContext,
+    Path(id): Path<String>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::Publish) {
+        return forbidden.into_response();
+    }
+    let paths: Vec<_> = metadata_path(&id).into_iter().collect();
+    let entry = audit.entry(AuditAction::MediaDelete);
+    match record_file_changes(&entry, &paths, || delete_media(&id)) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => media_error_response(e),
+    }
+}
+
+/// Map a media error to an HTTP response
+fn media_error_response(error: MediaError) -> Response {
+    let status = match &error {
+        MediaError::NotFound(_) => StatusCode::NOT_FOUND,
+        MediaError::InvalidId(_) | MediaError::InvalidImage(_) => StatusCode::BAD_REQUEST,
+        MediaError::InUse(_) => StatusCode::CONFLICT,
+        MediaError::Io(_) | MediaError::Metadata(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, error.to_string()).into_response()
+}
+
+/// Render the media library page
+///
+/// Each item shows a thumbnail, its dimensions, an edit form for alt text and
+/// focal point, the routes that use it, and a delete button. The
+/// `media-library` web component handles submission via the JSON API.
+fn render_media_index(user: &CurrentUser, items: &[MediaItem]) -> Markup {
+    let (can_edit, can_delete) = (user.can(Permission::Edit), user.can(Permission::Publish));
+    html! {
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Media - Admin" }
+                link rel="stylesheet" href="/features/admin/editor/styles.css";
+            }
+            body {
+                h1 { "Media Library" }
+
+                media-library {
+                    @if can_edit {
+                        form class="media-upload" data-action="upload" {
+                            div class="form-group" {
+                                label for="media-file" { "Image" }
+                                input id="media-file" type="file" name="file" accept="image/*" required;
+                            }
+                            div class="form-group" {
+                                label for="media-alt" { "Alt text" }
+                                input id="media-alt" type="text" name="alt";
+                            }
+                            div class="button-group" {
+                                button type="submit" { "Upload" }
+                            }
+                        }
+                    }
+
+                    @if items.is_empty() {
+                        p class="block-list-empty" { "No media uploaded yet." }
+                    } @else {
+                        ul class="media-list" role="list" {
+                            @for item in items {
+                                (render_media_item(item, can_edit, can_delete))
+                            }
+                        }
+                    }
+
+                    message-banner {}
+                }
+
+                div class="button-group" {
+                    a href="/admin" {
+                        button type="button" { "Back to Admin" }
+                    }
+                }
+
+                script type="module" nonce=[csp_nonce()] src="/features/admin/editor/components/index.js" {}
+            }
+        }
+    }
+}
+
+fn render_media_item(item: &MediaItem, can_edit: bool, can_delete: bool) -> Markup {
+    let usage = media::media_usage(&item.id);
+    let FocalPoint { x, y } = item.focal_point;
+
+    html! {
+        li class="media-item" id=(format!("media-{}", item.id)) {
+            img class="media-item__thumb" src=(item.default_src()) alt=(item.alt) width="160";
+            div class="media-item__details" {
+                strong { (item.filename) }
+                " "
+                code { (item.width) "×" (item.height) }
+                p { code { (item.id) } }
+                @if usage.is_empty() {
+                    p { "Not used anywhere." }
+                } @else {
+                    p { "Used by: " (usage.join(", ")) }
+                }
+
+                @if can_edit {
+                    form class="media-edit" data-action="update" data-media-id=(item.id) {
+                        label { "Alt text" input type="text" name="alt" value=(item.alt); }
+                        label { "Focal X" input type="number" name="focal_x" min="0" max="1" step="0.05" value=(x); }
+                        label { "Focal Y" input type="number" name="focal_y" min="0" max="1" step="0.05" value=(y); }
+                        div class="button-group" {
+                            button type="submit" { "Save" }
+                            @if can_delete {
+                                button class="btn-delete" type="button" data-action="delete" data-media-id=(item.id) disabled[!usage.is_empty()] {
+                                    "Delete"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
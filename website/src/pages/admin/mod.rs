@@ -5,29 +5,90 @@
 /// # Routes
 ///
 /// - `GET /admin` - Admin index page
+/// - `GET|POST /admin/login`, `POST /admin/login/two-factor`, `POST /admin/logout` - Sign in and out
 /// - `GET /admin/route/` - Route index page (list all routes)
 /// - `GET /admin/route/:name/` - Generic page editor for any route (homepage, foo, etc.)
+/// - `GET /admin/route/:name/preview` - Live preview that reloads on publish
+/// - `GET /admin/ws/route/:name` - WebSocket for editor presence and publish events
 /// - `GET /admin/features/` - Component stories index page
 /// - `GET /admin/features/:name/` - Component story preview or block editor
+/// - `GET /admin/features/:name/:variant` - Single story variant in isolation
+/// - `POST /admin/api/features/:name/render` - Render story props JSON (playground)
+/// - `GET /admin/media/` - Media library (upload, alt text, focal point, delete)
+/// - `GET /admin/settings/` - Site settings and theme tokens with live preview
+/// - `GET /admin/schedule/` - Calendar of scheduled publishing changes
+/// - `GET /admin/audit/` - Filterable audit log of admin actions
+/// - `GET|POST /admin/users/`, `POST /admin/users/:name/delete` - Users and roles
+/// - `POST /admin/users/:name/two-factor/reset` - Turn off a user's two-factor sign-in
+/// - `GET|POST /admin/account/two-factor` (and `/disable`, `/recovery-codes`) - Two-factor sign-in
+/// - `GET|POST /admin/tokens/`, `POST /admin/tokens/:id/revoke` - API tokens
 /// - `GET /admin/schema-test/:component/` - Schema-driven form test routes (dev only)
-/// - `POST /admin/api/homepage` - Legacy homepage update API (use /admin/api/:route_name instead)
+/// - `POST /admin/api/a11y` - Accessibility warnings for unsaved blocks
+/// - `GET|POST /admin/api/homepage` - Legacy homepage read/update API (use /admin/api/:route_name instead)
+/// - `GET /admin/api/:route_name` - Route content, in the shape the update API accepts
 /// - `POST /admin/api/:route_name` - Generic route update API (saves to data/content/{route_name}.json)
-use axum::response::Html;
+/// - `GET|POST /admin/api/routes` - List or replace routes.json
+/// - `GET|POST /admin/api/media`, `POST|DELETE /admin/api/media/:id` - Media library API
+/// - `POST /admin/api/settings`, `POST /admin/api/settings/preview` - Site settings API
+/// - `GET /admin/api/schema/block-options` - JSON Schema for the per-block options envelope
+/// - `GET /admin/api/export`, `POST /admin/api/import` - Whole-site archive export/import
+/// - `DELETE /admin/api/scheduled/:id` - Cancel a scheduled draft
+/// - `GET /admin/api/audit/export` - Audit log as JSON Lines
+///
+/// # Permissions
+///
+/// Every handler takes a `CurrentUser` and checks the permission its action
+/// needs (see `auth` and `core::users`); pages hide what the user may not do.
+/// Requests to `/admin/api/*` may authenticate with an API token instead of
+/// a session (see `core::tokens`).
+use axum::response::{Html, IntoResponse, Response};
 use maud::html;
 
+use crate::core::security_headers::csp_nonce_meta;
+use crate::core::users::Permission;
+
 // Submodules
 pub mod admin_index_template;
 pub mod api;
+pub mod archive;
+pub mod audit;
+pub mod auth;
 pub mod features;
+pub mod media;
 pub mod page_editor;
+pub mod playground;
+pub mod presence;
 pub mod routes;
+pub mod schedule;
+pub mod settings;
+pub mod tokens;
+pub mod two_factor;
+pub mod users;
 
 // Re-export handlers
 pub use admin_index_template::render_admin_index;
-pub use api::{update_homepage, update_route};
-pub use features::{feature_story, features_index};
+pub use api::{
+    block_options_schema_json, check_accessibility, read_homepage, read_route, update_homepage,
+    update_route,
+};
+pub use archive::{export_archive, import_archive};
+pub use audit::{AuditContext, audit_page, export_audit};
+pub use auth::{
+    CurrentUser, Forbidden, login_page, require_sign_in, sign_in, sign_in_two_factor, sign_out,
+};
+pub use features::{feature_story, feature_story_variant, features_index};
+pub use media::{delete_media_item, list_media_api, media_index, update_media_item, upload_media};
 pub use page_editor::admin_route_page;
-pub use routes::admin_route_index;
+pub use playground::render_story_props;
+pub use presence::{route_preview_page, route_socket};
+pub use routes::{admin_route_index, list_routes_api, replace_routes};
+pub use schedule::{cancel_scheduled_draft, schedule_page};
+pub use settings::{settings_page, settings_preview, settings_preview_saved, update_settings};
+pub use tokens::{create_token_form, revoke_token_form, tokens_page};
+pub use two_factor::{
+    disable_two_factor_form, enable_two_factor_form, reset_recovery_codes_form, two_factor_page,
+};
+pub use users::{delete_user_form, reset_two_factor_form, save_user_form, users_page};
 
 /// Admin index page
 ///
@@ -38,12 +99,16 @@ pub use routes::admin_route_index;
 /// The page includes:
 /// - Global styles (Monument Extended font, Utopia fluid scales)
 /// - Admin index component styles
-/// - Admin index component (black circle, heading, routes link)
-pub async fn admin_index() -> Html<String> {
+/// - Admin index component (black circle, heading, links the user may use)
+pub async fn admin_index(user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
     let markup = html! {
         html {
             head {
                 meta charset="utf-8";
+                (csp_nonce_meta())
                 meta name="viewport" content="width=device-width, initial-scale=1";
                 title { "Admin" }
 
@@ -54,9 +119,9 @@ pub async fn admin_index() -> Html<String> {
                 link rel="stylesheet" href="/assets/admin-index.css";
             }
             body {
-                (render_admin_index())
+                (render_admin_index(&user))
             }
         }
     };
-    Html(markup.into_string())
+    Html(markup.into_string()).into_response()
 }
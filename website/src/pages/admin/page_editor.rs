@@ -8,18 +8,84 @@
 ///
 /// Content is loaded from and saved to data/content/{route_name}.json files.
 /// This works generically for any route (homepage, foo, etc.) via:
-/// - `load_blocks(route_name)` - Reads from data/content/{route_name}.json
-/// - `save_blocks(route_name)` - Writes to data/content/{route_name}.json (via API)
+/// - `load_content_files(route_name)` - Reads every file in the route's blockIds
+/// - `save_content_files(route_name)` - Writes each file back (via API)
+///
+/// The editor JSON groups blocks by content file (`{"files": [{"source",
+/// "blocks"}]}`, see `api::SaveRequest`), and the list view shows which file
+/// each block comes from, so blocks are saved back to the file they came from.
 ///
 /// The template and route handler live together in the pages directory
 /// because this is a page-level concern, not a reusable component.
-use axum::extract::Path;
+///
+/// # Locales
+///
+/// On a multilingual site (see `core::i18n`) a locale switcher links the
+/// editor for each locale (`?locale=de`). A translation starts as a copy of
+/// the default locale's content, is saved to the locale's variant files, and
+/// a panel lists the blocks that are still missing or unchanged.
+///
+/// # Scheduling
+///
+/// Filling in "Publish at" queues the save as a scheduled draft instead of
+/// publishing it (see `core::schedule`); drafts already queued for the route
+/// are listed above the editor. Blocks can also carry their own `schedule`
+/// window in the JSON view.
+///
+/// # Live Presence
+///
+/// The `editor-presence` component connects to the route's WebSocket (see
+/// `presence`) and lists the other editors. When one of them publishes, an
+/// editor without unsaved changes loads their content; otherwise it warns
+/// that publishing would overwrite it. "Preview" opens a live preview that
+/// reloads on every publish.
+///
+/// # Permissions
+///
+/// Users without Edit on the route see the content read-only: no publish
+/// form and no presence (see `core::users`). "Publish at" is only offered
+/// to users with Publish on the route.
+///
+/// # Invalid Blocks
+///
+/// Blocks that fail to parse are listed in a warning above the editor with
+/// their parse error. They stay in the JSON (hidden on the published page)
+/// and are saved back unchanged until fixed or deleted.
+use axum::extract::{Path, Query};
 use axum::http::StatusCode;
 use axum::response::{Html, IntoResponse, Response};
 use maud::{Markup, html};
 
-use crate::core::{load_blocks, load_routes};
-use crate::pages::homepage::HomepageData;
+use crate::core::a11y::{BlockIssue, lint_blocks};
+use crate::core::block::{BlockWithId, ContentBlock, InvalidBlock};
+use crate::core::i18n::{UntranslatedBlock, localized_files, untranslated_blocks};
+use crate::core::load_routes;
+use crate::core::persistence::{content_file_name, load_content_files};
+use crate::core::schedule::{ScheduledDraft, scheduled_drafts};
+use crate::core::security_headers::{csp_nonce, csp_nonce_meta};
+use crate::core::settings::load_settings;
+use crate::core::users::Permission;
+use crate::pages::admin::CurrentUser;
+use crate::pages::admin::api::SaveRequest;
+use crate::pages::admin::auth::render_account_bar;
+
+/// Query parameters for `GET /admin/route/:name/`
+#[derive(Debug, Default, serde::Deserialize)]
+pub struct EditorQuery {
+    /// Locale to edit; the default locale when absent
+    pub locale: Option<String>,
+}
+
+/// Locale context of the editor on a multilingual site
+struct EditorLocale {
+    current: String,
+    default: String,
+    all: Vec<String>,
+    /// Blocks of the default locale still missing or unchanged in `current`
+    untranslated: Vec<UntranslatedBlock>,
+    /// Content files with no variant for `current` yet
+    new_files: Vec<String>,
+}
 
 /// Route handler: GET /admin/route/:name/
 ///
@@ -33,13 +99,21 @@ use crate::pages::homepage::HomepageData;
 ///
 /// # Data Flow
 ///
-/// - **Load**: Reads blocks from data/content/{name}.json via `load_blocks(name)`
+/// - **Load**: Reads blocks from data/content/{name}.json via `load_content(name)`
 /// - **Save**: Persists blocks to data/content/{name}.json via `save_blocks(name)` (API endpoint)
 ///
 /// # Error Handling
 ///
-/// Returns 404 if the route name is not found in routes.json.
-pub async fn admin_route_page(Path(name): Path<String>) -> Response {
+/// Returns 403 without View, and 404 if the route name is not found in
+/// routes.json, or if `?locale=` isn't a configured locale.
+pub async fn admin_route_page(
+    user: CurrentUser,
+    Path(name): Path<String>,
+    Query(query): Query<EditorQuery>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
     // Load routes and find the requested route
     let routes = load_routes();
     let route = match routes.iter().find(|r| r.name == name) {
@@ -56,10 +130,67 @@ pub async fn admin_route_page(Path(name): Path<String>) -> Response {
         }
     };
 
+    let i18n = load_settings().i18n;
+    let locale = match i18n.variant(query.locale.as_deref().unwrap_or(&i18n.default_locale)) {
+        Ok(locale) => locale,
+        Err(message) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Html(format!("<h1>404 Not Found</h1><p>{}</p>", message)),
+            )
+                .into_response();
+        }
+    };
+
     // Load blocks for this specific route using the generic loader
-    let blocks = load_blocks(&name);
-    let data = HomepageData::new(blocks);
-    let markup = render_editor_template(&data, route, &name);
+    let data = match locale {
+        None => SaveRequest {
+            files: Some(load_content_files(&name)),
+            ..Default::default()
+        },
+        Some(locale) => SaveRequest {
+            locale: Some(locale.to_string()),
+            files: Some(
+                localized_files(&name, locale)
+                    .into_iter()
+                    .map(|(file, _)| file)
+                    .collect(),
+            ),
+            ..Default::default()
+        },
+    };
+
+    let editor_locale = i18n.is_multilingual().then(|| {
+        let current = locale.unwrap_or(&i18n.default_locale);
+        let (untranslated, new_files) = match locale {
+            Some(locale) => {
+                let (translated, new): (Vec<_>, Vec<_>) = localized_files(&name, locale)
+                    .into_iter()
+                    .partition(|(_, translated)| *translated);
+                let translated: Vec<_> = translated.into_iter().map(|(file, _)| file).collect();
+                (
+                    untranslated_blocks(&load_content_files(&name), &translated),
+                    new.into_iter().map(|(file, _)| file.source).collect(),
+                )
+            }
+            None => (vec![], vec![]),
+        };
+        EditorLocale {
+            current: current.to_string(),
+            default: i18n.default_locale.clone(),
+            all: i18n.all().map(str::to_string).collect(),
+            untranslated,
+            new_files,
+        }
+    });
+
+    let drafts: Vec<ScheduledDraft> = scheduled_drafts()
+        .into_iter()
+        .filter(|draft| draft.route == name)
+        .collect();
+
+    let markup =
+        render_editor_template(&user, &data, route, &name, editor_locale.as_ref(), &drafts);
     Html(markup.into_string()).into_response()
 }
 
@@ -69,34 +200,108 @@ pub async fn admin_route_page(Path(name): Path<String>) -> Response {
 /// - **List View**: Visual block management with add/delete
 /// - **JSON View**: Raw JSON editor for advanced editing
 ///
+/// Below the editor, an accessibility panel lists linter warnings for the
+/// saved content; the editor re-checks unsaved content before publishing.
+///
+/// On a multilingual site, a locale switcher sits above the editor, and the
+/// untranslated blocks of the current locale are listed. Scheduled drafts
+/// of the route are listed above the editor too.
+///
 /// # Asset Dependencies
 ///
 /// - `/features/admin/editor/styles.css` - Editor styles
 /// - `/features/admin/editor/components/index.js` - Web components (ES module)
 fn render_editor_template(
-    data: &HomepageData,
+    user: &CurrentUser,
+    data: &SaveRequest,
     route: &crate::core::Route,
     route_name: &str,
+    locale: Option<&EditorLocale>,
+    drafts: &[ScheduledDraft],
 ) -> Markup {
     let json = serde_json::to_string_pretty(data).unwrap_or_default();
+    let can_edit = user.can_on_route(Permission::Edit, route_name);
+    let can_publish = user.can_on_route(Permission::Publish, route_name);
+    let blocks: Vec<BlockWithId> = data
+        .all_blocks()
+        .filter_map(ContentBlock::as_valid)
+        .cloned()
+        .collect();
+    let issues = lint_blocks(&blocks);
+    let invalid: Vec<(&str, &InvalidBlock)> = data
+        .files
+        .iter()
+        .flatten()
+        .flat_map(|file| {
+            file.blocks.iter().filter_map(|block| match block {
+                ContentBlock::Invalid(invalid) => Some((file.source.as_str(), invalid)),
+                ContentBlock::Valid(_) => None,
+            })
+        })
+        .collect();
+    // Translations are edited and previewed in their own locale channel
+    let current_locale = locale
+        .filter(|locale| locale.current != locale.default)
+        .map(|locale| locale.current.as_str());
+    let preview_path = match current_locale {
+        Some(locale) => format!("/admin/route/{}/preview?locale={}", route_name, locale),
+        None => format!("/admin/route/{}/preview", route_name),
+    };
 
     html! {
         html {
             head {
                 meta charset="utf-8";
+                (csp_nonce_meta())
                 meta name="viewport" content="width=device-width, initial-scale=1";
                 title { "Edit " (route.name) }
                 link rel="stylesheet" href="/features/admin/editor/styles.css";
             }
             body {
+                (render_account_bar(user))
                 h1 { "Edit " (route.name) " Content" }
-                p style="color: #666; margin-bottom: 1rem;" {
+                p class="route-path" {
                     "Route: "
                     code { (route.path) }
                 }
 
+                @if let Some(locale) = locale {
+                    (render_locale_switcher(locale))
+                    @if locale.current != locale.default {
+                        (render_untranslated_blocks(locale))
+                    }
+                }
+
+                @if !can_edit {
+                    p class="read-only-note" {
+                        "You can view this route but not edit it."
+                    }
+                }
+
+                @if !drafts.is_empty() {
+                    (render_scheduled_drafts(drafts))
+                }
+
+                @if !invalid.is_empty() {
+                    (render_invalid_blocks(&invalid))
+                }
+
                 // Web component structure - using custom elements
                 admin-editor data-route-name=(route_name) {
+                    // Who else has this route open, and publishes by others;
+                    // signed-in editors are named by their user
+                    @if can_edit {
+                        editor-presence data-route-name=(route_name) data-locale=[current_locale] {
+                            @if user.open {
+                                label class="editor-presence__name" {
+                                    "Your name "
+                                    input type="text" name="editor_name" maxlength="40" autocomplete="name";
+                                }
+                            }
+                            p class="editor-presence__list" role="status" aria-live="polite" {}
+                        }
+                    }
+
                     // Tab switcher component
                     tab-switcher active-tab="list" {
                         button class="tab" data-tab="list" { "List View" }
@@ -115,22 +320,176 @@ fn render_editor_template(
                     }
 
                     // Form for submission
-                    form {
+                    @if can_edit {
+                        form {
+                            @if can_publish {
+                                div class="form-group" {
+                                    label for="publish-at" { "Publish at (optional, your local time)" }
+                                    input id="publish-at" type="datetime-local" name="publish_at";
+                                }
+                            }
+                            div class="button-group" {
+                                button type="submit" { "Publish Changes" }
+                                a href=(preview_path) target="_blank" {
+                                    button type="button" { "Preview " (route.name) }
+                                }
+                            }
+                        }
+                    } @else {
                         div class="button-group" {
-                            button type="submit" { "Publish Changes" }
-                            a href=(route.path) {
+                            a href=(preview_path) target="_blank" {
                                 button type="button" { "Preview " (route.name) }
                             }
                         }
                     }
 
+                    // Accessibility warnings, re-checked before publishing
+                    (render_a11y_panel(&issues))
+
                     // Message banner component
                     message-banner {}
                 }
 
                 // Load web components as ES module
-                script type="module" src="/features/admin/editor/components/index.js" {}
+                script type="module" nonce=[csp_nonce()] src="/features/admin/editor/components/index.js" {}
+            }
+        }
+    }
+}
+
+/// Render links to the editor for each locale
+fn render_locale_switcher(locale: &EditorLocale) -> Markup {
+    html! {
+        nav class="locale-switcher" aria-label="Content locale" {
+            @for tag in &locale.all {
+                a href=(format!("?locale={}", tag))
+                    aria-current=[(tag == &locale.current).then_some("page")] {
+                    (tag)
+                    @if tag == &locale.default {
+                        " (default)"
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render the list of blocks not yet translated into the current locale
+fn render_untranslated_blocks(locale: &EditorLocale) -> Markup {
+    html! {
+        section class="untranslated-blocks" {
+            h2 {
+                @if locale.untranslated.is_empty() {
+                    "All blocks are translated into " (locale.current) "."
+                } @else {
+                    (locale.untranslated.len()) " untranslated block(s) in " (locale.current)
+                }
+            }
+            @for source in &locale.new_files {
+                p {
+                    "No " (locale.current) " translation of " code { (source) } " yet: "
+                    "showing the " (locale.default) " content. Publishing creates "
+                    code { (content_file_name(source, Some(&locale.current))) } "."
+                }
+            }
+            @if !locale.untranslated.is_empty() {
+                ul {
+                    @for block in &locale.untranslated {
+                        li {
+                            code { (block.type_name.as_deref().unwrap_or("(no type)")) }
+                            " "
+                            small { "(block " (block.id) " in " (block.source) ")" }
+                            ": "
+                            @if block.missing {
+                                "missing"
+                            } @else {
+                                "unchanged from " (locale.default)
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render the list of drafts queued for this route
+fn render_scheduled_drafts(drafts: &[ScheduledDraft]) -> Markup {
+    html! {
+        section class="scheduled-drafts" {
+            h2 { (drafts.len()) " scheduled draft(s)" }
+            ul {
+                @for draft in drafts {
+                    li {
+                        time datetime=(draft.publish_at.to_rfc3339()) {
+                            (draft.publish_at.format("%Y-%m-%d %H:%M UTC"))
+                        }
+                        @if let Some(locale) = &draft.locale {
+                            " (" (locale) ")"
+                        }
+                    }
+                }
+            }
+            p { a href="/admin/schedule/" { "View or cancel in the schedule" } }
+        }
+    }
+}
+
+/// Render the warning listing blocks that couldn't be loaded
+fn render_invalid_blocks(blocks: &[(&str, &InvalidBlock)]) -> Markup {
+    html! {
+        section class="invalid-blocks" role="alert" {
+            h2 { (blocks.len()) " block(s) couldn't be loaded" }
+            p {
+                "These blocks are hidden on the published page and are saved "
+                "unchanged. Fix or delete them in the JSON view."
+            }
+            ul {
+                @for (source, block) in blocks {
+                    li {
+                        code { (block.type_name().unwrap_or("(no type)")) }
+                        " "
+                        small {
+                            "(block " (block.id().unwrap_or("(no id)")) " in " (source) ")"
+                        }
+                        ": "
+                        (block.error)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render the accessibility warnings panel
+///
+/// The `a11y-panel` web component replaces the list with fresh results from
+/// `POST /admin/api/a11y`.
+fn render_a11y_panel(issues: &[BlockIssue]) -> Markup {
+    html! {
+        a11y-panel class="a11y-panel" {
+            h2 { "Accessibility" }
+            p class="a11y-panel__summary" role="status" {
+                @if issues.is_empty() {
+                    "No accessibility warnings."
+                } @else {
+                    (issues.len()) " accessibility warning(s)."
+                }
+            }
+            ul class="a11y-panel__list" {
+                @for issue in issues {
+                    li class="a11y-panel__issue" {
+                        code { (issue.issue.rule) }
+                        " "
+                        (issue.issue.message)
+                        @if let Some(block_id) = &issue.block_id {
+                            " "
+                            small { "(block " (block_id) ")" }
+                        }
+                    }
+                }
             }
+            button type="button" data-action="check" { "Check accessibility" }
         }
     }
 }
@@ -0,0 +1,198 @@
+/// Props playground ("controls") for component stories
+///
+/// Story pages include an editable props panel so a component can be tried
+/// with arbitrary data (a long headline, an empty label) without touching
+/// Rust code. The panel is generated from the story's props shape and the
+/// server renders every edit, so the preview is always the real template.
+///
+/// # Routes
+///
+/// - `POST /admin/api/features/{name}/render` - Render props JSON with the
+///   story's template
+///
+/// # Props Shape
+///
+/// The controls are generated from the JSON of the story's variants: the
+/// first variant provides the initial values and any field that only appears
+/// in later variants (e.g. an optional `error`) is added as an empty,
+/// nullable field. Each JSON type maps to one control:
+///
+/// - string → text input, number → number input, boolean → checkbox
+/// - null → text input that sends `null` when empty
+/// - object → fieldset, array → JSON textarea
+///
+/// The `props-playground` web component serializes the controls into the raw
+/// JSON textarea, which can also be edited directly.
+///
+/// # Error Handling
+///
+/// Props that don't deserialize into the story's type (see
+/// `StoryEntry::render_json`) return 422 Unprocessable Entity with the field
+/// path and serde message
+/// (e.g. `button.text: invalid type: integer `3`, expected a string`),
+/// which the editor shows inline next to the preview.
+use axum::extract::Path;
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use maud::{Markup, html};
+use serde_json::Value;
+
+use crate::core::users::Permission;
+use crate::features::story::{StoryEntry, find_story};
+use crate::pages::admin::CurrentUser;
+
+/// Strings longer than this get a textarea instead of a text input
+const TEXTAREA_THRESHOLD: usize = 60;
+
+/// Route handler: POST /admin/api/features/{name}/render
+///
+/// # Response
+///
+/// - **200 OK**: Rendered component HTML fragment
+/// - **404 Not Found**: Unknown story
+/// - **422 Unprocessable Entity**: Props don't match the story's type
+pub async fn render_story_props(
+    user: CurrentUser,
+    Path(name): Path<String>,
+    body: String,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    let Some(story) = find_story(&name) else {
+        return (
+            StatusCode::NOT_FOUND,
+            format!("Story \"{}\" does not exist", name),
+        )
+            .into_response();
+    };
+
+    match story.render_json(&body) {
+        Ok(markup) => Html(markup.into_string()).into_response(),
+        Err(e) => (StatusCode::UNPROCESSABLE_ENTITY, e).into_response(),
+    }
+}
+
+/// Render the playground panel for a story
+///
+/// The preview starts with the story's first variant, rendered server-side.
+pub fn render_playground(story: &StoryEntry) -> Markup {
+    let shape = story.props_shape();
+    let json = serde_json::to_string_pretty(&shape).unwrap_or_else(|_| "{}".to_string());
+    let initial = story.variants().into_iter().next();
+
+    html! {
+        props-playground class="props-playground" data-story=(story.name()) {
+            h2 { "Playground" }
+            div class="props-playground__layout" {
+                div class="props-playground__controls" {
+                    form {
+                        @if let Value::Object(fields) = &shape {
+                            @for (key, value) in fields {
+                                (control(key, key, value))
+                            }
+                        }
+                    }
+                    div class="props-playground__field" {
+                        label for="playground-props-json" { "Props JSON" }
+                        textarea id="playground-props-json" name="props-json" rows="12" spellcheck="false" {
+                            (json)
+                        }
+                    }
+                }
+                div class="props-playground__output" {
+                    p class="props-playground__error" role="alert" hidden {}
+                    div class="props-playground__preview story-component" aria-live="polite" {
+                        @if let Some(initial) = initial {
+                            (initial.markup)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render the control for one field
+///
+/// `path` is the dotted path from the props root (e.g. `button.text`) and is
+/// used as the control's `name`; the web component rebuilds nested JSON from it.
+fn control(path: &str, label: &str, value: &Value) -> Markup {
+    let id = format!("playground-{}", path.replace('.', "-"));
+
+    match value {
+        Value::Object(fields) => html! {
+            fieldset class="props-playground__group" {
+                legend { (label) }
+                @for (key, value) in fields {
+                    (control(&format!("{}.{}", path, key), key, value))
+                }
+            }
+        },
+        Value::Bool(checked) => html! {
+            div class="props-playground__field props-playground__field--inline" {
+                input id=(id) type="checkbox" name=(path) data-type="boolean" checked[*checked];
+                label for=(id) { (label) }
+            }
+        },
+        Value::Number(number) => html! {
+            div class="props-playground__field" {
+                label for=(id) { (label) }
+                input id=(id) type="number" name=(path) data-type="number" step="any" value=(number);
+            }
+        },
+        Value::String(text) if text.len() > TEXTAREA_THRESHOLD => html! {
+            div class="props-playground__field" {
+                label for=(id) { (label) }
+                textarea id=(id) name=(path) data-type="string" rows="3" { (text) }
+            }
+        },
+        Value::String(text) => html! {
+            div class="props-playground__field" {
+                label for=(id) { (label) }
+                input id=(id) type="text" name=(path) data-type="string" value=(text);
+            }
+        },
+        Value::Null => html! {
+            div class="props-playground__field" {
+                label for=(id) { (label) " " small { "(optional)" } }
+                input id=(id) type="text" name=(path) data-type="nullable" placeholder="null";
+            }
+        },
+        Value::Array(_) => html! {
+            div class="props-playground__field" {
+                label for=(id) { (label) " " small { "(JSON)" } }
+                textarea id=(id) name=(path) data-type="json" rows="3" {
+                    (value.to_string())
+                }
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_controls_match_json_types() {
+        let html = render_playground(find_story("header").unwrap()).into_string();
+        assert!(html.contains(r#"name="button.text""#));
+        assert!(html.contains("<legend>button</legend>"));
+
+        let html = render_playground(find_story("checkbox").unwrap()).into_string();
+        assert!(html.contains(r#"type="checkbox" name="checked" data-type="boolean""#));
+
+        let html = render_playground(find_story("input").unwrap()).into_string();
+        assert!(html.contains(r#"name="error" data-type="nullable""#));
+    }
+
+    #[test]
+    fn test_long_strings_use_textarea() {
+        let short = control("headline", "headline", &Value::from("Short")).into_string();
+        assert!(short.contains(r#"<input id="playground-headline" type="text""#));
+
+        let long = control("headline", "headline", &Value::from("x".repeat(61))).into_string();
+        assert!(long.contains(r#"<textarea id="playground-headline""#));
+    }
+}
@@ -0,0 +1,257 @@
+/// Live editor presence over WebSockets, and the live preview page
+///
+/// Each page editor and preview tab opens a WebSocket to its route's
+/// channel (see `core::presence`). Editors see who else has the route open;
+/// when anyone publishes, the other editors get the saved content and the
+/// previews reload.
+///
+/// # Routes
+///
+/// - `GET /admin/ws/route/{name}` - WebSocket for a route's channel
+/// - `GET /admin/route/{name}/preview` - Public page in a frame that reloads
+///   whenever the route is published
+///
+/// # Socket Parameters
+///
+/// - `role`: `editor` (default) to appear in the editors list, or `preview`
+///   to only listen. Joining as an editor needs Edit on the route; listening
+///   needs View
+/// - `name`: Display name of the editor while the admin is open; signed-in
+///   editors are shown by their user name
+/// - `locale`: Locale being edited, shown next to the name
+///
+/// Messages from the client are ignored; the socket only pushes events.
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use maud::{Markup, html};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+use uuid::Uuid;
+
+use crate::app::AppState;
+use crate::core::load_routes;
+use crate::core::presence::{Editor, Presence, PresenceEvent};
+use crate::core::security_headers::{csp_nonce, csp_nonce_meta};
+use crate::core::settings::load_settings;
+use crate::core::users::Permission;
+use crate::pages::admin::CurrentUser;
+
+/// Longest display name kept, in characters
+const MAX_NAME_CHARS: usize = 40;
+
+/// Header the page editor sends with a save so its own publish can be told
+/// apart from others'
+pub const EDITOR_SESSION_HEADER: &str = "x-editor-session";
+
+/// What a socket connection is for
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SocketRole {
+    #[default]
+    Editor,
+    Preview,
+}
+
+/// Query parameters for `GET /admin/ws/route/{name}`
+#[derive(Debug, Default, Deserialize)]
+pub struct SocketQuery {
+    #[serde(default)]
+    pub role: SocketRole,
+    pub name: Option<String>,
+    pub locale: Option<String>,
+}
+
+/// Query parameters for `GET /admin/route/{name}/preview`
+#[derive(Debug, Default, Deserialize)]
+pub struct PreviewQuery {
+    pub locale: Option<String>,
+}
+
+fn route_not_found(name: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Html(format!(
+            "<h1>404 Not Found</h1><p>Route '{}' not found</p>",
+            name
+        )),
+    )
+        .into_response()
+}
+
+/// Route handler: GET /admin/ws/route/{name}
+///
+/// # Response
+///
+/// - **101 Switching Protocols**: The socket is open; the first message is
+///   `welcome` with the connection's session id
+/// - **403 Forbidden**: The user may not view the route, or joins as an
+///   editor without Edit on it
+/// - **404 Not Found**: The route isn't in routes.json
+pub async fn route_socket(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    Path(name): Path<String>,
+    Query(query): Query<SocketQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let needed = match query.role {
+        SocketRole::Editor => Permission::Edit,
+        SocketRole::Preview => Permission::View,
+    };
+    if let Err(forbidden) = user.require_on_route(needed, &name) {
+        return forbidden.into_response();
+    }
+    if !load_routes().iter().any(|route| route.name == name) {
+        return route_not_found(&name);
+    }
+
+    let session = Uuid::new_v4().to_string();
+    let editor = (query.role == SocketRole::Editor).then(|| Editor {
+        name: if user.open {
+            display_name(query.name.as_deref(), &session)
+        } else {
+            user.name().to_string()
+        },
+        session: session.clone(),
+        locale: query.locale.filter(|locale| !locale.is_empty()),
+    });
+    upgrade.on_upgrade(move |socket| run_socket(socket, state.presence, name, session, editor))
+}
+
+/// The editor's chosen name, or "Editor" and the start of its session id
+fn display_name(requested: Option<&str>, session: &str) -> String {
+    let name: String = requested
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .filter(|c| !c.is_control())
+        .take(MAX_NAME_CHARS)
+        .collect();
+    if name.is_empty() {
+        format!("Editor {}", &session[..4])
+    } else {
+        name
+    }
+}
+
+/// Forward the route's events to the socket until either side closes
+async fn run_socket(
+    mut socket: WebSocket,
+    presence: Presence,
+    route: String,
+    session: String,
+    editor: Option<Editor>,
+) {
+    let mut events = presence.subscribe(&route);
+    if send(&mut socket, &PresenceEvent::Welcome { session })
+        .await
+        .is_err()
+    {
+        return;
+    }
+    // Joining after subscribing means the editor's own list arrives too
+    let _membership = editor.map(|editor| presence.join(&route, editor));
+
+    loop {
+        tokio::select! {
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if send(&mut socket, &event).await.is_err() {
+                        break;
+                    }
+                }
+                // A slow client misses events but stays connected
+                Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            },
+        }
+    }
+}
+
+async fn send(socket: &mut WebSocket, event: &PresenceEvent) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(event).unwrap_or_default();
+    socket.send(Message::Text(json.into())).await
+}
+
+/// Route handler: GET /admin/route/{name}/preview
+///
+/// Returns 404 if the route isn't in routes.json or `?locale=` isn't a
+/// configured locale.
+pub async fn route_preview_page(
+    user: CurrentUser,
+    Path(name): Path<String>,
+    Query(query): Query<PreviewQuery>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    let Some(route) = load_routes().into_iter().find(|r| r.name == name) else {
+        return route_not_found(&name);
+    };
+    let i18n = load_settings().i18n;
+    let locale = match i18n.variant(query.locale.as_deref().unwrap_or(&i18n.default_locale)) {
+        Ok(locale) => locale.map(str::to_string),
+        Err(message) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Html(format!("<h1>404 Not Found</h1><p>{}</p>", message)),
+            )
+                .into_response();
+        }
+    };
+
+    let src = match &locale {
+        Some(locale) => format!("/{}{}", locale, route.path),
+        None => route.path.clone(),
+    };
+    Html(render_preview_page(&route.name, locale.as_deref(), &src).into_string()).into_response()
+}
+
+/// Render the preview frame and its `live-preview` component
+fn render_preview_page(route_name: &str, locale: Option<&str>, src: &str) -> Markup {
+    html! {
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Preview " (route_name) " - Admin" }
+                link rel="stylesheet" href="/features/admin/editor/styles.css";
+            }
+            body class="live-preview-page" {
+                live-preview data-route-name=(route_name) data-locale=[locale] {
+                    p class="live-preview__status" role="status" {
+                        "Live preview of " code { (src) } " - reloads when "
+                        (route_name) " is published."
+                    }
+                    iframe class="live-preview__frame" src=(src) title={ "Preview of " (route_name) } {}
+                }
+
+                script type="module" nonce=[csp_nonce()] src="/features/admin/editor/components/index.js" {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_name() {
+        let session = "abcd1234-0000";
+        assert_eq!(display_name(Some("  Ana "), session), "Ana");
+        assert_eq!(display_name(None, session), "Editor abcd");
+        assert_eq!(display_name(Some("\n"), session), "Editor abcd");
+        assert_eq!(
+            display_name(Some(&"x".repeat(100)), session).len(),
+            MAX_NAME_CHARS
+        );
+    }
+}
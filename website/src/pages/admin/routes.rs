@@ -3,33 +3,102 @@
 /// Displays a list of all available routes for editing in the admin interface.
 ///
 /// This page loads routes from routes.json and provides links to edit each route.
-use axum::response::Html;
+/// Routes the signed-in user may not edit are marked view only.
+///
+/// The routes API lists and replaces routes.json, for scripts that manage
+/// the site's routes (see `core::tokens`):
+///
+/// - `GET /admin/api/routes` - Every route, as stored in routes.json
+/// - `POST /admin/api/routes` - Replace routes.json with the posted list
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
 use maud::{Markup, html};
 
+use crate::core::audit::{AuditAction, hash_file, record};
 use crate::core::load_routes;
+use crate::core::persistence::{Route, RoutesError, routes_json_path, save_routes};
+use crate::core::security_headers::csp_nonce_meta;
+use crate::core::users::Permission;
+use crate::pages::admin::auth::render_account_bar;
+use crate::pages::admin::{AuditContext, CurrentUser};
 
 /// Route handler: GET /admin/route/
 ///
 /// Displays the list of all routes with links to their editors.
-pub async fn admin_route_index() -> Html<String> {
+pub async fn admin_route_index(user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
     let routes = load_routes();
-    let markup = render_route_index(&routes);
-    Html(markup.into_string())
+    let markup = render_route_index(&user, &routes);
+    Html(markup.into_string()).into_response()
+}
+
+/// GET /admin/api/routes
+///
+/// Returns every route, in the routes.json format.
+pub async fn list_routes_api(user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    Json(load_routes()).into_response()
+}
+
+/// POST /admin/api/routes
+///
+/// Replaces routes.json with the posted routes. Content files of new
+/// routes start empty; files of removed routes are kept. The change is
+/// recorded in the audit log with routes.json's hash before and after.
+///
+/// # Response
+///
+/// - **200 OK**: "N routes saved"
+/// - **403 Forbidden**: The user (or token) may not manage routes
+/// - **422 Unprocessable Entity**: A name or path is invalid or repeated,
+///   or a `blockIds` entry isn't a content id (see `check_routes`)
+/// - **500 Internal Server Error**: routes.json couldn't be written
+pub async fn replace_routes(
+    user: CurrentUser,
+    audit: AuditContext,
+    Json(routes): Json<Vec<Route>>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::ManageRoutes) {
+        return forbidden.into_response();
+    }
+    let before = hash_file(&routes_json_path());
+    match save_routes(&routes) {
+        Ok(()) => {
+            record(
+                &audit
+                    .entry(AuditAction::RoutesUpdate)
+                    .target("routes.json")
+                    .hashes(before, hash_file(&routes_json_path())),
+            );
+            format!("{} routes saved", routes.len()).into_response()
+        }
+        Err(e @ (RoutesError::Io(_) | RoutesError::Json(_))) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+        Err(e) => (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()).into_response(),
+    }
 }
 
 /// Render the route index template
 ///
 /// Shows a list of routes with links to edit each one.
-fn render_route_index(routes: &[crate::core::Route]) -> Markup {
+fn render_route_index(user: &CurrentUser, routes: &[crate::core::Route]) -> Markup {
     html! {
         html {
             head {
                 meta charset="utf-8";
+                (csp_nonce_meta())
                 meta name="viewport" content="width=device-width, initial-scale=1";
                 title { "Routes - Admin" }
                 link rel="stylesheet" href="/features/admin/editor/styles.css";
             }
             body {
+                (render_account_bar(user))
                 h1 { "Routes" }
 
                 div class="route-list" {
@@ -41,6 +110,9 @@ fn render_route_index(routes: &[crate::core::Route]) -> Markup {
                                     " - "
                                     code { (route.path) }
                                 }
+                                @if !user.can_on_route(Permission::Edit, &route.name) {
+                                    " (view only)"
+                                }
                             }
                         }
                     }
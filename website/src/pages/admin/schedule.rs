@@ -0,0 +1,173 @@
+/// Scheduled changes calendar and API
+///
+/// Lists every upcoming change to the public site, grouped by day: routes
+/// and blocks whose publish window opens or closes, and queued drafts.
+///
+/// # Routes
+///
+/// - `GET /admin/schedule/` - Calendar of upcoming changes
+/// - `DELETE /admin/api/scheduled/{id}` - Cancel a scheduled draft
+///
+/// Drafts are queued from the page editor (see `api::update_route`); the
+/// `schedule-calendar` web component handles cancelling them. Cancel buttons
+/// are only shown for routes the user may publish.
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use maud::{Markup, html};
+
+use crate::app::AppState;
+use crate::core::audit::{AuditAction, record_file_changes};
+use crate::core::schedule::{
+    ChangeKind, ScheduleError, ScheduledChange, cancel_draft, draft_path, scheduled_drafts,
+    upcoming_changes,
+};
+use crate::core::security_headers::{csp_nonce, csp_nonce_meta};
+use crate::core::users::Permission;
+use crate::pages::admin::{AuditContext, CurrentUser};
+
+/// Route handler: GET /admin/schedule/
+pub async fn schedule_page(State(state): State<AppState>, user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    let now = state.clock.now();
+    Html(render_schedule_page(&user, &upcoming_changes(now), now).into_string()).into_response()
+}
+
+/// Route handler: DELETE /admin/api/scheduled/{id}
+///
+/// # Response
+///
+/// - **204 No Content**: The draft was removed and won't be published
+/// - **403 Forbidden**: The user may not publish the draft's route
+/// - **404 Not Found**: No queued draft has this id
+pub async fn cancel_scheduled_draft(
+    user: CurrentUser,
+    audit: AuditContext,
+    Path(id): Path<String>,
+) -> Response {
+    let route = scheduled_drafts()
+        .into_iter()
+        .find(|draft| draft.id == id)
+        .map(|draft| draft.route);
+    let allowed = match &route {
+        Some(route) => user.require_on_route(Permission::Publish, route),
+        None => user.require(Permission::Publish),
+    };
+    if let Err(forbidden) = allowed {
+        return forbidden.into_response();
+    }
+    let paths: Vec<_> = draft_path(&id).into_iter().collect();
+    let mut entry = audit.entry(AuditAction::DraftCancel);
+    entry.route = route;
+    match record_file_changes(&entry, &paths, || cancel_draft(&id)) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            let status = match e {
+                ScheduleError::NotFound(_) => StatusCode::NOT_FOUND,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            (status, e.to_string()).into_response()
+        }
+    }
+}
+
+/// Render the calendar of upcoming changes, one section per day (UTC)
+fn render_schedule_page(
+    user: &CurrentUser,
+    changes: &[ScheduledChange],
+    now: DateTime<Utc>,
+) -> Markup {
+    let days = changes.chunk_by(|a, b| a.day() == b.day());
+
+    html! {
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Schedule - Admin" }
+                link rel="stylesheet" href="/features/admin/editor/styles.css";
+            }
+            body {
+                h1 { "Scheduled Changes" }
+
+                schedule-calendar {
+                    @if changes.is_empty() {
+                        p class="block-list-empty" { "Nothing is scheduled." }
+                    }
+                    @for day in days {
+                        section class="schedule-day" {
+                            h2 {
+                                time datetime=(day[0].day().to_string()) {
+                                    (day[0].at.format("%A, %-d %B %Y"))
+                                }
+                            }
+                            ul class="schedule-list" role="list" {
+                                @for change in day {
+                                    (render_change(user, change, now))
+                                }
+                            }
+                        }
+                    }
+
+                    message-banner {}
+                }
+
+                div class="button-group" {
+                    a href="/admin" {
+                        button type="button" { "Back to Admin" }
+                    }
+                }
+
+                script type="module" nonce=[csp_nonce()] src="/features/admin/editor/components/index.js" {}
+            }
+        }
+    }
+}
+
+fn render_change(user: &CurrentUser, change: &ScheduledChange, now: DateTime<Utc>) -> Markup {
+    let editor = match &change.locale {
+        Some(locale) => format!("/admin/route/{}/?locale={}", change.route, locale),
+        None => format!("/admin/route/{}/", change.route),
+    };
+
+    html! {
+        li class="schedule-item" {
+            time datetime=(change.at.to_rfc3339()) { (change.at.format("%H:%M UTC")) }
+            " "
+            @match &change.kind {
+                ChangeKind::RoutePublish => { "Route goes live" }
+                ChangeKind::RouteUnpublish => { "Route is taken down" }
+                ChangeKind::BlockPublish { block_id, type_name } => {
+                    code { (type_name) } " block " small { (block_id) } " appears"
+                }
+                ChangeKind::BlockUnpublish { block_id, type_name } => {
+                    code { (type_name) } " block " small { (block_id) } " expires"
+                }
+                ChangeKind::Draft { .. } => {
+                    "Draft is published"
+                    @if change.at <= now {
+                        " " strong class="schedule-item__overdue" { "(overdue)" }
+                    }
+                }
+            }
+            " on "
+            a href=(editor) {
+                (change.route)
+                @if let Some(locale) = &change.locale {
+                    " (" (locale) ")"
+                }
+            }
+            @if let ChangeKind::Draft { id } = &change.kind
+                && user.can_on_route(Permission::Publish, &change.route) {
+                " "
+                button class="btn-delete" type="button" data-action="cancel" data-draft-id=(id) {
+                    "Cancel"
+                }
+            }
+        }
+    }
+}
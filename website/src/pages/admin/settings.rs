@@ -0,0 +1,280 @@
+/// Site settings admin page and API
+///
+/// Edits the site-wide design tokens (palette, fonts, type and spacing
+/// scales), site name, logo, content locales and the roles that must use
+/// two-factor sign-in, stored in data/settings.json.
+///
+/// # Routes
+///
+/// - `GET /admin/settings/` - Settings form with live preview
+/// - `GET /admin/settings/preview` - Preview document using the saved settings
+/// - `POST /admin/api/settings/preview` - Preview document for unsaved settings (JSON body)
+/// - `POST /admin/api/settings` - Validate and save settings (JSON body)
+///
+/// Every route is for admins only (403 Forbidden for other roles).
+///
+/// # Live Preview
+///
+/// The preview is a complete HTML document rendered into an iframe so the
+/// theme's `:root` variables can't leak into the admin page. It reuses the
+/// component story renderer, showing every story fixture with the theme
+/// applied.
+use axum::Json;
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Response};
+use maud::{DOCTYPE, Markup, html};
+use strum::IntoEnumIterator;
+
+use crate::core::audit::{AuditAction, record_file_changes};
+use crate::core::i18n::FallbackPolicy;
+use crate::core::security_headers::{csp_nonce, csp_nonce_meta};
+use crate::core::settings::{
+    FontChoice, SiteSettings, load_settings, save_settings, settings_json_path, theme_style,
+};
+use crate::core::users::Permission;
+use crate::pages::admin::features::render_all_story_previews;
+use crate::pages::admin::{AuditContext, CurrentUser};
+
+/// Route handler: GET /admin/settings/
+pub async fn settings_page(user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    let settings = load_settings();
+    Html(render_settings_page(&settings).into_string()).into_response()
+}
+
+/// Route handler: GET /admin/settings/preview
+pub async fn settings_preview_saved(user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    Html(render_preview(&load_settings()).into_string()).into_response()
+}
+
+/// Route handler: POST /admin/api/settings/preview
+///
+/// Renders the preview for unsaved settings. Invalid values return
+/// 400 Bad Request with the validation message so the editor can show it.
+pub async fn settings_preview(user: CurrentUser, Json(settings): Json<SiteSettings>) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    match settings.validate() {
+        Ok(()) => Html(render_preview(&settings).into_string()).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response(),
+    }
+}
+
+/// Route handler: POST /admin/api/settings
+///
+/// # Response
+///
+/// - **200 OK**: "Settings saved"
+/// - **400 Bad Request**: Validation message
+/// - **500 Internal Server Error**: Write failure
+///
+/// A successful save is recorded in the audit log with the hash of
+/// `settings.json` before and after.
+pub async fn update_settings(
+    user: CurrentUser,
+    audit: AuditContext,
+    Json(settings): Json<SiteSettings>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    if let Err(e) = settings.validate() {
+        return (StatusCode::BAD_REQUEST, e).into_response();
+    }
+    let entry = audit.entry(AuditAction::SettingsUpdate);
+    match record_file_changes(&entry, &[settings_json_path()], || save_settings(&settings)) {
+        Ok(()) => "Settings saved".into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+/// Render the preview document: every story fixture with the theme applied
+fn render_preview(settings: &SiteSettings) -> Markup {
+    let (story_stylesheets, stories) = render_all_story_previews();
+
+    html! {
+        (DOCTYPE)
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                title { (settings.site_name) " - Theme Preview" }
+                link rel="stylesheet" href="/assets/styles.css";
+                @for stylesheet in story_stylesheets.iter().filter(|s| *s != "/assets/styles.css") {
+                    link rel="stylesheet" href=(stylesheet);
+                }
+                (theme_style(settings))
+            }
+            body {
+                (stories)
+            }
+        }
+    }
+}
+
+/// Render the settings form and preview iframe
+///
+/// Field names are dotted paths into `SiteSettings` (e.g. `palette.primary`);
+/// the `settings-editor` web component turns the form into JSON.
+fn render_settings_page(settings: &SiteSettings) -> Markup {
+    let two_factor_roles: Vec<String> = settings
+        .two_factor_roles
+        .iter()
+        .map(|role| role.to_string())
+        .collect();
+    html! {
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Settings - Admin" }
+                link rel="stylesheet" href="/features/admin/editor/styles.css";
+            }
+            body {
+                h1 { "Site Settings" }
+
+                settings-editor {
+                    form class="settings-form" {
+                        fieldset {
+                            legend { "Site" }
+                            (text_field("site_name", "Site name", &settings.site_name))
+                            (text_field("logo", "Logo media id", settings.logo.as_deref().unwrap_or("")))
+                        }
+
+                        fieldset {
+                            legend { "Palette" }
+                            div class="settings-grid" {
+                                @for (token, value) in settings.palette.tokens() {
+                                    @let name = format!("palette.{}", token.replace('-', "_"));
+                                    div class="form-group" {
+                                        label for=(name) { (token) }
+                                        input id=(name) type="color" name=(name) value=(value);
+                                    }
+                                }
+                            }
+                        }
+
+                        fieldset {
+                            legend { "Fonts" }
+                            (font_select("fonts.display", "Display", settings.fonts.display))
+                            (font_select("fonts.body", "Body", settings.fonts.body))
+                        }
+
+                        fieldset {
+                            legend { "Type scale" }
+                            div class="settings-grid" {
+                                (number_field("type_scale.min_size", "Base size at 360px (px)", settings.type_scale.min_size, "0.5"))
+                                (number_field("type_scale.max_size", "Base size at 2560px (px)", settings.type_scale.max_size, "0.5"))
+                                (number_field("type_scale.min_ratio", "Ratio at 360px", settings.type_scale.min_ratio, "0.01"))
+                                (number_field("type_scale.max_ratio", "Ratio at 2560px", settings.type_scale.max_ratio, "0.01"))
+                            }
+                        }
+
+                        fieldset {
+                            legend { "Spacing scale" }
+                            div class="settings-grid" {
+                                (number_field("spacing.min_size", "Base space at 360px (px)", settings.spacing.min_size, "0.5"))
+                                (number_field("spacing.max_size", "Base space at 2560px (px)", settings.spacing.max_size, "0.5"))
+                            }
+                        }
+
+                        fieldset {
+                            legend { "Locales" }
+                            (text_field("i18n.default_locale", "Default locale", &settings.i18n.default_locale))
+                            (list_field("i18n.locales", "Other locales (comma-separated)", &settings.i18n.locales))
+                            (fallback_select("i18n.fallback", "Untranslated pages", settings.i18n.fallback))
+                        }
+
+                        fieldset {
+                            legend { "Security" }
+                            (list_field("two_factor_roles", "Roles that must use two-factor sign-in (comma-separated)", &two_factor_roles))
+                        }
+
+                        div class="button-group" {
+                            button type="submit" { "Save Settings" }
+                        }
+                    }
+
+                    div class="settings-preview" {
+                        h2 { "Preview" }
+                        iframe class="settings-preview__frame" title="Theme preview" src="/admin/settings/preview" {}
+                    }
+
+                    message-banner {}
+                }
+
+                div class="button-group" {
+                    a href="/admin" {
+                        button type="button" { "Back to Admin" }
+                    }
+                }
+
+                script type="module" nonce=[csp_nonce()] src="/features/admin/editor/components/index.js" {}
+            }
+        }
+    }
+}
+
+fn text_field(name: &str, label: &str, value: &str) -> Markup {
+    html! {
+        div class="form-group" {
+            label for=(name) { (label) }
+            input id=(name) type="text" name=(name) value=(value);
+        }
+    }
+}
+
+fn list_field(name: &str, label: &str, values: &[String]) -> Markup {
+    html! {
+        div class="form-group" {
+            label for=(name) { (label) }
+            input id=(name) type="text" name=(name) value=(values.join(", ")) data-type="list";
+        }
+    }
+}
+
+fn number_field(name: &str, label: &str, value: f64, step: &str) -> Markup {
+    html! {
+        div class="form-group" {
+            label for=(name) { (label) }
+            input id=(name) type="number" name=(name) value=(value) step=(step) data-type="number";
+        }
+    }
+}
+
+fn font_select(name: &str, label: &str, selected: FontChoice) -> Markup {
+    html! {
+        div class="form-group" {
+            label for=(name) { (label) }
+            select id=(name) name=(name) {
+                @for font in FontChoice::iter() {
+                    option value=(font) selected[font == selected] { (font.label()) }
+                }
+            }
+        }
+    }
+}
+
+fn fallback_select(name: &str, label: &str, selected: FallbackPolicy) -> Markup {
+    html! {
+        div class="form-group" {
+            label for=(name) { (label) }
+            select id=(name) name=(name) {
+                @for policy in FallbackPolicy::iter() {
+                    option value=(policy) selected[policy == selected] { (policy.label()) }
+                }
+            }
+        }
+    }
+}
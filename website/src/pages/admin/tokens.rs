@@ -0,0 +1,410 @@
+/// API tokens admin screen
+///
+/// Lets each user create and revoke their own API tokens (see
+/// `core::tokens`); admins see and can revoke everyone's. Plain HTML forms;
+/// no JavaScript.
+///
+/// # Routes
+///
+/// - `GET /admin/tokens/` - The user's tokens and the create form
+/// - `POST /admin/tokens/` - Create a token (form: `name`, one checkbox per
+///   scope, `routes`, `expires_in_days`) and show it once
+/// - `POST /admin/tokens/{id}/revoke` - Revoke a token
+///
+/// A user can only give a token scopes they have themselves: `write_content`
+/// needs Edit, `manage_routes` needs Manage routes, and `routes` must be
+/// routes they may edit. Creating and revoking tokens is recorded in the
+/// audit log.
+use axum::Form;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use chrono::{DateTime, Duration, Utc};
+use maud::{Markup, html};
+use serde::Deserialize;
+use strum::IntoEnumIterator;
+
+use crate::app::AppState;
+use crate::core::audit::{AuditAction, hash_file, record};
+use crate::core::security_headers::csp_nonce_meta;
+use crate::core::tokens::{
+    ApiToken, NewToken, TokenError, TokenScope, create_token, load_tokens, revoke_token,
+    tokens_path,
+};
+use crate::core::users::Permission;
+use crate::pages::admin::AuditContext;
+use crate::pages::admin::auth::{CurrentUser, render_account_bar};
+
+/// Expiry choices of the create form, in days; "never" is also offered
+const EXPIRY_DAYS: [i64; 3] = [30, 90, 365];
+
+/// Fields of the create form
+#[derive(Debug, Default, Deserialize)]
+pub struct TokenForm {
+    #[serde(default)]
+    pub name: String,
+    pub read_content: Option<String>,
+    pub write_content: Option<String>,
+    pub manage_routes: Option<String>,
+    #[serde(default)]
+    pub routes: String,
+    #[serde(default)]
+    pub expires_in_days: String,
+}
+
+impl TokenForm {
+    fn scopes(&self) -> Vec<TokenScope> {
+        [
+            (TokenScope::ReadContent, &self.read_content),
+            (TokenScope::WriteContent, &self.write_content),
+            (TokenScope::ManageRoutes, &self.manage_routes),
+        ]
+        .into_iter()
+        .filter(|(_, checked)| checked.is_some())
+        .map(|(scope, _)| scope)
+        .collect()
+    }
+
+    /// The token to create for `user`, or why they can't have it
+    fn new_token(&self, user: &CurrentUser, now: DateTime<Utc>) -> Result<NewToken, String> {
+        let scopes = self.scopes();
+        if let Some(scope) = scopes.iter().find(|scope| !user.can(scope.permission())) {
+            return Err(format!(
+                "You may not {}, so you can't create a {} token",
+                scope.permission().verb(),
+                scope
+            ));
+        }
+
+        let routes: Vec<String> = self
+            .routes
+            .split(',')
+            .map(str::trim)
+            .filter(|route| !route.is_empty())
+            .map(str::to_string)
+            .collect();
+        if let Some(route) = routes
+            .iter()
+            .find(|route| !user.can_on_route(Permission::Edit, route))
+        {
+            return Err(format!("You may not edit route '{}'", route));
+        }
+
+        let expires = match self.expires_in_days.trim() {
+            "" | "never" => None,
+            days => match days.parse::<i64>() {
+                Ok(days) if EXPIRY_DAYS.contains(&days) => Some(now + Duration::days(days)),
+                _ => return Err(format!("Unknown expiry: {}", days)),
+            },
+        };
+
+        Ok(NewToken {
+            name: self.name.clone(),
+            owner: user.name().to_string(),
+            scopes,
+            routes: (!routes.is_empty()).then_some(routes),
+            expires,
+        })
+    }
+}
+
+/// What the screen shows besides the tokens
+enum Notice<'a> {
+    None,
+    Error(&'a str),
+    /// A token was just created; its secret is shown this once
+    Created(&'a ApiToken, &'a str),
+}
+
+/// Route handler: GET /admin/tokens/
+pub async fn tokens_page(State(state): State<AppState>, user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    tokens_response(&state, &user, StatusCode::OK, Notice::None)
+}
+
+/// Route handler: POST /admin/tokens/
+///
+/// # Response
+///
+/// - **201 Created**: The screen, showing the new token once
+/// - **403 Forbidden**: The user may not view the admin
+/// - **422 Unprocessable Entity**: Invalid name, scope, route or expiry
+///   (the screen with the error)
+pub async fn create_token_form(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    audit: AuditContext,
+    Form(form): Form<TokenForm>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    let now = state.clock.now();
+    let new = match form.new_token(&user, now) {
+        Ok(new) => new,
+        Err(message) => {
+            return tokens_response(
+                &state,
+                &user,
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Notice::Error(&message),
+            );
+        }
+    };
+
+    let before = hash_file(&tokens_path());
+    match create_token(new, now) {
+        Ok((token, secret)) => {
+            record(
+                &audit
+                    .entry(AuditAction::TokenCreate)
+                    .target(&token.id)
+                    .hashes(before, hash_file(&tokens_path())),
+            );
+            tokens_response(
+                &state,
+                &user,
+                StatusCode::CREATED,
+                Notice::Created(&token, &secret),
+            )
+        }
+        Err(e) => token_error_response(&state, &user, e),
+    }
+}
+
+/// Route handler: POST /admin/tokens/{id}/revoke
+///
+/// # Response
+///
+/// - **303 See Other**: Revoked; back to the tokens screen
+/// - **403 Forbidden**: The token is someone else's and the user isn't an admin
+/// - **404 Not Found**: No token with that id
+pub async fn revoke_token_form(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    audit: AuditContext,
+    Path(id): Path<String>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::View) {
+        return forbidden.into_response();
+    }
+    let owner = match load_tokens() {
+        Ok(tokens) => tokens
+            .into_iter()
+            .find(|token| token.id == id)
+            .map(|token| token.owner),
+        Err(e) => return token_error_response(&state, &user, e),
+    };
+    match owner {
+        None => return token_error_response(&state, &user, TokenError::NotFound(id)),
+        Some(owner) if owner != user.name() => {
+            if let Err(forbidden) = user.require(Permission::Administer) {
+                return forbidden.into_response();
+            }
+        }
+        Some(_) => {}
+    }
+
+    let before = hash_file(&tokens_path());
+    match revoke_token(&id) {
+        Ok(token) => {
+            record(
+                &audit
+                    .entry(AuditAction::TokenRevoke)
+                    .target(&token.id)
+                    .hashes(before, hash_file(&tokens_path())),
+            );
+            Redirect::to("/admin/tokens/").into_response()
+        }
+        Err(e) => token_error_response(&state, &user, e),
+    }
+}
+
+fn token_error_response(state: &AppState, user: &CurrentUser, error: TokenError) -> Response {
+    let status = match error {
+        TokenError::NotFound(_) => StatusCode::NOT_FOUND,
+        TokenError::InvalidName | TokenError::NoScopes | TokenError::UnknownRoute(_) => {
+            StatusCode::UNPROCESSABLE_ENTITY
+        }
+        TokenError::Json(_) | TokenError::Io(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to save tokens: {}", error),
+            )
+                .into_response();
+        }
+    };
+    tokens_response(state, user, status, Notice::Error(&error.to_string()))
+}
+
+fn tokens_response(
+    state: &AppState,
+    user: &CurrentUser,
+    status: StatusCode,
+    notice: Notice,
+) -> Response {
+    let all = user.can(Permission::Administer);
+    match load_tokens() {
+        Ok(tokens) => {
+            let tokens: Vec<ApiToken> = tokens
+                .into_iter()
+                .filter(|token| all || token.owner == user.name())
+                .collect();
+            let markup = render_tokens_page(user, &tokens, all, state.clock.now(), notice);
+            (status, Html(markup.into_string())).into_response()
+        }
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load tokens: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+fn render_tokens_page(
+    user: &CurrentUser,
+    tokens: &[ApiToken],
+    show_owner: bool,
+    now: DateTime<Utc>,
+    notice: Notice,
+) -> Markup {
+    let date = |at: &DateTime<Utc>| at.format("%Y-%m-%d %H:%M UTC").to_string();
+    html! {
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "API Tokens - Admin" }
+                link rel="stylesheet" href="/features/admin/editor/styles.css";
+            }
+            body {
+                (render_account_bar(user))
+                h1 { "API Tokens" }
+                p {
+                    "Scripts send a token as " code { "Authorization: Bearer …" }
+                    " to " code { "/admin/api/*" } ". A token can do no more than its owner."
+                }
+
+                @match notice {
+                    Notice::None => {}
+                    Notice::Error(error) => {
+                        p class="form-error" role="alert" { (error) }
+                    }
+                    Notice::Created(token, secret) => {
+                        div class="token-created" role="status" {
+                            "Token " strong { (token.name) } " created. "
+                            "Copy it now; it won't be shown again."
+                            code { (secret) }
+                        }
+                    }
+                }
+
+                @if tokens.is_empty() {
+                    p class="block-list-empty" { "No tokens yet." }
+                } @else {
+                    table class="tokens-table" {
+                        thead {
+                            tr {
+                                th scope="col" { "Name" }
+                                @if show_owner {
+                                    th scope="col" { "Owner" }
+                                }
+                                th scope="col" { "Scopes" }
+                                th scope="col" { "Routes" }
+                                th scope="col" { "Created" }
+                                th scope="col" { "Expires" }
+                                th scope="col" { "Last used" }
+                                th scope="col" { span class="visually-hidden" { "Actions" } }
+                            }
+                        }
+                        tbody {
+                            @for token in tokens {
+                                tr {
+                                    td { (token.name) }
+                                    @if show_owner {
+                                        td { (token.owner) }
+                                    }
+                                    td {
+                                        @for (i, scope) in token.scopes.iter().enumerate() {
+                                            @if i > 0 { ", " }
+                                            code { (scope) }
+                                        }
+                                    }
+                                    td {
+                                        @match &token.routes {
+                                            Some(routes) => (routes.join(", ")),
+                                            None => "All routes",
+                                        }
+                                    }
+                                    td { (date(&token.created)) }
+                                    td {
+                                        @match token.expires {
+                                            Some(expires) if expires <= now => strong { "Expired" },
+                                            Some(expires) => (date(&expires)),
+                                            None => "Never",
+                                        }
+                                    }
+                                    td {
+                                        @match &token.last_used {
+                                            Some(last_used) => (date(last_used)),
+                                            None => "Never",
+                                        }
+                                    }
+                                    td {
+                                        form method="post" action=(format!("/admin/tokens/{}/revoke", token.id)) {
+                                            button class="btn-delete" type="submit" { "Revoke" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                h2 { "Create a token" }
+                form class="token-form" method="post" action="/admin/tokens/" {
+                    div class="form-group" {
+                        label for="token-name" { "Name" }
+                        input id="token-name" type="text" name="name" maxlength="60" placeholder="changelog sync" required;
+                    }
+                    fieldset class="scope-options" {
+                        legend { "Scopes" }
+                        @for scope in TokenScope::iter() {
+                            @if user.can(scope.permission()) {
+                                label {
+                                    input type="checkbox" name=(scope) checked[scope == TokenScope::ReadContent];
+                                    " " code { (scope) }
+                                }
+                            }
+                        }
+                    }
+                    div class="form-group" {
+                        label for="token-routes" { "Routes write_content applies to (comma-separated, empty for all)" }
+                        input id="token-routes" type="text" name="routes" placeholder="homepage, foo";
+                    }
+                    div class="form-group" {
+                        label for="token-expires" { "Expires" }
+                        select id="token-expires" name="expires_in_days" {
+                            @for days in EXPIRY_DAYS {
+                                option value=(days) selected[days == 90] { "In " (days) " days" }
+                            }
+                            option value="never" { "Never" }
+                        }
+                    }
+                    div class="button-group" {
+                        button type="submit" { "Create token" }
+                    }
+                }
+
+                div class="button-group" {
+                    a href="/admin" {
+                        button type="button" { "Back to Admin" }
+                    }
+                }
+            }
+        }
+    }
+}
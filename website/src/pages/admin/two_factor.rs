@@ -0,0 +1,414 @@
+/// Two-factor sign-in account screen
+///
+/// Lets each signed-in user turn TOTP two-factor sign-in on and off and
+/// replace their recovery codes (see `core::totp`). Plain HTML forms; the
+/// QR code is an inline SVG, so no JavaScript either.
+///
+/// # Routes
+///
+/// - `GET /admin/account/two-factor` - Status, or a new secret to enroll
+/// - `POST /admin/account/two-factor` - Enroll (form: `secret`, `code`) and
+///   show the recovery codes once
+/// - `POST /admin/account/two-factor/disable` - Turn it off (form: `code`)
+/// - `POST /admin/account/two-factor/recovery-codes` - Replace the recovery
+///   codes (form: `code`) and show them once
+///
+/// The secret is generated when the page is shown and only saved once the
+/// user proves their authenticator has it by entering a code. Turning
+/// two-factor sign-in off or replacing the codes needs a current code too,
+/// and users whose role requires it (`two_factor_roles` in the site
+/// settings) can't turn it off. Every change is recorded in the audit log.
+///
+/// The screen is for user accounts: it is 404 Not Found while the admin is
+/// open.
+use axum::Form;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use maud::{Markup, PreEscaped, html};
+use serde::Deserialize;
+
+use crate::app::AppState;
+use crate::core::audit::{AuditAction, hash_file, record};
+use crate::core::security_headers::csp_nonce_meta;
+use crate::core::settings::load_settings;
+use crate::core::totp::{
+    RECOVERY_CODES, generate_secret, is_secret, provisioning_uri, qr_code_svg,
+};
+use crate::core::users::{
+    UserError, disable_two_factor, enable_two_factor, reset_recovery_codes, users_path,
+    verify_second_factor,
+};
+use crate::pages::admin::AuditContext;
+use crate::pages::admin::auth::{CurrentUser, Forbidden, render_account_bar};
+
+/// Path of the two-factor account screen
+pub const TWO_FACTOR_PATH: &str = "/admin/account/two-factor";
+
+/// Fields of the enroll form
+#[derive(Debug, Default, Deserialize)]
+pub struct EnrollForm {
+    #[serde(default)]
+    pub secret: String,
+    #[serde(default)]
+    pub code: String,
+}
+
+/// Fields of the disable and recovery codes forms
+#[derive(Debug, Default, Deserialize)]
+pub struct CodeForm {
+    #[serde(default)]
+    pub code: String,
+}
+
+/// What the screen shows
+enum Screen<'a> {
+    /// Not enrolled: a secret to scan, with an optional error
+    Enroll {
+        secret: &'a str,
+        error: Option<&'a str>,
+    },
+    /// Enrolled, with `remaining` recovery codes
+    Enrolled {
+        remaining: usize,
+        error: Option<&'a str>,
+        /// Recovery codes just created; shown this once
+        codes: Option<&'a [String]>,
+    },
+}
+
+/// Route handler: GET /admin/account/two-factor
+pub async fn two_factor_page(user: CurrentUser) -> Response {
+    if let Some(response) = no_account(&user) {
+        return response;
+    }
+    match &user.user.two_factor {
+        Some(two_factor) => screen_response(
+            &user,
+            StatusCode::OK,
+            Screen::Enrolled {
+                remaining: two_factor.recovery_codes.len(),
+                error: None,
+                codes: None,
+            },
+        ),
+        None => screen_response(
+            &user,
+            StatusCode::OK,
+            Screen::Enroll {
+                secret: &generate_secret(),
+                error: None,
+            },
+        ),
+    }
+}
+
+/// Route handler: POST /admin/account/two-factor
+///
+/// # Response
+///
+/// - **200 OK**: Enrolled; the screen with the recovery codes. The user's
+///   other sessions are signed out.
+/// - **303 See Other**: Already enrolled; back to the screen
+/// - **422 Unprocessable Entity**: Wrong code or invalid secret (the enroll
+///   form again)
+pub async fn enable_two_factor_form(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    audit: AuditContext,
+    Form(form): Form<EnrollForm>,
+) -> Response {
+    if let Some(response) = no_account(&user) {
+        return response;
+    }
+    if user.user.has_two_factor() {
+        return Redirect::to(TWO_FACTOR_PATH).into_response();
+    }
+    if !is_secret(&form.secret) {
+        return screen_response(
+            &user,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Screen::Enroll {
+                secret: &generate_secret(),
+                error: Some("The secret was invalid; scan this new code instead."),
+            },
+        );
+    }
+
+    let before = hash_file(&users_path());
+    match enable_two_factor(user.name(), &form.secret, &form.code, state.clock.now()) {
+        Ok(codes) => {
+            record(
+                &audit
+                    .entry(AuditAction::TwoFactorEnable)
+                    .target(user.name())
+                    .hashes(before, hash_file(&users_path())),
+            );
+            state
+                .sessions
+                .remove_user(user.name(), user.session.as_deref());
+            screen_response(
+                &user,
+                StatusCode::OK,
+                Screen::Enrolled {
+                    remaining: codes.len(),
+                    error: None,
+                    codes: Some(&codes),
+                },
+            )
+        }
+        Err(UserError::InvalidCode) => screen_response(
+            &user,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Screen::Enroll {
+                secret: &form.secret,
+                error: Some("Wrong code. Check the time on your device and try again."),
+            },
+        ),
+        Err(e) => user_error_response(e),
+    }
+}
+
+/// Route handler: POST /admin/account/two-factor/disable
+///
+/// # Response
+///
+/// - **303 See Other**: Turned off; back to the screen
+/// - **403 Forbidden**: The user's role requires two-factor sign-in
+/// - **422 Unprocessable Entity**: Wrong code (the screen with the error)
+pub async fn disable_two_factor_form(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    audit: AuditContext,
+    Form(form): Form<CodeForm>,
+) -> Response {
+    if let Some(response) = no_account(&user) {
+        return response;
+    }
+    if load_settings().two_factor_roles.contains(&user.user.role) {
+        return Forbidden(format!(
+            "two-factor sign-in is required for the {} role",
+            user.user.role
+        ))
+        .into_response();
+    }
+
+    let before = hash_file(&users_path());
+    if let Err(e) = verify_second_factor(user.name(), &form.code, state.clock.now()) {
+        return code_error_response(&user, e);
+    }
+    match disable_two_factor(user.name()) {
+        Ok(()) => {
+            record(
+                &audit
+                    .entry(AuditAction::TwoFactorDisable)
+                    .target(user.name())
+                    .hashes(before, hash_file(&users_path())),
+            );
+            Redirect::to(TWO_FACTOR_PATH).into_response()
+        }
+        Err(e) => user_error_response(e),
+    }
+}
+
+/// Route handler: POST /admin/account/two-factor/recovery-codes
+///
+/// # Response
+///
+/// - **200 OK**: The screen with the new recovery codes; the old ones no
+///   longer work
+/// - **422 Unprocessable Entity**: Wrong code (the screen with the error)
+pub async fn reset_recovery_codes_form(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    audit: AuditContext,
+    Form(form): Form<CodeForm>,
+) -> Response {
+    if let Some(response) = no_account(&user) {
+        return response;
+    }
+
+    let before = hash_file(&users_path());
+    if let Err(e) = verify_second_factor(user.name(), &form.code, state.clock.now()) {
+        return code_error_response(&user, e);
+    }
+    match reset_recovery_codes(user.name()) {
+        Ok(codes) => {
+            record(
+                &audit
+                    .entry(AuditAction::RecoveryCodesReset)
+                    .target(user.name())
+                    .hashes(before, hash_file(&users_path())),
+            );
+            screen_response(
+                &user,
+                StatusCode::OK,
+                Screen::Enrolled {
+                    remaining: codes.len(),
+                    error: None,
+                    codes: Some(&codes),
+                },
+            )
+        }
+        Err(e) => user_error_response(e),
+    }
+}
+
+/// 404 while the admin is open: there is no account to protect
+fn no_account(user: &CurrentUser) -> Option<Response> {
+    user.open.then(|| {
+        (
+            StatusCode::NOT_FOUND,
+            "Two-factor sign-in needs a user account; add users first",
+        )
+            .into_response()
+    })
+}
+
+fn code_error_response(user: &CurrentUser, error: UserError) -> Response {
+    match error {
+        UserError::InvalidCode => screen_response(
+            user,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Screen::Enrolled {
+                remaining: user
+                    .user
+                    .two_factor
+                    .as_ref()
+                    .map_or(0, |two_factor| two_factor.recovery_codes.len()),
+                error: Some(&error.to_string()),
+                codes: None,
+            },
+        ),
+        e => user_error_response(e),
+    }
+}
+
+fn user_error_response(error: UserError) -> Response {
+    let status = match error {
+        UserError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (
+        status,
+        format!("Failed to update two-factor sign-in: {}", error),
+    )
+        .into_response()
+}
+
+fn screen_response(user: &CurrentUser, status: StatusCode, screen: Screen) -> Response {
+    let settings = load_settings();
+    let required = settings.two_factor_roles.contains(&user.user.role);
+    let markup = render_two_factor_page(user, &settings.site_name, required, screen);
+    (status, Html(markup.into_string())).into_response()
+}
+
+fn render_two_factor_page(
+    user: &CurrentUser,
+    issuer: &str,
+    required: bool,
+    screen: Screen,
+) -> Markup {
+    html! {
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Two-Factor Sign-In - Admin" }
+                link rel="stylesheet" href="/features/admin/editor/styles.css";
+            }
+            body {
+                (render_account_bar(user))
+                h1 { "Two-Factor Sign-In" }
+
+                @match screen {
+                    Screen::Enroll { secret, error } => {
+                        @if required {
+                            p class="form-error" role="alert" {
+                                "Your role (" (user.user.role) ") must use two-factor sign-in. "
+                                "Set it up to continue."
+                            }
+                        }
+                        p {
+                            "Two-factor sign-in is off. Scan this code with an authenticator app, "
+                            "then enter the 6-digit code it shows."
+                        }
+                        @if let Some(error) = error {
+                            p class="form-error" role="alert" { (error) }
+                        }
+                        @let uri = provisioning_uri(issuer, user.name(), secret);
+                        @if let Some(svg) = qr_code_svg(&uri) {
+                            div class="qr-code" role="img" aria-label="QR code for your authenticator app" {
+                                (PreEscaped(svg))
+                            }
+                        }
+                        p {
+                            "Can't scan it? Enter this key instead: "
+                            code class="totp-secret" { (secret) }
+                        }
+                        form class="two-factor-form" method="post" action=(TWO_FACTOR_PATH) {
+                            input type="hidden" name="secret" value=(secret);
+                            div class="form-group" {
+                                label for="two-factor-code" { "Code" }
+                                input id="two-factor-code" type="text" name="code" inputmode="numeric" autocomplete="one-time-code" pattern="[0-9 ]*" required autofocus;
+                            }
+                            div class="button-group" {
+                                button type="submit" { "Turn on two-factor sign-in" }
+                            }
+                        }
+                    }
+                    Screen::Enrolled { remaining, error, codes } => {
+                        p { "Two-factor sign-in is on. Signing in needs a code from your authenticator app." }
+                        @if let Some(codes) = codes {
+                            div class="recovery-codes" role="status" {
+                                "Save these recovery codes somewhere safe. Each signs you in once "
+                                "if you lose your authenticator; they won't be shown again."
+                                ul {
+                                    @for code in codes {
+                                        li { code { (code) } }
+                                    }
+                                }
+                            }
+                        } @else {
+                            p { (remaining) " of " (RECOVERY_CODES) " recovery codes left." }
+                        }
+                        @if let Some(error) = error {
+                            p class="form-error" role="alert" { (error) }
+                        }
+
+                        h2 { "New recovery codes" }
+                        form class="two-factor-form" method="post" action=(format!("{}/recovery-codes", TWO_FACTOR_PATH)) {
+                            div class="form-group" {
+                                label for="recovery-codes-code" { "Current code" }
+                                input id="recovery-codes-code" type="text" name="code" autocomplete="one-time-code" required;
+                            }
+                            div class="button-group" {
+                                button type="submit" { "Replace recovery codes" }
+                            }
+                        }
+
+                        @if !required {
+                            h2 { "Turn off" }
+                            form class="two-factor-form" method="post" action=(format!("{}/disable", TWO_FACTOR_PATH)) {
+                                div class="form-group" {
+                                    label for="disable-code" { "Current code" }
+                                    input id="disable-code" type="text" name="code" autocomplete="one-time-code" required;
+                                }
+                                div class="button-group" {
+                                    button class="btn-delete" type="submit" { "Turn off two-factor sign-in" }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                div class="button-group" {
+                    a href="/admin" {
+                        button type="button" { "Back to Admin" }
+                    }
+                }
+            }
+        }
+    }
+}
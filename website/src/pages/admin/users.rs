@@ -0,0 +1,354 @@
+/// Users and roles admin screen
+///
+/// Lists the admin users with their role and the routes they may edit, and
+/// lets admins add users, change roles, grants and passwords, and delete
+/// users (see `core::users`). Plain HTML forms; no JavaScript.
+///
+/// # Routes
+///
+/// - `GET /admin/users/` - Users table and the add/update form
+/// - `POST /admin/users/` - Add a user, or update the user with that name
+///   (form: `name`, `role`, `routes`, `password`)
+/// - `POST /admin/users/{name}/delete` - Delete a user
+/// - `POST /admin/users/{name}/two-factor/reset` - Turn off a user's
+///   two-factor sign-in, e.g. after they lost their authenticator
+///
+/// `routes` is a comma-separated list of route names; empty means every
+/// route. An empty `password` keeps an existing user's password.
+///
+/// Changing a user's password signs out their other sessions; deleting a
+/// user signs out all of them. These and two-factor resets are recorded in
+/// the audit log.
+use axum::Form;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use maud::{Markup, html};
+use serde::Deserialize;
+use strum::IntoEnumIterator;
+
+use crate::app::AppState;
+use crate::core::audit::{AuditAction, hash_file, record};
+use crate::core::persistence::{data_dir, with_data_dir_sync};
+use crate::core::security_headers::csp_nonce_meta;
+use crate::core::settings::load_settings;
+use crate::core::users::{
+    MIN_PASSWORD_CHARS, Permission, Role, User, UserError, UserUpdate, delete_user,
+    disable_two_factor, load_users, save_user, users_path,
+};
+use crate::pages::admin::AuditContext;
+use crate::pages::admin::auth::{CurrentUser, render_account_bar};
+
+/// Fields of the add/update form
+#[derive(Debug, Default, Deserialize)]
+pub struct UserForm {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub role: String,
+    #[serde(default)]
+    pub routes: String,
+    #[serde(default)]
+    pub password: String,
+}
+
+impl UserForm {
+    fn update(&self) -> Result<UserUpdate, String> {
+        let role: Role = self
+            .role
+            .trim()
+            .parse()
+            .map_err(|_| format!("Unknown role: {}", self.role))?;
+        let routes: Vec<String> = self
+            .routes
+            .split(',')
+            .map(str::trim)
+            .filter(|route| !route.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok(UserUpdate {
+            name: self.name.trim().to_string(),
+            role,
+            routes: (!routes.is_empty()).then_some(routes),
+            password: Some(self.password.clone()).filter(|password| !password.is_empty()),
+        })
+    }
+}
+
+/// Route handler: GET /admin/users/
+pub async fn users_page(user: CurrentUser) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    users_response(&user, StatusCode::OK, None)
+}
+
+/// Route handler: POST /admin/users/
+///
+/// # Response
+///
+/// - **303 See Other**: Saved; back to the users screen
+/// - **403 Forbidden**: The user isn't an admin
+/// - **422 Unprocessable Entity**: Invalid name, role, route or password,
+///   or the change would leave no admin (the screen with the error)
+pub async fn save_user_form(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    audit: AuditContext,
+    Form(form): Form<UserForm>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    let update = match form.update() {
+        Ok(update) => update,
+        Err(message) => {
+            return users_response(&user, StatusCode::UNPROCESSABLE_ENTITY, Some(&message));
+        }
+    };
+    let (name, password_changed) = (update.name.clone(), update.password.is_some());
+
+    // Password hashing is slow on purpose; keep it off the async workers
+    let dir = data_dir();
+    let result = tokio::task::spawn_blocking(move || {
+        with_data_dir_sync(dir, || {
+            let before = hash_file(&users_path());
+            save_user(update).map(|_| (before, hash_file(&users_path())))
+        })
+    })
+    .await;
+
+    match result {
+        Ok(Ok((before, after))) => {
+            record(
+                &audit
+                    .entry(AuditAction::UserSave)
+                    .target(&name)
+                    .hashes(before, after),
+            );
+            if password_changed {
+                state.sessions.remove_user(&name, user.session.as_deref());
+            }
+            Redirect::to("/admin/users/").into_response()
+        }
+        Ok(Err(e)) => user_error_response(&user, e),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to save user: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+/// Route handler: POST /admin/users/{name}/delete
+///
+/// # Response
+///
+/// - **303 See Other**: Deleted; back to the users screen
+/// - **403 Forbidden**: The user isn't an admin
+/// - **404 Not Found**: No user with that name
+/// - **422 Unprocessable Entity**: It's the last admin
+pub async fn delete_user_form(
+    State(state): State<AppState>,
+    user: CurrentUser,
+    audit: AuditContext,
+    Path(name): Path<String>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    let before = hash_file(&users_path());
+    match delete_user(&name) {
+        Ok(()) => {
+            record(
+                &audit
+                    .entry(AuditAction::UserDelete)
+                    .target(&name)
+                    .hashes(before, hash_file(&users_path())),
+            );
+            state.sessions.remove_user(&name, None);
+            Redirect::to("/admin/users/").into_response()
+        }
+        Err(e) => user_error_response(&user, e),
+    }
+}
+
+/// Route handler: POST /admin/users/{name}/two-factor/reset
+///
+/// The user can sign in with their password alone again, and enroll anew
+/// (which they must, if their role requires two-factor sign-in).
+///
+/// # Response
+///
+/// - **303 See Other**: Reset; back to the users screen
+/// - **403 Forbidden**: The user isn't an admin
+/// - **404 Not Found**: No user with that name
+pub async fn reset_two_factor_form(
+    user: CurrentUser,
+    audit: AuditContext,
+    Path(name): Path<String>,
+) -> Response {
+    if let Err(forbidden) = user.require(Permission::Administer) {
+        return forbidden.into_response();
+    }
+    let before = hash_file(&users_path());
+    match disable_two_factor(&name) {
+        Ok(()) => {
+            record(
+                &audit
+                    .entry(AuditAction::TwoFactorDisable)
+                    .target(&name)
+                    .hashes(before, hash_file(&users_path())),
+            );
+            Redirect::to("/admin/users/").into_response()
+        }
+        Err(e) => user_error_response(&user, e),
+    }
+}
+
+fn user_error_response(user: &CurrentUser, error: UserError) -> Response {
+    let status = match error {
+        UserError::NotFound(_) => StatusCode::NOT_FOUND,
+        UserError::InvalidName(_)
+        | UserError::PasswordTooShort
+        | UserError::PasswordRequired
+        | UserError::UnknownRoute(_)
+        | UserError::LastAdmin
+        | UserError::InvalidCode => StatusCode::UNPROCESSABLE_ENTITY,
+        UserError::Json(_) | UserError::Io(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to save users: {}", error),
+            )
+                .into_response();
+        }
+    };
+    users_response(user, status, Some(&error.to_string()))
+}
+
+fn users_response(user: &CurrentUser, status: StatusCode, error: Option<&str>) -> Response {
+    match load_users() {
+        Ok(users) => (
+            status,
+            Html(render_users_page(user, &users, error).into_string()),
+        )
+            .into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load users: {}", e),
+        )
+            .into_response(),
+    }
+}
+
+fn render_users_page(current: &CurrentUser, users: &[User], error: Option<&str>) -> Markup {
+    let two_factor_roles = load_settings().two_factor_roles;
+    html! {
+        html {
+            head {
+                meta charset="utf-8";
+                (csp_nonce_meta())
+                meta name="viewport" content="width=device-width, initial-scale=1";
+                title { "Users - Admin" }
+                link rel="stylesheet" href="/features/admin/editor/styles.css";
+            }
+            body {
+                (render_account_bar(current))
+                h1 { "Users" }
+
+                @if let Some(error) = error {
+                    p class="form-error" role="alert" { (error) }
+                }
+
+                @if users.is_empty() {
+                    p class="block-list-empty" {
+                        "No users yet: the admin is open to everyone. "
+                        "Add an admin to require sign-in."
+                    }
+                } @else {
+                    table class="users-table" {
+                        thead {
+                            tr {
+                                th scope="col" { "Name" }
+                                th scope="col" { "Role" }
+                                th scope="col" { "Routes" }
+                                th scope="col" { "Two-factor" }
+                                th scope="col" { span class="visually-hidden" { "Actions" } }
+                            }
+                        }
+                        tbody {
+                            @for user in users {
+                                tr {
+                                    td { (user.name) }
+                                    td { (user.role) }
+                                    td {
+                                        @match (&user.routes, user.role) {
+                                            (_, Role::Admin) | (None, _) => "All routes",
+                                            (Some(routes), _) if routes.is_empty() => "None",
+                                            (Some(routes), _) => (routes.join(", ")),
+                                        }
+                                    }
+                                    td {
+                                        @if user.has_two_factor() {
+                                            form method="post" action=(format!("/admin/users/{}/two-factor/reset", user.name)) {
+                                                "On "
+                                                button type="submit" { "Reset" }
+                                            }
+                                        } @else if two_factor_roles.contains(&user.role) {
+                                            strong { "Required, not set up" }
+                                        } @else {
+                                            "Off"
+                                        }
+                                    }
+                                    td {
+                                        form method="post" action=(format!("/admin/users/{}/delete", user.name)) {
+                                            button class="btn-delete" type="submit" { "Delete" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                h2 { "Add or update a user" }
+                p {
+                    "Saving an existing name changes that user. Leave the password empty to keep it."
+                }
+                form class="user-form" method="post" action="/admin/users/" {
+                    div class="form-group" {
+                        label for="user-name" { "Name" }
+                        input id="user-name" type="text" name="name" maxlength="40" required;
+                    }
+                    div class="form-group" {
+                        label for="user-role" { "Role" }
+                        select id="user-role" name="role" {
+                            @for role in Role::iter() {
+                                option value=(role) selected[users.is_empty() && role == Role::Admin] {
+                                    (role)
+                                }
+                            }
+                        }
+                    }
+                    div class="form-group" {
+                        label for="user-routes" { "Routes (comma-separated, empty for all)" }
+                        input id="user-routes" type="text" name="routes" placeholder="homepage, foo";
+                    }
+                    div class="form-group" {
+                        label for="user-password" { "Password (at least " (MIN_PASSWORD_CHARS) " characters)" }
+                        input id="user-password" type="password" name="password" autocomplete="new-password";
+                    }
+                    div class="button-group" {
+                        button type="submit" { "Save user" }
+                    }
+                }
+
+                div class="button-group" {
+                    a href="/admin" {
+                        button type="button" { "Back to Admin" }
+                    }
+                }
+            }
+        }
+    }
+}
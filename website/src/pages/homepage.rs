@@ -17,14 +17,38 @@
 /// - Type-safe component selection
 /// - Content editors to compose pages without code
 /// - Easy addition of new block types
-use axum::response::Html;
-use maud::html;
+///
+/// # Locales
+///
+/// When locales are configured (see `core::i18n`), `/` negotiates a locale
+/// from `Accept-Language` and `/{locale}/` serves each translation, with
+/// `hreflang` links between them.
+///
+/// # Scheduling
+///
+/// Blocks outside their publish window are left out, and the whole page is
+/// not found outside the route's window (see `core::schedule`). "Now" comes
+/// from `AppState::clock`.
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
+use axum::response::{Html, IntoResponse, Redirect, Response};
+use chrono::{DateTime, Utc};
+use maud::{Markup, html};
 use serde::{Deserialize, Serialize};
 
-use crate::core::{BlockWithId, block::Block, load_homepage_blocks, render_block};
+use crate::app::AppState;
+use crate::core::i18n::load_localized_content;
+use crate::core::media::find_media;
+use crate::core::migrations::CONTENT_VERSION;
+use crate::core::options::BlockOptions;
+use crate::core::persistence::find_route_by_path;
+use crate::core::schedule::{Schedule, live_blocks};
+use crate::core::settings::{SiteSettings, load_settings, theme_style};
+use crate::core::{BlockWithId, ContentBlock, block::Block, render_content_block};
 use crate::features::button::ButtonProps;
 use crate::features::header::HeaderProps;
 use crate::features::hero::HeroProps;
+use crate::pages::seo::base_url;
 
 // ============================================================================
 // Homepage Data Structure
@@ -42,15 +66,32 @@ use crate::features::hero::HeroProps;
 /// - It's specific to the homepage, not a core domain concern
 /// - Pages layer composes core blocks into page-specific structures
 /// - Core remains focused on the block primitives
+///
+/// # Versioning
+///
+/// `version` is the content schema version the file was written with (see
+/// `core::migrations`). Files from before versioning have no `version` field
+/// and deserialize as version 0; stored files are upgraded through the
+/// migration chain on load, and `new` always stamps the current version.
+///
+/// `blocks` deserializes leniently: a block that doesn't parse is kept as
+/// `ContentBlock::Invalid` rather than failing the whole document (see
+/// `core::block`).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HomepageData {
-    pub blocks: Vec<BlockWithId>,
+    #[serde(default)]
+    pub version: u32,
+    pub blocks: Vec<ContentBlock>,
 }
 
 impl HomepageData {
-    /// Create a new HomepageData with the given blocks
-    pub fn new(blocks: Vec<BlockWithId>) -> Self {
-        Self { blocks }
+    /// Create a new HomepageData with the given blocks at the current
+    /// content schema version
+    pub fn new(blocks: Vec<ContentBlock>) -> Self {
+        Self {
+            version: CONTENT_VERSION,
+            blocks,
+        }
     }
 
     /// Get default homepage blocks when no persisted data exists
@@ -69,6 +110,8 @@ impl HomepageData {
                         aria_label: "Contact us to discuss your engineering needs".to_string(),
                     },
                 }),
+                options: BlockOptions::default(),
+                schedule: Schedule::default(),
             },
             BlockWithId {
                 id: "550e8400-e29b-41d4-a716-446655440002".to_string(),
@@ -76,6 +119,8 @@ impl HomepageData {
                     headline: "Building world-class engineering teams".to_string(),
                     subheadline: "Leadership through example, expertise, and empathy".to_string(),
                 }),
+                options: BlockOptions::default(),
+                schedule: Schedule::default(),
             },
         ]
     }
@@ -86,14 +131,21 @@ impl HomepageData {
 /// Loads blocks from data/homepage.json and renders them in sequence.
 /// Each block is rendered using its corresponding feature component.
 ///
+/// On a multilingual site, visitors whose `Accept-Language` prefers another
+/// locale are redirected to `/{locale}/`; the response varies on
+/// `Accept-Language` either way.
+///
 /// # Layout Structure
 ///
 /// ```html
-/// <html>
+/// <html lang="en">
 ///   <head>
+///     <link rel="alternate" hreflang="de" href="https://example.com/de/">
 ///     <link rel="stylesheet" href="/assets/styles.css">
+///     <style id="theme-tokens">:root { --color-primary: ...; }</style>
 ///     <link rel="stylesheet" href="/features/header/styles.css">
 ///     <link rel="stylesheet" href="/features/hero/styles.css">
+///     <link rel="stylesheet" href="/features/image/styles.css">
 ///   </head>
 ///   <body>
 ///     <!-- Blocks rendered here -->
@@ -107,31 +159,132 @@ impl HomepageData {
 /// - Styles are available before render (no FOUC)
 /// - Browser can cache per-component stylesheets
 /// - Clear dependency between components and their styles
-pub async fn homepage() -> Html<String> {
-    let blocks = load_homepage_blocks();
+pub async fn homepage(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    let now = state.clock.now();
+    let settings = load_settings();
+    if !settings.i18n.is_multilingual() {
+        return render_localized(&settings, &settings.i18n.default_locale, now);
+    }
+
+    let accept_language = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok());
+    let locale = settings.i18n.negotiate(accept_language);
+    let mut response = if locale == settings.i18n.default_locale {
+        render_localized(&settings, locale, now)
+    } else {
+        Redirect::temporary(&format!("/{}/", locale)).into_response()
+    };
+    response
+        .headers_mut()
+        .insert(header::VARY, HeaderValue::from_static("accept-language"));
+    response
+}
+
+/// GET /{locale}/ - Homepage in a specific locale
+///
+/// Responds 404 for locales that aren't configured, and for untranslated
+/// content when the fallback policy is `NotFound`.
+pub async fn localized_homepage(
+    State(state): State<AppState>,
+    Path(locale): Path<String>,
+) -> Response {
+    let settings = load_settings();
+    match settings.i18n.find(&locale) {
+        Some(locale) => render_localized(&settings, locale, state.clock.now()),
+        None => not_found(),
+    }
+}
+
+fn not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Html("<h1>404 Not Found</h1>".to_string()),
+    )
+        .into_response()
+}
+
+/// Render the homepage in `locale`, applying the fallback policy
+fn render_localized(settings: &SiteSettings, locale: &str, now: DateTime<Utc>) -> Response {
+    if find_route_by_path("/").is_some_and(|route| !route.schedule.is_live(now)) {
+        return not_found();
+    }
+    let Some(mut blocks) = load_localized_content("homepage", locale, &settings.i18n) else {
+        return not_found();
+    };
+    // No content yet: show the built-in example blocks
+    if blocks.is_empty() {
+        blocks = HomepageData::default_blocks()
+            .into_iter()
+            .map(ContentBlock::from)
+            .collect();
+    }
+    let blocks = live_blocks(blocks, now);
+
+    let alternates = if settings.i18n.is_multilingual() {
+        let base = base_url();
+        let mut links: Vec<(String, String)> = settings
+            .i18n
+            .all()
+            .map(|l| (l.to_string(), format!("{}/{}/", base, l)))
+            .collect();
+        links.push(("x-default".to_string(), format!("{}/", base)));
+        links
+    } else {
+        vec![]
+    };
+
+    Html(render_homepage(settings, locale, &blocks, &alternates).into_string()).into_response()
+}
 
-    let markup = html! {
-        html {
+/// Render the homepage document
+///
+/// `alternates` are (hreflang, URL) pairs for the page's
+/// translations, empty on a single-language site.
+fn render_homepage(
+    settings: &SiteSettings,
+    locale: &str,
+    blocks: &[ContentBlock],
+    alternates: &[(String, String)],
+) -> Markup {
+    let noindex = find_route_by_path("/").is_some_and(|route| route.noindex);
+    let logo = settings.logo.as_deref().and_then(find_media);
+
+    html! {
+        html lang=(locale) {
             head {
                 meta charset="utf-8";
                 meta name="viewport" content="width=device-width, initial-scale=1";
-                title { "Eng Manager" }
+                @if noindex {
+                    meta name="robots" content="noindex";
+                }
+                title { (settings.site_name) }
+                @for (hreflang, href) in alternates {
+                    link rel="alternate" hreflang=(hreflang) href=(href);
+                }
+                meta property="og:site_name" content=(settings.site_name);
+                @if let Some(logo) = &logo {
+                    meta property="og:image" content=(logo.default_src());
+                    link rel="icon" href=(logo.default_src());
+                }
 
                 // Global styles (Utopia fluid typography, resets)
                 link rel="stylesheet" href="/assets/styles.css";
 
+                // Theme tokens from data/settings.json override the static defaults
+                (theme_style(settings))
+
                 // Feature-specific styles
                 link rel="stylesheet" href="/features/header/styles.css";
                 link rel="stylesheet" href="/features/hero/styles.css";
+                link rel="stylesheet" href="/features/image/styles.css";
             }
             body {
                 // Render blocks in sequence
-                @for block in &blocks {
-                    (render_block(block))
+                @for block in blocks {
+                    (render_content_block(block))
                 }
             }
         }
-    };
-
-    Html(markup.into_string())
+    }
 }
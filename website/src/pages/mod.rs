@@ -15,6 +15,7 @@
 ///
 /// - **homepage**: Dynamic block-based homepage
 /// - **admin**: Administrative interface for content management
+/// - **seo**: sitemap.xml and robots.txt generated from routes.json
 ///
 /// # Relationship to Features
 ///
@@ -23,6 +24,8 @@
 /// - **Core** provides shared types and operations
 pub mod admin;
 pub mod homepage;
+pub mod seo;
 
 // Re-export route handlers for convenience
-pub use homepage::homepage;
+pub use homepage::{homepage, localized_homepage};
+pub use seo::{robots_txt, sitemap_xml};
@@ -0,0 +1,311 @@
+/// Search engine discovery: sitemap.xml and robots.txt
+///
+/// Both documents are generated on each request from routes.json and the
+/// locale settings, so publishing a page or adding a locale is enough for
+/// crawlers (and Cloudflare) to find it.
+///
+/// # Architecture
+///
+/// Following axum-web-framework patterns, handlers are thin: they gather the
+/// routes and the public base URL, then delegate to pure rendering functions
+/// that are easy to unit test.
+///
+/// # Listed Pages
+///
+/// Only URLs the router serves are listed: the route at `/` is the one
+/// route with a public page, served at `/` and at `/{locale}/` for every
+/// configured locale whose content can be shown (see `core::i18n`). A route
+/// is left out when:
+/// - Its path starts with one of `EXCLUDED_PREFIXES` (`/admin`, `/features`, `/assets`)
+/// - It is flagged `noindex` or `excludeFromSitemap` in routes.json
+/// - It is outside its publish window (see `core::schedule`)
+///
+/// The same prefixes are disallowed in robots.txt.
+///
+/// # Base URL
+///
+/// Absolute URLs use `SITE_URL` (e.g. `https://engmanager.xyz`). Without it,
+/// sitemap URLs are relative and robots.txt has no `Sitemap:` line; the
+/// request's `Host` header is never used, since clients control it.
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+
+use crate::app::AppState;
+use crate::core::i18n::load_localized_content;
+use crate::core::persistence::content_last_modified;
+use crate::core::settings::load_settings;
+use crate::core::{Route, load_routes};
+
+/// Environment variable holding the canonical public origin
+const SITE_URL_ENV_VAR: &str = "SITE_URL";
+
+/// Path prefixes that are never advertised to crawlers
+pub const EXCLUDED_PREFIXES: [&str; 3] = ["/admin", "/features", "/assets"];
+
+/// A single `<url>` entry in sitemap.xml
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SitemapEntry {
+    /// URL of the page; absolute when `SITE_URL` is set
+    pub loc: String,
+    /// Last content modification time, if known
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// Route handler: GET /sitemap.xml
+pub async fn sitemap_xml(State(state): State<AppState>) -> impl IntoResponse {
+    let i18n = load_settings().i18n;
+    let locales: Vec<String> = i18n
+        .locales
+        .iter()
+        .filter(|locale| load_localized_content("homepage", locale, &i18n).is_some())
+        .cloned()
+        .collect();
+    let entries = sitemap_entries(
+        &load_routes(),
+        &base_url(),
+        state.clock.now(),
+        &locales,
+        |route| content_last_modified(route).map(DateTime::<Utc>::from),
+    );
+
+    (
+        [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+        render_sitemap(&entries),
+    )
+}
+
+/// Route handler: GET /robots.txt
+pub async fn robots_txt() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        render_robots(&base_url()),
+    )
+}
+
+/// Whether a route should be listed in sitemap.xml
+pub fn is_listed(route: &Route) -> bool {
+    !route.noindex
+        && !route.exclude_from_sitemap
+        && !EXCLUDED_PREFIXES
+            .iter()
+            .any(|prefix| has_path_prefix(&route.path, prefix))
+}
+
+/// Public paths of a route: `/` and `/{locale}/` for the homepage route,
+/// none for the others, which have no public page
+fn served_paths(route: &Route, locales: &[String]) -> Vec<String> {
+    if route.path != "/" {
+        return vec![];
+    }
+    std::iter::once("/".to_string())
+        .chain(locales.iter().map(|locale| format!("/{}/", locale)))
+        .collect()
+}
+
+/// Build sitemap entries for every served page of the listed, live routes
+///
+/// `locales` are the non-default locales with content to show.
+/// `lastmod_for` is injected so tests don't depend on filesystem timestamps.
+pub fn sitemap_entries(
+    routes: &[Route],
+    base_url: &str,
+    now: DateTime<Utc>,
+    locales: &[String],
+    lastmod_for: impl Fn(&Route) -> Option<DateTime<Utc>>,
+) -> Vec<SitemapEntry> {
+    routes
+        .iter()
+        .filter(|route| is_listed(route) && route.schedule.is_live(now))
+        .flat_map(|route| {
+            let lastmod = lastmod_for(route);
+            served_paths(route, locales)
+                .into_iter()
+                .map(move |path| SitemapEntry {
+                    loc: format!("{}{}", base_url, path),
+                    lastmod,
+                })
+        })
+        .collect()
+}
+
+/// Render entries as a sitemaps.org urlset document
+pub fn render_sitemap(entries: &[SitemapEntry]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+    for entry in entries {
+        xml.push_str("  <url>\n");
+        xml.push_str(&format!("    <loc>{}</loc>\n", escape_xml(&entry.loc)));
+        if let Some(lastmod) = entry.lastmod {
+            xml.push_str(&format!(
+                "    <lastmod>{}</lastmod>\n",
+                lastmod.format("%Y-%m-%dT%H:%M:%SZ")
+            ));
+        }
+        xml.push_str("  </url>\n");
+    }
+    xml.push_str("</urlset>\n");
+    xml
+}
+
+/// Render robots.txt, disallowing the excluded prefixes
+///
+/// A `Disallow` rule matches by prefix, so `/admin` covers the admin index
+/// as well as everything below it. The `Sitemap:` line needs an absolute
+/// URL, so it is left out while `base_url` is empty.
+pub fn render_robots(base_url: &str) -> String {
+    let mut txt = String::from("User-agent: *\n");
+    for prefix in EXCLUDED_PREFIXES {
+        txt.push_str(&format!("Disallow: {}\n", prefix));
+    }
+    if !base_url.is_empty() {
+        txt.push_str(&format!("\nSitemap: {}/sitemap.xml\n", base_url));
+    }
+    txt
+}
+
+/// The public origin from `SITE_URL`, without a trailing slash
+///
+/// Empty when unset, which makes URLs built from it relative.
+pub(crate) fn base_url() -> String {
+    std::env::var(SITE_URL_ENV_VAR)
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .unwrap_or_default()
+}
+
+/// Match a path against a prefix on segment boundaries
+///
+/// `/admin` matches `/admin` and `/admin/route/` but not `/administrivia`.
+fn has_path_prefix(path: &str, prefix: &str) -> bool {
+    path == prefix
+        || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Escape the five XML special characters
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::schedule::Schedule;
+    use chrono::TimeZone;
+
+    fn route(path: &str) -> Route {
+        Route {
+            path: path.to_string(),
+            name: path.trim_matches('/').to_string(),
+            block_ids: vec![],
+            ..Default::default()
+        }
+    }
+
+    fn noon() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+    }
+
+    fn locs(routes: &[Route], base_url: &str, locales: &[&str]) -> Vec<String> {
+        let locales: Vec<String> = locales.iter().map(|l| l.to_string()).collect();
+        sitemap_entries(routes, base_url, noon(), &locales, |_| None)
+            .into_iter()
+            .map(|entry| entry.loc)
+            .collect()
+    }
+
+    #[test]
+    fn test_sitemap_lists_only_served_pages() {
+        let routes = vec![
+            route("/"),
+            route("/foo"),
+            route("/admin/route/"),
+            route("/features/hero"),
+            route("/assets/styles.css"),
+        ];
+
+        assert_eq!(
+            locs(&routes, "https://example.com", &["de", "fr"]),
+            vec![
+                "https://example.com/",
+                "https://example.com/de/",
+                "https://example.com/fr/"
+            ]
+        );
+        assert_eq!(locs(&routes, "", &[]), vec!["/"]);
+    }
+
+    #[test]
+    fn test_sitemap_skips_flagged_and_unpublished_routes() {
+        let hidden = [
+            Route {
+                noindex: true,
+                ..route("/")
+            },
+            Route {
+                exclude_from_sitemap: true,
+                ..route("/")
+            },
+            Route {
+                schedule: Schedule {
+                    publish_at: Some(noon() + chrono::Duration::hours(1)),
+                    ..Default::default()
+                },
+                ..route("/")
+            },
+            Route {
+                schedule: Schedule {
+                    unpublish_at: Some(noon()),
+                    ..Default::default()
+                },
+                ..route("/")
+            },
+        ];
+        for route in hidden {
+            assert!(locs(&[route], "https://example.com", &["de"]).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_render_sitemap_includes_lastmod_and_escapes() {
+        let lastmod = Utc.with_ymd_and_hms(2025, 11, 25, 5, 35, 55).unwrap();
+        let entries = vec![
+            SitemapEntry {
+                loc: "https://example.com/?a=1&b=2".to_string(),
+                lastmod: Some(lastmod),
+            },
+            SitemapEntry {
+                loc: "https://example.com/foo".to_string(),
+                lastmod: None,
+            },
+        ];
+
+        let xml = render_sitemap(&entries);
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>"));
+        assert!(xml.contains("<loc>https://example.com/?a=1&amp;b=2</loc>"));
+        assert!(xml.contains("<lastmod>2025-11-25T05:35:55Z</lastmod>"));
+        assert_eq!(xml.matches("<lastmod>").count(), 1);
+    }
+
+    #[test]
+    fn test_render_robots_disallows_internal_prefixes() {
+        let txt = render_robots("https://example.com");
+
+        assert!(txt.contains("Disallow: /admin\n"));
+        assert!(txt.contains("Disallow: /features\n"));
+        assert!(txt.contains("Disallow: /assets\n"));
+        assert!(txt.contains("Sitemap: https://example.com/sitemap.xml\n"));
+
+        // Without SITE_URL there is no absolute URL to give
+        assert!(!render_robots("").contains("Sitemap:"));
+    }
+}
@@ -0,0 +1,2209 @@
+/// End-to-end HTTP tests for the application router
+///
+/// Each test builds the router with `website::app` over its own temporary
+/// data directory and sends requests with `tower::ServiceExt::oneshot`, so no
+/// port is bound and the workspace `data/` directory is never touched.
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::{Body, to_bytes};
+use axum::http::{HeaderMap, Method, Request, StatusCode, header};
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::{Value, json};
+use tempfile::TempDir;
+use tower::ServiceExt;
+use website::core::persistence::with_data_dir;
+use website::core::presence::Presence;
+use website::core::schedule::{Clock, ManualClock, run_scheduler};
+use website::core::totp;
+use website::{AppState, app};
+
+/// A router over a temporary data directory with two routes
+struct TestApp {
+    dir: TempDir,
+    router: Router,
+}
+
+/// A response with its body read into a string
+struct TestResponse {
+    status: StatusCode,
+    content_type: String,
+    headers: HeaderMap,
+    body: String,
+    bytes: Vec<u8>,
+}
+
+impl TestApp {
+    fn new() -> Self {
+        Self::build(|state| state)
+    }
+
+    /// A test app whose time only moves when `clock` is moved
+    fn with_clock(clock: &ManualClock) -> Self {
+        let clock = clock.clone();
+        Self::build(|state| state.with_clock(clock))
+    }
+
+    fn build(configure: impl FnOnce(AppState) -> AppState) -> Self {
+        let dir = TempDir::new().unwrap();
+        let content = dir.path().join("content");
+        fs::create_dir_all(&content).unwrap();
+
+        let routes = json!([
+            { "path": "/", "name": "homepage", "blockIds": ["homepage"] },
+            { "path": "/foo", "name": "foo", "blockIds": ["foo"] },
+        ]);
+        write_json(&dir.path().join("routes.json"), &routes);
+        write_json(
+            &content.join("homepage.json"),
+            &json!({ "blocks": [hero_block("hero-1", "Fixture Headline")] }),
+        );
+
+        let router = app(configure(AppState::new(dir.path())));
+        Self { dir, router }
+    }
+
+    async fn request(&self, method: Method, uri: &str, body: Option<Value>) -> TestResponse {
+        let mut request = Request::builder().method(method).uri(uri);
+        let body = match body {
+            Some(json) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(json.to_string())
+            }
+            None => Body::empty(),
+        };
+        self.send(request.body(body).unwrap()).await
+    }
+
+    async fn send(&self, request: Request<Body>) -> TestResponse {
+        let response = self.router.clone().oneshot(request).await.unwrap();
+
+        let status = response.status();
+        let content_type = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let headers = response.headers().clone();
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap()
+            .to_vec();
+        TestResponse {
+            status,
+            content_type,
+            headers,
+            body: String::from_utf8_lossy(&bytes).into_owned(),
+            bytes,
+        }
+    }
+
+    async fn get(&self, uri: &str) -> TestResponse {
+        self.request(Method::GET, uri, None).await
+    }
+
+    async fn post(&self, uri: &str, body: Value) -> TestResponse {
+        self.request(Method::POST, uri, Some(body)).await
+    }
+
+    async fn post_bytes(&self, uri: &str, bytes: Vec<u8>) -> TestResponse {
+        let request = Request::post(uri)
+            .header(header::CONTENT_TYPE, "application/gzip")
+            .body(Body::from(bytes))
+            .unwrap();
+        self.send(request).await
+    }
+
+    /// Configure `de` and `fr` besides the default `en` locale
+    fn with_locales(self, fallback: &str) -> Self {
+        write_json(
+            &self.dir.path().join("settings.json"),
+            &json!({ "i18n": { "default_locale": "en", "locales": ["de", "fr"], "fallback": fallback } }),
+        );
+        self
+    }
+
+    fn read_content(&self, route_name: &str) -> Value {
+        let path = self
+            .dir
+            .path()
+            .join("content")
+            .join(format!("{route_name}.json"));
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+}
+
+fn write_json(path: &Path, value: &Value) {
+    fs::write(path, serde_json::to_string_pretty(value).unwrap()).unwrap();
+}
+
+fn hero_block(id: &str, headline: &str) -> Value {
+    json!({
+        "id": id,
+        "type": "Hero",
+        "props": { "headline": headline, "subheadline": "Fixture subheadline" }
+    })
+}
+
+// ============================================================================
+// Public Pages
+// ============================================================================
+
+#[tokio::test]
+async fn test_homepage_renders_content_from_data_dir() {
+    let app = TestApp::new();
+    let response = app.get("/").await;
+
+    assert_eq!(response.status, StatusCode::OK);
+    assert!(response.content_type.starts_with("text/html"));
+    assert!(response.body.contains("Fixture Headline"));
+}
+
+#[tokio::test]
+async fn test_health_robots_and_sitemap() {
+    let app = TestApp::new();
+
+    assert_eq!(app.get("/health").await.body, "OK");
+
+    let robots = app.get("/robots.txt").await;
+    assert_eq!(robots.status, StatusCode::OK);
+    assert!(robots.body.contains("Disallow: /admin\n"));
+
+    // Only pages the router serves, and no URLs built from the Host header
+    let request = Request::get("/sitemap.xml")
+        .header(header::HOST, "evil.example")
+        .body(Body::empty())
+        .unwrap();
+    let sitemap = app.send(request).await;
+    assert_eq!(sitemap.status, StatusCode::OK);
+    assert!(sitemap.body.contains("<loc>/</loc>"));
+    assert!(!sitemap.body.contains("/foo</loc>"));
+    assert!(!sitemap.body.contains("evil.example"));
+}
+
+#[tokio::test]
+async fn test_unknown_path_is_not_found() {
+    let app = TestApp::new();
+    assert_eq!(app.get("/no-such-page").await.status, StatusCode::NOT_FOUND);
+}
+
+// ============================================================================
+// Admin Pages
+// ============================================================================
+
+#[tokio::test]
+async fn test_admin_pages_render() {
+    let app = TestApp::new();
+
+    for uri in [
+        "/admin",
+        "/admin/route/",
+        "/admin/media/",
+        "/admin/settings/",
+        "/admin/settings/preview",
+        "/admin/schedule/",
+        "/admin/audit/",
+    ] {
+        let response = app.get(uri).await;
+        assert_eq!(response.status, StatusCode::OK, "GET {uri}");
+        assert!(response.content_type.starts_with("text/html"), "GET {uri}");
+    }
+
+    let routes = app.get("/admin/route/").await;
+    assert!(routes.body.contains("/admin/route/foo/"));
+}
+
+#[tokio::test]
+async fn test_page_editor_loads_route_content() {
+    let app = TestApp::new();
+    let response = app.get("/admin/route/homepage/").await;
+
+    assert_eq!(response.status, StatusCode::OK);
+    assert!(response.body.contains("<admin-editor"));
+    assert!(response.body.contains("hero-1"));
+}
+
+// ============================================================================
+// Story Pages
+// ============================================================================
+
+#[tokio::test]
+async fn test_story_pages() {
+    let app = TestApp::new();
+
+    let index = app.get("/admin/features/").await;
+    assert_eq!(index.status, StatusCode::OK);
+    for name in ["button", "checkbox", "header", "hero", "image", "input"] {
+        assert!(
+            index.body.contains(&format!("/admin/features/{name}/")),
+            "story index lists {name}"
+        );
+        assert_eq!(
+            app.get(&format!("/admin/features/{name}/")).await.status,
+            StatusCode::OK,
+            "story page for {name}"
+        );
+    }
+
+    let isolated = app.get("/admin/features/button/text-only").await;
+    assert_eq!(isolated.status, StatusCode::OK);
+    assert!(isolated.body.contains(r#"data-variant="text-only""#));
+
+    assert_eq!(
+        app.get("/admin/features/button/no-such-variant")
+            .await
+            .status,
+        StatusCode::NOT_FOUND
+    );
+    let missing = app.get("/admin/features/no-such-story/").await;
+    assert!(missing.body.contains("Story Not Found"));
+}
+
+#[tokio::test]
+async fn test_story_render_api() {
+    let app = TestApp::new();
+
+    let rendered = app
+        .post(
+            "/admin/api/features/hero/render",
+            json!({ "headline": "Played", "subheadline": "Around" }),
+        )
+        .await;
+    assert_eq!(rendered.status, StatusCode::OK);
+    assert!(rendered.body.contains("Played"));
+
+    let invalid = app
+        .post("/admin/api/features/hero/render", json!({ "headline": 3 }))
+        .await;
+    assert_eq!(invalid.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(invalid.body.contains("headline"));
+}
+
+// ============================================================================
+// Save API
+// ============================================================================
+
+#[tokio::test]
+async fn test_save_route_writes_data_dir_and_assigns_ids() {
+    let app = TestApp::new();
+    let blocks = json!({
+        "blocks": [hero_block("", "Saved Headline"), hero_block("dup", "A"), hero_block("dup", "B")]
+    });
+
+    let response = app.post("/admin/api/foo", blocks).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body, "foo updated successfully");
+
+    let saved = app.read_content("foo");
+    let ids: Vec<&str> = saved["blocks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|b| b["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids.len(), 3);
+    assert!(ids.iter().all(|id| !id.is_empty()));
+    assert_eq!(ids[1], "dup");
+    assert_ne!(ids[2], "dup");
+}
+
+#[tokio::test]
+async fn test_save_homepage_is_served() {
+    let app = TestApp::new();
+
+    let response = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "blocks": [hero_block("hero-1", "Updated Headline")] }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+
+    let page = app.get("/").await;
+    assert!(page.body.contains("Updated Headline"));
+    assert!(!page.body.contains("Fixture Headline"));
+}
+
+#[tokio::test]
+async fn test_route_composed_from_several_files() {
+    let app = TestApp::new();
+    let content = app.dir.path().join("content");
+    let shared = content.join("shared.json");
+    write_json(
+        &shared,
+        &json!({ "blocks": [hero_block("shared-1", "Shared Banner")] }),
+    );
+    write_json(
+        &app.dir.path().join("routes.json"),
+        &json!([{ "path": "/", "name": "homepage", "blockIds": ["shared", "homepage"] }]),
+    );
+
+    let page = app.get("/").await;
+    let banner = page
+        .body
+        .find("Shared Banner")
+        .expect("shared block rendered");
+    let fixture = page
+        .body
+        .find("Fixture Headline")
+        .expect("page block rendered");
+    assert!(banner < fixture, "blocks render in blockIds order");
+
+    let editor = app.get("/admin/route/homepage/").await;
+    assert!(editor.body.contains("&quot;shared&quot;"));
+    assert!(editor.body.contains("&quot;files&quot;"));
+
+    // Each file's blocks are written back to that file
+    let response = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "files": [
+                { "source": "shared", "blocks": [hero_block("shared-1", "New Banner")] },
+                { "source": "homepage", "blocks": [hero_block("shared-1", "Body"), hero_block("", "More")] },
+            ] }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    let saved_shared: Value = serde_json::from_str(&fs::read_to_string(&shared).unwrap()).unwrap();
+    assert_eq!(saved_shared["blocks"][0]["props"]["headline"], "New Banner");
+    let saved_page = app.read_content("homepage");
+    assert_eq!(saved_page["blocks"].as_array().unwrap().len(), 2);
+    assert_ne!(
+        saved_page["blocks"][0]["id"], "shared-1",
+        "ids are unique across files"
+    );
+    assert_ne!(saved_page["blocks"][1]["id"], "");
+
+    // A flat block list is ambiguous for a multi-file route
+    let response = app
+        .post("/admin/api/homepage", json!({ "blocks": [] }))
+        .await;
+    assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    // Only the route's own files can be written
+    for source in [
+        "stray",
+        "../routes",
+        &content.join("shared.json").to_string_lossy(),
+    ] {
+        let response = app
+            .post(
+                "/admin/api/homepage",
+                json!({ "files": [{ "source": source, "blocks": [] }] }),
+            )
+            .await;
+        assert_eq!(
+            response.status,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "{source}"
+        );
+    }
+    assert!(!content.join("stray.json").exists());
+
+    let response = app.post("/admin/api/nope", json!({ "blocks": [] })).await;
+    assert_eq!(response.status, StatusCode::NOT_FOUND);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_content_symlinks_out_of_the_content_dir_are_refused() {
+    let app = TestApp::new();
+    let outside = TempDir::new().unwrap();
+    let secret = outside.path().join("secret.json");
+    write_json(
+        &secret,
+        &json!({ "blocks": [hero_block("s", "Secret Headline")] }),
+    );
+    std::os::unix::fs::symlink(&secret, app.dir.path().join("content/leak.json")).unwrap();
+    write_json(
+        &app.dir.path().join("routes.json"),
+        &json!([{ "path": "/", "name": "homepage", "blockIds": ["leak"] }]),
+    );
+
+    assert!(!app.get("/").await.body.contains("Secret Headline"));
+    let response = app
+        .post("/admin/api/homepage", json!({ "blocks": [] }))
+        .await;
+    assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(response.body.contains("outside the content directory"));
+    assert!(
+        fs::read_to_string(&secret)
+            .unwrap()
+            .contains("Secret Headline")
+    );
+}
+
+#[tokio::test]
+async fn test_save_rejects_invalid_blocks() {
+    let app = TestApp::new();
+
+    let response = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "blocks": [{ "id": "x", "type": "Unknown", "props": {} }] }),
+        )
+        .await;
+    assert!(response.status.is_client_error());
+    assert_eq!(
+        app.read_content("homepage")["blocks"][0]["id"],
+        "hero-1",
+        "content is unchanged"
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_block_is_kept_through_render_edit_and_save() {
+    let app = TestApp::new();
+    let unknown = json!({ "id": "gallery-1", "type": "Gallery", "props": { "images": [] } });
+    write_json(
+        &app.dir.path().join("content").join("homepage.json"),
+        &json!({ "version": 1, "blocks": [hero_block("hero-1", "Still Here"), unknown] }),
+    );
+
+    let page = app.get("/").await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert!(page.body.contains("Still Here"));
+    assert!(
+        page.body
+            .contains(r#"<div hidden data-invalid-block="gallery-1"></div>"#)
+    );
+    assert!(
+        !page.body.contains("Gallery"),
+        "parse errors stay out of public pages"
+    );
+
+    let editor = app.get("/admin/route/homepage/").await;
+    assert!(editor.body.contains("1 block(s) couldn't be loaded"));
+    assert!(editor.body.contains("unknown variant"));
+
+    // Re-saving the editor's content keeps the unknown block unchanged
+    let response = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "blocks": [hero_block("hero-1", "Edited"), unknown] }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    let saved = app.read_content("homepage");
+    assert_eq!(saved["blocks"][0]["props"]["headline"], "Edited");
+    assert_eq!(saved["blocks"][1], unknown);
+
+    // An edited invalid block is not stored as-is, so it's rejected
+    let edited = json!({ "id": "gallery-1", "type": "Gallery", "props": { "images": [1] } });
+    let response = app
+        .post("/admin/api/homepage", json!({ "blocks": [edited] }))
+        .await;
+    assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(response.body.contains("gallery-1"));
+    assert_eq!(app.read_content("homepage")["blocks"][1], unknown);
+}
+
+#[tokio::test]
+async fn test_a11y_api() {
+    let app = TestApp::new();
+
+    let response = app
+        .post(
+            "/admin/api/a11y",
+            json!({ "blocks": [hero_block("hero-1", "Fine")] }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body, "[]");
+}
+
+// ============================================================================
+// Export / Import
+// ============================================================================
+
+#[tokio::test]
+async fn test_export_then_import_into_another_site() {
+    let source = TestApp::new();
+    source
+        .post(
+            "/admin/api/foo",
+            json!({ "blocks": [hero_block("foo-1", "Exported Foo")] }),
+        )
+        .await;
+
+    let export = source.get("/admin/api/export").await;
+    assert_eq!(export.status, StatusCode::OK);
+    assert_eq!(export.content_type, "application/gzip");
+
+    let target = TestApp::new();
+    let dry_run = target
+        .post_bytes("/admin/api/import?dry_run=true", export.bytes.clone())
+        .await;
+    assert_eq!(dry_run.status, StatusCode::OK);
+    let report: Value = serde_json::from_str(&dry_run.body).unwrap();
+    assert_eq!(report["dry_run"], true);
+    assert_eq!(report["created"], json!(["content/foo.json"]));
+    assert!(!target.dir.path().join("content/foo.json").exists());
+
+    let applied = target.post_bytes("/admin/api/import", export.bytes).await;
+    assert_eq!(applied.status, StatusCode::OK);
+    assert_eq!(
+        target.read_content("foo")["blocks"][0]["props"]["headline"],
+        "Exported Foo"
+    );
+}
+
+#[tokio::test]
+async fn test_import_rejects_invalid_archive() {
+    let app = TestApp::new();
+    let response = app
+        .post_bytes("/admin/api/import", b"not an archive".to_vec())
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+}
+
+// ============================================================================
+// Static Assets
+// ============================================================================
+
+#[tokio::test]
+async fn test_static_assets_are_served() {
+    let app = TestApp::new();
+
+    let styles = app.get("/assets/styles.css").await;
+    assert_eq!(styles.status, StatusCode::OK);
+    assert!(styles.content_type.starts_with("text/css"));
+
+    let feature_css = app.get("/features/hero/styles.css").await;
+    assert_eq!(feature_css.status, StatusCode::OK);
+
+    let script = app.get("/features/admin/editor/components/index.js").await;
+    assert_eq!(script.status, StatusCode::OK);
+    assert!(script.content_type.contains("javascript"));
+
+    assert_eq!(
+        app.get("/assets/missing.css").await.status,
+        StatusCode::NOT_FOUND
+    );
+}
+
+#[tokio::test]
+async fn test_media_is_served_from_data_dir() {
+    let app = TestApp::new();
+    let item_dir = app.dir.path().join("media").join("item");
+    fs::create_dir_all(&item_dir).unwrap();
+    fs::write(item_dir.join("320.webp"), b"webp bytes").unwrap();
+
+    let response = app.get("/media/item/320.webp").await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.body, "webp bytes");
+}
+
+#[tokio::test]
+async fn test_homepage_negotiates_locale() {
+    let app = TestApp::new().with_locales("default_locale");
+    write_json(
+        &app.dir.path().join("content/homepage.de.json"),
+        &json!({ "blocks": [hero_block("hero-1", "Willkommen")] }),
+    );
+
+    let request = Request::get("/")
+        .header(header::ACCEPT_LANGUAGE, "de-CH, en;q=0.5")
+        .body(Body::empty())
+        .unwrap();
+    let redirect = app.send(request).await;
+    assert_eq!(redirect.status, StatusCode::TEMPORARY_REDIRECT);
+    assert_eq!(redirect.headers[header::LOCATION], "/de/");
+    assert_eq!(redirect.headers[header::VARY], "accept-language");
+
+    let default = app.get("/").await;
+    assert_eq!(default.status, StatusCode::OK);
+    assert_eq!(default.headers[header::VARY], "accept-language");
+    assert!(default.body.contains(r#"<html lang="en">"#));
+    assert!(default.body.contains("Fixture Headline"));
+    assert!(
+        default
+            .body
+            .contains(r#"<link rel="alternate" hreflang="de" href="/de/">"#)
+    );
+    assert!(default.body.contains(r#"hreflang="x-default" href="/">"#));
+
+    let german = app.get("/de/").await;
+    assert_eq!(german.status, StatusCode::OK);
+    assert!(german.body.contains(r#"<html lang="de">"#));
+    assert!(german.body.contains("Willkommen"));
+
+    let sitemap = app.get("/sitemap.xml").await;
+    assert!(sitemap.body.contains("<loc>/de/</loc>"));
+
+    // No French variant: the default locale's content is shown
+    let french = app.get("/fr/").await;
+    assert_eq!(french.status, StatusCode::OK);
+    assert!(french.body.contains(r#"<html lang="fr">"#));
+    assert!(french.body.contains("Fixture Headline"));
+
+    assert_eq!(app.get("/it/").await.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_untranslated_page_is_not_found_under_not_found_policy() {
+    let app = TestApp::new().with_locales("not_found");
+    write_json(
+        &app.dir.path().join("content/homepage.de.json"),
+        &json!({ "blocks": [hero_block("hero-1", "Willkommen")] }),
+    );
+
+    assert_eq!(app.get("/de/").await.status, StatusCode::OK);
+    assert_eq!(app.get("/fr/").await.status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_translation_is_edited_and_saved_per_locale() {
+    let app = TestApp::new().with_locales("default_locale");
+
+    let editor = app.get("/admin/route/homepage/?locale=de").await;
+    assert_eq!(editor.status, StatusCode::OK);
+    assert!(editor.body.contains(r#"aria-current="page">de</a>"#));
+    assert!(editor.body.contains("1 untranslated block(s) in de"));
+    assert!(editor.body.contains("homepage.de.json"));
+    assert!(
+        editor
+            .body
+            .contains(r#"href="/admin/route/homepage/preview?locale=de""#)
+    );
+    let preview = app.get("/admin/route/homepage/preview?locale=de").await;
+    assert!(preview.body.contains(r#"src="/de/""#));
+
+    let saved = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "locale": "de", "blocks": [hero_block("hero-1", "Willkommen")] }),
+        )
+        .await;
+    assert_eq!(saved.status, StatusCode::OK, "{}", saved.body);
+    let german = app.read_content("homepage.de");
+    assert_eq!(german["blocks"][0]["props"]["headline"], "Willkommen");
+    assert_eq!(
+        app.read_content("homepage")["blocks"][0]["props"]["headline"],
+        "Fixture Headline"
+    );
+
+    let editor = app.get("/admin/route/homepage/?locale=de").await;
+    assert!(editor.body.contains("All blocks are translated into de."));
+
+    let unknown = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "locale": "it", "blocks": [] }),
+        )
+        .await;
+    assert_eq!(unknown.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        app.get("/admin/route/homepage/?locale=it").await.status,
+        StatusCode::NOT_FOUND
+    );
+}
+
+fn noon() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap()
+}
+
+#[tokio::test]
+async fn test_scheduled_blocks_and_routes_follow_the_clock() {
+    let clock = ManualClock::new(noon());
+    let app = TestApp::with_clock(&clock);
+    let one_pm = noon() + chrono::Duration::hours(1);
+    let mut promo = hero_block("promo", "Spring Sale");
+    promo["schedule"] = json!({ "publish_at": one_pm });
+    let mut notice = hero_block("notice", "Winter Notice");
+    notice["schedule"] = json!({ "unpublish_at": one_pm });
+    write_json(
+        &app.dir.path().join("content/homepage.json"),
+        &json!({ "blocks": [promo, notice] }),
+    );
+
+    let page = app.get("/").await;
+    assert!(!page.body.contains("Spring Sale"));
+    assert!(page.body.contains("Winter Notice"));
+
+    let calendar = app.get("/admin/schedule/").await;
+    assert_eq!(calendar.status, StatusCode::OK);
+    assert!(calendar.body.contains("Sunday, 1 March 2026"));
+    assert!(calendar.body.contains("13:00 UTC"));
+    assert!(calendar.body.contains(" appears"));
+    assert!(calendar.body.contains(" expires"));
+
+    clock.advance(chrono::Duration::hours(1));
+    let page = app.get("/").await;
+    assert!(page.body.contains("Spring Sale"));
+    assert!(!page.body.contains("Winter Notice"));
+    assert!(
+        app.get("/admin/schedule/")
+            .await
+            .body
+            .contains("Nothing is scheduled.")
+    );
+
+    // Outside the route's window the page and its sitemap entry disappear
+    write_json(
+        &app.dir.path().join("routes.json"),
+        &json!([{ "path": "/", "name": "homepage", "blockIds": ["homepage"],
+                  "schedule": { "unpublish_at": noon() + chrono::Duration::hours(2) } }]),
+    );
+    assert!(app.get("/sitemap.xml").await.body.contains("<loc>"));
+    clock.advance(chrono::Duration::hours(1));
+    assert_eq!(app.get("/").await.status, StatusCode::NOT_FOUND);
+    assert!(!app.get("/sitemap.xml").await.body.contains("<loc>"));
+}
+
+#[tokio::test]
+async fn test_scheduled_draft_is_published_when_due() {
+    let clock = ManualClock::new(noon());
+    let app = TestApp::with_clock(&clock);
+
+    let response = app
+        .post(
+            "/admin/api/homepage",
+            json!({
+                "publish_at": noon() + chrono::Duration::hours(1),
+                "blocks": [hero_block("hero-1", "Launch Day")]
+            }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    assert_eq!(response.body, "homepage scheduled for 2026-03-01 13:00 UTC");
+    assert!(!app.get("/").await.body.contains("Launch Day"));
+    assert!(
+        app.get("/admin/route/homepage/")
+            .await
+            .body
+            .contains("1 scheduled draft(s)")
+    );
+    assert!(
+        app.get("/admin/schedule/")
+            .await
+            .body
+            .contains("Draft is published")
+    );
+
+    let scheduler = tokio::spawn(with_data_dir(
+        app.dir.path().to_path_buf(),
+        run_scheduler(
+            Arc::new(clock.clone()),
+            Duration::from_millis(5),
+            Presence::new(),
+        ),
+    ));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(
+        !app.get("/").await.body.contains("Launch Day"),
+        "not due yet"
+    );
+
+    clock.advance(chrono::Duration::hours(1));
+    let mut published = false;
+    for _ in 0..200 {
+        if app.get("/").await.body.contains("Launch Day") {
+            published = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    scheduler.abort();
+    assert!(published, "scheduler published the draft");
+    assert!(
+        app.get("/admin/schedule/")
+            .await
+            .body
+            .contains("Nothing is scheduled.")
+    );
+}
+
+#[tokio::test]
+async fn test_cancel_scheduled_draft() {
+    let clock = ManualClock::new(noon());
+    let app = TestApp::with_clock(&clock);
+    let tomorrow = noon() + chrono::Duration::days(1);
+
+    let response = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "publish_at": tomorrow, "blocks": [hero_block("hero-1", "Later")] }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK);
+    let draft = fs::read_dir(app.dir.path().join("scheduled"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let id = draft.file_stem().unwrap().to_str().unwrap().to_string();
+
+    let uri = format!("/admin/api/scheduled/{id}");
+    let cancelled = app.request(Method::DELETE, &uri, None).await;
+    assert_eq!(cancelled.status, StatusCode::NO_CONTENT);
+    assert!(!draft.exists());
+    let again = app.request(Method::DELETE, &uri, None).await;
+    assert_eq!(again.status, StatusCode::NOT_FOUND);
+
+    // A past publish_at publishes right away
+    let response = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "publish_at": noon(), "blocks": [hero_block("hero-1", "Now")] }),
+        )
+        .await;
+    assert_eq!(response.body, "homepage updated successfully");
+
+    // An empty block window is rejected
+    let mut block = hero_block("hero-1", "Never");
+    block["schedule"] = json!({ "publish_at": tomorrow, "unpublish_at": noon() });
+    let response = app
+        .post("/admin/api/homepage", json!({ "blocks": [block] }))
+        .await;
+    assert_eq!(response.status, StatusCode::BAD_REQUEST);
+}
+
+// ============================================================================
+// Audit Log
+// ============================================================================
+
+/// Parse the audit log export into one JSON value per line
+async fn audit_export(app: &TestApp, query: &str) -> Vec<Value> {
+    let response = app.get(&format!("/admin/api/audit/export{query}")).await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+    assert_eq!(response.content_type, "application/x-ndjson");
+    response
+        .body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_content_save_is_audited_with_hashes_and_request_id() {
+    let clock = ManualClock::new(noon());
+    let app = TestApp::with_clock(&clock);
+
+    let request = Request::post("/admin/api/homepage")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-request-id", "req-42")
+        .body(Body::from(
+            json!({ "blocks": [hero_block("hero-1", "Audited")] }).to_string(),
+        ))
+        .unwrap();
+    let response = app.send(request).await;
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(response.headers["x-request-id"], "req-42");
+
+    let generated = app.get("/health").await;
+    let id = generated.headers["x-request-id"].to_str().unwrap();
+    assert_eq!(id.len(), 36, "a UUID is assigned when none is sent");
+
+    let entries = audit_export(&app, "").await;
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry["action"], "content_save");
+    assert_eq!(entry["actor"], "anonymous");
+    assert_eq!(entry["route"], "homepage");
+    assert_eq!(entry["request_id"], "req-42");
+    assert_eq!(entry["timestamp"], "2026-03-01T12:00:00Z");
+    assert_eq!(entry["target"], "content/homepage.json");
+    let before = entry["before"].as_str().unwrap();
+    let after = entry["after"].as_str().unwrap();
+    assert!(before.starts_with("sha256:"));
+    assert_ne!(before, after);
+
+    // A second save chains: its before is the first save's after
+    app.post(
+        "/admin/api/homepage",
+        json!({ "blocks": [hero_block("hero-1", "Again")] }),
+    )
+    .await;
+    let entries = audit_export(&app, "").await;
+    assert_eq!(entries[1]["before"], after);
+}
+
+#[tokio::test]
+async fn test_admin_actions_are_audited_and_filtered() {
+    let clock = ManualClock::new(noon());
+    let app = TestApp::with_clock(&clock);
+
+    app.post(
+        "/admin/api/foo",
+        json!({ "blocks": [hero_block("", "Foo")] }),
+    )
+    .await;
+    clock.advance(chrono::Duration::days(1));
+    let settings = app
+        .post(
+            "/admin/api/settings",
+            json!({ "site_name": "Audited Site" }),
+        )
+        .await;
+    assert_eq!(settings.status, StatusCode::OK, "{}", settings.body);
+    let tomorrow = noon() + chrono::Duration::days(2);
+    app.post(
+        "/admin/api/homepage",
+        json!({ "publish_at": tomorrow, "blocks": [hero_block("hero-1", "Later")] }),
+    )
+    .await;
+    let missing = app.post("/admin/api/nope", json!({ "blocks": [] })).await;
+    assert_eq!(missing.status, StatusCode::NOT_FOUND);
+
+    let actions: Vec<Value> = audit_export(&app, "")
+        .await
+        .into_iter()
+        .map(|entry| entry["action"].clone())
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            json!("content_save"),
+            json!("settings_update"),
+            json!("content_schedule")
+        ],
+        "failed requests aren't recorded"
+    );
+
+    assert_eq!(audit_export(&app, "?route=foo").await.len(), 1);
+    assert_eq!(audit_export(&app, "?action=settings_update").await.len(), 1);
+    assert_eq!(
+        audit_export(&app, "?since=2026-03-02&until=2026-03-02")
+            .await
+            .len(),
+        2
+    );
+    assert!(audit_export(&app, "?actor=someone").await.is_empty());
+    let bad = app.get("/admin/api/audit/export?action=explode").await;
+    assert_eq!(bad.status, StatusCode::BAD_REQUEST);
+
+    let page = app.get("/admin/audit/?action=content_save&route=").await;
+    assert_eq!(page.status, StatusCode::OK);
+    assert!(page.body.contains("1 entry."));
+    assert!(page.body.contains("<code>content_save</code>"));
+    assert!(!page.body.contains("<code>settings_update</code>"));
+    assert!(
+        page.body
+            .contains("/admin/api/audit/export?action=content_save")
+    );
+}
+
+// ============================================================================
+// Live Presence
+// ============================================================================
+
+type Socket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Serve the test app on a local port, for WebSocket clients
+async fn serve(app: &TestApp) -> std::net::SocketAddr {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(axum::serve(listener, app.router.clone()).into_future());
+    addr
+}
+
+async fn connect(addr: std::net::SocketAddr, path: &str) -> Socket {
+    let url = format!("ws://{addr}/admin/ws/route/{path}");
+    tokio_tungstenite::connect_async(url).await.unwrap().0
+}
+
+/// The next JSON event on `socket`, failing after a few seconds
+async fn next_event(socket: &mut Socket) -> Value {
+    use futures_util::StreamExt;
+    use tokio_tungstenite::tungstenite::Message;
+
+    loop {
+        let message = tokio::time::timeout(Duration::from_secs(5), socket.next())
+            .await
+            .expect("event within 5s")
+            .expect("socket open")
+            .unwrap();
+        if let Message::Text(text) = message {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+fn editor_names(event: &Value) -> Vec<&str> {
+    assert_eq!(event["type"], "editors", "{event}");
+    event["editors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|editor| editor["name"].as_str().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_editors_see_each_other_and_publishes() {
+    let app = TestApp::new();
+    let addr = serve(&app).await;
+
+    let mut ana = connect(addr, "homepage?name=Ana").await;
+    let welcome = next_event(&mut ana).await;
+    assert_eq!(welcome["type"], "welcome");
+    let ana_session = welcome["session"].as_str().unwrap().to_string();
+    assert_eq!(editor_names(&next_event(&mut ana).await), vec!["Ana"]);
+
+    let mut ben = connect(addr, "homepage?role=editor&name=Ben&locale=de").await;
+    assert_eq!(next_event(&mut ben).await["type"], "welcome");
+    assert_eq!(
+        editor_names(&next_event(&mut ben).await),
+        vec!["Ana", "Ben"]
+    );
+    let joined = next_event(&mut ana).await;
+    assert_eq!(editor_names(&joined), vec!["Ana", "Ben"]);
+    assert_eq!(joined["editors"][1]["locale"], "de");
+
+    let mut preview = connect(addr, "homepage?role=preview").await;
+    assert_eq!(next_event(&mut preview).await["type"], "welcome");
+
+    // Ana publishes; Ben and the preview get the saved content
+    let request = Request::post("/admin/api/homepage")
+        .header(header::CONTENT_TYPE, "application/json")
+        .header("x-editor-session", &ana_session)
+        .body(Body::from(
+            json!({ "blocks": [hero_block("hero-1", "Live Headline")] }).to_string(),
+        ))
+        .unwrap();
+    assert_eq!(app.send(request).await.status, StatusCode::OK);
+
+    for socket in [&mut ben, &mut preview, &mut ana] {
+        let published = next_event(socket).await;
+        assert_eq!(published["type"], "published");
+        assert_eq!(published["by"], "Ana");
+        assert_eq!(published["session"], ana_session.as_str());
+        assert_eq!(published["locale"], Value::Null);
+        assert_eq!(
+            published["files"][0]["blocks"][0]["props"]["headline"],
+            "Live Headline"
+        );
+    }
+
+    // A save from outside the editor is published by the audit actor
+    app.post(
+        "/admin/api/homepage",
+        json!({ "blocks": [hero_block("hero-1", "Scripted")] }),
+    )
+    .await;
+    let published = next_event(&mut preview).await;
+    assert_eq!(published["by"], "anonymous");
+    assert_eq!(published["session"], Value::Null);
+
+    ben.close(None).await.unwrap();
+    loop {
+        let event = next_event(&mut ana).await;
+        if event["type"] == "editors" {
+            assert_eq!(editor_names(&event), vec!["Ana"]);
+            break;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_presence_socket_and_preview_need_a_known_route() {
+    let app = TestApp::new();
+    let addr = serve(&app).await;
+
+    let url = format!("ws://{addr}/admin/ws/route/nope");
+    let error = tokio_tungstenite::connect_async(url).await.unwrap_err();
+    assert!(error.to_string().contains("404"), "{error}");
+
+    let preview = app.get("/admin/route/foo/preview").await;
+    assert_eq!(preview.status, StatusCode::OK);
+    assert!(
+        preview
+            .body
+            .contains(r#"live-preview data-route-name="foo""#)
+    );
+    assert!(preview.body.contains(r#"src="/foo""#));
+    assert_eq!(
+        app.get("/admin/route/nope/preview").await.status,
+        StatusCode::NOT_FOUND
+    );
+    assert_eq!(
+        app.get("/admin/route/foo/preview?locale=xx").await.status,
+        StatusCode::NOT_FOUND
+    );
+
+    let editor = app.get("/admin/route/foo/").await;
+    assert!(
+        editor
+            .body
+            .contains(r#"editor-presence data-route-name="foo""#)
+    );
+    assert!(editor.body.contains(r#"href="/admin/route/foo/preview""#));
+}
+
+/// The nonce in a response's Content-Security-Policy
+fn csp_nonce(response: &TestResponse) -> String {
+    let csp = response.headers["content-security-policy"]
+        .to_str()
+        .unwrap();
+    let start = csp.find("'nonce-").expect("policy has a nonce") + "'nonce-".len();
+    csp[start..].split('\'').next().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn test_public_pages_send_security_headers_with_a_fresh_nonce() {
+    let app = TestApp::new();
+
+    let page = app.get("/").await;
+    assert_eq!(page.headers["x-content-type-options"], "nosniff");
+    assert_eq!(page.headers["x-frame-options"], "SAMEORIGIN");
+    assert!(page.headers.contains_key("strict-transport-security"));
+    assert!(page.headers.contains_key("referrer-policy"));
+    let csp = page.headers["content-security-policy"].to_str().unwrap();
+    assert!(csp.contains("frame-ancestors 'self'"), "{csp}");
+    assert!(!csp.contains("unsafe-inline"), "{csp}");
+
+    let nonce = csp_nonce(&page);
+    assert!(
+        page.body
+            .contains(&format!(r#"<style id="theme-tokens" nonce="{nonce}">"#)),
+        "the theme style carries the nonce"
+    );
+    assert!(!page.body.contains("style=\""));
+    assert_ne!(
+        csp_nonce(&app.get("/").await),
+        nonce,
+        "one nonce per request"
+    );
+}
+
+#[tokio::test]
+async fn test_admin_pages_send_the_stricter_headers() {
+    let app = TestApp::new();
+
+    let editor = app.get("/admin/route/homepage/").await;
+    let csp = editor.headers["content-security-policy"].to_str().unwrap();
+    assert!(csp.contains("base-uri 'none'"), "{csp}");
+    assert!(csp.contains("connect-src 'self'"), "{csp}");
+    assert_eq!(editor.headers["referrer-policy"], "no-referrer");
+    assert_eq!(editor.headers["cache-control"], "no-store");
+
+    let nonce = csp_nonce(&editor);
+    assert!(
+        editor
+            .body
+            .contains(&format!(r#"<meta property="csp-nonce" nonce="{nonce}">"#))
+    );
+    assert!(editor.body.contains(&format!(
+        r#"<script type="module" nonce="{nonce}" src="/features/admin/editor/components/index.js">"#
+    )));
+    assert!(
+        !editor.body.contains("style=\""),
+        "no inline style attributes"
+    );
+
+    let api = app.get("/admin/api/audit/export").await;
+    assert_eq!(api.headers["cache-control"], "no-store");
+}
+
+#[tokio::test]
+async fn test_security_headers_are_configurable() {
+    use website::core::security_headers::{HeaderSet, SecurityHeaders};
+
+    let headers = SecurityHeaders {
+        public: HeaderSet::public()
+            .without("strict-transport-security")
+            .with("permissions-policy", "camera=()"),
+        ..SecurityHeaders::default()
+    };
+    let app = TestApp::build(|state| state.with_security_headers(headers));
+
+    let page = app.get("/").await;
+    assert!(!page.headers.contains_key("strict-transport-security"));
+    assert_eq!(page.headers["permissions-policy"], "camera=()");
+    let admin = app.get("/admin").await;
+    assert!(admin.headers.contains_key("strict-transport-security"));
+}
+
+#[tokio::test]
+async fn test_oversized_bodies_and_saves_are_refused() {
+    use website::core::limits::RequestLimits;
+
+    let limits = RequestLimits {
+        max_body_bytes: 4096,
+        max_blocks_per_route: 2,
+        max_string_chars: 40,
+        ..RequestLimits::default()
+    };
+    let app = TestApp::build(|state| state.with_limits(limits));
+    let before = app.read_content("homepage");
+
+    let padding = "x".repeat(5000);
+    let response = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "blocks": [hero_block("a", "Hi")], "padding": padding }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+
+    let blocks: Vec<Value> = (0..3).map(|i| hero_block(&format!("b{i}"), "Hi")).collect();
+    let response = app
+        .post("/admin/api/homepage", json!({ "blocks": blocks }))
+        .await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(
+        response.body.contains("too many blocks: 3"),
+        "{}",
+        response.body
+    );
+
+    let headline = "A".repeat(41);
+    let response = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "blocks": [hero_block("a", &headline)] }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::PAYLOAD_TOO_LARGE);
+    assert!(
+        response.body.contains("$.blocks[0].props.headline"),
+        "{}",
+        response.body
+    );
+    assert_eq!(app.read_content("homepage"), before);
+
+    let response = app
+        .post(
+            "/admin/api/homepage",
+            json!({ "blocks": [hero_block("a", &"A".repeat(40))] }),
+        )
+        .await;
+    assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+}
+
+#[tokio::test]
+async fn test_mutating_requests_are_rate_limited_per_address_and_token() {
+    use axum::extract::ConnectInfo;
+    use website::core::limits::{RateLimit, RequestLimits};
+
+    let clock = ManualClock::new(noon());
+    let limits = RequestLimits {
+        rate_limit: RateLimit {
+            burst: 2,
+            per_minute: 30,
+        },
+        ..RequestLimits::default()
+    };
+    let app = TestApp::build(|state| state.with_clock(clock.clone()).with_limits(limits));
+    let check = |peer: [u8; 4], token: Option<&str>| {
+        let mut request = Request::post("/admin/api/a11y")
+            .header(header::CONTENT_TYPE, "application/json")
+            .extension(ConnectInfo(std::net::SocketAddr::from((peer, 4000))));
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        app.send(request.body(Body::from(r#"{"blocks":[]}"#)).unwrap())
+    };
+
+    let token = app.create_token("", "name=ci&read_content=on").await;
+    let client = [10, 0, 0, 1];
+    assert_eq!(check(client, None).await.status, StatusCode::OK);
+    assert_eq!(check(client, None).await.status, StatusCode::OK);
+    let limited = check(client, None).await;
+    assert_eq!(limited.status, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(limited.headers[header::RETRY_AFTER], "2");
+    assert!(limited.headers.contains_key("content-security-policy"));
+
+    // Reads aren't limited; other addresses have their own buckets
+    assert_eq!(app.get("/admin/audit/").await.status, StatusCode::OK);
+    assert_eq!(check([10, 0, 0, 2], None).await.status, StatusCode::OK);
+
+    // Bearer values don't pick the bucket, valid or not
+    for bearer in [token.as_str(), "wst_made_up", "anything"] {
+        let response = check(client, Some(bearer)).await;
+        assert_eq!(response.status, StatusCode::TOO_MANY_REQUESTS, "{bearer}");
+    }
+
+    // An authenticated token also has a bucket of its own across addresses
+    assert_eq!(
+        check([10, 0, 0, 3], Some(&token)).await.status,
+        StatusCode::OK
+    );
+    assert_eq!(
+        check([10, 0, 0, 4], Some(&token)).await.status,
+        StatusCode::OK
+    );
+    assert_eq!(
+        check([10, 0, 0, 5], Some(&token)).await.status,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+    let unauthenticated = check([10, 0, 0, 5], Some("wst_made_up")).await;
+    assert_eq!(unauthenticated.status, StatusCode::UNAUTHORIZED);
+
+    clock.advance(chrono::Duration::seconds(1));
+    assert_eq!(check(client, None).await.headers[header::RETRY_AFTER], "1");
+    clock.advance(chrono::Duration::seconds(1));
+    assert_eq!(check(client, None).await.status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_forwarded_for_is_only_trusted_when_configured() {
+    use website::core::limits::{RateLimit, RequestLimits};
+
+    let limits = |trust_forwarded_for| RequestLimits {
+        rate_limit: RateLimit {
+            burst: 1,
+            per_minute: 1,
+        },
+        trust_forwarded_for,
+        ..RequestLimits::default()
+    };
+    async fn post_from(app: &TestApp, address: &str) -> TestResponse {
+        let request = Request::post("/admin/api/a11y")
+            .header(header::CONTENT_TYPE, "application/json")
+            .header("x-forwarded-for", format!("{address}, 10.0.0.1"))
+            .body(Body::from(r#"{"blocks":[]}"#))
+            .unwrap();
+        app.send(request).await
+    }
+
+    let trusting = TestApp::build(|state| state.with_limits(limits(true)));
+    assert_eq!(post_from(&trusting, "1.1.1.1").await.status, StatusCode::OK);
+    assert_eq!(post_from(&trusting, "2.2.2.2").await.status, StatusCode::OK);
+
+    let ignoring = TestApp::build(|state| state.with_limits(limits(false)));
+    assert_eq!(post_from(&ignoring, "1.1.1.1").await.status, StatusCode::OK);
+    assert_eq!(
+        post_from(&ignoring, "2.2.2.2").await.status,
+        StatusCode::TOO_MANY_REQUESTS
+    );
+}
+
+// ============================================================================
+// Users and Sign-in
+// ============================================================================
+
+const PASSWORD: &str = "correct horse battery";
+
+/// `PASSWORD` hashed with 1,000 PBKDF2 iterations instead of 600,000
+///
+/// Verifying costs what the stored hash says, so sign-ins in tests stay
+/// fast; `hash_password` itself is covered in `core::users`.
+const PASSWORD_HASH: &str = "$pbkdf2-sha256$i=1000,l=32$dGVzdC1maXh0dXJlLXNhbHQ$2yC+WOG7qCUFRhI841yw6jVnZaernxtSgFBJZyHH9Fw";
+
+impl TestApp {
+    /// Add `admin`, an `editor` granted only the homepage, and a `viewer`
+    fn with_users(self) -> Self {
+        let password = PASSWORD_HASH;
+        write_json(
+            &self.dir.path().join("users.json"),
+            &json!([
+                { "name": "admin", "role": "admin", "password": password },
+                { "name": "editor", "role": "editor", "routes": ["homepage"], "password": password },
+                { "name": "viewer", "role": "viewer", "password": password },
+            ]),
+        );
+        self
+    }
+
+    /// Sign in and return the session cookie to send
+    async fn sign_in(&self, name: &str) -> String {
+        let response = self.sign_in_with(name, PASSWORD).await;
+        assert_eq!(response.status, StatusCode::SEE_OTHER, "{}", response.body);
+        let cookie = response.headers[header::SET_COOKIE].to_str().unwrap();
+        cookie.split(';').next().unwrap().to_string()
+    }
+
+    async fn sign_in_with(&self, name: &str, password: &str) -> TestResponse {
+        let request = Request::post("/admin/login")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(format!(
+                "name={name}&password={}&next=/admin/route/",
+                password.replace(' ', "+")
+            )))
+            .unwrap();
+        self.send(request).await
+    }
+
+    async fn request_as(
+        &self,
+        cookie: &str,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> TestResponse {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::COOKIE, cookie);
+        let body = match body {
+            Some(json) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(json.to_string())
+            }
+            None => Body::empty(),
+        };
+        self.send(request.body(body).unwrap()).await
+    }
+
+    async fn post_form_as(&self, cookie: &str, uri: &str, form: &str) -> TestResponse {
+        let request = Request::post(uri)
+            .header(header::COOKIE, cookie)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(form.to_string()))
+            .unwrap();
+        self.send(request).await
+    }
+}
+
+#[tokio::test]
+async fn test_admin_requires_sign_in_once_users_exist() {
+    let app = TestApp::new().with_users();
+
+    let page = app.get("/admin/route/homepage/?locale=en").await;
+    assert_eq!(page.status, StatusCode::SEE_OTHER);
+    assert_eq!(
+        page.headers[header::LOCATION],
+        "/admin/login?next=%2Fadmin%2Froute%2Fhomepage%2F%3Flocale%3Den"
+    );
+    let save = app
+        .post("/admin/api/homepage", json!({ "blocks": [] }))
+        .await;
+    assert_eq!(save.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(app.get("/").await.status, StatusCode::OK);
+    assert_eq!(app.get("/admin/login").await.status, StatusCode::OK);
+
+    let wrong = app.sign_in_with("admin", "wrong password").await;
+    assert_eq!(wrong.status, StatusCode::UNAUTHORIZED);
+    assert!(wrong.body.contains("Wrong name or password."));
+    let unknown = app.sign_in_with("nobody", PASSWORD).await;
+    assert_eq!(unknown.status, StatusCode::UNAUTHORIZED);
+
+    let signed_in = app.sign_in_with("admin", PASSWORD).await;
+    assert_eq!(signed_in.status, StatusCode::SEE_OTHER);
+    assert_eq!(signed_in.headers[header::LOCATION], "/admin/route/");
+    let cookie = signed_in.headers[header::SET_COOKIE].to_str().unwrap();
+    assert!(cookie.starts_with("admin_session="));
+    assert!(cookie.contains("HttpOnly"));
+    assert!(cookie.contains("SameSite=Strict"));
+
+    let cookie = cookie.split(';').next().unwrap();
+    let index = app.request_as(cookie, Method::GET, "/admin", None).await;
+    assert_eq!(index.status, StatusCode::OK);
+    assert!(index.body.contains("Signed in as <strong>admin</strong>"));
+
+    let signed_out = app
+        .request_as(cookie, Method::POST, "/admin/logout", None)
+        .await;
+    assert_eq!(signed_out.status, StatusCode::SEE_OTHER);
+    let again = app.request_as(cookie, Method::GET, "/admin", None).await;
+    assert_eq!(again.status, StatusCode::SEE_OTHER, "the session has ended");
+}
+
+#[tokio::test]
+async fn test_sign_ins_wait_for_a_password_check_permit() {
+    use tokio::sync::Semaphore;
+    use website::core::limits::RequestLimits;
+
+    let permits = Arc::new(Semaphore::new(0));
+    let limits = RequestLimits {
+        request_timeout: Duration::from_millis(200),
+        ..RequestLimits::default()
+    };
+    let app = TestApp::build(|mut state| {
+        state.sign_ins = Arc::clone(&permits);
+        state.with_limits(limits)
+    })
+    .with_users();
+
+    // Every permit is taken: the sign-in waits until the request times out
+    let waiting = app.sign_in_with("admin", PASSWORD).await;
+    assert_eq!(waiting.status, StatusCode::REQUEST_TIMEOUT);
+
+    permits.add_permits(1);
+    let signed_in = app.sign_in_with("admin", PASSWORD).await;
+    assert_eq!(signed_in.status, StatusCode::SEE_OTHER);
+    assert_eq!(permits.available_permits(), 1, "the permit is returned");
+}
+
+#[tokio::test]
+async fn test_roles_and_route_grants_limit_what_users_can_do() {
+    let app = TestApp::new().with_users();
+    let editor = app.sign_in("editor").await;
+    let viewer = app.sign_in("viewer").await;
+    let save = json!({ "blocks": [hero_block("hero-1", "Edited")] });
+
+    let homepage = app
+        .request_as(
+            &editor,
+            Method::POST,
+            "/admin/api/homepage",
+            Some(save.clone()),
+        )
+        .await;
+    assert_eq!(homepage.status, StatusCode::OK, "{}", homepage.body);
+    let foo = app
+        .request_as(&editor, Method::POST, "/admin/api/foo", Some(save.clone()))
+        .await;
+    assert_eq!(foo.status, StatusCode::FORBIDDEN);
+    assert!(foo.body.contains("may not edit route 'foo'"));
+
+    // Editors save but don't schedule, and don't reach admin screens
+    let later = json!({ "publish_at": "2099-01-01T00:00:00Z", "blocks": [] });
+    let scheduled = app
+        .request_as(&editor, Method::POST, "/admin/api/homepage", Some(later))
+        .await;
+    assert_eq!(scheduled.status, StatusCode::FORBIDDEN);
+    for uri in ["/admin/settings/", "/admin/users/", "/admin/audit/"] {
+        let page = app.request_as(&editor, Method::GET, uri, None).await;
+        assert_eq!(page.status, StatusCode::FORBIDDEN, "{uri}");
+    }
+
+    let viewed = app
+        .request_as(&viewer, Method::POST, "/admin/api/homepage", Some(save))
+        .await;
+    assert_eq!(viewed.status, StatusCode::FORBIDDEN);
+    assert_eq!(
+        app.read_content("homepage")["blocks"][0]["props"]["headline"],
+        "Edited"
+    );
+
+    // The audit log names the signed-in user
+    let admin = app.sign_in("admin").await;
+    let export = app
+        .request_as(&admin, Method::GET, "/admin/api/audit/export", None)
+        .await;
+    let entry: Value = serde_json::from_str(export.body.lines().next().unwrap()).unwrap();
+    assert_eq!(entry["actor"], "editor");
+}
+
+#[tokio::test]
+async fn test_screens_hide_what_the_user_cannot_do() {
+    let app = TestApp::new().with_users();
+    let editor = app.sign_in("editor").await;
+    let viewer = app.sign_in("viewer").await;
+
+    let index = app.request_as(&editor, Method::GET, "/admin", None).await;
+    assert!(!index.body.contains("/admin/settings/"));
+    assert!(!index.body.contains("/admin/users/"));
+    let routes = app
+        .request_as(&editor, Method::GET, "/admin/route/", None)
+        .await;
+    assert_eq!(routes.body.matches("(view only)").count(), 1);
+
+    let homepage = app
+        .request_as(&editor, Method::GET, "/admin/route/homepage/", None)
+        .await;
+    assert!(homepage.body.contains("Publish Changes"));
+    assert!(
+        !homepage.body.contains("publish_at"),
+        "editors don't schedule"
+    );
+    assert!(
+        !homepage.body.contains("editor_name"),
+        "named by their user"
+    );
+
+    let foo = app
+        .request_as(&viewer, Method::GET, "/admin/route/foo/", None)
+        .await;
+    assert_eq!(foo.status, StatusCode::OK);
+    assert!(foo.body.contains("read-only-note"));
+    assert!(!foo.body.contains("Publish Changes"));
+    assert!(!foo.body.contains("<editor-presence"));
+
+    let media = app
+        .request_as(&viewer, Method::GET, "/admin/media/", None)
+        .await;
+    assert_eq!(media.status, StatusCode::OK);
+    assert!(!media.body.contains("type=\"file\""));
+}
+
+#[tokio::test]
+async fn test_users_screen_adds_and_deletes_users() {
+    let app = TestApp::new();
+
+    // The first user must be an admin, and sign-in starts once it exists
+    let form = "name=ada&role=editor&routes=&password=long+enough+password";
+    let refused = app.post_form_as("", "/admin/users/", form).await;
+    assert_eq!(refused.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(refused.body.contains("form-error"));
+    let form = "name=ada&role=admin&routes=&password=long+enough+password";
+    let created = app.post_form_as("", "/admin/users/", form).await;
+    assert_eq!(created.status, StatusCode::SEE_OTHER, "{}", created.body);
+    assert_eq!(app.get("/admin/users/").await.status, StatusCode::SEE_OTHER);
+
+    let admin = app.sign_in_with("ada", "long enough password").await;
+    let admin = admin.headers[header::SET_COOKIE].to_str().unwrap();
+    let admin = admin.split(';').next().unwrap();
+    let form = "name=mia&role=editor&routes=foo,+nope&password=another+password";
+    let unknown = app.post_form_as(admin, "/admin/users/", form).await;
+    assert_eq!(unknown.status, StatusCode::UNPROCESSABLE_ENTITY);
+    let form = "name=mia&role=editor&routes=foo&password=another+password";
+    let added = app.post_form_as(admin, "/admin/users/", form).await;
+    assert_eq!(added.status, StatusCode::SEE_OTHER);
+
+    let page = app
+        .request_as(admin, Method::GET, "/admin/users/", None)
+        .await;
+    assert!(
+        page.body
+            .contains("<td>mia</td><td>editor</td><td>foo</td>")
+    );
+    assert!(
+        !page.body.contains("pbkdf2"),
+        "hashes never leave the server"
+    );
+
+    let last_admin = app.post_form_as(admin, "/admin/users/ada/delete", "").await;
+    assert_eq!(last_admin.status, StatusCode::UNPROCESSABLE_ENTITY);
+    let deleted = app.post_form_as(admin, "/admin/users/mia/delete", "").await;
+    assert_eq!(deleted.status, StatusCode::SEE_OTHER);
+    let missing = app.post_form_as(admin, "/admin/users/mia/delete", "").await;
+    assert_eq!(missing.status, StatusCode::NOT_FOUND);
+
+    let export = app
+        .request_as(admin, Method::GET, "/admin/api/audit/export", None)
+        .await;
+    let actions: Vec<Value> = export
+        .body
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap()["action"].clone())
+        .collect();
+    assert_eq!(
+        actions,
+        vec![json!("user_save"), json!("user_save"), json!("user_delete")]
+    );
+}
+
+// ============================================================================
+// API Tokens
+// ============================================================================
+
+impl TestApp {
+    /// Create a token on the tokens screen and return it
+    async fn create_token(&self, cookie: &str, form: &str) -> String {
+        let response = self.post_form_as(cookie, "/admin/tokens/", form).await;
+        assert_eq!(response.status, StatusCode::CREATED, "{}", response.body);
+        let start = response.body.find("wst_").expect("the token is shown");
+        response.body[start..start + 68].to_string()
+    }
+
+    async fn request_with_token(
+        &self,
+        token: &str,
+        method: Method,
+        uri: &str,
+        body: Option<Value>,
+    ) -> TestResponse {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {token}"));
+        let body = match body {
+            Some(json) => {
+                request = request.header(header::CONTENT_TYPE, "application/json");
+                Body::from(json.to_string())
+            }
+            None => Body::empty(),
+        };
+        self.send(request.body(body).unwrap()).await
+    }
+}
+
+#[tokio::test]
+async fn test_api_tokens_are_scoped_and_expire() {
+    let clock = ManualClock::new(noon());
+    let app = TestApp::with_clock(&clock);
+    let token = app
+        .create_token(
+            "",
+            "name=ci&read_content=on&write_content=on&routes=homepage&expires_in_days=30",
+        )
+        .await;
+    let stored = fs::read_to_string(app.dir.path().join("tokens.json")).unwrap();
+    assert!(!stored.contains(&token), "only the hash is stored");
+
+    let read = app
+        .request_with_token(&token, Method::GET, "/admin/api/homepage", None)
+        .await;
+    assert_eq!(read.status, StatusCode::OK, "{}", read.body);
+    let mut content: Value = serde_json::from_str(&read.body).unwrap();
+    content["files"][0]["blocks"][0]["props"]["headline"] = json!("From CI");
+    let write = app
+        .request_with_token(&token, Method::POST, "/admin/api/homepage", Some(content))
+        .await;
+    assert_eq!(write.status, StatusCode::OK, "{}", write.body);
+    assert_eq!(
+        app.read_content("homepage")["blocks"][0]["props"]["headline"],
+        "From CI"
+    );
+
+    // Scopes and routes limit the token, even while the admin is open
+    let save = json!({ "blocks": [hero_block("", "Foo")] });
+    let foo = app
+        .request_with_token(&token, Method::POST, "/admin/api/foo", Some(save))
+        .await;
+    assert_eq!(foo.status, StatusCode::FORBIDDEN);
+    assert!(
+        foo.body
+            .contains("with token 'ci' may not edit route 'foo'")
+    );
+    let routes = app
+        .request_with_token(&token, Method::POST, "/admin/api/routes", Some(json!([])))
+        .await;
+    assert_eq!(routes.status, StatusCode::FORBIDDEN);
+    let export = app
+        .request_with_token(&token, Method::GET, "/admin/api/audit/export", None)
+        .await;
+    assert_eq!(
+        export.status,
+        StatusCode::FORBIDDEN,
+        "tokens never administer"
+    );
+    let wrong = app
+        .request_with_token("wst_nope", Method::GET, "/admin/api/homepage", None)
+        .await;
+    assert_eq!(wrong.status, StatusCode::UNAUTHORIZED);
+
+    let page = app.get("/admin/tokens/").await;
+    assert!(page.body.contains("2026-03-01 12:00 UTC"), "last used");
+    assert!(page.body.contains("2026-03-31 12:00 UTC"), "expires");
+
+    clock.advance(chrono::Duration::days(30));
+    let expired = app
+        .request_with_token(&token, Method::GET, "/admin/api/homepage", None)
+        .await;
+    assert_eq!(expired.status, StatusCode::UNAUTHORIZED);
+    assert!(app.get("/admin/tokens/").await.body.contains("Expired"));
+}
+
+#[tokio::test]
+async fn test_api_tokens_act_for_their_owner() {
+    let app = TestApp::new().with_users();
+    let editor = app.sign_in("editor").await;
+
+    let refused = app
+        .post_form_as(&editor, "/admin/tokens/", "name=ci&manage_routes=on")
+        .await;
+    assert_eq!(refused.status, StatusCode::UNPROCESSABLE_ENTITY);
+    let refused = app
+        .post_form_as(
+            &editor,
+            "/admin/tokens/",
+            "name=ci&write_content=on&routes=foo",
+        )
+        .await;
+    assert_eq!(refused.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(refused.body.contains("You may not edit route 'foo'"));
+
+    // No routes on the token: the owner's grants still apply
+    let token = app
+        .create_token(&editor, "name=ci&write_content=on&expires_in_days=never")
+        .await;
+    let save = json!({ "blocks": [hero_block("hero-1", "Token")] });
+    let homepage = app
+        .request_with_token(
+            &token,
+            Method::POST,
+            "/admin/api/homepage",
+            Some(save.clone()),
+        )
+        .await;
+    assert_eq!(homepage.status, StatusCode::OK, "{}", homepage.body);
+    let foo = app
+        .request_with_token(&token, Method::POST, "/admin/api/foo", Some(save))
+        .await;
+    assert_eq!(foo.status, StatusCode::FORBIDDEN);
+
+    // Tokens only work on the API, not on pages
+    let page = app
+        .request_with_token(&token, Method::GET, "/admin/route/", None)
+        .await;
+    assert_eq!(page.status, StatusCode::SEE_OTHER);
+
+    // Other users don't see the token; admins do, and the log names its owner
+    let viewer = app.sign_in("viewer").await;
+    let tokens = app
+        .request_as(&viewer, Method::GET, "/admin/tokens/", None)
+        .await;
+    assert!(tokens.body.contains("No tokens yet."));
+    let admin = app.sign_in("admin").await;
+    let tokens = app
+        .request_as(&admin, Method::GET, "/admin/tokens/", None)
+        .await;
+    assert!(tokens.body.contains("<td>editor</td>"));
+    let export = app
+        .request_as(
+            &admin,
+            Method::GET,
+            "/admin/api/audit/export?action=content_save",
+            None,
+        )
+        .await;
+    let entry: Value = serde_json::from_str(export.body.lines().next().unwrap()).unwrap();
+    assert_eq!(entry["actor"], "editor");
+
+    // Deleting the owner disables the token
+    let deleted = app
+        .post_form_as(&admin, "/admin/users/editor/delete", "")
+        .await;
+    assert_eq!(deleted.status, StatusCode::SEE_OTHER);
+    let orphaned = app
+        .request_with_token(&token, Method::GET, "/admin/api/homepage", None)
+        .await;
+    assert_eq!(orphaned.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_revoked_tokens_stop_working() {
+    let app = TestApp::new().with_users();
+    let editor = app.sign_in("editor").await;
+    let viewer = app.sign_in("viewer").await;
+    let token = app.create_token(&editor, "name=ci&read_content=on").await;
+    let tokens: Value =
+        serde_json::from_str(&fs::read_to_string(app.dir.path().join("tokens.json")).unwrap())
+            .unwrap();
+    let revoke = format!("/admin/tokens/{}/revoke", tokens[0]["id"].as_str().unwrap());
+
+    let not_theirs = app.post_form_as(&viewer, &revoke, "").await;
+    assert_eq!(not_theirs.status, StatusCode::FORBIDDEN);
+    let revoked = app.post_form_as(&editor, &revoke, "").await;
+    assert_eq!(revoked.status, StatusCode::SEE_OTHER);
+    let again = app.post_form_as(&editor, &revoke, "").await;
+    assert_eq!(again.status, StatusCode::NOT_FOUND);
+
+    let read = app
+        .request_with_token(&token, Method::GET, "/admin/api/homepage", None)
+        .await;
+    assert_eq!(read.status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_routes_api_replaces_routes() {
+    let app = TestApp::new();
+    let token = app.create_token("", "name=routes&manage_routes=on").await;
+
+    let listed = app
+        .request_with_token(&token, Method::GET, "/admin/api/routes", None)
+        .await;
+    let mut routes: Vec<Value> = serde_json::from_str(&listed.body).unwrap();
+    assert_eq!(routes.len(), 2);
+    routes.push(json!({ "path": "/changelog", "name": "changelog", "blockIds": ["changelog"] }));
+
+    let saved = app
+        .request_with_token(
+            &token,
+            Method::POST,
+            "/admin/api/routes",
+            Some(json!(routes)),
+        )
+        .await;
+    assert_eq!(saved.status, StatusCode::OK, "{}", saved.body);
+    let content = app.get("/admin/api/changelog").await;
+    assert_eq!(content.status, StatusCode::OK);
+    assert_eq!(
+        serde_json::from_str::<Value>(&content.body).unwrap(),
+        json!({ "files": [{ "source": "changelog", "blocks": [] }] })
+    );
+    assert_eq!(audit_export(&app, "?action=routes_update").await.len(), 1);
+
+    routes.push(json!({ "path": "/changelog", "name": "again", "blockIds": ["../secrets"] }));
+    let invalid = app
+        .request_with_token(
+            &token,
+            Method::POST,
+            "/admin/api/routes",
+            Some(json!(routes)),
+        )
+        .await;
+    assert_eq!(invalid.status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        app.get("/admin/api/routes")
+            .await
+            .body
+            .matches("\"name\"")
+            .count(),
+        3
+    );
+}
+
+// ============================================================================
+// Two-Factor Sign-in
+// ============================================================================
+
+/// The value of a hidden form field in a page
+fn hidden_value(body: &str, name: &str) -> String {
+    let marker = format!("name=\"{name}\" value=\"");
+    let start = body.find(&marker).expect("the field is in the form") + marker.len();
+    let end = start + body[start..].find('"').unwrap();
+    body[start..end].to_string()
+}
+
+impl TestApp {
+    /// Enroll the signed-in user; returns the secret and recovery codes
+    async fn enroll_two_factor(&self, cookie: &str, clock: &ManualClock) -> (String, Vec<String>) {
+        let page = self
+            .request_as(cookie, Method::GET, "/admin/account/two-factor", None)
+            .await;
+        assert_eq!(page.status, StatusCode::OK);
+        let secret = hidden_value(&page.body, "secret");
+        let code = totp::code_at(&secret, clock.now()).unwrap();
+        let enrolled = self
+            .post_form_as(
+                cookie,
+                "/admin/account/two-factor",
+                &format!("secret={secret}&code={code}"),
+            )
+            .await;
+        assert_eq!(enrolled.status, StatusCode::OK, "{}", enrolled.body);
+        let codes = enrolled
+            .body
+            .split("<li><code>")
+            .skip(1)
+            .map(|item| item[..item.find('<').unwrap()].to_string())
+            .collect();
+        (secret, codes)
+    }
+
+    /// Sign in with the password; returns the challenge of the code form
+    async fn start_two_factor_sign_in(&self, name: &str) -> String {
+        let response = self.sign_in_with(name, PASSWORD).await;
+        assert_eq!(response.status, StatusCode::OK, "{}", response.body);
+        assert!(!response.headers.contains_key(header::SET_COOKIE));
+        hidden_value(&response.body, "challenge")
+    }
+
+    async fn finish_two_factor_sign_in(&self, challenge: &str, code: &str) -> TestResponse {
+        let form = format!("challenge={challenge}&code={code}&next=/admin/route/");
+        self.post_form_as("", "/admin/login/two-factor", &form)
+            .await
+    }
+}
+
+#[tokio::test]
+async fn test_two_factor_sign_in_needs_a_fresh_code() {
+    let clock = ManualClock::new(noon());
+    let app = TestApp::with_clock(&clock).with_users();
+    let cookie = app.sign_in("editor").await;
+
+    let page = app
+        .request_as(&cookie, Method::GET, "/admin/account/two-factor", None)
+        .await;
+    assert!(page.body.contains("<svg"), "QR code");
+    let secret = hidden_value(&page.body, "secret");
+    assert!(
+        page.body
+            .contains(&format!("<code class=\"totp-secret\">{secret}</code>"))
+    );
+    let wrong = app
+        .post_form_as(
+            &cookie,
+            "/admin/account/two-factor",
+            &format!("secret={secret}&code=abcdef"),
+        )
+        .await;
+    assert_eq!(wrong.status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (secret, recovery) = app.enroll_two_factor(&cookie, &clock).await;
+    assert_eq!(recovery.len(), totp::RECOVERY_CODES);
+    let users = fs::read_to_string(app.dir.path().join("users.json")).unwrap();
+    assert!(users.contains(&secret));
+    assert!(!users.contains(&recovery[0]), "only hashes are stored");
+
+    // The password alone no longer signs in
+    let challenge = app.start_two_factor_sign_in("editor").await;
+    let wrong = app.finish_two_factor_sign_in(&challenge, "abcdef").await;
+    assert_eq!(wrong.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(hidden_value(&wrong.body, "challenge"), challenge);
+
+    // The code used to enroll can't be used again, even within its step
+    let enrolled_code = totp::code_at(&secret, clock.now()).unwrap();
+    let replayed = app
+        .finish_two_factor_sign_in(&challenge, &enrolled_code)
+        .await;
+    assert_eq!(replayed.status, StatusCode::UNAUTHORIZED);
+
+    clock.advance(chrono::Duration::seconds(30));
+    let code = totp::code_at(&secret, clock.now()).unwrap();
+    let signed_in = app.finish_two_factor_sign_in(&challenge, &code).await;
+    assert_eq!(signed_in.status, StatusCode::SEE_OTHER);
+    assert_eq!(signed_in.headers[header::LOCATION], "/admin/route/");
+    let session = signed_in.headers[header::SET_COOKIE].to_str().unwrap();
+    let session = session.split(';').next().unwrap();
+    let routes = app
+        .request_as(session, Method::GET, "/admin/route/", None)
+        .await;
+    assert_eq!(routes.status, StatusCode::OK);
+
+    // An answered challenge is gone, and the code is used up
+    let reused = app.finish_two_factor_sign_in(&challenge, &code).await;
+    assert_eq!(reused.status, StatusCode::UNAUTHORIZED);
+    let challenge = app.start_two_factor_sign_in("editor").await;
+    let replayed = app.finish_two_factor_sign_in(&challenge, &code).await;
+    assert_eq!(replayed.status, StatusCode::UNAUTHORIZED);
+
+    // A recovery code works once
+    let recovered = app
+        .finish_two_factor_sign_in(&challenge, &recovery[0])
+        .await;
+    assert_eq!(recovered.status, StatusCode::SEE_OTHER);
+    let challenge = app.start_two_factor_sign_in("editor").await;
+    let again = app
+        .finish_two_factor_sign_in(&challenge, &recovery[0])
+        .await;
+    assert_eq!(again.status, StatusCode::UNAUTHORIZED);
+    let page = app
+        .request_as(session, Method::GET, "/admin/account/two-factor", None)
+        .await;
+    assert!(page.body.contains("9 of 10 recovery codes left"));
+}
+
+#[tokio::test]
+async fn test_two_factor_challenges_expire_and_limit_attempts() {
+    let clock = ManualClock::new(noon());
+    let app = TestApp::with_clock(&clock).with_users();
+    let cookie = app.sign_in("viewer").await;
+    let (secret, _) = app.enroll_two_factor(&cookie, &clock).await;
+
+    let challenge = app.start_two_factor_sign_in("viewer").await;
+    for _ in 1..5 {
+        let wrong = app.finish_two_factor_sign_in(&challenge, "000000").await;
+        assert!(wrong.body.contains("name=\"challenge\""));
+    }
+    let locked = app.finish_two_factor_sign_in(&challenge, "000000").await;
+    assert_eq!(locked.status, StatusCode::UNAUTHORIZED);
+    assert!(locked.body.contains("Too many wrong codes"));
+    clock.advance(chrono::Duration::seconds(30));
+    let code = totp::code_at(&secret, clock.now()).unwrap();
+    let refused = app.finish_two_factor_sign_in(&challenge, &code).await;
+    assert_eq!(refused.status, StatusCode::UNAUTHORIZED);
+
+    let challenge = app.start_two_factor_sign_in("viewer").await;
+    clock.advance(chrono::Duration::minutes(5));
+    let code = totp::code_at(&secret, clock.now()).unwrap();
+    let expired = app.finish_two_factor_sign_in(&challenge, &code).await;
+    assert_eq!(expired.status, StatusCode::UNAUTHORIZED);
+    assert!(expired.body.contains("Sign-in expired"));
+}
+
+#[tokio::test]
+async fn test_roles_can_require_two_factor() {
+    let clock = ManualClock::new(noon());
+    let app = TestApp::with_clock(&clock).with_users();
+    write_json(
+        &app.dir.path().join("settings.json"),
+        &json!({ "two_factor_roles": ["editor"] }),
+    );
+
+    // Until they enroll, editors only reach the two-factor screen
+    let editor = app.sign_in("editor").await;
+    let page = app
+        .request_as(&editor, Method::GET, "/admin/route/", None)
+        .await;
+    assert_eq!(page.status, StatusCode::SEE_OTHER);
+    assert_eq!(page.headers[header::LOCATION], "/admin/account/two-factor");
+    let save = app
+        .request_as(
+            &editor,
+            Method::POST,
+            "/admin/api/homepage",
+            Some(json!({ "blocks": [] })),
+        )
+        .await;
+    assert_eq!(save.status, StatusCode::FORBIDDEN);
+    let viewer = app.sign_in("viewer").await;
+    let page = app
+        .request_as(&viewer, Method::GET, "/admin/route/", None)
+        .await;
+    assert_eq!(page.status, StatusCode::OK);
+
+    let admin = app.sign_in("admin").await;
+    let users = app
+        .request_as(&admin, Method::GET, "/admin/users/", None)
+        .await;
+    assert!(users.body.contains("Required, not set up"));
+
+    let (secret, _) = app.enroll_two_factor(&editor, &clock).await;
+    let page = app
+        .request_as(&editor, Method::GET, "/admin/route/", None)
+        .await;
+    assert_eq!(page.status, StatusCode::OK);
+
+    // It can't be turned off while the role requires it
+    clock.advance(chrono::Duration::seconds(30));
+    let code = totp::code_at(&secret, clock.now()).unwrap();
+    let disable = app
+        .post_form_as(
+            &editor,
+            "/admin/account/two-factor/disable",
+            &format!("code={code}"),
+        )
+        .await;
+    assert_eq!(disable.status, StatusCode::FORBIDDEN);
+
+    // An admin can reset it, after which the editor must enroll again
+    let viewer_reset = app
+        .post_form_as(&viewer, "/admin/users/editor/two-factor/reset", "")
+        .await;
+    assert_eq!(viewer_reset.status, StatusCode::FORBIDDEN);
+    let reset = app
+        .post_form_as(&admin, "/admin/users/editor/two-factor/reset", "")
+        .await;
+    assert_eq!(reset.status, StatusCode::SEE_OTHER);
+    let page = app
+        .request_as(&editor, Method::GET, "/admin/route/", None)
+        .await;
+    assert_eq!(page.status, StatusCode::SEE_OTHER);
+
+    let export = app
+        .request_as(&admin, Method::GET, "/admin/api/audit/export", None)
+        .await;
+    let actions: Vec<Value> = export
+        .body
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap()["action"].clone())
+        .collect();
+    assert_eq!(
+        actions,
+        vec![json!("two_factor_enable"), json!("two_factor_disable")]
+    );
+}
+
+#[tokio::test]
+async fn test_optional_two_factor_can_be_turned_off() {
+    let clock = ManualClock::new(noon());
+    let app = TestApp::with_clock(&clock).with_users();
+    let cookie = app.sign_in("admin").await;
+    let (secret, _) = app.enroll_two_factor(&cookie, &clock).await;
+
+    clock.advance(chrono::Duration::seconds(30));
+    let code = totp::code_at(&secret, clock.now()).unwrap();
+    let codes = app
+        .post_form_as(
+            &cookie,
+            "/admin/account/two-factor/recovery-codes",
+            &format!("code={code}"),
+        )
+        .await;
+    assert_eq!(codes.status, StatusCode::OK);
+    assert_eq!(codes.body.matches("<li><code>").count(), 10);
+
+    let wrong = app
+        .post_form_as(&cookie, "/admin/account/two-factor/disable", "code=000000")
+        .await;
+    assert_eq!(wrong.status, StatusCode::UNPROCESSABLE_ENTITY);
+    clock.advance(chrono::Duration::seconds(30));
+    let code = totp::code_at(&secret, clock.now()).unwrap();
+    let disabled = app
+        .post_form_as(
+            &cookie,
+            "/admin/account/two-factor/disable",
+            &format!("code={code}"),
+        )
+        .await;
+    assert_eq!(disabled.status, StatusCode::SEE_OTHER);
+    app.sign_in("admin").await;
+
+    // There is no account to protect while the admin is open
+    let open = TestApp::new();
+    let page = open.get("/admin/account/two-factor").await;
+    assert_eq!(page.status, StatusCode::NOT_FOUND);
+}